@@ -1,22 +1,330 @@
-use bytes::{Bytes, BufMut, BytesMut, Buf};
+//! IPC 二进制线格式
+//!
+//! 所有跨进程消息都以定长帧头开始，随后是消息体：
+//!
+//! | 偏移 | 长度 | 字段                      |
+//! |------|------|---------------------------|
+//! | 0    | 2    | 魔数 `FRAME_MAGIC`（LE）  |
+//! | 2    | 1    | 线格式版本 `WIRE_VERSION` |
+//! | 3    | 1    | 消息类型 `MessageType`    |
+//! | 4    | 4    | 消息体长度（LE）          |
+//! | 8    | 4    | 消息体 CRC-32（LE）       |
+//!
+//! 帧之后允许有补零（iceoryx2 定长缓冲区），解码时按帧头中的长度截取消息体。
+//! 消息体中的整数均为小端，字符串为 u32 长度前缀的 UTF-8，可选字段前有 0/1 标志字节，
+//! 时间戳为 UTC 微秒，标的为 `InstrumentId`（场所ID u32 + 符号ID u32）。风控消息（`risk_control.proto`）的消息体直接为 protobuf 编码。
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use uuid::Uuid;
+
+use crate::events::{
+    TradingEvent, OpenPositionEvent, ClosePositionEvent, HedgePositionEvent, CancelOrderEvent, ModifyOrderEvent,
+};
+use crate::instrument::InstrumentId;
+use crate::messages::EventMessage;
+use crate::risk_proto::risk_control::{RiskInitResponse, RiskStateSummary};
 use crate::types::{
     Signal, SignalData, SignalType, FundingDirection, RiskLevel, OrderResponseStatus,
+    ExecutionReport, ExecutionType, OrderStatus, OrderType, Side, TriggerType,
 };
-use crate::events::TradingEvent;
-use crate::messages::EventMessage;
 
-#[repr(u32)]
+/// 帧头魔数（"CT"）
+pub const FRAME_MAGIC: u16 = 0x5443;
+
+/// 线格式版本，任何消息体布局变化都需要递增
+pub const WIRE_VERSION: u8 = 2;
+
+/// magic(2) + version(1) + message type(1) + payload length(4) + checksum(4)
+pub const FRAME_HEADER_LEN: usize = 12;
+
+/// 消息类型
+#[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum BinarySignalType {
-    AdaptiveSpreadDeviation = 0,
-    FixedSpreadDeviation = 1,
-    FundingRateDirection = 2,
-    RealTimeFundingRisk = 3,
-    OrderResponse = 4,
+pub enum MessageType {
+    Signal = 1,
+    Event = 2,
+    ExecutionCommand = 3,
+    ExecutionResult = 4,
+    ExecutionReport = 5,
+    RiskInitResponse = 6,
+    RiskStateSummary = 7,
+}
+
+impl TryFrom<u8> for MessageType {
+    type Error = DecodeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(MessageType::Signal),
+            2 => Ok(MessageType::Event),
+            3 => Ok(MessageType::ExecutionCommand),
+            4 => Ok(MessageType::ExecutionResult),
+            5 => Ok(MessageType::ExecutionReport),
+            6 => Ok(MessageType::RiskInitResponse),
+            7 => Ok(MessageType::RiskStateSummary),
+            v => Err(DecodeError::UnknownMessageType(v)),
+        }
+    }
+}
+
+/// 解码错误
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum DecodeError {
+    #[error("frame truncated: {field} needs {needed} bytes, {remaining} left")]
+    Truncated { field: &'static str, needed: usize, remaining: usize },
+    #[error("bad frame magic {0:#06x}")]
+    BadMagic(u16),
+    #[error("unsupported wire version {0}, expected {WIRE_VERSION}")]
+    UnsupportedVersion(u8),
+    #[error("unknown message type {0}")]
+    UnknownMessageType(u8),
+    #[error("expected {expected:?} message, got {found:?}")]
+    UnexpectedMessageType { expected: MessageType, found: MessageType },
+    #[error("checksum mismatch: header {expected:#010x}, payload {actual:#010x}")]
+    ChecksumMismatch { expected: u32, actual: u32 },
+    #[error("invalid value {value} for {field}")]
+    InvalidEnum { field: &'static str, value: u8 },
+    #[error("invalid utf-8 in {0}")]
+    InvalidUtf8(&'static str),
+    #[error("invalid timestamp {0}")]
+    InvalidTimestamp(i64),
+    #[error("{0} unread bytes after payload")]
+    TrailingBytes(usize),
+    #[error("invalid protobuf payload for {0}")]
+    InvalidProtobuf(&'static str),
+}
+
+/// 编码错误
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EncodeError {
+    #[error("frame of {len} bytes does not fit into a {capacity} byte buffer")]
+    FrameTooLarge { len: usize, capacity: usize },
+    #[error("{field} of {len} bytes does not fit into {capacity} inline bytes")]
+    FieldTooLong { field: &'static str, len: usize, capacity: usize },
+    #[error("{field} has {len} entries, more than the {max} its length prefix can count")]
+    TooManyEntries { field: &'static str, len: usize, max: usize },
+}
+
+/// 可按帧收发的消息
+pub trait WireMessage: Sized {
+    const MESSAGE_TYPE: MessageType;
+
+    /// 写入消息体（不含帧头），长度前缀放不下时返回错误
+    fn encode_payload(&self, buf: &mut BytesMut) -> Result<(), EncodeError>;
+
+    /// 从消息体解码，需读完整个消息体
+    fn decode_payload(buf: &mut Bytes) -> Result<Self, DecodeError>;
+}
+
+/// 编码为完整帧
+pub fn encode_frame<M: WireMessage>(message: &M) -> Result<Bytes, EncodeError> {
+    let mut payload = BytesMut::with_capacity(256);
+    message.encode_payload(&mut payload)?;
+
+    let mut frame = BytesMut::with_capacity(FRAME_HEADER_LEN + payload.len());
+    frame.put_u16_le(FRAME_MAGIC);
+    frame.put_u8(WIRE_VERSION);
+    frame.put_u8(M::MESSAGE_TYPE as u8);
+    frame.put_u32_le(payload.len() as u32);
+    frame.put_u32_le(checksum(&payload));
+    frame.put_slice(&payload);
+    Ok(frame.freeze())
+}
+
+/// 编码到 iceoryx2 定长缓冲区，尾部补零
+pub fn encode_to_array<M: WireMessage, const N: usize>(message: &M) -> Result<[u8; N], EncodeError> {
+    let frame = encode_frame(message)?;
+    if frame.len() > N {
+        return Err(EncodeError::FrameTooLarge { len: frame.len(), capacity: N });
+    }
+
+    let mut buffer = [0u8; N];
+    buffer[..frame.len()].copy_from_slice(&frame);
+    Ok(buffer)
+}
+
+/// 解码完整帧，允许帧后补零
+pub fn decode_frame<M: WireMessage>(frame: &[u8]) -> Result<M, DecodeError> {
+    let (message_type, mut payload) = open_frame(frame)?;
+    if message_type != M::MESSAGE_TYPE {
+        return Err(DecodeError::UnexpectedMessageType {
+            expected: M::MESSAGE_TYPE,
+            found: message_type,
+        });
+    }
+
+    let message = M::decode_payload(&mut payload)?;
+    if payload.has_remaining() {
+        return Err(DecodeError::TrailingBytes(payload.remaining()));
+    }
+    Ok(message)
+}
+
+/// 读取帧头中的消息类型，用于同一 topic 上承载多种消息的场景
+pub fn peek_message_type(frame: &[u8]) -> Result<MessageType, DecodeError> {
+    let header = read_header(frame)?;
+    Ok(header.0)
 }
 
-#[repr(u32)]
+/// 校验帧头和校验和，返回消息类型和消息体
+fn open_frame(frame: &[u8]) -> Result<(MessageType, Bytes), DecodeError> {
+    let (message_type, len, expected) = read_header(frame)?;
+
+    let remaining = frame.len() - FRAME_HEADER_LEN;
+    if remaining < len {
+        return Err(DecodeError::Truncated { field: "payload", needed: len, remaining });
+    }
+
+    let payload = &frame[FRAME_HEADER_LEN..FRAME_HEADER_LEN + len];
+    let actual = checksum(payload);
+    if actual != expected {
+        return Err(DecodeError::ChecksumMismatch { expected, actual });
+    }
+
+    Ok((message_type, Bytes::copy_from_slice(payload)))
+}
+
+fn read_header(frame: &[u8]) -> Result<(MessageType, usize, u32), DecodeError> {
+    if frame.len() < FRAME_HEADER_LEN {
+        return Err(DecodeError::Truncated {
+            field: "frame header",
+            needed: FRAME_HEADER_LEN,
+            remaining: frame.len(),
+        });
+    }
+
+    let magic = u16::from_le_bytes([frame[0], frame[1]]);
+    if magic != FRAME_MAGIC {
+        return Err(DecodeError::BadMagic(magic));
+    }
+    if frame[2] != WIRE_VERSION {
+        return Err(DecodeError::UnsupportedVersion(frame[2]));
+    }
+    let message_type = MessageType::try_from(frame[3])?;
+    let len = u32::from_le_bytes([frame[4], frame[5], frame[6], frame[7]]) as usize;
+    let checksum = u32::from_le_bytes([frame[8], frame[9], frame[10], frame[11]]);
+
+    Ok((message_type, len, checksum))
+}
+
+/// CRC-32（IEEE 802.3）
+pub fn checksum(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+// ========== 枚举编码 ==========
+
+/// 以 u8 编码的枚举
+pub trait WireEnum: Sized + Copy {
+    const FIELD: &'static str;
+
+    fn to_wire(self) -> u8;
+    fn from_wire(value: u8) -> Option<Self>;
+}
+
+/// 为枚举生成 `WireEnum` 实现，编号一经发布不得复用
+#[macro_export]
+macro_rules! wire_enum {
+    ($ty:ty, $field:literal, { $($variant:path => $code:literal),+ $(,)? }) => {
+        impl $crate::binary::WireEnum for $ty {
+            const FIELD: &'static str = $field;
+
+            fn to_wire(self) -> u8 {
+                match self {
+                    $($variant => $code,)+
+                }
+            }
+
+            fn from_wire(value: u8) -> Option<Self> {
+                match value {
+                    $($code => Some($variant),)+
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+wire_enum!(SignalType, "signal_type", {
+    SignalType::AdaptiveSpreadDeviation => 0,
+    SignalType::FixedSpreadDeviation => 1,
+    SignalType::FundingRateDirection => 2,
+    SignalType::RealTimeFundingRisk => 3,
+    SignalType::OrderResponse => 4,
+    SignalType::Arbitrage => 5,
+    SignalType::Market => 6,
+    SignalType::Hedge => 7,
+    SignalType::RiskControlInit => 8,
+});
+
+wire_enum!(Side, "side", {
+    Side::Buy => 0,
+    Side::Sell => 1,
+});
+
+wire_enum!(OrderType, "order_type", {
+    OrderType::Market => 0,
+    OrderType::Limit => 1,
+    OrderType::PostOnly => 2,
+});
+
+wire_enum!(OrderStatus, "order_status", {
+    OrderStatus::Pending => 0,
+    OrderStatus::Placed => 1,
+    OrderStatus::PartiallyFilled => 2,
+    OrderStatus::Filled => 3,
+    OrderStatus::Cancelled => 4,
+    OrderStatus::Rejected => 5,
+});
+
+wire_enum!(ExecutionType, "execution_type", {
+    ExecutionType::New => 0,
+    ExecutionType::Trade => 1,
+    ExecutionType::Replaced => 2,
+    ExecutionType::Cancelled => 3,
+    ExecutionType::Rejected => 4,
+    ExecutionType::Expired => 5,
+});
+
+wire_enum!(TriggerType, "trigger_type", {
+    TriggerType::MTTrigger => 0,
+    TriggerType::MTCloseTrigger => 1,
+    TriggerType::HedgeTrigger => 2,
+});
+
+wire_enum!(FundingDirection, "funding_direction", {
+    FundingDirection::Positive => 0,
+    FundingDirection::Negative => 1,
+    FundingDirection::Neutral => 2,
+});
+
+wire_enum!(RiskLevel, "risk_level", {
+    RiskLevel::Low => 0,
+    RiskLevel::Medium => 1,
+    RiskLevel::High => 2,
+    RiskLevel::Critical => 3,
+});
+
+wire_enum!(OrderResponseStatus, "order_response_status", {
+    OrderResponseStatus::Filled => 0,
+    OrderResponseStatus::PartiallyFilled => 1,
+    OrderResponseStatus::Rejected => 2,
+    OrderResponseStatus::Cancelled => 3,
+});
+
+/// 事件类型标签
+#[repr(u8)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EventType {
     OpenPosition = 0,
@@ -26,286 +334,952 @@ pub enum EventType {
     ModifyOrder = 4,
 }
 
-impl Signal {
-    pub fn to_bytes(&self) -> Bytes {
-        let mut buf = BytesMut::with_capacity(256);
-        
-        // 写入信号类型
-        buf.put_u32_le(self.signal_type as u32);
-        
-        // 写入基本字段
-        buf.put_u32_le(self.id.len() as u32);
-        buf.put_slice(self.id.as_bytes());
-        buf.put_u32_le(self.symbol.len() as u32);
-        buf.put_slice(self.symbol.as_bytes());
-        buf.put_u32_le(self.exchange.len() as u32);
-        buf.put_slice(self.exchange.as_bytes());
-        buf.put_i64_le(self.timestamp.timestamp_millis());
-        
-        // 根据具体数据类型写入详细信息
+wire_enum!(EventType, "event_type", {
+    EventType::OpenPosition => 0,
+    EventType::ClosePosition => 1,
+    EventType::HedgePosition => 2,
+    EventType::CancelOrder => 3,
+    EventType::ModifyOrder => 4,
+});
+
+// ========== 基本类型读写 ==========
+
+pub fn put_enum<E: WireEnum>(buf: &mut BytesMut, value: E) {
+    buf.put_u8(value.to_wire());
+}
+
+pub fn put_bool(buf: &mut BytesMut, value: bool) {
+    buf.put_u8(value as u8);
+}
+
+/// 写入 u16 元素个数，超出 u16 时返回错误而不是截断
+pub fn put_count(buf: &mut BytesMut, field: &'static str, len: usize) -> Result<(), EncodeError> {
+    let count = u16::try_from(len).map_err(|_| EncodeError::TooManyEntries {
+        field,
+        len,
+        max: u16::MAX as usize,
+    })?;
+    buf.put_u16_le(count);
+    Ok(())
+}
+
+pub fn put_str(buf: &mut BytesMut, value: &str) {
+    put_bytes(buf, value.as_bytes());
+}
+
+pub fn put_bytes(buf: &mut BytesMut, value: &[u8]) {
+    buf.put_u32_le(value.len() as u32);
+    buf.put_slice(value);
+}
+
+pub fn put_opt_str(buf: &mut BytesMut, value: Option<&str>) {
+    match value {
+        Some(value) => {
+            buf.put_u8(1);
+            put_str(buf, value);
+        }
+        None => buf.put_u8(0),
+    }
+}
+
+pub fn put_opt_f64(buf: &mut BytesMut, value: Option<f64>) {
+    match value {
+        Some(value) => {
+            buf.put_u8(1);
+            buf.put_f64_le(value);
+        }
+        None => buf.put_u8(0),
+    }
+}
+
+pub fn put_decimal(buf: &mut BytesMut, value: Decimal) {
+    buf.put_slice(&value.serialize());
+}
+
+pub fn put_opt_decimal(buf: &mut BytesMut, value: Option<Decimal>) {
+    match value {
+        Some(value) => {
+            buf.put_u8(1);
+            put_decimal(buf, value);
+        }
+        None => buf.put_u8(0),
+    }
+}
+
+pub fn put_uuid(buf: &mut BytesMut, value: Uuid) {
+    buf.put_slice(value.as_bytes());
+}
+
+pub fn put_instrument(buf: &mut BytesMut, value: InstrumentId) {
+    buf.put_u32_le(value.venue);
+    buf.put_u32_le(value.symbol);
+}
+
+pub fn put_timestamp(buf: &mut BytesMut, value: DateTime<Utc>) {
+    buf.put_i64_le(value.timestamp_micros());
+}
+
+fn ensure(buf: &Bytes, needed: usize, field: &'static str) -> Result<(), DecodeError> {
+    if buf.remaining() < needed {
+        return Err(DecodeError::Truncated { field, needed, remaining: buf.remaining() });
+    }
+    Ok(())
+}
+
+pub fn get_u8(buf: &mut Bytes, field: &'static str) -> Result<u8, DecodeError> {
+    ensure(buf, 1, field)?;
+    Ok(buf.get_u8())
+}
+
+pub fn get_u16(buf: &mut Bytes, field: &'static str) -> Result<u16, DecodeError> {
+    ensure(buf, 2, field)?;
+    Ok(buf.get_u16_le())
+}
+
+pub fn get_u32(buf: &mut Bytes, field: &'static str) -> Result<u32, DecodeError> {
+    ensure(buf, 4, field)?;
+    Ok(buf.get_u32_le())
+}
+
+pub fn get_u64(buf: &mut Bytes, field: &'static str) -> Result<u64, DecodeError> {
+    ensure(buf, 8, field)?;
+    Ok(buf.get_u64_le())
+}
+
+pub fn get_i64(buf: &mut Bytes, field: &'static str) -> Result<i64, DecodeError> {
+    ensure(buf, 8, field)?;
+    Ok(buf.get_i64_le())
+}
+
+pub fn get_f64(buf: &mut Bytes, field: &'static str) -> Result<f64, DecodeError> {
+    ensure(buf, 8, field)?;
+    Ok(buf.get_f64_le())
+}
+
+pub fn get_bool(buf: &mut Bytes, field: &'static str) -> Result<bool, DecodeError> {
+    match get_u8(buf, field)? {
+        0 => Ok(false),
+        1 => Ok(true),
+        value => Err(DecodeError::InvalidEnum { field, value }),
+    }
+}
+
+pub fn get_enum<E: WireEnum>(buf: &mut Bytes) -> Result<E, DecodeError> {
+    let value = get_u8(buf, E::FIELD)?;
+    E::from_wire(value).ok_or(DecodeError::InvalidEnum { field: E::FIELD, value })
+}
+
+pub fn get_bytes(buf: &mut Bytes, field: &'static str) -> Result<Bytes, DecodeError> {
+    let len = get_u32(buf, field)? as usize;
+    ensure(buf, len, field)?;
+    Ok(buf.copy_to_bytes(len))
+}
+
+pub fn get_str(buf: &mut Bytes, field: &'static str) -> Result<String, DecodeError> {
+    let bytes = get_bytes(buf, field)?;
+    String::from_utf8(bytes.to_vec()).map_err(|_| DecodeError::InvalidUtf8(field))
+}
+
+/// 可选字段的标志字节
+fn get_flag(buf: &mut Bytes, field: &'static str) -> Result<bool, DecodeError> {
+    get_bool(buf, field)
+}
+
+pub fn get_opt_str(buf: &mut Bytes, field: &'static str) -> Result<Option<String>, DecodeError> {
+    if get_flag(buf, field)? {
+        get_str(buf, field).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn get_opt_f64(buf: &mut Bytes, field: &'static str) -> Result<Option<f64>, DecodeError> {
+    if get_flag(buf, field)? {
+        get_f64(buf, field).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn get_decimal(buf: &mut Bytes, field: &'static str) -> Result<Decimal, DecodeError> {
+    ensure(buf, 16, field)?;
+    let mut raw = [0u8; 16];
+    buf.copy_to_slice(&mut raw);
+    Ok(Decimal::deserialize(raw))
+}
+
+pub fn get_opt_decimal(buf: &mut Bytes, field: &'static str) -> Result<Option<Decimal>, DecodeError> {
+    if get_flag(buf, field)? {
+        get_decimal(buf, field).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn get_uuid(buf: &mut Bytes, field: &'static str) -> Result<Uuid, DecodeError> {
+    ensure(buf, 16, field)?;
+    let mut raw = [0u8; 16];
+    buf.copy_to_slice(&mut raw);
+    Ok(Uuid::from_bytes(raw))
+}
+
+pub fn get_instrument(buf: &mut Bytes, field: &'static str) -> Result<InstrumentId, DecodeError> {
+    ensure(buf, 8, field)?;
+    Ok(InstrumentId::new(buf.get_u32_le(), buf.get_u32_le()))
+}
+
+pub fn get_timestamp(buf: &mut Bytes, field: &'static str) -> Result<DateTime<Utc>, DecodeError> {
+    let micros = get_i64(buf, field)?;
+    DateTime::<Utc>::from_timestamp_micros(micros).ok_or(DecodeError::InvalidTimestamp(micros))
+}
+
+// ========== Signal ==========
+
+impl WireMessage for Signal {
+    const MESSAGE_TYPE: MessageType = MessageType::Signal;
+
+    fn encode_payload(&self, buf: &mut BytesMut) -> Result<(), EncodeError> {
+        put_enum(buf, self.signal_type);
+        put_str(buf, &self.id);
+        put_instrument(buf, self.instrument);
+        put_str(buf, &self.symbol);
+        put_str(buf, &self.exchange);
+        match self.side {
+            Some(side) => {
+                buf.put_u8(1);
+                put_enum(buf, side);
+            }
+            None => buf.put_u8(0),
+        }
+        put_opt_f64(buf, self.price);
+        put_opt_f64(buf, self.quantity);
+        put_str(buf, &self.source);
+        buf.put_u8(self.priority);
+
+        // 按键排序，保证同一信号的编码结果确定
+        let metadata: BTreeMap<_, _> = self.metadata.iter().collect();
+        put_count(buf, "signal.metadata", metadata.len())?;
+        for (key, value) in metadata {
+            put_str(buf, key);
+            put_str(buf, value);
+        }
+
+        put_timestamp(buf, self.timestamp);
+
+        // 具体信号数据
         match &self.data {
-            SignalData::AdaptiveSpreadDeviation { 
-                exchange_id, 
-                symbol_id, 
-                spread_percentile, 
-                current_spread, 
-                threshold_percentile 
+            SignalData::AdaptiveSpreadDeviation {
+                exchange_id,
+                symbol_id,
+                spread_percentile,
+                current_spread,
+                threshold_percentile,
             } => {
+                put_enum(buf, SignalType::AdaptiveSpreadDeviation);
                 buf.put_u32_le(*exchange_id);
                 buf.put_u32_le(*symbol_id);
                 buf.put_f64_le(*spread_percentile);
                 buf.put_f64_le(*current_spread);
                 buf.put_f64_le(*threshold_percentile);
             }
-            SignalData::FixedSpreadDeviation { 
-                exchange_id, 
-                symbol_id, 
-                current_spread, 
-                fixed_threshold 
+            SignalData::FixedSpreadDeviation {
+                exchange_id,
+                symbol_id,
+                current_spread,
+                fixed_threshold,
             } => {
+                put_enum(buf, SignalType::FixedSpreadDeviation);
                 buf.put_u32_le(*exchange_id);
                 buf.put_u32_le(*symbol_id);
                 buf.put_f64_le(*current_spread);
                 buf.put_f64_le(*fixed_threshold);
             }
-            SignalData::FundingRateDirection { 
-                exchange_id, 
-                symbol_id, 
-                funding_rate, 
-                direction 
+            SignalData::FundingRateDirection {
+                exchange_id,
+                symbol_id,
+                funding_rate,
+                direction,
             } => {
+                put_enum(buf, SignalType::FundingRateDirection);
                 buf.put_u32_le(*exchange_id);
                 buf.put_u32_le(*symbol_id);
                 buf.put_f64_le(*funding_rate);
-                buf.put_u32_le(*direction as u32);
+                put_enum(buf, *direction);
             }
-            SignalData::RealTimeFundingRisk { 
-                exchange_id, 
-                symbol_id, 
-                risk_level, 
-                funding_rate, 
-                position_cost 
+            SignalData::RealTimeFundingRisk {
+                exchange_id,
+                symbol_id,
+                risk_level,
+                funding_rate,
+                position_cost,
             } => {
+                put_enum(buf, SignalType::RealTimeFundingRisk);
                 buf.put_u32_le(*exchange_id);
                 buf.put_u32_le(*symbol_id);
-                buf.put_u32_le(*risk_level as u32);
+                put_enum(buf, *risk_level);
                 buf.put_f64_le(*funding_rate);
                 buf.put_f64_le(*position_cost);
             }
-            SignalData::OrderResponse { 
-                order_id, 
-                exchange_id, 
-                symbol_id, 
-                status 
+            SignalData::OrderResponse {
+                order_id,
+                exchange_id,
+                symbol_id,
+                status,
             } => {
-                buf.put_u32_le(order_id.len() as u32);
-                buf.put_slice(order_id.as_bytes());
+                put_enum(buf, SignalType::OrderResponse);
+                put_str(buf, order_id);
                 buf.put_u32_le(*exchange_id);
                 buf.put_u32_le(*symbol_id);
-                buf.put_u32_le(*status as u32);
+                put_enum(buf, *status);
             }
-            _ => {
-                // 其他类型暂不处理二进制序列化
-            }
-        }
-        
-        buf.freeze()
-    }
-    
-    pub fn from_bytes(mut buf: Bytes) -> Result<Self, String> {
-        if buf.remaining() < 4 {
-            return Err("Buffer too small for signal type".to_string());
-        }
-        
-        let signal_type = buf.get_u32_le();
-        
-        // 读取基本字段
-        let id_len = buf.get_u32_le() as usize;
-        let id = String::from_utf8(buf.copy_to_bytes(id_len).to_vec())
-            .map_err(|e| e.to_string())?;
-            
-        let symbol_len = buf.get_u32_le() as usize;
-        let symbol = String::from_utf8(buf.copy_to_bytes(symbol_len).to_vec())
-            .map_err(|e| e.to_string())?;
-            
-        let exchange_len = buf.get_u32_le() as usize;
-        let exchange = String::from_utf8(buf.copy_to_bytes(exchange_len).to_vec())
-            .map_err(|e| e.to_string())?;
-            
-        let timestamp_millis = buf.get_i64_le();
-        let timestamp = DateTime::<Utc>::from_timestamp_millis(timestamp_millis)
-            .ok_or("Invalid timestamp")?;
-        
-        // 根据信号类型读取具体数据
-        let (signal_type_enum, data) = match signal_type {
-            0 => {
-                let exchange_id = buf.get_u32_le();
-                let symbol_id = buf.get_u32_le();
-                let spread_percentile = buf.get_f64_le();
-                let current_spread = buf.get_f64_le();
-                let threshold_percentile = buf.get_f64_le();
-                
-                (SignalType::AdaptiveSpreadDeviation, SignalData::AdaptiveSpreadDeviation {
-                    exchange_id,
-                    symbol_id,
-                    spread_percentile,
-                    current_spread,
-                    threshold_percentile,
-                })
-            }
-            1 => {
-                let exchange_id = buf.get_u32_le();
-                let symbol_id = buf.get_u32_le();
-                let current_spread = buf.get_f64_le();
-                let fixed_threshold = buf.get_f64_le();
-                
-                (SignalType::FixedSpreadDeviation, SignalData::FixedSpreadDeviation {
-                    exchange_id,
-                    symbol_id,
-                    current_spread,
-                    fixed_threshold,
-                })
+            SignalData::Arbitrage {
+                arbitrage_id,
+                pair,
+                expected_profit,
+            } => {
+                put_enum(buf, SignalType::Arbitrage);
+                put_str(buf, arbitrage_id);
+                put_str(buf, &pair.0);
+                put_str(buf, &pair.1);
+                buf.put_f64_le(*expected_profit);
             }
-            2 => {
-                let exchange_id = buf.get_u32_le();
-                let symbol_id = buf.get_u32_le();
-                let funding_rate = buf.get_f64_le();
-                let direction = match buf.get_u32_le() {
-                    0 => FundingDirection::Positive,
-                    1 => FundingDirection::Negative,
-                    _ => FundingDirection::Neutral,
-                };
-                
-                (SignalType::FundingRateDirection, SignalData::FundingRateDirection {
-                    exchange_id,
-                    symbol_id,
-                    funding_rate,
-                    direction,
-                })
+            SignalData::Market { market_data } => {
+                put_enum(buf, SignalType::Market);
+                put_str(buf, market_data);
             }
-            3 => {
-                let exchange_id = buf.get_u32_le();
-                let symbol_id = buf.get_u32_le();
-                let risk_level = match buf.get_u32_le() {
-                    0 => RiskLevel::Low,
-                    1 => RiskLevel::Medium,
-                    2 => RiskLevel::High,
-                    _ => RiskLevel::Critical,
-                };
-                let funding_rate = buf.get_f64_le();
-                let position_cost = buf.get_f64_le();
-                
-                (SignalType::RealTimeFundingRisk, SignalData::RealTimeFundingRisk {
-                    exchange_id,
-                    symbol_id,
-                    risk_level,
-                    funding_rate,
-                    position_cost,
-                })
+            SignalData::Hedge {
+                hedge_id,
+                target_position,
+            } => {
+                put_enum(buf, SignalType::Hedge);
+                put_str(buf, hedge_id);
+                buf.put_f64_le(*target_position);
             }
-            4 => {
-                let order_id_len = buf.get_u32_le() as usize;
-                let order_id = String::from_utf8(buf.copy_to_bytes(order_id_len).to_vec())
-                    .map_err(|e| e.to_string())?;
-                let exchange_id = buf.get_u32_le();
-                let symbol_id = buf.get_u32_le();
-                let status = match buf.get_u32_le() {
-                    0 => OrderResponseStatus::Filled,
-                    1 => OrderResponseStatus::PartiallyFilled,
-                    2 => OrderResponseStatus::Rejected,
-                    _ => OrderResponseStatus::Cancelled,
-                };
-                
-                (SignalType::OrderResponse, SignalData::OrderResponse {
-                    order_id,
-                    exchange_id,
-                    symbol_id,
-                    status,
-                })
+            SignalData::RiskControlInit { protobuf_data } => {
+                put_enum(buf, SignalType::RiskControlInit);
+                put_bytes(buf, protobuf_data);
             }
-            _ => return Err(format!("Unknown signal type: {}", signal_type)),
+        }
+        Ok(())
+    }
+
+    fn decode_payload(buf: &mut Bytes) -> Result<Self, DecodeError> {
+        let signal_type = get_enum::<SignalType>(buf)?;
+        let id = get_str(buf, "signal.id")?;
+        let instrument = get_instrument(buf, "signal.instrument")?;
+        let symbol = get_str(buf, "signal.symbol")?;
+        let exchange = get_str(buf, "signal.exchange")?;
+        let side = if get_flag(buf, "signal.side")? {
+            Some(get_enum::<Side>(buf)?)
+        } else {
+            None
         };
-        
-        let mut signal = Signal::new(signal_type_enum, data);
-        signal.id = id;
-        signal.symbol = symbol;
-        signal.exchange = exchange;
-        signal.timestamp = timestamp;
-        
-        Ok(signal)
+        let price = get_opt_f64(buf, "signal.price")?;
+        let quantity = get_opt_f64(buf, "signal.quantity")?;
+        let source = get_str(buf, "signal.source")?;
+        let priority = get_u8(buf, "signal.priority")?;
+
+        let count = get_u16(buf, "signal.metadata")? as usize;
+        let mut metadata = std::collections::HashMap::with_capacity(count);
+        for _ in 0..count {
+            let key = get_str(buf, "signal.metadata")?;
+            let value = get_str(buf, "signal.metadata")?;
+            metadata.insert(key, value);
+        }
+
+        let timestamp = get_timestamp(buf, "signal.timestamp")?;
+
+        // 根据数据类型读取具体信号数据
+        let data = match get_enum::<SignalType>(buf)? {
+            SignalType::AdaptiveSpreadDeviation => SignalData::AdaptiveSpreadDeviation {
+                exchange_id: get_u32(buf, "exchange_id")?,
+                symbol_id: get_u32(buf, "symbol_id")?,
+                spread_percentile: get_f64(buf, "spread_percentile")?,
+                current_spread: get_f64(buf, "current_spread")?,
+                threshold_percentile: get_f64(buf, "threshold_percentile")?,
+            },
+            SignalType::FixedSpreadDeviation => SignalData::FixedSpreadDeviation {
+                exchange_id: get_u32(buf, "exchange_id")?,
+                symbol_id: get_u32(buf, "symbol_id")?,
+                current_spread: get_f64(buf, "current_spread")?,
+                fixed_threshold: get_f64(buf, "fixed_threshold")?,
+            },
+            SignalType::FundingRateDirection => SignalData::FundingRateDirection {
+                exchange_id: get_u32(buf, "exchange_id")?,
+                symbol_id: get_u32(buf, "symbol_id")?,
+                funding_rate: get_f64(buf, "funding_rate")?,
+                direction: get_enum(buf)?,
+            },
+            SignalType::RealTimeFundingRisk => SignalData::RealTimeFundingRisk {
+                exchange_id: get_u32(buf, "exchange_id")?,
+                symbol_id: get_u32(buf, "symbol_id")?,
+                risk_level: get_enum(buf)?,
+                funding_rate: get_f64(buf, "funding_rate")?,
+                position_cost: get_f64(buf, "position_cost")?,
+            },
+            SignalType::OrderResponse => SignalData::OrderResponse {
+                order_id: get_str(buf, "order_id")?,
+                exchange_id: get_u32(buf, "exchange_id")?,
+                symbol_id: get_u32(buf, "symbol_id")?,
+                status: get_enum(buf)?,
+            },
+            SignalType::Arbitrage => SignalData::Arbitrage {
+                arbitrage_id: get_str(buf, "arbitrage_id")?,
+                pair: (get_str(buf, "pair")?, get_str(buf, "pair")?),
+                expected_profit: get_f64(buf, "expected_profit")?,
+            },
+            SignalType::Market => SignalData::Market {
+                market_data: get_str(buf, "market_data")?,
+            },
+            SignalType::Hedge => SignalData::Hedge {
+                hedge_id: get_str(buf, "hedge_id")?,
+                target_position: get_f64(buf, "target_position")?,
+            },
+            SignalType::RiskControlInit => SignalData::RiskControlInit {
+                protobuf_data: get_bytes(buf, "protobuf_data")?.to_vec(),
+            },
+        };
+
+        Ok(Self {
+            id,
+            signal_type,
+            instrument,
+            symbol,
+            exchange,
+            side,
+            price,
+            quantity,
+            source,
+            priority,
+            metadata,
+            timestamp,
+            data,
+        })
     }
 }
 
-impl EventMessage {
-    pub fn to_bytes(&self) -> Bytes {
-        let mut buf = BytesMut::with_capacity(256);
-        
+impl Signal {
+    /// 编码为完整帧
+    pub fn to_bytes(&self) -> Result<Bytes, EncodeError> {
+        encode_frame(self)
+    }
+
+    /// 解码完整帧，允许尾部补零（定长IPC缓冲区）
+    pub fn from_bytes(buf: Bytes) -> Result<Self, DecodeError> {
+        decode_frame(&buf)
+    }
+}
+
+// ========== EventMessage ==========
+
+impl WireMessage for EventMessage {
+    const MESSAGE_TYPE: MessageType = MessageType::Event;
+
+    fn encode_payload(&self, buf: &mut BytesMut) -> Result<(), EncodeError> {
         // 写入序列号和时间戳
         buf.put_u64_le(self.sequence_id);
-        buf.put_i64_le(self.timestamp.timestamp_millis());
-        
+        put_timestamp(buf, self.timestamp);
+
         // 根据事件类型写入具体数据
         match &self.event {
             TradingEvent::OpenPosition(e) => {
-                buf.put_u32_le(EventType::OpenPosition as u32);
-                buf.put_u32_le(e.symbol.0);  // Symbol是包装类型
-                buf.put_u32_le(e.exchange as u32);
-                buf.put_u32_le(e.side as u32);
-                buf.put_f64_le(e.quantity);
-                if let Some(price) = e.price {
-                    buf.put_u8(1);
-                    buf.put_f64_le(price);
-                } else {
-                    buf.put_u8(0);
-                }
+                put_enum(buf, EventType::OpenPosition);
+                put_position_event(buf, e.instrument, e.side, e.quantity, e.order_type, e.price);
+                put_enum(buf, e.trigger_type);
+                put_str(buf, &e.reason);
+                put_timestamp(buf, e.timestamp);
             }
             TradingEvent::ClosePosition(e) => {
-                buf.put_u32_le(EventType::ClosePosition as u32);
-                buf.put_u32_le(e.symbol.0);
-                buf.put_u32_le(e.exchange as u32);
-                buf.put_u32_le(e.side as u32);
-                buf.put_f64_le(e.quantity);
-                if let Some(price) = e.price {
-                    buf.put_u8(1);
-                    buf.put_f64_le(price);
-                } else {
-                    buf.put_u8(0);
-                }
+                put_enum(buf, EventType::ClosePosition);
+                put_position_event(buf, e.instrument, e.side, e.quantity, e.order_type, e.price);
+                put_enum(buf, e.trigger_type);
+                put_str(buf, &e.reason);
+                put_timestamp(buf, e.timestamp);
             }
             TradingEvent::HedgePosition(e) => {
-                buf.put_u32_le(EventType::HedgePosition as u32);
-                buf.put_u32_le(e.symbol.0);
-                buf.put_u32_le(e.primary_exchange as u32);
-                buf.put_u32_le(e.hedge_exchange as u32);
-                buf.put_u32_le(e.side as u32);
+                put_enum(buf, EventType::HedgePosition);
+                put_instrument(buf, e.instrument);
+                put_instrument(buf, e.hedge_instrument);
+                put_enum(buf, e.side);
                 buf.put_f64_le(e.quantity);
+                put_enum(buf, e.trigger_type);
+                put_str(buf, &e.reason);
+                put_timestamp(buf, e.timestamp);
             }
             TradingEvent::CancelOrder(e) => {
-                buf.put_u32_le(EventType::CancelOrder as u32);
-                buf.put_u32_le(e.order_id.len() as u32);
-                buf.put_slice(e.order_id.as_bytes());
-                buf.put_u32_le(e.reason.len() as u32);
-                buf.put_slice(e.reason.as_bytes());
+                put_enum(buf, EventType::CancelOrder);
+                put_str(buf, &e.order_id);
+                put_instrument(buf, e.instrument);
+                put_str(buf, &e.reason);
+                put_timestamp(buf, e.timestamp);
             }
             TradingEvent::ModifyOrder(e) => {
-                buf.put_u32_le(EventType::ModifyOrder as u32);
-                buf.put_u32_le(e.order_id.len() as u32);
-                buf.put_slice(e.order_id.as_bytes());
-                if let Some(new_price) = e.new_price {
-                    buf.put_u8(1);
-                    buf.put_f64_le(new_price);
-                } else {
-                    buf.put_u8(0);
-                }
-                if let Some(new_quantity) = e.new_quantity {
-                    buf.put_u8(1);
-                    buf.put_f64_le(new_quantity);
-                } else {
-                    buf.put_u8(0);
-                }
+                put_enum(buf, EventType::ModifyOrder);
+                put_str(buf, &e.order_id);
+                put_instrument(buf, e.instrument);
+                put_opt_f64(buf, e.new_price);
+                put_opt_f64(buf, e.new_quantity);
+                put_str(buf, &e.reason);
+                put_timestamp(buf, e.timestamp);
+            }
+        }
+        Ok(())
+    }
+
+    fn decode_payload(buf: &mut Bytes) -> Result<Self, DecodeError> {
+        let sequence_id = get_u64(buf, "event.sequence_id")?;
+        let timestamp = get_timestamp(buf, "event.timestamp")?;
+
+        let event = match get_enum::<EventType>(buf)? {
+            EventType::OpenPosition => TradingEvent::OpenPosition(OpenPositionEvent {
+                instrument: get_instrument(buf, "instrument")?,
+                side: get_enum(buf)?,
+                quantity: get_f64(buf, "quantity")?,
+                order_type: get_enum(buf)?,
+                price: get_opt_f64(buf, "price")?,
+                trigger_type: get_enum(buf)?,
+                reason: get_str(buf, "reason")?,
+                timestamp: get_timestamp(buf, "timestamp")?,
+            }),
+            EventType::ClosePosition => TradingEvent::ClosePosition(ClosePositionEvent {
+                instrument: get_instrument(buf, "instrument")?,
+                side: get_enum(buf)?,
+                quantity: get_f64(buf, "quantity")?,
+                order_type: get_enum(buf)?,
+                price: get_opt_f64(buf, "price")?,
+                trigger_type: get_enum(buf)?,
+                reason: get_str(buf, "reason")?,
+                timestamp: get_timestamp(buf, "timestamp")?,
+            }),
+            EventType::HedgePosition => TradingEvent::HedgePosition(HedgePositionEvent {
+                instrument: get_instrument(buf, "instrument")?,
+                hedge_instrument: get_instrument(buf, "hedge_instrument")?,
+                side: get_enum(buf)?,
+                quantity: get_f64(buf, "quantity")?,
+                trigger_type: get_enum(buf)?,
+                reason: get_str(buf, "reason")?,
+                timestamp: get_timestamp(buf, "timestamp")?,
+            }),
+            EventType::CancelOrder => TradingEvent::CancelOrder(CancelOrderEvent {
+                order_id: get_str(buf, "order_id")?,
+                instrument: get_instrument(buf, "instrument")?,
+                reason: get_str(buf, "reason")?,
+                timestamp: get_timestamp(buf, "timestamp")?,
+            }),
+            EventType::ModifyOrder => TradingEvent::ModifyOrder(ModifyOrderEvent {
+                order_id: get_str(buf, "order_id")?,
+                instrument: get_instrument(buf, "instrument")?,
+                new_price: get_opt_f64(buf, "new_price")?,
+                new_quantity: get_opt_f64(buf, "new_quantity")?,
+                reason: get_str(buf, "reason")?,
+                timestamp: get_timestamp(buf, "timestamp")?,
+            }),
+        };
+
+        Ok(Self {
+            event,
+            sequence_id,
+            timestamp,
+        })
+    }
+}
+
+/// 开仓/平仓事件共用的字段布局
+fn put_position_event(
+    buf: &mut BytesMut,
+    instrument: InstrumentId,
+    side: Side,
+    quantity: f64,
+    order_type: OrderType,
+    price: Option<f64>,
+) {
+    put_instrument(buf, instrument);
+    put_enum(buf, side);
+    buf.put_f64_le(quantity);
+    put_enum(buf, order_type);
+    put_opt_f64(buf, price);
+}
+
+impl EventMessage {
+    /// 编码为完整帧
+    pub fn to_bytes(&self) -> Result<Bytes, EncodeError> {
+        encode_frame(self)
+    }
+
+    /// 解码完整帧，允许尾部补零（定长IPC缓冲区）
+    pub fn from_bytes(buf: Bytes) -> Result<Self, DecodeError> {
+        decode_frame(&buf)
+    }
+}
+
+// ========== ExecutionReport ==========
+
+impl WireMessage for ExecutionReport {
+    const MESSAGE_TYPE: MessageType = MessageType::ExecutionReport;
+
+    fn encode_payload(&self, buf: &mut BytesMut) -> Result<(), EncodeError> {
+        put_str(buf, &self.order_id);
+        put_str(buf, &self.client_order_id);
+        put_instrument(buf, self.instrument);
+        put_enum(buf, self.side);
+        put_enum(buf, self.order_type);
+        buf.put_f64_le(self.price);
+        buf.put_f64_le(self.quantity);
+        buf.put_f64_le(self.filled_quantity);
+        put_enum(buf, self.status);
+        put_enum(buf, self.execution_type);
+        put_timestamp(buf, self.timestamp);
+        Ok(())
+    }
+
+    fn decode_payload(buf: &mut Bytes) -> Result<Self, DecodeError> {
+        Ok(Self {
+            order_id: get_str(buf, "report.order_id")?,
+            client_order_id: get_str(buf, "report.client_order_id")?,
+            instrument: get_instrument(buf, "report.instrument")?,
+            side: get_enum(buf)?,
+            order_type: get_enum(buf)?,
+            price: get_f64(buf, "report.price")?,
+            quantity: get_f64(buf, "report.quantity")?,
+            filled_quantity: get_f64(buf, "report.filled_quantity")?,
+            status: get_enum(buf)?,
+            execution_type: get_enum(buf)?,
+            timestamp: get_timestamp(buf, "report.timestamp")?,
+        })
+    }
+}
+
+impl ExecutionReport {
+    /// 编码为完整帧
+    pub fn to_bytes(&self) -> Result<Bytes, EncodeError> {
+        encode_frame(self)
+    }
+
+    /// 解码完整帧，允许尾部补零（定长IPC缓冲区）
+    pub fn from_bytes(buf: Bytes) -> Result<Self, DecodeError> {
+        decode_frame(&buf)
+    }
+}
+
+// ========== 风控消息（protobuf消息体） ==========
+
+macro_rules! protobuf_wire_message {
+    ($message:ident) => {
+        impl WireMessage for $message {
+            const MESSAGE_TYPE: MessageType = MessageType::$message;
+
+            fn encode_payload(&self, buf: &mut BytesMut) -> Result<(), EncodeError> {
+                prost::Message::encode(self, buf).expect("BytesMut grows on demand");
+                Ok(())
+            }
+
+            fn decode_payload(buf: &mut Bytes) -> Result<Self, DecodeError> {
+                <Self as prost::Message>::decode(buf.split_off(0))
+                    .map_err(|_| DecodeError::InvalidProtobuf(stringify!($message)))
             }
         }
-        
-        buf.freeze()
+    };
+}
+
+protobuf_wire_message!(RiskInitResponse);
+protobuf_wire_message!(RiskStateSummary);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamp() -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp_micros(1_700_000_000_123_456).unwrap()
     }
-}
\ No newline at end of file
+
+    /// 模拟定长IPC缓冲区的尾部补零后解码
+    fn round_trip<M: WireMessage>(message: &M) -> M {
+        let frame: [u8; 4096] = encode_to_array(message).unwrap();
+        decode_frame(&frame).unwrap()
+    }
+
+    fn event_round_trip(event: TradingEvent) -> EventMessage {
+        let message = EventMessage {
+            event,
+            sequence_id: 42,
+            timestamp: timestamp(),
+        };
+
+        let decoded = round_trip(&message);
+        assert_eq!(decoded.sequence_id, message.sequence_id);
+        assert_eq!(decoded.timestamp, message.timestamp);
+        decoded
+    }
+
+    #[test]
+    fn test_signal_round_trip_covers_every_data_variant() {
+        let variants = vec![
+            SignalData::AdaptiveSpreadDeviation {
+                exchange_id: 1,
+                symbol_id: 2,
+                spread_percentile: 0.95,
+                current_spread: 0.0012,
+                threshold_percentile: 0.9,
+            },
+            SignalData::FixedSpreadDeviation {
+                exchange_id: 1,
+                symbol_id: 2,
+                current_spread: 0.002,
+                fixed_threshold: 0.001,
+            },
+            SignalData::FundingRateDirection {
+                exchange_id: 3,
+                symbol_id: 4,
+                funding_rate: -0.0001,
+                direction: FundingDirection::Negative,
+            },
+            SignalData::RealTimeFundingRisk {
+                exchange_id: 3,
+                symbol_id: 4,
+                risk_level: RiskLevel::Critical,
+                funding_rate: 0.003,
+                position_cost: 12.5,
+            },
+            SignalData::OrderResponse {
+                order_id: "ORD_1".to_string(),
+                exchange_id: 1,
+                symbol_id: 2,
+                status: OrderResponseStatus::PartiallyFilled,
+            },
+            SignalData::Arbitrage {
+                arbitrage_id: "ARB_1".to_string(),
+                pair: ("binance".to_string(), "okex".to_string()),
+                expected_profit: 3.2,
+            },
+            SignalData::Market { market_data: "{\"bid\":1}".to_string() },
+            SignalData::Hedge { hedge_id: "HEDGE_1".to_string(), target_position: -2.0 },
+            SignalData::RiskControlInit { protobuf_data: vec![0x08, 0x96, 0x01, 0x00] },
+        ];
+
+        for data in variants {
+            let signal_type = match &data {
+                SignalData::AdaptiveSpreadDeviation { .. } => SignalType::AdaptiveSpreadDeviation,
+                SignalData::FixedSpreadDeviation { .. } => SignalType::FixedSpreadDeviation,
+                SignalData::FundingRateDirection { .. } => SignalType::FundingRateDirection,
+                SignalData::RealTimeFundingRisk { .. } => SignalType::RealTimeFundingRisk,
+                SignalData::OrderResponse { .. } => SignalType::OrderResponse,
+                SignalData::Arbitrage { .. } => SignalType::Arbitrage,
+                SignalData::Market { .. } => SignalType::Market,
+                SignalData::Hedge { .. } => SignalType::Hedge,
+                SignalData::RiskControlInit { .. } => SignalType::RiskControlInit,
+            };
+            let mut signal = Signal::new(signal_type, data);
+            signal.instrument = InstrumentId::new(2, 5);
+            signal.symbol = "BTCUSDT".to_string();
+            signal.exchange = "binance_futures".to_string();
+            signal.side = Some(Side::Sell);
+            signal.price = Some(65000.5);
+            signal.source = "test".to_string();
+            signal.priority = 7;
+            signal.metadata.insert("market_type".to_string(), "futures".to_string());
+            signal.metadata.insert("account".to_string(), "main".to_string());
+            signal.timestamp = timestamp();
+
+            let decoded = Signal::from_bytes(signal.to_bytes().unwrap()).unwrap();
+            assert_eq!(decoded.id, signal.id);
+            assert_eq!(decoded.signal_type, signal.signal_type);
+            assert_eq!(decoded.instrument, signal.instrument);
+            assert_eq!(decoded.symbol, signal.symbol);
+            assert_eq!(decoded.side, signal.side);
+            assert_eq!(decoded.price, signal.price);
+            assert_eq!(decoded.quantity, None);
+            assert_eq!(decoded.priority, 7);
+            assert_eq!(decoded.metadata, signal.metadata);
+            assert_eq!(decoded.timestamp, signal.timestamp);
+            // SignalData 没有实现 PartialEq，按 Debug 输出比较
+            assert_eq!(format!("{:?}", decoded.data), format!("{:?}", signal.data));
+        }
+    }
+
+    #[test]
+    fn test_event_message_round_trip() {
+        let decoded = event_round_trip(TradingEvent::ClosePosition(ClosePositionEvent {
+            instrument: InstrumentId::new(6, 7),
+            side: Side::Sell,
+            quantity: 1.5,
+            order_type: OrderType::Limit,
+            price: Some(42000.5),
+            trigger_type: TriggerType::MTCloseTrigger,
+            reason: "spread closed".to_string(),
+            timestamp: timestamp(),
+        }));
+        let TradingEvent::ClosePosition(e) = decoded.event else {
+            panic!("expected close event");
+        };
+        assert_eq!(e.instrument, InstrumentId::new(6, 7));
+        assert_eq!(e.side, Side::Sell);
+        assert_eq!(e.quantity, 1.5);
+        assert_eq!(e.order_type, OrderType::Limit);
+        assert_eq!(e.price, Some(42000.5));
+        assert_eq!(e.trigger_type, TriggerType::MTCloseTrigger);
+        assert_eq!(e.reason, "spread closed");
+        assert_eq!(e.timestamp, timestamp());
+
+        let decoded = event_round_trip(TradingEvent::HedgePosition(HedgePositionEvent {
+            instrument: InstrumentId::new(1, 3),
+            hedge_instrument: InstrumentId::new(4, 3),
+            side: Side::Buy,
+            quantity: 2.0,
+            trigger_type: TriggerType::HedgeTrigger,
+            reason: "hedge".to_string(),
+            timestamp: timestamp(),
+        }));
+        let TradingEvent::HedgePosition(e) = decoded.event else {
+            panic!("expected hedge event");
+        };
+        assert_eq!(e.instrument, InstrumentId::new(1, 3));
+        assert_eq!(e.hedge_instrument, InstrumentId::new(4, 3));
+        assert_eq!(e.quantity, 2.0);
+
+        let decoded = event_round_trip(TradingEvent::ModifyOrder(ModifyOrderEvent {
+            order_id: "ORD_1".to_string(),
+            instrument: InstrumentId::new(8, 3),
+            new_price: None,
+            new_quantity: Some(0.5),
+            reason: "requote".to_string(),
+            timestamp: timestamp(),
+        }));
+        let TradingEvent::ModifyOrder(e) = decoded.event else {
+            panic!("expected modify event");
+        };
+        assert_eq!(e.order_id, "ORD_1");
+        assert_eq!(e.instrument, InstrumentId::new(8, 3));
+        assert_eq!(e.new_price, None);
+        assert_eq!(e.new_quantity, Some(0.5));
+        assert_eq!(e.reason, "requote");
+    }
+
+    #[test]
+    fn test_execution_report_round_trip() {
+        let report = ExecutionReport {
+            order_id: "123456".to_string(),
+            client_order_id: "ORD_1".to_string(),
+            instrument: InstrumentId::new(4, 9),
+            side: Side::Buy,
+            order_type: OrderType::PostOnly,
+            price: 100.25,
+            quantity: 3.0,
+            filled_quantity: 1.0,
+            status: OrderStatus::PartiallyFilled,
+            execution_type: ExecutionType::Trade,
+            timestamp: timestamp(),
+        };
+
+        let decoded = round_trip(&report);
+        assert_eq!(decoded.order_id, report.order_id);
+        assert_eq!(decoded.client_order_id, report.client_order_id);
+        assert_eq!(decoded.instrument, InstrumentId::new(4, 9));
+        assert_eq!(decoded.order_type, OrderType::PostOnly);
+        assert_eq!(decoded.filled_quantity, 1.0);
+        assert_eq!(decoded.status, OrderStatus::PartiallyFilled);
+        assert_eq!(decoded.execution_type, ExecutionType::Trade);
+        assert_eq!(decoded.timestamp, report.timestamp);
+    }
+
+    #[test]
+    fn test_decode_errors_are_typed() {
+        let message = EventMessage {
+            event: TradingEvent::CancelOrder(CancelOrderEvent {
+                order_id: "ORD_1".to_string(),
+                instrument: InstrumentId::new(1, 1),
+                reason: "stale".to_string(),
+                timestamp: timestamp(),
+            }),
+            sequence_id: 1,
+            timestamp: timestamp(),
+        };
+        let frame = message.to_bytes().unwrap().to_vec();
+
+        let mut bad_magic = frame.clone();
+        bad_magic[0] ^= 0xFF;
+        assert!(matches!(EventMessage::from_bytes(bad_magic.into()), Err(DecodeError::BadMagic(_))));
+
+        let mut stale = frame.clone();
+        stale[2] = WIRE_VERSION + 1;
+        assert_eq!(
+            EventMessage::from_bytes(stale.into()).unwrap_err(),
+            DecodeError::UnsupportedVersion(WIRE_VERSION + 1)
+        );
+
+        let mut corrupted = frame.clone();
+        *corrupted.last_mut().unwrap() ^= 0x01;
+        assert!(matches!(
+            EventMessage::from_bytes(corrupted.into()),
+            Err(DecodeError::ChecksumMismatch { .. })
+        ));
+
+        let truncated = frame[..frame.len() - 1].to_vec();
+        assert!(matches!(
+            EventMessage::from_bytes(truncated.into()),
+            Err(DecodeError::Truncated { field: "payload", .. })
+        ));
+
+        assert_eq!(
+            Signal::from_bytes(frame.clone().into()).unwrap_err(),
+            DecodeError::UnexpectedMessageType {
+                expected: MessageType::Signal,
+                found: MessageType::Event,
+            }
+        );
+        assert_eq!(peek_message_type(&frame), Ok(MessageType::Event));
+
+        // 全零缓冲区（无消息）不是合法帧
+        assert!(EventMessage::from_bytes(Bytes::from_static(&[0u8; 64])).is_err());
+    }
+
+    #[test]
+    fn test_risk_messages_round_trip() {
+        let summary = RiskStateSummary {
+            total_exposure: 12_500.5,
+            risk_level: "Medium".to_string(),
+            active_positions: 2,
+            available_capital: 87_499.5,
+            daily_pnl: -120.0,
+            restricted_symbols: vec!["ETHUSDT".to_string()],
+            global_restricted: false,
+            timestamp: 1_700_000_000_123,
+        };
+        assert_eq!(round_trip(&summary), summary);
+
+        let response = RiskInitResponse {
+            success: false,
+            message: "Failed to decode protobuf".to_string(),
+            state_summary: Some(summary),
+            request_id: "init-1".to_string(),
+        };
+        assert_eq!(round_trip(&response), response);
+
+        let frame = encode_frame(&response).unwrap();
+        assert_eq!(peek_message_type(&frame), Ok(MessageType::RiskInitResponse));
+    }
+
+    #[test]
+    fn test_encode_to_array_rejects_oversized_frame() {
+        let mut signal = Signal::new(SignalType::Market, SignalData::Market { market_data: "x".repeat(2000) });
+        signal.timestamp = timestamp();
+
+        let result: Result<[u8; 1024], _> = encode_to_array(&signal);
+        assert!(matches!(result, Err(EncodeError::FrameTooLarge { capacity: 1024, .. })));
+    }
+
+    #[test]
+    fn test_oversized_count_is_an_error() {
+        let mut signal = Signal::new(SignalType::Market, SignalData::Market { market_data: String::new() });
+        signal.metadata = (0..=u16::MAX as usize).map(|i| (i.to_string(), String::new())).collect();
+
+        assert_eq!(
+            signal.to_bytes().unwrap_err(),
+            EncodeError::TooManyEntries { field: "signal.metadata", len: 65536, max: 65535 }
+        );
+    }
+
+    #[test]
+    fn test_checksum_matches_crc32() {
+        assert_eq!(checksum(b"123456789"), 0xCBF4_3926);
+    }
+}
@@ -0,0 +1,214 @@
+//! 交易引擎执行命令/结果的消息体，使用 `binary` 的公共帧
+//!
+//! 帧头与其他消息相同（魔数、线格式版本、消息类型、消息体长度、CRC-32），
+//! iceoryx2 传输时补零到 `IPC_FRAME_SIZE`。
+
+use crate::binary::{
+    decode_frame, encode_to_array, get_bool, get_enum, get_i64, get_opt_decimal, get_opt_str,
+    get_instrument, get_str, get_u16, get_u8, get_uuid, get_decimal, put_bool, put_count, put_decimal,
+    put_enum, put_instrument, put_opt_decimal, put_opt_str, put_str, put_uuid, MessageType, WireMessage,
+};
+use crate::execution::{
+    CommandAction, ExecutionCommand, ExecutionResult, OrderResponse, OrderSide, OrderStatus,
+    OrderType, TimeInForce,
+};
+use crate::wire_enum;
+use bytes::{BufMut, Bytes, BytesMut};
+
+pub use crate::binary::{DecodeError, EncodeError, WIRE_VERSION};
+
+/// 与交易引擎交换的 iceoryx2 样本的定长大小
+pub const IPC_FRAME_SIZE: usize = 4096;
+
+pub type IpcFrame = [u8; IPC_FRAME_SIZE];
+
+pub fn encode_command(cmd: &ExecutionCommand) -> Result<IpcFrame, EncodeError> {
+    encode_to_array(cmd)
+}
+
+pub fn decode_command(frame: &[u8]) -> Result<ExecutionCommand, DecodeError> {
+    decode_frame(frame)
+}
+
+pub fn encode_result(result: &ExecutionResult) -> Result<IpcFrame, EncodeError> {
+    encode_to_array(result)
+}
+
+pub fn decode_result(frame: &[u8]) -> Result<ExecutionResult, DecodeError> {
+    decode_frame(frame)
+}
+
+impl WireMessage for ExecutionCommand {
+    const MESSAGE_TYPE: MessageType = MessageType::ExecutionCommand;
+
+    fn encode_payload(&self, buf: &mut BytesMut) -> Result<(), EncodeError> {
+        put_uuid(buf, self.id);
+        put_instrument(buf, self.instrument);
+        put_str(buf, &self.exchange);
+        put_str(buf, &self.market_type);
+        put_str(buf, &self.symbol);
+        put_enum(buf, self.side);
+        put_enum(buf, self.order_type);
+        put_decimal(buf, self.quantity);
+        put_opt_decimal(buf, self.price);
+        put_enum(buf, self.time_in_force);
+        put_opt_str(buf, self.client_order_id.as_deref());
+        put_bool(buf, self.reduce_only);
+        put_bool(buf, self.post_only);
+        put_action(buf, &self.action);
+        Ok(())
+    }
+
+    fn decode_payload(buf: &mut Bytes) -> Result<Self, DecodeError> {
+        Ok(ExecutionCommand {
+            id: get_uuid(buf, "command.id")?,
+            instrument: get_instrument(buf, "command.instrument")?,
+            exchange: get_str(buf, "command.exchange")?,
+            market_type: get_str(buf, "command.market_type")?,
+            symbol: get_str(buf, "command.symbol")?,
+            side: get_enum(buf)?,
+            order_type: get_enum(buf)?,
+            quantity: get_decimal(buf, "command.quantity")?,
+            price: get_opt_decimal(buf, "command.price")?,
+            time_in_force: get_enum(buf)?,
+            client_order_id: get_opt_str(buf, "command.client_order_id")?,
+            reduce_only: get_bool(buf, "command.reduce_only")?,
+            post_only: get_bool(buf, "command.post_only")?,
+            action: get_action(buf)?,
+        })
+    }
+}
+
+impl WireMessage for ExecutionResult {
+    const MESSAGE_TYPE: MessageType = MessageType::ExecutionResult;
+
+    fn encode_payload(&self, buf: &mut BytesMut) -> Result<(), EncodeError> {
+        put_uuid(buf, self.command_id);
+        put_bool(buf, self.success);
+        put_bool(buf, self.outcome_unknown);
+        put_count(buf, "result.responses", self.responses.len())?;
+        for response in &self.responses {
+            put_response(buf, response);
+        }
+        match &self.selected_response {
+            Some(response) => {
+                buf.put_u8(1);
+                put_response(buf, response);
+            }
+            None => buf.put_u8(0),
+        }
+        put_opt_str(buf, self.error.as_deref());
+        Ok(())
+    }
+
+    fn decode_payload(buf: &mut Bytes) -> Result<Self, DecodeError> {
+        let command_id = get_uuid(buf, "result.command_id")?;
+        let success = get_bool(buf, "result.success")?;
+        let outcome_unknown = get_bool(buf, "result.outcome_unknown")?;
+        let count = get_u16(buf, "result.responses")? as usize;
+        let mut responses = Vec::with_capacity(count);
+        for _ in 0..count {
+            responses.push(get_response(buf)?);
+        }
+        let selected_response = if get_bool(buf, "result.selected_response")? {
+            Some(get_response(buf)?)
+        } else {
+            None
+        };
+        let error = get_opt_str(buf, "result.error")?;
+
+        Ok(ExecutionResult {
+            command_id,
+            success,
+            outcome_unknown,
+            responses,
+            selected_response,
+            error,
+        })
+    }
+}
+
+fn put_response(buf: &mut BytesMut, response: &OrderResponse) {
+    put_str(buf, &response.order_id);
+    put_str(buf, &response.client_order_id);
+    put_str(buf, &response.symbol);
+    put_enum(buf, response.status);
+    put_decimal(buf, response.executed_qty);
+    put_opt_decimal(buf, response.executed_price);
+    buf.put_i64_le(response.timestamp);
+    put_opt_str(buf, response.error.as_deref());
+}
+
+fn get_response(buf: &mut Bytes) -> Result<OrderResponse, DecodeError> {
+    Ok(OrderResponse {
+        order_id: get_str(buf, "response.order_id")?,
+        client_order_id: get_str(buf, "response.client_order_id")?,
+        symbol: get_str(buf, "response.symbol")?,
+        status: get_enum(buf)?,
+        executed_qty: get_decimal(buf, "response.executed_qty")?,
+        executed_price: get_opt_decimal(buf, "response.executed_price")?,
+        timestamp: get_i64(buf, "response.timestamp")?,
+        error: get_opt_str(buf, "response.error")?,
+    })
+}
+
+fn put_action(buf: &mut BytesMut, action: &CommandAction) {
+    match action {
+        CommandAction::Place => buf.put_u8(0),
+        CommandAction::Cancel { orig_client_order_id } => {
+            buf.put_u8(1);
+            put_str(buf, orig_client_order_id);
+        }
+        CommandAction::CancelAll => buf.put_u8(2),
+        CommandAction::Amend { orig_client_order_id, new_price, new_quantity } => {
+            buf.put_u8(3);
+            put_str(buf, orig_client_order_id);
+            put_opt_decimal(buf, *new_price);
+            put_opt_decimal(buf, *new_quantity);
+        }
+    }
+}
+
+fn get_action(buf: &mut Bytes) -> Result<CommandAction, DecodeError> {
+    match get_u8(buf, "action")? {
+        0 => Ok(CommandAction::Place),
+        1 => Ok(CommandAction::Cancel {
+            orig_client_order_id: get_str(buf, "action.orig_client_order_id")?,
+        }),
+        2 => Ok(CommandAction::CancelAll),
+        3 => Ok(CommandAction::Amend {
+            orig_client_order_id: get_str(buf, "action.orig_client_order_id")?,
+            new_price: get_opt_decimal(buf, "action.new_price")?,
+            new_quantity: get_opt_decimal(buf, "action.new_quantity")?,
+        }),
+        value => Err(DecodeError::InvalidEnum { field: "action", value }),
+    }
+}
+
+wire_enum!(OrderSide, "side", {
+    OrderSide::Buy => 0,
+    OrderSide::Sell => 1,
+});
+
+wire_enum!(OrderType, "order_type", {
+    OrderType::Market => 0,
+    OrderType::Limit => 1,
+    OrderType::StopMarket => 2,
+    OrderType::StopLimit => 3,
+});
+
+wire_enum!(TimeInForce, "time_in_force", {
+    TimeInForce::GTC => 0,
+    TimeInForce::IOC => 1,
+    TimeInForce::FOK => 2,
+    TimeInForce::GTX => 3,
+});
+
+wire_enum!(OrderStatus, "status", {
+    OrderStatus::New => 0,
+    OrderStatus::PartiallyFilled => 1,
+    OrderStatus::Filled => 2,
+    OrderStatus::Canceled => 3,
+    OrderStatus::Rejected => 4,
+    OrderStatus::Expired => 5,
+});
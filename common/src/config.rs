@@ -3,12 +3,17 @@ use std::fs;
 use std::path::Path;
 use serde::{Deserialize, Serialize};
 use anyhow::{Result, Context};
+use rust_decimal::Decimal;
+
+use crate::instrument::{SymbolFilters, SymbolStatus};
 
 /// 交易所配置
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ExchangeConfig {
     pub id: u32,
     pub name: String,
+    /// 所属交易所，对应交易引擎配置中的交易所键
+    pub exchange: String,
     #[serde(rename = "type")]
     pub exchange_type: String,
     pub description: String,
@@ -21,6 +26,13 @@ pub struct SymbolConfig {
     pub id: u32,
     pub symbol: String,
     pub exchange_id: u32,
+    /// 基础币，未配置时为空
+    pub base_asset: String,
+    /// 计价币，未配置时为空
+    pub quote_asset: String,
+    pub status: SymbolStatus,
+    /// 下单过滤器，未配置的项不做限制
+    pub filters: SymbolFilters,
 }
 
 /// 市场配置管理器
@@ -87,33 +99,65 @@ impl MarketConfig {
     }
     
     /// 从CSV文件加载符号列表
+    ///
+    /// 按标题行定位列：`id,symbol` 必需，
+    /// `base,quote,status,tick_size,step_size,min_qty,min_notional,contract_value` 可选，
+    /// 缺失或为空的列取默认值（正常交易、无过滤器、合约面值1）
     fn load_symbols_csv(file_path: &str, exchange_id: u32) -> Result<Vec<SymbolConfig>> {
         let content = fs::read_to_string(file_path)
             .with_context(|| format!("Failed to read symbols file: {}", file_path))?;
         
+        Self::parse_symbols_csv(&content, exchange_id)
+            .with_context(|| format!("Failed to parse symbols file: {}", file_path))
+    }
+    
+    fn parse_symbols_csv(content: &str, exchange_id: u32) -> Result<Vec<SymbolConfig>> {
         let mut symbols = Vec::new();
         let mut lines = content.lines();
         
-        // 跳过标题行
-        lines.next();
+        let header: Vec<&str> = lines.next().unwrap_or_default().split(',').map(str::trim).collect();
+        let column = |name: &str| header.iter().position(|h| *h == name);
+        let id_col = column("id").unwrap_or(0);
+        let symbol_col = column("symbol").unwrap_or(1);
         
         for line in lines {
             if line.trim().is_empty() {
                 continue;
             }
             
-            let parts: Vec<&str> = line.split(',').collect();
-            if parts.len() >= 2 {
-                let id = parts[0].trim().parse::<u32>()
-                    .with_context(|| format!("Invalid symbol ID: {}", parts[0]))?;
-                let symbol = parts[1].trim().to_string();
-                
-                symbols.push(SymbolConfig {
-                    id,
-                    symbol,
-                    exchange_id,
-                });
+            let parts: Vec<&str> = line.split(',').map(str::trim).collect();
+            if parts.len() < 2 {
+                continue;
             }
+            
+            // 可选列，缺失时为空串
+            let field = |name: &str| column(name).and_then(|i| parts.get(i).copied()).unwrap_or("");
+            let decimal = |name: &str, default: Decimal| -> Result<Decimal> {
+                match field(name) {
+                    "" => Ok(default),
+                    value => value.parse::<Decimal>()
+                        .with_context(|| format!("Invalid {} for symbol {}: {}", name, parts[symbol_col], value)),
+                }
+            };
+            
+            let id = parts[id_col].parse::<u32>()
+                .with_context(|| format!("Invalid symbol ID: {}", parts[id_col]))?;
+            
+            symbols.push(SymbolConfig {
+                id,
+                symbol: parts[symbol_col].to_string(),
+                exchange_id,
+                base_asset: field("base").to_string(),
+                quote_asset: field("quote").to_string(),
+                status: field("status").parse()?,
+                filters: SymbolFilters {
+                    tick_size: decimal("tick_size", Decimal::ZERO)?,
+                    step_size: decimal("step_size", Decimal::ZERO)?,
+                    min_qty: decimal("min_qty", Decimal::ZERO)?,
+                    min_notional: decimal("min_notional", Decimal::ZERO)?,
+                    contract_value: decimal("contract_value", Decimal::ONE)?,
+                },
+            });
         }
         
         Ok(symbols)
@@ -190,7 +234,7 @@ mod tests {
         let config = MarketConfig::load("config").unwrap();
         
         // 验证交易所加载
-        assert_eq!(config.get_exchanges().len(), 6);
+        assert_eq!(config.get_exchanges().len(), 8);
         
         // 验证币安现货
         let binance_spot_id = config.get_exchange_id("binance_spot").unwrap();
@@ -204,4 +248,27 @@ mod tests {
         let btc_id = config.find_symbol_id(binance_spot_id, "BTCDOMUSDT");
         assert!(btc_id.is_some());
     }
+    
+    #[test]
+    fn test_parse_symbols_csv_columns() {
+        let content = "id,symbol,base,quote,status,tick_size,step_size,min_qty,min_notional,contract_value\n\
+                       1,BTCUSDT,BTC,USDT,trading,0.1,0.001,0.001,5,\n\
+                       2,LUNAUSDT,LUNA,USDT,delisted,,,,,\n";
+        let symbols = MarketConfig::parse_symbols_csv(content, 7).unwrap();
+        
+        assert_eq!(symbols.len(), 2);
+        assert_eq!(symbols[0].base_asset, "BTC");
+        assert_eq!(symbols[0].filters.tick_size, Decimal::new(1, 1));
+        assert_eq!(symbols[0].filters.min_notional, Decimal::from(5));
+        assert_eq!(symbols[0].filters.contract_value, Decimal::ONE);
+        assert_eq!(symbols[1].status, SymbolStatus::Delisted);
+        assert_eq!(symbols[1].filters, SymbolFilters::default());
+        
+        // 只有 id,symbol 两列的旧格式仍可加载
+        let symbols = MarketConfig::parse_symbols_csv("id,symbol\n3,ETHUSDT\n", 7).unwrap();
+        assert_eq!(symbols[0].symbol, "ETHUSDT");
+        assert_eq!(symbols[0].status, SymbolStatus::Trading);
+        
+        assert!(MarketConfig::parse_symbols_csv("id,symbol,status\n1,BTCUSDT,paused\n", 7).is_err());
+    }
 }
\ No newline at end of file
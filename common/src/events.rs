@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use crate::types::{Symbol, Exchange, Side, OrderType, Priority, TriggerType};
+use crate::instrument::InstrumentId;
+use crate::types::{Side, OrderType, Priority, TriggerType};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TradingEvent {
@@ -25,8 +26,7 @@ impl TradingEvent {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpenPositionEvent {
-    pub symbol: Symbol,
-    pub exchange: Exchange,
+    pub instrument: InstrumentId,
     pub side: Side,
     pub quantity: f64,
     pub order_type: OrderType,
@@ -38,8 +38,7 @@ pub struct OpenPositionEvent {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClosePositionEvent {
-    pub symbol: Symbol,
-    pub exchange: Exchange,
+    pub instrument: InstrumentId,
     pub side: Side,
     pub quantity: f64,
     pub order_type: OrderType,
@@ -51,9 +50,10 @@ pub struct ClosePositionEvent {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HedgePositionEvent {
-    pub symbol: Symbol,
-    pub primary_exchange: Exchange,
-    pub hedge_exchange: Exchange,
+    /// 成交腿
+    pub instrument: InstrumentId,
+    /// 对冲腿
+    pub hedge_instrument: InstrumentId,
     pub side: Side,
     pub quantity: f64,
     pub trigger_type: TriggerType,
@@ -64,8 +64,7 @@ pub struct HedgePositionEvent {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CancelOrderEvent {
     pub order_id: String,
-    pub symbol: Symbol,
-    pub exchange: Exchange,
+    pub instrument: InstrumentId,
     pub reason: String,
     pub timestamp: DateTime<Utc>,
 }
@@ -73,8 +72,7 @@ pub struct CancelOrderEvent {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModifyOrderEvent {
     pub order_id: String,
-    pub symbol: Symbol,
-    pub exchange: Exchange,
+    pub instrument: InstrumentId,
     pub new_price: Option<f64>,
     pub new_quantity: Option<f64>,
     pub reason: String,
@@ -0,0 +1,128 @@
+//! 交易引擎执行命令与结果，线格式见 `codec`
+
+use crate::instrument::InstrumentId;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// 下单/撤单/改单命令（pre-post-processor → trading-engine）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionCommand {
+    pub id: Uuid,
+    /// 标准标的；`exchange`、`market_type`、`symbol` 为引擎的路由键和交易所原生符号
+    #[serde(default)]
+    pub instrument: InstrumentId,
+    pub exchange: String,
+    pub market_type: String, // "spot" or "futures"
+    pub symbol: String,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub quantity: Decimal,
+    pub price: Option<Decimal>,
+    pub time_in_force: TimeInForce,
+    pub client_order_id: Option<String>,
+    pub reduce_only: bool,
+    pub post_only: bool,
+    #[serde(default)]
+    pub action: CommandAction,
+}
+
+/// `ExecutionCommand` 要求引擎执行的操作
+///
+/// 撤单和改单以下单时的 client order id 指定目标订单，命令的 `symbol`、`side`、
+/// `exchange`、`market_type` 须与该订单一致；`quantity`、`price` 和订单标志只在 `Place` 时读取
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum CommandAction {
+    #[default]
+    Place,
+    Cancel {
+        orig_client_order_id: String,
+    },
+    /// 撤销 `symbol` 上的全部挂单
+    CancelAll,
+    Amend {
+        orig_client_order_id: String,
+        new_price: Option<Decimal>,
+        new_quantity: Option<Decimal>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum OrderSide {
+    Buy,
+    Sell,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum OrderType {
+    Market,
+    Limit,
+    StopMarket,
+    StopLimit,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum TimeInForce {
+    GTC,  // Good Till Cancel
+    IOC,  // Immediate or Cancel
+    FOK,  // Fill or Kill
+    GTX,  // Good Till Crossing
+}
+
+/// 单个交易所连接对命令的应答
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderResponse {
+    pub order_id: String,
+    pub client_order_id: String,
+    pub symbol: String,
+    pub status: OrderStatus,
+    pub executed_qty: Decimal,
+    pub executed_price: Option<Decimal>,
+    pub timestamp: i64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum OrderStatus {
+    New,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    Rejected,
+    Expired,
+}
+
+/// 命令的执行结果（trading-engine → pre-post-processor）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionResult {
+    pub command_id: Uuid,
+    pub success: bool,
+    /// 请求已发出但没有收到交易所的确认（等待超时或连接断开），交易所可能已受理，不能按拒绝重试
+    #[serde(default)]
+    pub outcome_unknown: bool,
+    pub responses: Vec<OrderResponse>,
+    pub selected_response: Option<OrderResponse>,
+    pub error: Option<String>,
+}
+
+impl ExecutionResult {
+    /// 未到达交易所的命令的结果
+    pub fn failed(command_id: Uuid, error: impl Into<String>) -> Self {
+        Self {
+            command_id,
+            success: false,
+            outcome_unknown: false,
+            responses: vec![],
+            selected_response: None,
+            error: Some(error.into()),
+        }
+    }
+
+    /// 已发出但结果未知的命令的结果
+    pub fn unknown(command_id: Uuid, error: impl Into<String>) -> Self {
+        Self {
+            outcome_unknown: true,
+            ..Self::failed(command_id, error)
+        }
+    }
+}
@@ -0,0 +1,506 @@
+//! 统一的交易标的标识
+//!
+//! 交易场所（venue）即 `exchanges.toml` 中的一项，同一交易所的现货和合约是不同场所；
+//! 符号ID在场所内唯一。`InstrumentId` = (场所ID, 符号ID)，作为所有跨进程消息和状态表的键。
+//! `InstrumentRegistry` 由 `MarketConfig` 构建，支持按ID查标的信息，以及按
+//! (交易所, 市场类型, 交易所原生符号) 反查ID，两个方向都是 O(1)。
+//!
+//! 标的带有交易所的下单过滤器（价格步长、数量步长、最小数量、最小名义价值、合约面值），
+//! 预处理和交易引擎下单前都用 `Instrument::normalize_order` 按过滤器取整并校验。
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use anyhow::{anyhow, Result};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::config::MarketConfig;
+use crate::types::{Exchange, Side};
+
+/// 市场类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MarketType {
+    Spot,
+    Futures,
+}
+
+impl MarketType {
+    /// 与 exchanges.toml 的 `type` 以及交易引擎的市场类型字符串一致
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarketType::Spot => "spot",
+            MarketType::Futures => "futures",
+        }
+    }
+}
+
+impl FromStr for MarketType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "spot" => Ok(MarketType::Spot),
+            "futures" => Ok(MarketType::Futures),
+            other => Err(anyhow!("Unknown market type: {}", other)),
+        }
+    }
+}
+
+/// 标的ID（场所ID + 场所内符号ID）
+///
+/// 文本形式为 `venue:symbol`（如 `2:17`），序列化为该字符串，因此可以直接作为JSON对象的键。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct InstrumentId {
+    pub venue: u32,
+    pub symbol: u32,
+}
+
+impl InstrumentId {
+    /// 无法解析的标的（场所和符号ID都从1开始编号）
+    pub const UNKNOWN: Self = Self { venue: 0, symbol: 0 };
+
+    pub const fn new(venue: u32, symbol: u32) -> Self {
+        Self { venue, symbol }
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        *self == Self::UNKNOWN
+    }
+}
+
+impl fmt::Display for InstrumentId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.venue, self.symbol)
+    }
+}
+
+impl FromStr for InstrumentId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (venue, symbol) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Invalid instrument id: {}", s))?;
+        Ok(Self {
+            venue: venue.parse().map_err(|_| anyhow!("Invalid venue id in {}", s))?,
+            symbol: symbol.parse().map_err(|_| anyhow!("Invalid symbol id in {}", s))?,
+        })
+    }
+}
+
+impl Serialize for InstrumentId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for InstrumentId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        text.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// 交易状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum SymbolStatus {
+    /// 正常交易
+    #[default]
+    Trading,
+    /// 暂停交易，可能恢复
+    Halted,
+    /// 已下架
+    Delisted,
+}
+
+impl SymbolStatus {
+    /// 与符号CSV的 `status` 列一致
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SymbolStatus::Trading => "trading",
+            SymbolStatus::Halted => "halted",
+            SymbolStatus::Delisted => "delisted",
+        }
+    }
+}
+
+impl fmt::Display for SymbolStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SymbolStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "" | "trading" => Ok(SymbolStatus::Trading),
+            "halted" => Ok(SymbolStatus::Halted),
+            "delisted" => Ok(SymbolStatus::Delisted),
+            other => Err(anyhow!("Unknown symbol status: {}", other)),
+        }
+    }
+}
+
+/// 下单过滤器，为0的项不做限制
+///
+/// 数量的单位与交易所下单接口一致：现货为基础币数量，合约为张数，
+/// 名义价值 = 价格 × 数量 × 合约面值。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolFilters {
+    /// 价格最小变动单位
+    pub tick_size: Decimal,
+    /// 数量最小变动单位
+    pub step_size: Decimal,
+    /// 最小下单数量
+    pub min_qty: Decimal,
+    /// 最小名义价值（计价币）
+    pub min_notional: Decimal,
+    /// 合约面值（每张对应的基础币数量），现货为1
+    pub contract_value: Decimal,
+}
+
+impl Default for SymbolFilters {
+    fn default() -> Self {
+        Self {
+            tick_size: Decimal::ZERO,
+            step_size: Decimal::ZERO,
+            min_qty: Decimal::ZERO,
+            min_notional: Decimal::ZERO,
+            contract_value: Decimal::ONE,
+        }
+    }
+}
+
+impl SymbolFilters {
+    /// 价格取整到步长，买单向下、卖单向上，不会比原价更激进
+    pub fn round_price(&self, price: Decimal, side: Side) -> Decimal {
+        if self.tick_size <= Decimal::ZERO {
+            return price;
+        }
+        let ticks = price / self.tick_size;
+        let ticks = match side {
+            Side::Buy => ticks.floor(),
+            Side::Sell => ticks.ceil(),
+        };
+        (ticks * self.tick_size).normalize()
+    }
+
+    /// 数量向下取整到步长，不会超过原数量
+    pub fn round_quantity(&self, quantity: Decimal) -> Decimal {
+        if self.step_size <= Decimal::ZERO {
+            return quantity;
+        }
+        ((quantity / self.step_size).floor() * self.step_size).normalize()
+    }
+
+    /// 名义价值（计价币）
+    pub fn notional(&self, price: Decimal, quantity: Decimal) -> Decimal {
+        price * quantity * self.contract_value
+    }
+}
+
+/// 订单不满足下单过滤器
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum FilterViolation {
+    #[error("symbol is {0}")]
+    NotTrading(SymbolStatus),
+    #[error("quantity {quantity} below minimum {min}")]
+    BelowMinQuantity { min: Decimal, quantity: Decimal },
+    #[error("notional {notional} below minimum {min}")]
+    BelowMinNotional { min: Decimal, notional: Decimal },
+}
+
+impl FilterViolation {
+    /// 过滤器名称，用作拒绝原因的规则名
+    pub fn filter(&self) -> &'static str {
+        match self {
+            FilterViolation::NotTrading(_) => "SymbolStatus",
+            FilterViolation::BelowMinQuantity { .. } => "MinQuantity",
+            FilterViolation::BelowMinNotional { .. } => "MinNotional",
+        }
+    }
+}
+
+/// 标的信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instrument {
+    pub id: InstrumentId,
+    /// 交易所（交易引擎按其配置键路由）
+    pub exchange: Exchange,
+    pub market_type: MarketType,
+    /// 场所名称（exchanges.toml 中的 name）
+    pub venue: String,
+    /// 交易所原生符号，下单和回报中使用
+    pub symbol: String,
+    /// 基础币
+    pub base_asset: String,
+    /// 计价币
+    pub quote_asset: String,
+    pub status: SymbolStatus,
+    pub filters: SymbolFilters,
+}
+
+impl Instrument {
+    /// 无过滤器的正常交易标的
+    pub fn new(id: InstrumentId, exchange: Exchange, market_type: MarketType, venue: &str, symbol: &str) -> Self {
+        Self {
+            id,
+            exchange,
+            market_type,
+            venue: venue.to_string(),
+            symbol: symbol.to_string(),
+            base_asset: String::new(),
+            quote_asset: String::new(),
+            status: SymbolStatus::Trading,
+            filters: SymbolFilters::default(),
+        }
+    }
+
+    pub fn is_trading(&self) -> bool {
+        self.status == SymbolStatus::Trading
+    }
+
+    /// 按过滤器取整价格和数量并校验，返回取整后的 (价格, 数量)
+    ///
+    /// 市价单没有价格时传入参考价用于最小名义价值校验，参考价不会被取整返回；
+    /// 没有任何价格时跳过名义价值校验。
+    pub fn normalize_order(
+        &self,
+        side: Side,
+        price: Option<Decimal>,
+        quantity: Decimal,
+    ) -> Result<(Option<Decimal>, Decimal), FilterViolation> {
+        if !self.is_trading() {
+            return Err(FilterViolation::NotTrading(self.status));
+        }
+
+        let filters = &self.filters;
+        let price = price
+            .filter(|p| *p > Decimal::ZERO)
+            .map(|p| filters.round_price(p, side));
+        let quantity = filters.round_quantity(quantity);
+
+        if quantity <= Decimal::ZERO || quantity < filters.min_qty {
+            return Err(FilterViolation::BelowMinQuantity { min: filters.min_qty, quantity });
+        }
+        if let Some(price) = price {
+            let notional = filters.notional(price, quantity);
+            if notional < filters.min_notional {
+                return Err(FilterViolation::BelowMinNotional { min: filters.min_notional, notional });
+            }
+        }
+
+        Ok((price, quantity))
+    }
+}
+
+impl fmt::Display for Instrument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.venue, self.symbol)
+    }
+}
+
+/// 标的注册表
+#[derive(Debug, Default)]
+pub struct InstrumentRegistry {
+    instruments: HashMap<InstrumentId, Instrument>,
+    /// (交易所, 市场类型) -> 原生符号 -> ID，内层按 &str 查询无需分配
+    by_native: HashMap<(Exchange, MarketType), HashMap<String, InstrumentId>>,
+    /// 场所名称 -> (交易所, 市场类型)
+    venues: HashMap<String, (Exchange, MarketType)>,
+}
+
+impl InstrumentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从配置目录加载（exchanges.toml 及符号CSV）
+    pub fn load(config_dir: &str) -> Result<Self> {
+        Self::from_market_config(&MarketConfig::load(config_dir)?)
+    }
+
+    pub fn from_market_config(market: &MarketConfig) -> Result<Self> {
+        let mut registry = Self::new();
+
+        for venue in market.get_exchanges() {
+            let exchange = Exchange::from_key(&venue.exchange)
+                .ok_or_else(|| anyhow!("Unknown exchange {} for venue {}", venue.exchange, venue.name))?;
+            let market_type: MarketType = venue.exchange_type.parse()?;
+
+            for symbol in market.get_symbols(venue.id).into_iter().flatten() {
+                registry.insert(Instrument {
+                    id: InstrumentId::new(venue.id, symbol.id),
+                    exchange,
+                    market_type,
+                    venue: venue.name.clone(),
+                    symbol: symbol.symbol.clone(),
+                    base_asset: symbol.base_asset.clone(),
+                    quote_asset: symbol.quote_asset.clone(),
+                    status: symbol.status,
+                    filters: symbol.filters,
+                });
+            }
+            registry.venues.insert(venue.name.clone(), (exchange, market_type));
+        }
+
+        Ok(registry)
+    }
+
+    /// 注册标的，同一ID重复注册时覆盖
+    pub fn insert(&mut self, instrument: Instrument) {
+        self.venues
+            .entry(instrument.venue.clone())
+            .or_insert((instrument.exchange, instrument.market_type));
+        self.by_native
+            .entry((instrument.exchange, instrument.market_type))
+            .or_default()
+            .insert(instrument.symbol.clone(), instrument.id);
+        self.instruments.insert(instrument.id, instrument);
+    }
+
+    pub fn get(&self, id: InstrumentId) -> Option<&Instrument> {
+        self.instruments.get(&id)
+    }
+
+    /// 按交易所原生符号反查
+    pub fn resolve(&self, exchange: Exchange, market_type: MarketType, symbol: &str) -> Option<InstrumentId> {
+        self.by_native.get(&(exchange, market_type))?.get(symbol).copied()
+    }
+
+    /// 按场所名称和原生符号反查
+    pub fn resolve_venue(&self, venue: &str, symbol: &str) -> Option<InstrumentId> {
+        let &(exchange, market_type) = self.venues.get(venue)?;
+        self.resolve(exchange, market_type, symbol)
+    }
+
+    /// 解析文本形式的标的：`2:17` 或 `binance_futures:BTCUSDT`，数字形式不要求已注册
+    pub fn parse(&self, text: &str) -> Option<InstrumentId> {
+        if let Ok(id) = text.parse::<InstrumentId>() {
+            return Some(id);
+        }
+        let (venue, symbol) = text.split_once(':')?;
+        self.resolve_venue(venue, symbol)
+    }
+
+    /// 标的的可读名称，未注册时退回ID文本
+    pub fn name(&self, id: InstrumentId) -> String {
+        match self.get(id) {
+            Some(instrument) => instrument.to_string(),
+            None => id.to_string(),
+        }
+    }
+
+    pub fn instruments(&self) -> impl Iterator<Item = &Instrument> {
+        self.instruments.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.instruments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instruments.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instrument(venue: u32, symbol: u32, exchange: Exchange, market_type: MarketType, venue_name: &str, native: &str) -> Instrument {
+        Instrument::new(InstrumentId::new(venue, symbol), exchange, market_type, venue_name, native)
+    }
+
+    fn btc_swap() -> Instrument {
+        let mut swap = instrument(6, 1, Exchange::OKX, MarketType::Futures, "okex-swap", "BTC-USDT-SWAP");
+        swap.filters = SymbolFilters {
+            tick_size: Decimal::new(1, 1),
+            step_size: Decimal::ONE,
+            min_qty: Decimal::ONE,
+            min_notional: Decimal::from(5),
+            contract_value: Decimal::new(1, 2),
+        };
+        swap
+    }
+
+    #[test]
+    fn test_registry_lookups_in_both_directions() {
+        let mut registry = InstrumentRegistry::new();
+        registry.insert(instrument(1, 3, Exchange::Binance, MarketType::Spot, "binance_spot", "BTCUSDT"));
+        registry.insert(instrument(2, 3, Exchange::Binance, MarketType::Futures, "binance_futures", "BTCUSDT"));
+        registry.insert(instrument(6, 1, Exchange::OKX, MarketType::Futures, "okex-swap", "BTC-USDT-SWAP"));
+
+        // 同一原生符号在现货和合约是不同标的
+        let spot = registry.resolve(Exchange::Binance, MarketType::Spot, "BTCUSDT").unwrap();
+        let perp = registry.resolve(Exchange::Binance, MarketType::Futures, "BTCUSDT").unwrap();
+        assert_eq!(spot, InstrumentId::new(1, 3));
+        assert_eq!(perp, InstrumentId::new(2, 3));
+        assert_eq!(registry.get(perp).unwrap().venue, "binance_futures");
+        assert!(registry.resolve(Exchange::Bybit, MarketType::Spot, "BTCUSDT").is_none());
+
+        assert_eq!(registry.parse("6:1"), Some(InstrumentId::new(6, 1)));
+        assert_eq!(registry.parse("okex-swap:BTC-USDT-SWAP"), Some(InstrumentId::new(6, 1)));
+        assert_eq!(registry.parse("9:9"), Some(InstrumentId::new(9, 9)));
+        assert_eq!(registry.parse("okex-swap:ETH-USDT-SWAP"), None);
+        assert_eq!(registry.parse("BTCUSDT"), None);
+
+        assert_eq!(registry.name(spot), "binance_spot:BTCUSDT");
+        assert_eq!(registry.name(InstrumentId::new(9, 9)), "9:9");
+    }
+
+    #[test]
+    fn test_instrument_id_text_form() {
+        let id = InstrumentId::new(2, 17);
+        assert_eq!(id.to_string(), "2:17");
+        assert_eq!("2:17".parse::<InstrumentId>().unwrap(), id);
+        assert!("2".parse::<InstrumentId>().is_err());
+        assert!("x:1".parse::<InstrumentId>().is_err());
+        assert!(InstrumentId::UNKNOWN.is_unknown());
+    }
+
+    #[test]
+    fn test_filters_round_toward_passive_side() {
+        let filters = btc_swap().filters;
+        let price = Decimal::new(6543217, 2); // 65432.17
+        assert_eq!(filters.round_price(price, Side::Buy), Decimal::new(654321, 1));
+        assert_eq!(filters.round_price(price, Side::Sell), Decimal::new(654322, 1));
+        assert_eq!(filters.round_price(Decimal::new(654320, 1), Side::Sell), Decimal::new(65432, 0));
+        assert_eq!(filters.round_quantity(Decimal::new(39, 1)), Decimal::from(3));
+
+        // 无过滤器时原样返回
+        let none = SymbolFilters::default();
+        assert_eq!(none.round_price(price, Side::Buy), price);
+        assert_eq!(none.round_quantity(Decimal::new(39, 1)), Decimal::new(39, 1));
+    }
+
+    #[test]
+    fn test_normalize_order_checks_quantity_notional_and_status() {
+        let mut swap = btc_swap();
+
+        // 3.9张取整为3张，名义价值 = 100 × 3 × 0.01 = 3 < 5
+        let violation = swap.normalize_order(Side::Buy, Some(Decimal::new(10004, 2)), Decimal::new(39, 1)).unwrap_err();
+        assert_eq!(violation, FilterViolation::BelowMinNotional { min: Decimal::from(5), notional: Decimal::from(3) });
+        assert_eq!(violation.filter(), "MinNotional");
+
+        let (price, quantity) = swap.normalize_order(Side::Sell, Some(Decimal::new(6543217, 2)), Decimal::new(25, 1)).unwrap();
+        assert_eq!(price, Some(Decimal::new(654322, 1)));
+        assert_eq!(quantity, Decimal::from(2));
+
+        // 市价单没有价格，只校验数量
+        assert_eq!(swap.normalize_order(Side::Buy, None, Decimal::from(2)).unwrap(), (None, Decimal::from(2)));
+        assert_eq!(
+            swap.normalize_order(Side::Buy, None, Decimal::new(5, 1)).unwrap_err().filter(),
+            "MinQuantity"
+        );
+
+        swap.status = SymbolStatus::Halted;
+        assert_eq!(
+            swap.normalize_order(Side::Buy, None, Decimal::from(2)).unwrap_err(),
+            FilterViolation::NotTrading(SymbolStatus::Halted)
+        );
+    }
+}
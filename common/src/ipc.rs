@@ -2,4 +2,12 @@
 pub const IPC_SERVICE_SIGNAL: &str = "signal_service";
 pub const IPC_SERVICE_EXECUTION: &str = "execution_service";
 pub const IPC_SERVICE_ORDER: &str = "order_service";
-pub const IPC_SERVICE_MARKET: &str = "market_service";
\ No newline at end of file
+pub const IPC_SERVICE_MARKET: &str = "market_service";
+pub const IPC_SERVICE_RISK_INIT_RESPONSE: &str = "risk_init_response_service";
+pub const IPC_SERVICE_RISK_SUMMARY: &str = "risk_summary_service";
+
+// 信号定长缓冲区大小（Signal::to_bytes，尾部补零）
+pub const SIGNAL_FRAME_SIZE: usize = 1024;
+
+// 风控消息（RiskInitResponse/RiskStateSummary）定长缓冲区大小
+pub const RISK_FRAME_SIZE: usize = 4096;
@@ -1,8 +1,13 @@
 pub mod types;
+pub mod instrument;
 pub mod messages;
 pub mod signals;
 pub mod events;
 pub mod binary;
+pub mod execution;
+pub mod codec;
+pub mod pod;
 pub mod config;
 pub mod ipc;
+pub mod wakeup;
 pub mod risk_proto;
\ No newline at end of file
@@ -0,0 +1,520 @@
+//! 定长POD消息，直接作为 iceoryx2 载荷类型
+//!
+//! 发布端通过 `loan_uninit` + `write_payload` 把结构体写入共享内存，订阅端在
+//! `sample.payload()` 上原地读取，不再经过 `Bytes` 编码和定长数组拷贝。
+//! 所有字段均为定宽数值或内联字节数组（不含指针），枚举按 `WireEnum` 编号存为 u8，标的存为场所ID和符号ID，
+//! 时间戳为 UTC 微秒。与 `common::types` 富类型之间的转换在热路径之外完成。
+//!
+//! 含变长数据的消息（信号元数据、风控初始化 protobuf、执行命令）仍走 `binary` 帧格式。
+
+use chrono::{DateTime, Utc};
+use std::fmt;
+
+use crate::binary::{DecodeError, EncodeError, EventType, WireEnum};
+use crate::events::{
+    TradingEvent, OpenPositionEvent, ClosePositionEvent, HedgePositionEvent, CancelOrderEvent, ModifyOrderEvent,
+};
+use crate::instrument::InstrumentId;
+use crate::messages::EventMessage;
+use crate::types::{ExecutionReport, OrderType, Quote, Side, TriggerType};
+
+/// 订单ID内联容量
+pub const ORDER_ID_CAPACITY: usize = 64;
+
+/// 事件原因内联容量
+pub const REASON_CAPACITY: usize = 128;
+
+/// 内联定长字符串（UTF-8，长度单独记录）
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct FixedStr<const N: usize> {
+    len: u16,
+    bytes: [u8; N],
+}
+
+impl<const N: usize> FixedStr<N> {
+    pub const EMPTY: Self = Self { len: 0, bytes: [0; N] };
+
+    /// 超过容量时返回错误，不截断
+    pub fn new(field: &'static str, value: &str) -> Result<Self, EncodeError> {
+        if value.len() > N || value.len() > u16::MAX as usize {
+            return Err(EncodeError::FieldTooLong { field, len: value.len(), capacity: N });
+        }
+
+        let mut bytes = [0u8; N];
+        bytes[..value.len()].copy_from_slice(value.as_bytes());
+        Ok(Self { len: value.len() as u16, bytes })
+    }
+
+    /// 共享内存中的数据不可信，长度和编码都需校验
+    pub fn to_str(&self, field: &'static str) -> Result<&str, DecodeError> {
+        let len = self.len as usize;
+        if len > N {
+            return Err(DecodeError::Truncated { field, needed: len, remaining: N });
+        }
+        std::str::from_utf8(&self.bytes[..len]).map_err(|_| DecodeError::InvalidUtf8(field))
+    }
+}
+
+impl<const N: usize> Default for FixedStr<N> {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.to_str("fixed_str") {
+            Ok(value) => write!(f, "{:?}", value),
+            Err(_) => write!(f, "<invalid {} bytes>", self.len),
+        }
+    }
+}
+
+// 可选数值字段的存在标志
+const FLAG_PRICE: u8 = 1;
+const FLAG_NEW_QUANTITY: u8 = 1 << 1;
+const FLAG_MARK_PRICE: u8 = 1 << 2;
+
+/// 交易事件（signal-collector -> pre-post-processor）
+///
+/// 五种事件共用一个布局，未使用的字段为零：
+/// - 开仓/平仓：side、quantity、order_type、price、trigger_type、reason
+/// - 对冲：venue/symbol 为成交腿，hedge_venue/hedge_symbol 为对冲腿
+/// - 撤单：order_id、reason
+/// - 改单：order_id、price（新价格）、new_quantity、reason
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PodEvent {
+    pub sequence_id: u64,
+    pub timestamp_us: i64,        // 消息时间
+    pub event_timestamp_us: i64,  // 事件时间
+    pub quantity: f64,
+    pub price: f64,
+    pub new_quantity: f64,
+    pub venue: u32,
+    pub symbol: u32,
+    pub hedge_venue: u32,
+    pub hedge_symbol: u32,
+    pub event_type: u8,
+    pub side: u8,
+    pub order_type: u8,
+    pub trigger_type: u8,
+    pub flags: u8,
+    pub _reserved: [u8; 3],
+    pub order_id: FixedStr<ORDER_ID_CAPACITY>,
+    pub reason: FixedStr<REASON_CAPACITY>,
+}
+
+impl PodEvent {
+    fn empty(message: &EventMessage, event_type: EventType, instrument: InstrumentId, timestamp: DateTime<Utc>) -> Self {
+        Self {
+            sequence_id: message.sequence_id,
+            timestamp_us: message.timestamp.timestamp_micros(),
+            event_timestamp_us: timestamp.timestamp_micros(),
+            quantity: 0.0,
+            price: 0.0,
+            new_quantity: 0.0,
+            venue: instrument.venue,
+            symbol: instrument.symbol,
+            hedge_venue: 0,
+            hedge_symbol: 0,
+            event_type: event_type.to_wire(),
+            side: 0,
+            order_type: 0,
+            trigger_type: 0,
+            flags: 0,
+            _reserved: [0; 3],
+            order_id: FixedStr::EMPTY,
+            reason: FixedStr::EMPTY,
+        }
+    }
+
+    fn set_price(&mut self, price: Option<f64>) {
+        if let Some(price) = price {
+            self.price = price;
+            self.flags |= FLAG_PRICE;
+        }
+    }
+
+    fn instrument(&self) -> InstrumentId {
+        InstrumentId::new(self.venue, self.symbol)
+    }
+
+    fn price(&self) -> Option<f64> {
+        (self.flags & FLAG_PRICE != 0).then_some(self.price)
+    }
+
+    fn new_quantity(&self) -> Option<f64> {
+        (self.flags & FLAG_NEW_QUANTITY != 0).then_some(self.new_quantity)
+    }
+}
+
+impl TryFrom<&EventMessage> for PodEvent {
+    type Error = EncodeError;
+
+    fn try_from(message: &EventMessage) -> Result<Self, Self::Error> {
+        let pod = match &message.event {
+            TradingEvent::OpenPosition(e) => {
+                let mut pod = Self::empty(message, EventType::OpenPosition, e.instrument, e.timestamp);
+                pod.side = e.side.to_wire();
+                pod.quantity = e.quantity;
+                pod.order_type = e.order_type.to_wire();
+                pod.set_price(e.price);
+                pod.trigger_type = e.trigger_type.to_wire();
+                pod.reason = FixedStr::new("reason", &e.reason)?;
+                pod
+            }
+            TradingEvent::ClosePosition(e) => {
+                let mut pod = Self::empty(message, EventType::ClosePosition, e.instrument, e.timestamp);
+                pod.side = e.side.to_wire();
+                pod.quantity = e.quantity;
+                pod.order_type = e.order_type.to_wire();
+                pod.set_price(e.price);
+                pod.trigger_type = e.trigger_type.to_wire();
+                pod.reason = FixedStr::new("reason", &e.reason)?;
+                pod
+            }
+            TradingEvent::HedgePosition(e) => {
+                let mut pod = Self::empty(message, EventType::HedgePosition, e.instrument, e.timestamp);
+                pod.hedge_venue = e.hedge_instrument.venue;
+                pod.hedge_symbol = e.hedge_instrument.symbol;
+                pod.side = e.side.to_wire();
+                pod.quantity = e.quantity;
+                pod.trigger_type = e.trigger_type.to_wire();
+                pod.reason = FixedStr::new("reason", &e.reason)?;
+                pod
+            }
+            TradingEvent::CancelOrder(e) => {
+                let mut pod = Self::empty(message, EventType::CancelOrder, e.instrument, e.timestamp);
+                pod.order_id = FixedStr::new("order_id", &e.order_id)?;
+                pod.reason = FixedStr::new("reason", &e.reason)?;
+                pod
+            }
+            TradingEvent::ModifyOrder(e) => {
+                let mut pod = Self::empty(message, EventType::ModifyOrder, e.instrument, e.timestamp);
+                pod.order_id = FixedStr::new("order_id", &e.order_id)?;
+                pod.set_price(e.new_price);
+                if let Some(quantity) = e.new_quantity {
+                    pod.new_quantity = quantity;
+                    pod.flags |= FLAG_NEW_QUANTITY;
+                }
+                pod.reason = FixedStr::new("reason", &e.reason)?;
+                pod
+            }
+        };
+
+        Ok(pod)
+    }
+}
+
+impl TryFrom<&PodEvent> for EventMessage {
+    type Error = DecodeError;
+
+    fn try_from(pod: &PodEvent) -> Result<Self, Self::Error> {
+        let instrument = pod.instrument();
+        let timestamp = from_micros(pod.event_timestamp_us)?;
+        let reason = pod.reason.to_str("reason")?.to_string();
+
+        let event = match wire::<EventType>(pod.event_type)? {
+            EventType::OpenPosition => TradingEvent::OpenPosition(OpenPositionEvent {
+                instrument,
+                side: wire::<Side>(pod.side)?,
+                quantity: pod.quantity,
+                order_type: wire::<OrderType>(pod.order_type)?,
+                price: pod.price(),
+                trigger_type: wire::<TriggerType>(pod.trigger_type)?,
+                reason,
+                timestamp,
+            }),
+            EventType::ClosePosition => TradingEvent::ClosePosition(ClosePositionEvent {
+                instrument,
+                side: wire::<Side>(pod.side)?,
+                quantity: pod.quantity,
+                order_type: wire::<OrderType>(pod.order_type)?,
+                price: pod.price(),
+                trigger_type: wire::<TriggerType>(pod.trigger_type)?,
+                reason,
+                timestamp,
+            }),
+            EventType::HedgePosition => TradingEvent::HedgePosition(HedgePositionEvent {
+                instrument,
+                hedge_instrument: InstrumentId::new(pod.hedge_venue, pod.hedge_symbol),
+                side: wire::<Side>(pod.side)?,
+                quantity: pod.quantity,
+                trigger_type: wire::<TriggerType>(pod.trigger_type)?,
+                reason,
+                timestamp,
+            }),
+            EventType::CancelOrder => TradingEvent::CancelOrder(CancelOrderEvent {
+                order_id: pod.order_id.to_str("order_id")?.to_string(),
+                instrument,
+                reason,
+                timestamp,
+            }),
+            EventType::ModifyOrder => TradingEvent::ModifyOrder(ModifyOrderEvent {
+                order_id: pod.order_id.to_str("order_id")?.to_string(),
+                instrument,
+                new_price: pod.price(),
+                new_quantity: pod.new_quantity(),
+                reason,
+                timestamp,
+            }),
+        };
+
+        Ok(EventMessage {
+            event,
+            sequence_id: pod.sequence_id,
+            timestamp: from_micros(pod.timestamp_us)?,
+        })
+    }
+}
+
+/// 执行报告（trading-engine -> pre-post-processor）
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PodExecutionReport {
+    pub price: f64,
+    pub quantity: f64,
+    pub filled_quantity: f64,
+    pub timestamp_us: i64,
+    pub venue: u32,
+    pub symbol: u32,
+    pub side: u8,
+    pub order_type: u8,
+    pub status: u8,
+    pub execution_type: u8,
+    pub order_id: FixedStr<ORDER_ID_CAPACITY>,
+    pub client_order_id: FixedStr<ORDER_ID_CAPACITY>,
+}
+
+impl TryFrom<&ExecutionReport> for PodExecutionReport {
+    type Error = EncodeError;
+
+    fn try_from(report: &ExecutionReport) -> Result<Self, Self::Error> {
+        Ok(Self {
+            price: report.price,
+            quantity: report.quantity,
+            filled_quantity: report.filled_quantity,
+            timestamp_us: report.timestamp.timestamp_micros(),
+            venue: report.instrument.venue,
+            symbol: report.instrument.symbol,
+            side: report.side.to_wire(),
+            order_type: report.order_type.to_wire(),
+            status: report.status.to_wire(),
+            execution_type: report.execution_type.to_wire(),
+            order_id: FixedStr::new("order_id", &report.order_id)?,
+            client_order_id: FixedStr::new("client_order_id", &report.client_order_id)?,
+        })
+    }
+}
+
+impl TryFrom<&PodExecutionReport> for ExecutionReport {
+    type Error = DecodeError;
+
+    fn try_from(pod: &PodExecutionReport) -> Result<Self, Self::Error> {
+        Ok(Self {
+            order_id: pod.order_id.to_str("order_id")?.to_string(),
+            client_order_id: pod.client_order_id.to_str("client_order_id")?.to_string(),
+            instrument: InstrumentId::new(pod.venue, pod.symbol),
+            side: wire(pod.side)?,
+            order_type: wire(pod.order_type)?,
+            price: pod.price,
+            quantity: pod.quantity,
+            filled_quantity: pod.filled_quantity,
+            status: wire(pod.status)?,
+            execution_type: wire(pod.execution_type)?,
+            timestamp: from_micros(pod.timestamp_us)?,
+        })
+    }
+}
+
+/// 最优买卖价（行情进程 -> pre-post-processor）
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct PodQuote {
+    pub bid_price: f64,
+    pub bid_quantity: f64,
+    pub ask_price: f64,
+    pub ask_quantity: f64,
+    pub mark_price: f64,
+    pub timestamp_us: i64,
+    pub venue: u32,
+    pub symbol: u32,
+    pub flags: u8,
+    pub _reserved: [u8; 7],
+}
+
+impl From<&Quote> for PodQuote {
+    fn from(quote: &Quote) -> Self {
+        Self {
+            bid_price: quote.bid_price,
+            bid_quantity: quote.bid_quantity,
+            ask_price: quote.ask_price,
+            ask_quantity: quote.ask_quantity,
+            mark_price: quote.mark_price.unwrap_or_default(),
+            timestamp_us: quote.timestamp.timestamp_micros(),
+            venue: quote.instrument.venue,
+            symbol: quote.instrument.symbol,
+            flags: if quote.mark_price.is_some() { FLAG_MARK_PRICE } else { 0 },
+            _reserved: [0; 7],
+        }
+    }
+}
+
+impl TryFrom<&PodQuote> for Quote {
+    type Error = DecodeError;
+
+    fn try_from(pod: &PodQuote) -> Result<Self, Self::Error> {
+        Ok(Self {
+            instrument: InstrumentId::new(pod.venue, pod.symbol),
+            bid_price: pod.bid_price,
+            bid_quantity: pod.bid_quantity,
+            ask_price: pod.ask_price,
+            ask_quantity: pod.ask_quantity,
+            mark_price: (pod.flags & FLAG_MARK_PRICE != 0).then_some(pod.mark_price),
+            timestamp: from_micros(pod.timestamp_us)?,
+        })
+    }
+}
+
+fn wire<E: WireEnum>(value: u8) -> Result<E, DecodeError> {
+    E::from_wire(value).ok_or(DecodeError::InvalidEnum { field: E::FIELD, value })
+}
+
+fn from_micros(micros: i64) -> Result<DateTime<Utc>, DecodeError> {
+    DateTime::<Utc>::from_timestamp_micros(micros).ok_or(DecodeError::InvalidTimestamp(micros))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ExecutionType, OrderStatus};
+
+    fn timestamp() -> DateTime<Utc> {
+        DateTime::<Utc>::from_timestamp_micros(1_700_000_000_123_456).unwrap()
+    }
+
+    #[test]
+    fn test_event_round_trip() {
+        let message = EventMessage {
+            event: TradingEvent::ModifyOrder(ModifyOrderEvent {
+                order_id: "ORD_42".to_string(),
+                instrument: InstrumentId::new(5, 12),
+                new_price: Some(101.5),
+                new_quantity: None,
+                reason: "requote".to_string(),
+                timestamp: timestamp(),
+            }),
+            sequence_id: 9,
+            timestamp: timestamp(),
+        };
+
+        let pod = PodEvent::try_from(&message).unwrap();
+        let decoded = EventMessage::try_from(&pod).unwrap();
+        assert_eq!(decoded.sequence_id, 9);
+        assert_eq!(decoded.timestamp, message.timestamp);
+        let TradingEvent::ModifyOrder(e) = decoded.event else {
+            panic!("expected modify event");
+        };
+        assert_eq!(e.order_id, "ORD_42");
+        assert_eq!(e.instrument, InstrumentId::new(5, 12));
+        assert_eq!(e.new_price, Some(101.5));
+        assert_eq!(e.new_quantity, None);
+        assert_eq!(e.reason, "requote");
+
+        let message = EventMessage {
+            event: TradingEvent::HedgePosition(HedgePositionEvent {
+                instrument: InstrumentId::new(1, 3),
+                hedge_instrument: InstrumentId::new(4, 3),
+                side: Side::Sell,
+                quantity: 2.5,
+                trigger_type: TriggerType::HedgeTrigger,
+                reason: "hedge".to_string(),
+                timestamp: timestamp(),
+            }),
+            sequence_id: 10,
+            timestamp: timestamp(),
+        };
+        let decoded = EventMessage::try_from(&PodEvent::try_from(&message).unwrap()).unwrap();
+        let TradingEvent::HedgePosition(e) = decoded.event else {
+            panic!("expected hedge event");
+        };
+        assert_eq!(e.instrument, InstrumentId::new(1, 3));
+        assert_eq!(e.hedge_instrument, InstrumentId::new(4, 3));
+        assert_eq!(e.side, Side::Sell);
+        assert_eq!(e.quantity, 2.5);
+    }
+
+    #[test]
+    fn test_execution_report_round_trip() {
+        let report = ExecutionReport {
+            order_id: "123456".to_string(),
+            client_order_id: "ORD_1".to_string(),
+            instrument: InstrumentId::new(8, 9),
+            side: Side::Buy,
+            order_type: OrderType::Limit,
+            price: 100.25,
+            quantity: 3.0,
+            filled_quantity: 1.0,
+            status: OrderStatus::PartiallyFilled,
+            execution_type: ExecutionType::Trade,
+            timestamp: timestamp(),
+        };
+
+        let decoded = ExecutionReport::try_from(&PodExecutionReport::try_from(&report).unwrap()).unwrap();
+        assert_eq!(decoded.order_id, report.order_id);
+        assert_eq!(decoded.client_order_id, report.client_order_id);
+        assert_eq!(decoded.instrument, InstrumentId::new(8, 9));
+        assert_eq!(decoded.status, OrderStatus::PartiallyFilled);
+        assert_eq!(decoded.execution_type, ExecutionType::Trade);
+        assert_eq!(decoded.timestamp, report.timestamp);
+    }
+
+    #[test]
+    fn test_quote_round_trip() {
+        let quote = Quote {
+            instrument: InstrumentId::new(6, 1),
+            bid_price: 64999.5,
+            bid_quantity: 3.0,
+            ask_price: 65000.5,
+            ask_quantity: 1.5,
+            mark_price: Some(65001.2),
+            timestamp: timestamp(),
+        };
+
+        let decoded = Quote::try_from(&PodQuote::from(&quote)).unwrap();
+        assert_eq!(decoded, quote);
+        assert_eq!(decoded.mid_price(), Some(65000.0));
+
+        // 现货没有标记价格
+        let spot = Quote { mark_price: None, ..quote };
+        assert_eq!(Quote::try_from(&PodQuote::from(&spot)).unwrap().mark_price, None);
+    }
+
+    #[test]
+    fn test_rejects_oversized_and_corrupted_fields() {
+        let long_id = "x".repeat(ORDER_ID_CAPACITY + 1);
+        assert_eq!(
+            FixedStr::<ORDER_ID_CAPACITY>::new("order_id", &long_id).unwrap_err(),
+            EncodeError::FieldTooLong { field: "order_id", len: ORDER_ID_CAPACITY + 1, capacity: ORDER_ID_CAPACITY }
+        );
+
+        let message = EventMessage {
+            event: TradingEvent::CancelOrder(CancelOrderEvent {
+                order_id: "ORD_1".to_string(),
+                instrument: InstrumentId::new(1, 1),
+                reason: "stale".to_string(),
+                timestamp: timestamp(),
+            }),
+            sequence_id: 1,
+            timestamp: timestamp(),
+        };
+        let mut pod = PodEvent::try_from(&message).unwrap();
+        pod.event_type = 9;
+        assert_eq!(
+            EventMessage::try_from(&pod).unwrap_err(),
+            DecodeError::InvalidEnum { field: "event_type", value: 9 }
+        );
+    }
+}
@@ -1,16 +1,7 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use std::collections::HashMap;
-use std::fmt;
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct Symbol(pub u32);
-
-impl fmt::Display for Symbol {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Symbol({})", self.0)
-    }
-}
+use crate::instrument::InstrumentId;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Exchange {
@@ -20,6 +11,28 @@ pub enum Exchange {
     Bitget,
 }
 
+impl Exchange {
+    /// 交易引擎配置中的交易所键
+    pub fn key(&self) -> &'static str {
+        match self {
+            Exchange::Binance => "binance",
+            Exchange::OKX => "okex",
+            Exchange::Bybit => "bybit",
+            Exchange::Bitget => "bitget",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "binance" => Some(Exchange::Binance),
+            "okex" => Some(Exchange::OKX),
+            "bybit" => Some(Exchange::Bybit),
+            "bitget" => Some(Exchange::Bitget),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Side {
     Buy,
@@ -54,8 +67,7 @@ pub enum OrderStatus {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Order {
     pub id: String,
-    pub symbol: Symbol,
-    pub exchange: Exchange,
+    pub instrument: InstrumentId,
     pub side: Side,
     pub order_type: OrderType,
     pub price: f64,
@@ -70,8 +82,7 @@ impl Order {
     pub fn from_signal(signal: &Signal) -> Self {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
-            symbol: Symbol(0), // TODO: 从signal.symbol解析
-            exchange: Exchange::Binance, // TODO: 从signal.exchange解析
+            instrument: signal.instrument,
             side: signal.side.unwrap_or(Side::Buy),
             order_type: OrderType::Market,
             price: signal.price.unwrap_or(0.0),
@@ -86,8 +97,7 @@ impl Order {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
-    pub symbol: Symbol,
-    pub exchange: Exchange,
+    pub instrument: InstrumentId,
     pub side: Side,
     pub quantity: f64,
     pub avg_price: f64,
@@ -114,8 +124,7 @@ pub enum Priority {
 pub struct ExecutionReport {
     pub order_id: String,
     pub client_order_id: String,
-    pub symbol: Symbol,
-    pub exchange: Exchange,
+    pub instrument: InstrumentId,
     pub side: Side,
     pub order_type: OrderType,
     pub price: f64,
@@ -126,10 +135,30 @@ pub struct ExecutionReport {
     pub timestamp: DateTime<Utc>,
 }
 
+/// 标准化最优买卖价（行情进程发布），合约附带交易所标记价格
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Quote {
+    pub instrument: InstrumentId,
+    pub bid_price: f64,
+    pub bid_quantity: f64,
+    pub ask_price: f64,
+    pub ask_quantity: f64,
+    pub mark_price: Option<f64>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Quote {
+    /// 中间价，任一侧缺失时为None
+    pub fn mid_price(&self) -> Option<f64> {
+        (self.bid_price > 0.0 && self.ask_price > 0.0).then(|| (self.bid_price + self.ask_price) / 2.0)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ExecutionType {
     New,
     Trade,
+    Replaced,  // 改单确认（价格/数量已更新）
     Cancelled,
     Rejected,
     Expired,
@@ -153,7 +182,10 @@ pub enum SignalType {
 pub struct Signal {
     pub id: String,
     pub signal_type: SignalType,
+    pub instrument: InstrumentId,
+    /// 交易所原生符号（仅用于展示）
     pub symbol: String,
+    /// 场所名称（仅用于展示）
     pub exchange: String,
     pub side: Option<Side>,
     pub price: Option<f64>,
@@ -222,6 +254,7 @@ impl Signal {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             signal_type,
+            instrument: InstrumentId::UNKNOWN,
             symbol: String::new(),
             exchange: String::new(),
             side: None,
@@ -0,0 +1,120 @@
+//! iceoryx2 事件唤醒
+//!
+//! 每个发布订阅 topic 配套一个 event 服务（`<topic>/notify`）：发布端发送样本后调用
+//! `TopicNotifier::notify`，订阅端阻塞在 `TopicWaiter::wait` 上，通知到达即返回，
+//! 不再按固定周期轮询。`max_wait_us` 为兜底超时，用于检查退出信号以及兼容不发通知的发布端。
+//!
+//! 绑核的热路径线程可配置为 `busy_spin`：不创建 listener，`wait` 只做一次自旋提示，
+//! 每 `TERMINATION_CHECK_SPINS` 次空转检查一次退出信号。
+
+use anyhow::Result;
+use core::time::Duration;
+use iceoryx2::port::listener::Listener;
+use iceoryx2::port::notifier::Notifier;
+use iceoryx2::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// busy-spin 模式下两次退出信号检查之间的空转次数
+const TERMINATION_CHECK_SPINS: u64 = 1 << 16;
+
+/// 订阅端等待方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitMode {
+    /// 阻塞在 iceoryx2 listener 上，由发布端通知唤醒
+    #[default]
+    Event,
+    /// 空转轮询，独占一个CPU核
+    BusySpin,
+}
+
+/// 订阅端唤醒参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WakeupConfig {
+    pub mode: WaitMode,
+    /// Event 模式下单次等待的兜底超时（微秒）
+    pub max_wait_us: u64,
+}
+
+impl Default for WakeupConfig {
+    fn default() -> Self {
+        Self {
+            mode: WaitMode::Event,
+            max_wait_us: 10_000,
+        }
+    }
+}
+
+/// topic 对应的 event 服务名
+pub fn notify_service_name(topic: &str) -> String {
+    format!("{}/notify", topic)
+}
+
+/// 发布端：样本发送后通知该 topic 的订阅端
+pub struct TopicNotifier {
+    notifier: Notifier<ipc::Service>,
+}
+
+impl TopicNotifier {
+    pub fn open(node: &Node<ipc::Service>, topic: &str) -> Result<Self> {
+        let service = node
+            .service_builder(&ServiceName::new(&notify_service_name(topic))?)
+            .event()
+            .open_or_create()?;
+        let notifier = service.notifier_builder().create()?;
+
+        Ok(Self { notifier })
+    }
+
+    /// 返回被唤醒的 listener 数量
+    pub fn notify(&self) -> Result<usize> {
+        Ok(self.notifier.notify()?)
+    }
+}
+
+/// 订阅端：等待该 topic 的下一次通知
+pub struct TopicWaiter {
+    listener: Option<Listener<ipc::Service>>,
+    max_wait: Duration,
+    spins: u64,
+}
+
+impl TopicWaiter {
+    pub fn open(node: &Node<ipc::Service>, topic: &str, config: &WakeupConfig) -> Result<Self> {
+        let listener = match config.mode {
+            WaitMode::Event => {
+                let service = node
+                    .service_builder(&ServiceName::new(&notify_service_name(topic))?)
+                    .event()
+                    .open_or_create()?;
+                Some(service.listener_builder().create()?)
+            }
+            WaitMode::BusySpin => None,
+        };
+
+        Ok(Self {
+            listener,
+            max_wait: Duration::from_micros(config.max_wait_us),
+            spins: 0,
+        })
+    }
+
+    /// 等待下一次通知，收到退出信号时返回 false
+    ///
+    /// 调用方在每次返回后都应取空订阅队列：通知可能合并，超时返回时也可能有样本到达。
+    pub fn wait(&mut self, node: &Node<ipc::Service>) -> Result<bool> {
+        match &self.listener {
+            Some(listener) => listener.timed_wait_all(|_| {}, self.max_wait)?,
+            None => {
+                std::hint::spin_loop();
+                self.spins = self.spins.wrapping_add(1);
+                if !self.spins.is_multiple_of(TERMINATION_CHECK_SPINS) {
+                    return Ok(true);
+                }
+            }
+        }
+
+        Ok(matches!(node.wait(Duration::ZERO), NodeEvent::Tick))
+    }
+}
@@ -0,0 +1,286 @@
+//! Local stand-in for exchange WebSocket endpoints, for end-to-end tests of the
+//! trading engine without network access.
+//!
+//! A `MockExchange` listens on localhost and speaks the order-entry protocol of
+//! one venue: it answers heartbeats and subscriptions, acks orders in the
+//! venue's format and lets a test script how each order is answered (latency,
+//! rejects, fills, dropped replies, disconnects). Market data can be pushed to
+//! every connected client.
+
+mod venue;
+
+pub use venue::{OrderKind, OrderRequest, Venue};
+
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::{accept_async, tungstenite::Message};
+use tracing::{debug, info, warn};
+use venue::{Inbound, Outcome};
+
+/// How the mock answers an order-entry request.
+///
+/// Fill state is only visible in Binance acks; OKX and Bybit ack a filled order
+/// the same way as a resting one, as the real venues do.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Behavior {
+    /// Ack with the order resting on the book
+    Accept,
+    /// Ack with the order fully filled
+    Fill,
+    /// Ack with this quantity filled
+    PartialFill(Decimal),
+    Reject { code: i64, msg: String },
+    /// Swallow the request; the client only sees its own timeout
+    Drop,
+    /// Drop the socket without a close handshake instead of replying
+    Disconnect,
+}
+
+#[derive(Debug, Clone)]
+enum Control {
+    Push(String),
+    Disconnect,
+}
+
+struct Script {
+    default: Behavior,
+    queue: VecDeque<Behavior>,
+    latency: Duration,
+}
+
+struct Shared {
+    venue: Venue,
+    script: Mutex<Script>,
+    received: Mutex<Vec<OrderRequest>>,
+    connections: AtomicUsize,
+    accepted: AtomicUsize,
+    next_order_id: AtomicU64,
+}
+
+pub struct MockExchange {
+    addr: SocketAddr,
+    shared: Arc<Shared>,
+    control: broadcast::Sender<Control>,
+    listener: JoinHandle<()>,
+}
+
+impl MockExchange {
+    /// Listen on an ephemeral localhost port
+    pub async fn start(venue: Venue) -> anyhow::Result<Self> {
+        Self::bind(venue, "127.0.0.1:0").await
+    }
+
+    pub async fn bind(venue: Venue, addr: &str) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let (control, _) = broadcast::channel(64);
+
+        let shared = Arc::new(Shared {
+            venue,
+            script: Mutex::new(Script {
+                default: Behavior::Accept,
+                queue: VecDeque::new(),
+                latency: Duration::ZERO,
+            }),
+            received: Mutex::new(Vec::new()),
+            connections: AtomicUsize::new(0),
+            accepted: AtomicUsize::new(0),
+            next_order_id: AtomicU64::new(1),
+        });
+
+        let listener = {
+            let shared = shared.clone();
+            let control = control.clone();
+            tokio::spawn(async move {
+                loop {
+                    let stream = match listener.accept().await {
+                        Ok((stream, _)) => stream,
+                        Err(e) => {
+                            warn!("Mock exchange accept failed: {}", e);
+                            continue;
+                        }
+                    };
+                    tokio::spawn(serve(stream, shared.clone(), control.subscribe()));
+                }
+            })
+        };
+
+        info!("Mock {:?} exchange listening on {}", venue, addr);
+        Ok(Self {
+            addr,
+            shared,
+            control,
+            listener,
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!("ws://{}", self.addr)
+    }
+
+    pub fn venue(&self) -> Venue {
+        self.shared.venue
+    }
+
+    /// Behavior for requests once the scripted queue is empty; `Accept` initially
+    pub fn set_default(&self, behavior: Behavior) {
+        self.shared.script.lock().default = behavior;
+    }
+
+    /// Answer the next request this way; queued behaviors are used in order and
+    /// shared by all connections
+    pub fn enqueue(&self, behavior: Behavior) {
+        self.shared.script.lock().queue.push_back(behavior);
+    }
+
+    /// Delay before every reply to an order-entry request
+    pub fn set_latency(&self, latency: Duration) {
+        self.shared.script.lock().latency = latency;
+    }
+
+    /// Order-entry requests received so far, across all connections
+    pub fn received(&self) -> Vec<OrderRequest> {
+        self.shared.received.lock().clone()
+    }
+
+    /// Currently open client connections
+    pub fn connection_count(&self) -> usize {
+        self.shared.connections.load(Ordering::Relaxed)
+    }
+
+    /// Client connections accepted since start, including closed ones
+    pub fn accepted_count(&self) -> usize {
+        self.shared.accepted.load(Ordering::Relaxed)
+    }
+
+    /// Send a frame (market data, say) to every connected client
+    pub fn push(&self, frame: impl Into<String>) {
+        let _ = self.control.send(Control::Push(frame.into()));
+    }
+
+    /// Close every client connection with a close frame, as on venue maintenance
+    pub fn disconnect_all(&self) {
+        let _ = self.control.send(Control::Disconnect);
+    }
+}
+
+impl Drop for MockExchange {
+    fn drop(&mut self) {
+        self.listener.abort();
+        let _ = self.control.send(Control::Disconnect);
+    }
+}
+
+impl Shared {
+    fn next_behavior(&self) -> (Behavior, Duration) {
+        let mut script = self.script.lock();
+        let behavior = script.queue.pop_front().unwrap_or_else(|| script.default.clone());
+        (behavior, script.latency)
+    }
+}
+
+async fn serve(stream: TcpStream, shared: Arc<Shared>, mut control: broadcast::Receiver<Control>) {
+    let ws = match accept_async(stream).await {
+        Ok(ws) => ws,
+        Err(e) => {
+            debug!("Mock exchange handshake failed: {}", e);
+            return;
+        }
+    };
+    let (mut write, mut read) = ws.split();
+    // Delayed replies come back through here so slow acks do not block the socket
+    let (reply_tx, mut reply_rx) = mpsc::unbounded_channel::<String>();
+
+    shared.connections.fetch_add(1, Ordering::Relaxed);
+    shared.accepted.fetch_add(1, Ordering::Relaxed);
+
+    loop {
+        tokio::select! {
+            command = control.recv() => match command {
+                Ok(Control::Push(frame)) => {
+                    if write.send(Message::Text(frame)).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(Control::Disconnect) | Err(broadcast::error::RecvError::Closed) => {
+                    let _ = write.send(Message::Close(None)).await;
+                    break;
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+            },
+
+            Some(reply) = reply_rx.recv() => {
+                if write.send(Message::Text(reply)).await.is_err() {
+                    break;
+                }
+            }
+
+            msg = read.next() => {
+                let frame = match msg {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Binary(data))) => match String::from_utf8(data) {
+                        Ok(text) => text,
+                        Err(_) => continue,
+                    },
+                    Some(Ok(Message::Ping(payload))) => {
+                        if write.send(Message::Pong(payload)).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+
+                let request = match shared.venue.classify(&frame) {
+                    Inbound::Control(reply) => {
+                        if write.send(Message::Text(reply)).await.is_err() {
+                            break;
+                        }
+                        continue;
+                    }
+                    Inbound::Order(request) => request,
+                    Inbound::Ignore => {
+                        debug!("Mock exchange ignoring frame: {}", frame);
+                        continue;
+                    }
+                };
+
+                shared.received.lock().push(request.clone());
+                let (behavior, latency) = shared.next_behavior();
+                let outcome = match behavior {
+                    Behavior::Accept => Outcome::Accepted { filled: Decimal::ZERO },
+                    Behavior::Fill => Outcome::Accepted { filled: request.quantity },
+                    Behavior::PartialFill(filled) => Outcome::Accepted { filled },
+                    Behavior::Reject { code, msg } => Outcome::Rejected { code, msg },
+                    Behavior::Drop => continue,
+                    // Closing without a close frame looks like a reset to the client
+                    Behavior::Disconnect => break,
+                };
+
+                let order_id = shared.next_order_id.fetch_add(1, Ordering::Relaxed);
+                let reply = shared.venue.reply(&request, &outcome, order_id);
+                if latency.is_zero() {
+                    let _ = reply_tx.send(reply);
+                } else {
+                    let reply_tx = reply_tx.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(latency).await;
+                        let _ = reply_tx.send(reply);
+                    });
+                }
+            }
+        }
+    }
+
+    shared.connections.fetch_sub(1, Ordering::Relaxed);
+}
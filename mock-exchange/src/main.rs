@@ -0,0 +1,50 @@
+use mock_exchange::{Behavior, MockExchange, Venue};
+use std::time::Duration;
+use tracing::info;
+use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+
+const USAGE: &str = "usage: mock-exchange <binance|okex|bybit> [--port N] [--latency-ms N] [--reject CODE:MSG]";
+
+/// Serve one venue on localhost until Ctrl-C, for running the engine against
+/// `ws://127.0.0.1:<port>` by hand
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::registry()
+        .with(fmt::layer())
+        .with(EnvFilter::from_default_env().add_directive("mock_exchange=info".parse()?))
+        .init();
+
+    let mut args = std::env::args().skip(1);
+    let venue = args
+        .next()
+        .and_then(|name| Venue::parse(&name))
+        .ok_or_else(|| anyhow::anyhow!(USAGE))?;
+
+    let mut port = 0u16;
+    let mut latency = Duration::ZERO;
+    let mut behavior = Behavior::Accept;
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or_else(|| anyhow::anyhow!("{} needs a value\n{}", flag, USAGE))?;
+        match flag.as_str() {
+            "--port" => port = value.parse()?,
+            "--latency-ms" => latency = Duration::from_millis(value.parse()?),
+            "--reject" => {
+                let (code, msg) = value.split_once(':').ok_or_else(|| anyhow::anyhow!(USAGE))?;
+                behavior = Behavior::Reject {
+                    code: code.parse()?,
+                    msg: msg.to_string(),
+                };
+            }
+            _ => anyhow::bail!("unknown flag {}\n{}", flag, USAGE),
+        }
+    }
+
+    let exchange = MockExchange::bind(venue, &format!("127.0.0.1:{}", port)).await?;
+    exchange.set_default(behavior);
+    exchange.set_latency(latency);
+    info!("Serving {:?} on {}", venue, exchange.url());
+
+    tokio::signal::ctrl_c().await?;
+    info!("Received {} order requests", exchange.received().len());
+    Ok(())
+}
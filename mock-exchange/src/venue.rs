@@ -0,0 +1,334 @@
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Wire protocol a mock endpoint speaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Venue {
+    Binance,
+    Okx,
+    Bybit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderKind {
+    Place,
+    Cancel,
+    CancelAll,
+    Amend,
+}
+
+/// An order-entry request as the mock understood it
+#[derive(Debug, Clone)]
+pub struct OrderRequest {
+    pub kind: OrderKind,
+    /// Id the reply must echo (`id` / `reqId`)
+    pub request_id: Value,
+    /// Order the request refers to; empty for cancel-all
+    pub client_order_id: String,
+    pub symbol: String,
+    pub side: String,
+    pub quantity: Decimal,
+    pub price: Option<Decimal>,
+    pub raw: Value,
+}
+
+/// How the mock answers one order-entry request
+#[derive(Debug, Clone)]
+pub(crate) enum Outcome {
+    /// Order state after the request; `filled` is the executed quantity
+    Accepted { filled: Decimal },
+    Rejected { code: i64, msg: String },
+}
+
+/// What to do with an inbound frame
+pub(crate) enum Inbound {
+    /// Heartbeats and subscriptions, answered right away
+    Control(String),
+    Order(OrderRequest),
+    Ignore,
+}
+
+impl Venue {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "binance" => Some(Venue::Binance),
+            "okex" | "okx" => Some(Venue::Okx),
+            "bybit" => Some(Venue::Bybit),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn classify(&self, frame: &str) -> Inbound {
+        // OKX heartbeats are bare text
+        if frame == "ping" {
+            return match self {
+                Venue::Okx => Inbound::Control("pong".to_string()),
+                _ => Inbound::Ignore,
+            };
+        }
+
+        let Ok(json) = serde_json::from_str::<Value>(frame) else {
+            return Inbound::Ignore;
+        };
+
+        match self {
+            Venue::Binance => classify_binance(json),
+            Venue::Okx => classify_okx(json),
+            Venue::Bybit => classify_bybit(json),
+        }
+    }
+
+    pub(crate) fn reply(&self, request: &OrderRequest, outcome: &Outcome, order_id: u64) -> String {
+        let reply = match self {
+            Venue::Binance => binance_reply(request, outcome, order_id),
+            Venue::Okx => okx_reply(request, outcome, order_id),
+            Venue::Bybit => bybit_reply(request, outcome, order_id),
+        };
+        reply.to_string()
+    }
+}
+
+fn classify_binance(json: Value) -> Inbound {
+    let method = json.get("method").and_then(|v| v.as_str()).unwrap_or("");
+    let id = json.get("id").cloned().unwrap_or(Value::Null);
+
+    let kind = match method {
+        "SUBSCRIBE" | "UNSUBSCRIBE" => return Inbound::Control(json!({"result": null, "id": id}).to_string()),
+        "order.place" => OrderKind::Place,
+        "order.cancel" => OrderKind::Cancel,
+        "openOrders.cancelAll" => OrderKind::CancelAll,
+        "order.modify" => OrderKind::Amend,
+        _ => return Inbound::Ignore,
+    };
+
+    // The WS API only reads parameters nested under "params", and every
+    // order-entry request must carry the API key it was signed with
+    let Some(params) = json.get("params").filter(|v| v.is_object()) else {
+        return binance_error(id, -1102, "Mandatory parameter 'params' was not sent, was empty/null, or malformed.");
+    };
+    if str_field(params, "apiKey").is_empty() {
+        return binance_error(id, -1102, "Mandatory parameter 'apiKey' was not sent, was empty/null, or malformed.");
+    }
+    let client_order_id = match kind {
+        OrderKind::Place => str_field(params, "newClientOrderId"),
+        OrderKind::CancelAll => String::new(),
+        OrderKind::Cancel | OrderKind::Amend => str_field(params, "origClientOrderId"),
+    };
+
+    Inbound::Order(OrderRequest {
+        kind,
+        request_id: id,
+        client_order_id,
+        symbol: str_field(params, "symbol"),
+        side: str_field(params, "side"),
+        quantity: decimal_field(params, "quantity"),
+        price: params.get("price").map(|_| decimal_field(params, "price")),
+        raw: json,
+    })
+}
+
+/// Malformed requests are answered right away, before reaching the order book
+fn binance_error(id: Value, code: i64, msg: &str) -> Inbound {
+    Inbound::Control(json!({"id": id, "status": 400, "error": {"code": code, "msg": msg}}).to_string())
+}
+
+fn classify_okx(json: Value) -> Inbound {
+    let op = json.get("op").and_then(|v| v.as_str()).unwrap_or("");
+    let arg = json.pointer("/args/0").cloned().unwrap_or_else(|| json!({}));
+
+    let kind = match op {
+        "subscribe" | "unsubscribe" => {
+            return Inbound::Control(json!({"event": op, "arg": arg, "connId": "mock"}).to_string());
+        }
+        "order" => OrderKind::Place,
+        "cancel-order" => OrderKind::Cancel,
+        "amend-order" => OrderKind::Amend,
+        _ => return Inbound::Ignore,
+    };
+
+    Inbound::Order(OrderRequest {
+        kind,
+        request_id: json.get("id").cloned().unwrap_or(Value::Null),
+        client_order_id: str_field(&arg, "clOrdId"),
+        symbol: str_field(&arg, "instId"),
+        side: str_field(&arg, "side"),
+        quantity: decimal_field(&arg, "sz"),
+        price: arg.get("px").filter(|v| v.is_string()).map(|_| decimal_field(&arg, "px")),
+        raw: json,
+    })
+}
+
+fn classify_bybit(json: Value) -> Inbound {
+    let op = json.get("op").and_then(|v| v.as_str()).unwrap_or("");
+
+    let kind = match op {
+        "ping" => {
+            return Inbound::Control(
+                json!({"success": true, "ret_msg": "pong", "conn_id": "mock", "op": "ping"}).to_string(),
+            );
+        }
+        "subscribe" | "unsubscribe" => {
+            return Inbound::Control(
+                json!({"success": true, "ret_msg": "", "conn_id": "mock", "op": op}).to_string(),
+            );
+        }
+        "order.create" => OrderKind::Place,
+        // Order entry without an op is a create
+        "" if json.get("orderLinkId").is_some() => OrderKind::Place,
+        "order.cancel" => OrderKind::Cancel,
+        "order.amend" => OrderKind::Amend,
+        _ => return Inbound::Ignore,
+    };
+
+    // The trade API nests the order under "args"; flat requests are accepted too
+    let params = json.pointer("/args/0").cloned().unwrap_or_else(|| json.clone());
+
+    Inbound::Order(OrderRequest {
+        kind,
+        request_id: json.get("reqId").cloned().unwrap_or(Value::Null),
+        client_order_id: str_field(&params, "orderLinkId"),
+        symbol: str_field(&params, "symbol"),
+        side: str_field(&params, "side"),
+        quantity: decimal_field(&params, "qty"),
+        price: params.get("price").filter(|v| v.is_string()).map(|_| decimal_field(&params, "price")),
+        raw: json,
+    })
+}
+
+/// Binance WS API envelope; the only venue whose acks carry the fill state
+fn binance_reply(request: &OrderRequest, outcome: &Outcome, order_id: u64) -> Value {
+    let filled = match outcome {
+        Outcome::Accepted { filled } => *filled,
+        Outcome::Rejected { code, msg } => {
+            return json!({
+                "id": request.request_id,
+                "status": 400,
+                "error": {"code": code, "msg": msg},
+            });
+        }
+    };
+
+    let now = now_ms();
+    let result = match request.kind {
+        OrderKind::Place | OrderKind::Amend => {
+            let status = if filled.is_zero() {
+                "NEW"
+            } else if filled >= request.quantity {
+                "FILLED"
+            } else {
+                "PARTIALLY_FILLED"
+            };
+            json!({
+                "symbol": request.symbol,
+                "orderId": order_id,
+                "orderListId": -1,
+                "clientOrderId": request.client_order_id,
+                "transactTime": now,
+                "price": request.price.unwrap_or_default().to_string(),
+                "origQty": request.quantity.to_string(),
+                "executedQty": filled.to_string(),
+                "status": status,
+                "side": request.side,
+            })
+        }
+        OrderKind::Cancel => json!({
+            "symbol": request.symbol,
+            "origClientOrderId": request.client_order_id,
+            "orderId": order_id,
+            "orderListId": -1,
+            "clientOrderId": format!("mock-cxl-{}", order_id),
+            "transactTime": now,
+            "executedQty": "0",
+            "status": "CANCELED",
+        }),
+        OrderKind::CancelAll => json!([]),
+    };
+
+    json!({
+        "id": request.request_id,
+        "status": 200,
+        "result": result,
+    })
+}
+
+fn okx_reply(request: &OrderRequest, outcome: &Outcome, order_id: u64) -> Value {
+    let op = request.raw.get("op").cloned().unwrap_or(Value::Null);
+    let now = now_ms().to_string();
+    let (code, s_code, s_msg) = match outcome {
+        Outcome::Accepted { .. } => ("0".to_string(), "0".to_string(), String::new()),
+        Outcome::Rejected { code, msg } => ("1".to_string(), code.to_string(), msg.clone()),
+    };
+
+    json!({
+        "id": request.request_id,
+        "op": op,
+        "code": code,
+        "msg": "",
+        "data": [{
+            "clOrdId": request.client_order_id,
+            "ordId": order_id.to_string(),
+            "tag": "",
+            "ts": now,
+            "sCode": s_code,
+            "sMsg": s_msg,
+        }],
+        "inTime": now,
+        "outTime": now,
+    })
+}
+
+fn bybit_reply(request: &OrderRequest, outcome: &Outcome, order_id: u64) -> Value {
+    let op = match request.kind {
+        OrderKind::Place => "order.create",
+        OrderKind::Cancel => "order.cancel",
+        OrderKind::Amend => "order.amend",
+        OrderKind::CancelAll => "order.cancel-all",
+    };
+    let header = json!({"Timenow": now_ms().to_string()});
+
+    match outcome {
+        Outcome::Accepted { .. } => json!({
+            "reqId": request.request_id,
+            "retCode": 0,
+            "retMsg": "OK",
+            "op": op,
+            "data": {
+                "orderId": order_id.to_string(),
+                "orderLinkId": request.client_order_id,
+            },
+            "header": header,
+            "connId": "mock",
+        }),
+        Outcome::Rejected { code, msg } => json!({
+            "reqId": request.request_id,
+            "retCode": code,
+            "retMsg": msg,
+            "op": op,
+            "data": {},
+            "header": header,
+            "connId": "mock",
+        }),
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> String {
+    value.get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+fn decimal_field(value: &Value, key: &str) -> Decimal {
+    value.get(key)
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(Decimal::ZERO)
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
@@ -0,0 +1,266 @@
+use anyhow::{Context, Result};
+use common::ipc::{IPC_SERVICE_MARKET, IPC_SERVICE_RISK_INIT_RESPONSE, IPC_SERVICE_RISK_SUMMARY};
+use common::wakeup::WakeupConfig;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Pre/Post Processor 配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrePostProcessorConfig {
+    #[serde(default)]
+    pub risk: RiskConfig,
+    #[serde(default)]
+    pub dispatch: DispatchConfig,
+    #[serde(default)]
+    pub events: EventConfig,
+    #[serde(default)]
+    pub wakeup: WakeupConfig,
+    #[serde(default)]
+    pub persistence: PersistenceConfig,
+    #[serde(default)]
+    pub risk_report: RiskReportConfig,
+    #[serde(default)]
+    pub instruments: InstrumentConfig,
+    #[serde(default)]
+    pub fees: FeeConfig,
+    #[serde(default)]
+    pub market_data: MarketDataConfig,
+    #[serde(default)]
+    pub hedge: HedgeConfig,
+}
+
+/// 风控规则链参数，RiskInitRequest 下发的规则会覆盖其中对应的限制
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskConfig {
+    /// 信号最大延迟（毫秒）
+    pub max_signal_age_ms: i64,
+    /// 单品种最大仓位（手数）
+    pub max_position: Decimal,
+    /// 单品种最大占用资金（USDT）
+    pub max_capital: Decimal,
+    /// 单品种最大挂单数
+    pub max_pending_orders: usize,
+    /// 总资金量，敞口比例的分母
+    pub total_capital: Decimal,
+    /// 总敞口占总资金的最大比例
+    pub max_total_exposure_ratio: Decimal,
+    /// 总敞口预警比例，超过后只告警
+    pub exposure_warning_ratio: Decimal,
+    /// 同一品种两次成交之间的冷却时间（秒）
+    pub cooldown_seconds: i64,
+    /// 单品种日内最大交易次数
+    pub max_daily_trades: usize,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            max_signal_age_ms: 100,
+            max_position: Decimal::from(100),
+            max_capital: Decimal::from(5000),
+            max_pending_orders: 3,
+            total_capital: Decimal::from(1_000_000),
+            max_total_exposure_ratio: Decimal::new(3, 2),
+            exposure_warning_ratio: Decimal::new(25, 3),
+            cooldown_seconds: 60,
+            max_daily_trades: 1000,
+        }
+    }
+}
+
+/// 订单分发参数，topic 和 buffer_size 需与交易引擎的 [ipc] 配置一致
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DispatchConfig {
+    /// 执行命令 topic（引擎的 input_topic）
+    pub command_topic: String,
+    /// 执行结果 topic（引擎的 output_topic）
+    pub result_topic: String,
+    /// iceoryx2 缓冲区大小
+    pub buffer_size: usize,
+    /// 最大在途命令数，达到后暂停分发
+    pub max_in_flight: usize,
+    /// 等待执行结果的超时时间（毫秒），超时的订单状态未知，等待迟到的结果或执行报告；应大于引擎的 order_timeout_ms
+    pub result_timeout_ms: u64,
+}
+
+impl Default for DispatchConfig {
+    fn default() -> Self {
+        Self {
+            command_topic: "execution_commands".to_string(),
+            result_topic: "execution_results".to_string(),
+            buffer_size: 1000,
+            max_in_flight: 64,
+            result_timeout_ms: 10_000,
+        }
+    }
+}
+
+/// 交易事件订阅参数，topic 需与 signal-collector 的 output_topic 一致
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventConfig {
+    /// 交易事件 topic
+    pub topic: String,
+}
+
+impl Default for EventConfig {
+    fn default() -> Self {
+        Self {
+            topic: "events/trading".to_string(),
+        }
+    }
+}
+
+/// 风控消息发布参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskReportConfig {
+    /// 风控初始化响应 topic，发起初始化的客户端在此等待回复
+    pub response_topic: String,
+    /// 风控状态摘要 topic
+    pub summary_topic: String,
+    /// 状态摘要广播间隔（秒）
+    pub summary_interval_secs: u64,
+}
+
+impl Default for RiskReportConfig {
+    fn default() -> Self {
+        Self {
+            response_topic: IPC_SERVICE_RISK_INIT_RESPONSE.to_string(),
+            summary_topic: IPC_SERVICE_RISK_SUMMARY.to_string(),
+            summary_interval_secs: 5,
+        }
+    }
+}
+
+/// 标的注册表来源，需与 signal-collector 和交易引擎使用同一份市场配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InstrumentConfig {
+    /// exchanges.toml 及符号列表所在目录
+    pub market_config_dir: String,
+}
+
+impl Default for InstrumentConfig {
+    fn default() -> Self {
+        Self {
+            market_config_dir: "config".to_string(),
+        }
+    }
+}
+
+/// 手续费率，成交记账时按成交额计算手续费
+///
+/// 执行报告不带流动性标记：市价单和IOC/FOK订单按吃单计，挂单（GTC/GTX）按挂单计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeeConfig {
+    /// 挂单费率
+    pub maker_rate: Decimal,
+    /// 吃单费率
+    pub taker_rate: Decimal,
+}
+
+impl Default for FeeConfig {
+    fn default() -> Self {
+        Self {
+            maker_rate: Decimal::new(2, 4),
+            taker_rate: Decimal::new(5, 4),
+        }
+    }
+}
+
+/// 行情订阅和仓位重估参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarketDataConfig {
+    /// 标准化最优买卖价 topic（定长 PodQuote）
+    pub topic: String,
+    /// 仓位重估间隔（毫秒）
+    pub revalue_interval_ms: u64,
+    /// 行情最大时效（毫秒），过期行情不参与重估
+    pub max_quote_age_ms: i64,
+}
+
+impl Default for MarketDataConfig {
+    fn default() -> Self {
+        Self {
+            topic: IPC_SERVICE_MARKET.to_string(),
+            revalue_interval_ms: 1000,
+            max_quote_age_ms: 5000,
+        }
+    }
+}
+
+/// 对冲闭环参数：成交腿的剩余敞口达到阈值时，向 signal-collector 发布对冲信号
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HedgeConfig {
+    /// 对冲信号 topic，需在 signal-collector 的 hedge_topic 上订阅
+    pub topic: String,
+    /// 对冲信号发出后等待对冲事件的超时（毫秒），超时后剩余敞口可重新触发对冲
+    pub request_timeout_ms: i64,
+    /// 成交腿与对冲腿的配对
+    pub pairs: Vec<HedgePairConfig>,
+}
+
+impl Default for HedgeConfig {
+    fn default() -> Self {
+        Self {
+            topic: "signals/hedge".to_string(),
+            request_timeout_ms: 5000,
+            pairs: Vec::new(),
+        }
+    }
+}
+
+/// 对冲配对，标的写作 `场所:原生符号` 或 `venue_id:symbol_id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HedgePairConfig {
+    /// 成交腿
+    pub primary: String,
+    /// 对冲腿
+    pub hedge: String,
+    /// 触发阈值（对冲腿数量），剩余敞口不小于阈值时对冲
+    pub threshold: Decimal,
+}
+
+/// 状态持久化参数：预写日志记录每次状态变更，定期写快照并截断日志
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersistenceConfig {
+    /// 是否启用持久化和启动恢复
+    pub enabled: bool,
+    /// 数据目录，存放 journal.log 和 snapshot.json
+    pub dir: String,
+    /// 快照间隔（秒）
+    pub snapshot_interval_secs: u64,
+    /// 每批日志写入后是否fsync
+    pub sync: bool,
+    /// 风控状态导出文件（RiskInitRequest），关闭时和收到SIGUSR1时写入，为空不导出
+    pub risk_export_path: String,
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dir: "data/pre_post_processor".to_string(),
+            snapshot_interval_secs: 60,
+            sync: true,
+            risk_export_path: "data/pre_post_processor/risk_init.pb".to_string(),
+        }
+    }
+}
+
+impl PrePostProcessorConfig {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+}
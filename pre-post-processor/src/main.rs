@@ -1,71 +1,180 @@
+mod config;
 mod pipeline;
 mod risk_control;
 mod order;
+mod persistence;
+mod subscriber;
 
+use std::collections::HashMap;
+use std::path::Path;
 use std::rc::Rc;
 use std::cell::RefCell;
 use tokio::sync::mpsc;
 use tokio::select;
 use tokio::time::{interval, Duration};
-use tracing::{info, error, debug};
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{info, error, debug, warn};
 use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use prost::Message;
 
 use iceoryx2::prelude::*;
 use iceoryx2::port::subscriber::Subscriber;
-use common::types::{Signal, ExecutionReport};
-use common::ipc::{IPC_SERVICE_SIGNAL, IPC_SERVICE_EXECUTION};
+use iceoryx2::port::publisher::Publisher;
+use common::instrument::{InstrumentId, InstrumentRegistry};
+use common::types::{Signal, SignalData, SignalType, ExecutionReport, ExecutionType, Quote, Side};
+use common::messages::EventMessage;
+use common::ipc::{IPC_SERVICE_SIGNAL, IPC_SERVICE_EXECUTION, SIGNAL_FRAME_SIZE, RISK_FRAME_SIZE};
+use common::binary::{encode_to_array, WireMessage};
+use common::risk_proto::risk_control::{RiskInitResponse, RiskStateSummary};
+use common::pod::{PodEvent, PodExecutionReport, PodQuote};
+use common::wakeup::TopicNotifier;
+use common::execution::{ExecutionCommand, ExecutionResult};
+use common::codec::{encode_command, decode_result, IpcFrame};
 
+use crate::config::PrePostProcessorConfig;
 use crate::pipeline::{
-    pipeline::{PreProcessContext, PostProcessContext, execute_pre_pipeline, execute_post_pipeline},
-    shared_state::SharedState,
+    pipeline::{PreProcessContext, PreProcessOutcome, PostProcessContext, execute_pre_pipeline, execute_post_pipeline},
+    shared_state::{PositionInfo, SharedState},
+    event_router::{route_event, EventAction},
+    price_book::PriceBook,
+    hedge::{HedgeLeg, HedgeRequest, PendingHedges},
 };
 use crate::risk_control::{
     risk_state::RiskState,
-    risk_initializer::RiskInitializer,
+    risk_initializer::{RiskInitializer, state_summary},
+    risk_exporter::RiskExporter,
+    risk_rules::RiskRuleChain,
 };
-use crate::order::order_manager::OrderManager;
+use crate::order::{order_manager::OrderManager, order_state::OrderState, dispatcher::OrderDispatcher};
+use crate::persistence::{write_atomic, JournalRecord, StateSnapshot, StateStore};
+
+/// 执行命令发布端口，发布后通知交易引擎
+struct CommandPort {
+    publisher: Publisher<ipc::Service, IpcFrame, ()>,
+    notifier: TopicNotifier,
+}
+
+/// 风控消息发布端口（定长帧），发布后通知订阅方
+struct RiskPort {
+    publisher: Publisher<ipc::Service, [u8; RISK_FRAME_SIZE], ()>,
+    notifier: TopicNotifier,
+}
+
+/// 对冲信号发布端口（定长帧），发布后通知 signal-collector
+struct SignalPort {
+    publisher: Publisher<ipc::Service, [u8; SIGNAL_FRAME_SIZE], ()>,
+    notifier: TopicNotifier,
+}
+
+/// 风控初始化响应和状态摘要的发布端口
+struct RiskReportPorts {
+    response: RiskPort,
+    summary: RiskPort,
+}
 
 /// Pre/Post Processor 主进程
 pub struct PrePostProcessor {
+    config: PrePostProcessorConfig,
+    
+    // 标的注册表（事件路由、下单和风控导入导出共用）
+    instruments: Rc<InstrumentRegistry>,
+    
     // 共享状态（单线程，使用Rc<RefCell>）
     shared_state: Rc<RefCell<SharedState>>,
     
+    // 最新行情，定期重估持仓
+    price_book: PriceBook,
+    
+    // 已发回 signal-collector、尚未回到Pre-process的对冲请求
+    pending_hedges: PendingHedges,
+    
     // 风控状态
     risk_state: RiskState,
     
     // 风控初始化器
     risk_initializer: RiskInitializer,
     
+    // 风控规则链（本地配置，风控初始化后按下发规则重建）
+    rule_chain: RiskRuleChain,
+    
     // 订单管理器
     order_manager: OrderManager,
     
+    // 订单分发器（发往交易引擎）
+    dispatcher: OrderDispatcher,
+    
+    // 状态持久化（未启用时为None）
+    store: Option<StateStore>,
+    
+    // 本轮待写入journal的仓位和风控变更
+    pending_records: Vec<JournalRecord>,
+    
+    // 最近一次成功的风控初始化信号，写入快照供恢复时重建规则链
+    risk_init: Option<Signal>,
+    
     // 内部队列
     pre_queue_rx: mpsc::UnboundedReceiver<Signal>,
     pre_queue_tx: mpsc::UnboundedSender<Signal>,
+    event_queue_rx: mpsc::UnboundedReceiver<EventMessage>,
+    event_queue_tx: mpsc::UnboundedSender<EventMessage>,
     post_queue_rx: mpsc::UnboundedReceiver<ExecutionReport>,
     post_queue_tx: mpsc::UnboundedSender<ExecutionReport>,
+    result_queue_rx: mpsc::UnboundedReceiver<ExecutionResult>,
+    result_queue_tx: mpsc::UnboundedSender<ExecutionResult>,
+    quote_queue_rx: mpsc::UnboundedReceiver<Quote>,
+    quote_queue_tx: mpsc::UnboundedSender<Quote>,
     
     // 统计信息
     processed_signals: usize,
+    processed_events: usize,
     processed_reports: usize,
+    rejections: HashMap<String, usize>,  // 按规则统计的拒绝次数
 }
 
 impl PrePostProcessor {
-    pub fn new() -> Self {
+    pub fn new(config: PrePostProcessorConfig, instruments: InstrumentRegistry) -> Self {
         let (pre_tx, pre_rx) = mpsc::unbounded_channel();
+        let (event_tx, event_rx) = mpsc::unbounded_channel();
         let (post_tx, post_rx) = mpsc::unbounded_channel();
+        let (result_tx, result_rx) = mpsc::unbounded_channel();
+        let (quote_tx, quote_rx) = mpsc::unbounded_channel();
+        let price_book = PriceBook::new(chrono::Duration::milliseconds(config.market_data.max_quote_age_ms));
+        let pending_hedges = PendingHedges::new(chrono::Duration::milliseconds(config.hedge.request_timeout_ms));
+        let rule_chain = RiskRuleChain::from_config(&config.risk);
+        let instruments = Rc::new(instruments);
+        let dispatcher = OrderDispatcher::new(config.dispatch.clone(), instruments.clone());
+        let order_manager = OrderManager::new().with_fees(config.fees.clone());
         
         Self {
+            config,
+            instruments: instruments.clone(),
             shared_state: Rc::new(RefCell::new(SharedState::new())),
+            price_book,
+            pending_hedges,
             risk_state: RiskState::new(),
-            risk_initializer: RiskInitializer::new(),
-            order_manager: OrderManager::new(),
+            risk_initializer: RiskInitializer::new(instruments),
+            rule_chain,
+            order_manager,
+            dispatcher,
+            store: None,
+            pending_records: Vec::new(),
+            risk_init: None,
             pre_queue_rx: pre_rx,
             pre_queue_tx: pre_tx,
+            event_queue_rx: event_rx,
+            event_queue_tx: event_tx,
             post_queue_rx: post_rx,
             post_queue_tx: post_tx,
+            result_queue_rx: result_rx,
+            result_queue_tx: result_tx,
+            quote_queue_rx: quote_rx,
+            quote_queue_tx: quote_tx,
             processed_signals: 0,
+            processed_events: 0,
             processed_reports: 0,
+            rejections: HashMap::new(),
         }
     }
     
@@ -73,34 +182,122 @@ impl PrePostProcessor {
     pub async fn run(mut self) -> Result<()> {
         info!("Starting Pre/Post Processor");
         
-        // 初始化IceOryx2订阅
-        let signal_subscriber = self.setup_signal_subscriber()?;
-        let execution_subscriber = self.setup_execution_subscriber()?;
+        // 先恢复状态，再开始接收新的输入；对冲配对以当前配置为准
+        self.recover()?;
+        self.configure_contract_values();
+        self.configure_hedges();
+        
+        // 启动IceOryx2订阅线程，样本解码后送入内部队列
+        let wakeup = &self.config.wakeup;
+        subscriber::spawn(
+            "signal",
+            IPC_SERVICE_SIGNAL.to_string(),
+            wakeup.clone(),
+            Self::open_signal_subscriber,
+            |frame: &[u8; SIGNAL_FRAME_SIZE]| Signal::from_bytes(bytes::Bytes::copy_from_slice(frame)),
+            self.pre_queue_tx.clone(),
+        )?;
+        subscriber::spawn(
+            "event",
+            self.config.events.topic.clone(),
+            wakeup.clone(),
+            Self::open_event_subscriber,
+            |pod: &PodEvent| EventMessage::try_from(pod),
+            self.event_queue_tx.clone(),
+        )?;
+        subscriber::spawn(
+            "execution report",
+            IPC_SERVICE_EXECUTION.to_string(),
+            wakeup.clone(),
+            Self::open_execution_subscriber,
+            |pod: &PodExecutionReport| ExecutionReport::try_from(pod),
+            self.post_queue_tx.clone(),
+        )?;
+        subscriber::spawn(
+            "quote",
+            self.config.market_data.topic.clone(),
+            wakeup.clone(),
+            Self::open_quote_subscriber,
+            |pod: &PodQuote| Quote::try_from(pod),
+            self.quote_queue_tx.clone(),
+        )?;
+        
+        // 初始化风控消息和对冲信号发布
+        let risk_ports = self.setup_risk_report_ports()?;
+        let hedge_port = self.setup_hedge_port()?;
+        
+        // 初始化与交易引擎之间的命令/结果通道
+        let command_port = self.setup_command_port()?;
+        let buffer_size = self.config.dispatch.buffer_size;
+        subscriber::spawn(
+            "execution result",
+            self.config.dispatch.result_topic.clone(),
+            wakeup.clone(),
+            move |node: &Node<ipc::Service>, topic: &str| Self::open_result_subscriber(node, topic, buffer_size),
+            |frame: &IpcFrame| decode_result(frame),
+            self.result_queue_tx.clone(),
+        )?;
         
         // 创建定时器
         let mut stats_timer = interval(Duration::from_secs(60));
+        let mut dispatch_timer = interval(Duration::from_secs(1)); // 检查执行结果超时
         let mut cleanup_timer = interval(Duration::from_secs(3600)); // 每小时清理
+        let mut snapshot_timer = interval(Duration::from_secs(self.config.persistence.snapshot_interval_secs.max(1)));
+        let mut summary_timer = interval(Duration::from_secs(self.config.risk_report.summary_interval_secs.max(1)));
+        let mut revalue_timer = interval(Duration::from_millis(self.config.market_data.revalue_interval_ms.max(1)));
+        
+        // SIGINT/SIGTERM 正常退出，SIGUSR1 导出风控状态
+        let mut terminate = signal(SignalKind::terminate())?;
+        let mut export_request = signal(SignalKind::user_defined1())?;
         
         loop {
             select! {
-                // 处理信号订阅
-                _ = Self::poll_signals(&signal_subscriber, &self.pre_queue_tx) => {
-                    // 信号已放入队列
+                // 处理Pre-process队列
+                Some(signal) = self.pre_queue_rx.recv() => {
+                    if signal.signal_type == SignalType::RiskControlInit {
+                        self.process_risk_init(&signal, &risk_ports.response)?;
+                    } else {
+                        self.process_signal(signal).await?;
+                    }
+                    self.dispatch_orders(&command_port)?;
                 }
                 
-                // 处理执行报告订阅
-                _ = Self::poll_executions(&execution_subscriber, &self.post_queue_tx) => {
-                    // 执行报告已放入队列
+                // 处理交易事件队列
+                Some(event) = self.event_queue_rx.recv() => {
+                    self.process_event(event, &command_port).await?;
+                    self.dispatch_orders(&command_port)?;
                 }
                 
-                // 处理Pre-process队列
-                Some(signal) = self.pre_queue_rx.recv() => {
-                    self.process_signal(signal).await?;
+                // 处理交易引擎返回的执行结果
+                Some(result) = self.result_queue_rx.recv() => {
+                    self.dispatcher.complete(&result, &mut self.order_manager);
+                    self.dispatch_orders(&command_port)?;
                 }
                 
                 // 处理Post-process队列
                 Some(report) = self.post_queue_rx.recv() => {
-                    self.process_execution_report(report).await?;
+                    self.process_execution_report(report, &hedge_port).await?;
+                }
+                
+                // 更新最新行情，重估在定时器上进行
+                Some(quote) = self.quote_queue_rx.recv() => {
+                    self.price_book.update(quote);
+                }
+                
+                // 定时按标记价格重估持仓
+                _ = revalue_timer.tick() => {
+                    self.revalue_positions();
+                }
+                
+                // 超时未返回结果的命令让出在途名额，订单状态未知待确认；超时的对冲请求不再计入在途
+                _ = dispatch_timer.tick() => {
+                    if self.dispatcher.expire() > 0 {
+                        self.dispatch_orders(&command_port)?;
+                    }
+                    let expired = self.pending_hedges.expire(Utc::now());
+                    if expired > 0 {
+                        warn!("{} hedge requests expired without a hedge event", expired);
+                    }
                 }
                 
                 // 定时输出统计
@@ -110,122 +307,543 @@ impl PrePostProcessor {
                 
                 // 定时清理
                 _ = cleanup_timer.tick() => {
-                    self.cleanup();
+                    self.cleanup()?;
+                }
+                
+                // 定时快照
+                _ = snapshot_timer.tick() => {
+                    self.take_snapshot()?;
+                }
+                
+                // 定时广播风控状态摘要
+                _ = summary_timer.tick() => {
+                    Self::publish_risk_message(&risk_ports.summary, &self.risk_summary());
+                }
+                
+                // 按需导出风控状态
+                _ = export_request.recv() => {
+                    if let Err(e) = self.export_risk_state() {
+                        error!("Failed to export risk state: {:?}", e);
+                    }
+                }
+                
+                _ = tokio::signal::ctrl_c() => {
+                    info!("Received SIGINT, shutting down");
+                    break;
+                }
+                
+                _ = terminate.recv() => {
+                    info!("Received SIGTERM, shutting down");
+                    break;
                 }
             }
+            
+            // 本轮处理完成，状态变更落盘
+            self.commit()?;
         }
+        
+        self.shutdown()
+    }
+    
+    /// 退出前写入最终快照并导出风控状态
+    fn shutdown(&mut self) -> Result<()> {
+        self.take_snapshot()?;
+        self.export_risk_state()?;
+        self.print_statistics();
+        info!("Pre/Post Processor stopped");
+        Ok(())
     }
     
-    /// 设置信号订阅
-    fn setup_signal_subscriber(&self) -> Result<Subscriber<iceoryx2::service::ipc::Service, Signal, ()>> {
-        let node = NodeBuilder::new().create::<iceoryx2::service::ipc::Service>()?;
+    /// 把当前风控状态导出为 RiskInitRequest，供重启或备用的处理器初始化
+    fn export_risk_state(&self) -> Result<()> {
+        let path = Path::new(&self.config.persistence.risk_export_path);
+        if path.as_os_str().is_empty() {
+            return Ok(());
+        }
         
+        let shared_state = self.shared_state.borrow();
+        let request = RiskExporter {
+            risk_state: &self.risk_state,
+            shared_state: &shared_state,
+            rules: self.risk_initializer.get_risk_rules(),
+            calculator: self.risk_initializer.get_risk_calculator(),
+            instruments: &self.instruments,
+            global_config: self.risk_initializer.get_global_config(),
+            fills: self.order_manager.all_fills().collect(),
+        }
+        .export();
+        
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        write_atomic(path, &request.encode_to_vec())?;
+        
+        info!(
+            "Exported risk state to {}: {} symbols, {} positions",
+            path.display(), request.symbol_states.len(), request.positions.len()
+        );
+        Ok(())
+    }
+    
+    /// 打开状态存储，从最近的快照和之后的journal恢复状态
+    fn recover(&mut self) -> Result<()> {
+        let persistence = &self.config.persistence;
+        if !persistence.enabled {
+            warn!("State persistence disabled, starting from empty state");
+            return Ok(());
+        }
+        
+        let (store, recovered) = StateStore::open(Path::new(&persistence.dir), persistence.sync)?;
+        self.store = Some(store);
+        if recovered.is_empty() {
+            return Ok(());
+        }
+        
+        // 重放风控初始化以重建规则链，风控状态和计算器以恢复的为准
+        if let Some(signal) = &recovered.risk_init {
+            match self.risk_initializer.process_init_signal(signal) {
+                Ok(response) if response.success => {
+                    self.rule_chain = RiskRuleChain::from_rules(
+                        &self.config.risk,
+                        self.risk_initializer.get_risk_rules(),
+                    );
+                }
+                Ok(response) => error!("Failed to replay risk control initialization: {}", response.message),
+                Err(e) => error!("Failed to replay risk control initialization: {}", e),
+            }
+        }
+        *self.risk_initializer.get_risk_calculator_mut() = recovered.calculator;
+        self.risk_init = recovered.risk_init;
+        self.risk_state = recovered.risk;
+        *self.shared_state.borrow_mut() = recovered.shared;
+        self.order_manager = OrderManager::restore(recovered.orders).with_fees(self.config.fees.clone());
+        
+        // 崩溃前已发出但未收到结果的命令无法确认，等待执行报告推进订单状态
+        let active = self.order_manager.get_active_orders();
+        let submitting = active.iter().filter(|o| o.state == OrderState::Submitting).count();
+        if submitting > 0 {
+            warn!("{} orders were submitting before restart, waiting for execution reports", submitting);
+        }
+        info!(
+            "Recovered {} active orders, {} positions",
+            active.len(),
+            self.shared_state.borrow().positions.len()
+        );
+        
+        Ok(())
+    }
+    
+    /// 把本轮处理产生的状态变更作为一批写入journal
+    fn commit(&mut self) -> Result<()> {
+        let mut records = self.order_manager.take_changes();
+        records.append(&mut self.pending_records);
+        
+        match &mut self.store {
+            Some(store) => store.append(records),
+            None => Ok(()),
+        }
+    }
+    
+    /// 写入全量快照并截断journal；快照失败时journal保留，不影响运行
+    fn take_snapshot(&mut self) -> Result<()> {
+        self.commit()?;
+        
+        let Some(store) = &mut self.store else {
+            return Ok(());
+        };
+        
+        let snapshot = StateSnapshot {
+            seq: 0,
+            taken_at: Utc::now(),
+            shared: self.shared_state.borrow().clone(),
+            risk: self.risk_state.clone(),
+            calculator: self.risk_initializer.get_risk_calculator().clone(),
+            risk_init: self.risk_init.clone(),
+            orders: self.order_manager.snapshot(),
+        };
+        
+        match store.write_snapshot(snapshot) {
+            Ok(()) => debug!("State snapshot written"),
+            Err(e) => error!("Failed to write state snapshot: {:?}", e),
+        }
+        Ok(())
+    }
+    
+    /// 打开信号订阅（定长 Signal 帧）
+    fn open_signal_subscriber(node: &Node<ipc::Service>, topic: &str) -> Result<Subscriber<ipc::Service, [u8; SIGNAL_FRAME_SIZE], ()>> {
         let service = node
-            .service_builder(&iceoryx2::prelude::ServiceName::new(IPC_SERVICE_SIGNAL)?)
-            .publish_subscribe::<Signal>()
+            .service_builder(&ServiceName::new(topic)?)
+            .publish_subscribe::<[u8; SIGNAL_FRAME_SIZE]>()
             .open_or_create()?;
         
-        let subscriber = service
-            .subscriber_builder()
-            .create()?;
+        Ok(service.subscriber_builder().create()?)
+    }
+    
+    /// 打开交易事件订阅（signal-collector 发布的定长 PodEvent）
+    fn open_event_subscriber(node: &Node<ipc::Service>, topic: &str) -> Result<Subscriber<ipc::Service, PodEvent, ()>> {
+        let service = node
+            .service_builder(&ServiceName::new(topic)?)
+            .publish_subscribe::<PodEvent>()
+            .open_or_create()?;
         
-        info!("Signal subscriber created");
-        Ok(subscriber)
+        Ok(service.subscriber_builder().create()?)
     }
     
-    /// 设置执行报告订阅
-    fn setup_execution_subscriber(&self) -> Result<Subscriber<iceoryx2::service::ipc::Service, ExecutionReport, ()>> {
-        let node = NodeBuilder::new().create::<iceoryx2::service::ipc::Service>()?;
+    /// 打开执行报告订阅（交易引擎用户数据流推送的定长 PodExecutionReport）
+    fn open_execution_subscriber(node: &Node<ipc::Service>, topic: &str) -> Result<Subscriber<ipc::Service, PodExecutionReport, ()>> {
+        let service = node
+            .service_builder(&ServiceName::new(topic)?)
+            .publish_subscribe::<PodExecutionReport>()
+            .open_or_create()?;
         
+        Ok(service.subscriber_builder().create()?)
+    }
+    
+    /// 打开行情订阅（行情进程发布的定长 PodQuote）
+    fn open_quote_subscriber(node: &Node<ipc::Service>, topic: &str) -> Result<Subscriber<ipc::Service, PodQuote, ()>> {
         let service = node
-            .service_builder(&iceoryx2::prelude::ServiceName::new(IPC_SERVICE_EXECUTION)?)
-            .publish_subscribe::<ExecutionReport>()
+            .service_builder(&ServiceName::new(topic)?)
+            .publish_subscribe::<PodQuote>()
             .open_or_create()?;
         
-        let subscriber = service
-            .subscriber_builder()
+        Ok(service.subscriber_builder().create()?)
+    }
+    
+    /// 打开执行结果订阅（交易引擎的结果输出）
+    fn open_result_subscriber(node: &Node<ipc::Service>, topic: &str, buffer_size: usize) -> Result<Subscriber<ipc::Service, IpcFrame, ()>> {
+        // 服务参数须与交易引擎一致，否则open_or_create会因配置不兼容失败
+        let service = node
+            .service_builder(&ServiceName::new(topic)?)
+            .publish_subscribe::<IpcFrame>()
+            .subscriber_max_buffer_size(buffer_size)
+            .enable_safe_overflow(false)
+            .open_or_create()?;
+        
+        Ok(service.subscriber_builder().buffer_size(buffer_size).create()?)
+    }
+    
+    /// 设置执行命令发布（交易引擎的命令输入）
+    fn setup_command_port(&self) -> Result<CommandPort> {
+        let dispatch = &self.config.dispatch;
+        let node = NodeBuilder::new().create::<ipc::Service>()?;
+        
+        // 服务参数须与交易引擎一致，否则open_or_create会因配置不兼容失败
+        let service = node
+            .service_builder(&ServiceName::new(&dispatch.command_topic)?)
+            .publish_subscribe::<IpcFrame>()
+            .subscriber_max_buffer_size(dispatch.buffer_size)
+            .enable_safe_overflow(false)
+            .open_or_create()?;
+        
+        let publisher = service
+            .publisher_builder()
             .create()?;
+        let notifier = TopicNotifier::open(&node, &dispatch.command_topic)?;
+        
+        info!("Execution command publisher created on {}", dispatch.command_topic);
+        Ok(CommandPort { publisher, notifier })
+    }
+    
+    /// 设置风控消息发布（初始化响应、状态摘要）
+    fn setup_risk_report_ports(&self) -> Result<RiskReportPorts> {
+        let config = &self.config.risk_report;
+        let node = NodeBuilder::new().create::<ipc::Service>()?;
+        
+        let open = |topic: &str| -> Result<RiskPort> {
+            let service = node
+                .service_builder(&ServiceName::new(topic)?)
+                .publish_subscribe::<[u8; RISK_FRAME_SIZE]>()
+                .open_or_create()?;
+            let publisher = service.publisher_builder().create()?;
+            let notifier = TopicNotifier::open(&node, topic)?;
+            Ok(RiskPort { publisher, notifier })
+        };
         
-        info!("Execution report subscriber created");
-        Ok(subscriber)
+        let ports = RiskReportPorts {
+            response: open(&config.response_topic)?,
+            summary: open(&config.summary_topic)?,
+        };
+        info!(
+            "Risk report publishers created on {} and {}",
+            config.response_topic, config.summary_topic
+        );
+        Ok(ports)
     }
     
-    /// 轮询信号
-    async fn poll_signals(
-        subscriber: &Subscriber<iceoryx2::service::ipc::Service, Signal, ()>,
-        tx: &mpsc::UnboundedSender<Signal>
-    ) {
-        while let Some(sample) = subscriber.receive().unwrap() {
-            let signal = sample.payload().clone();
-            debug!("Received signal: {:?}", signal.id);
+    /// 设置对冲信号发布（signal-collector 的对冲输入）
+    fn setup_hedge_port(&self) -> Result<SignalPort> {
+        let topic = &self.config.hedge.topic;
+        let node = NodeBuilder::new().create::<ipc::Service>()?;
+        
+        let service = node
+            .service_builder(&ServiceName::new(topic)?)
+            .publish_subscribe::<[u8; SIGNAL_FRAME_SIZE]>()
+            .open_or_create()?;
+        let publisher = service.publisher_builder().create()?;
+        let notifier = TopicNotifier::open(&node, topic)?;
+        
+        info!("Hedge signal publisher created on {}", topic);
+        Ok(SignalPort { publisher, notifier })
+    }
+    
+    /// 按标的定义设置合约面值，恢复的仓位也以当前定义为准
+    fn configure_contract_values(&self) {
+        let mut state = self.shared_state.borrow_mut();
+        for instrument in self.instruments.instruments() {
+            state.set_contract_value(instrument.id, instrument.filters.contract_value);
+        }
+    }
+    
+    /// 按配置设置对冲配对，换算比例为成交腿与对冲腿的合约面值之比；每个标的只能属于一个配对
+    fn configure_hedges(&self) {
+        let mut state = self.shared_state.borrow_mut();
+        state.hedge_legs.clear();
+        state.hedge_thresholds.clear();
+        
+        for pair in &self.config.hedge.pairs {
+            let resolve = |text: &str| self.instruments.parse(text).and_then(|id| self.instruments.get(id));
+            let (Some(primary), Some(hedge)) = (resolve(&pair.primary), resolve(&pair.hedge)) else {
+                warn!("Skipping hedge pair {} -> {}: unknown instrument", pair.primary, pair.hedge);
+                continue;
+            };
+            if primary.id == hedge.id || state.hedge_pair(primary.id).is_some() || state.hedge_pair(hedge.id).is_some() {
+                warn!("Skipping hedge pair {} -> {}: instrument already paired", primary, hedge);
+                continue;
+            }
+            if hedge.filters.contract_value <= Decimal::ZERO {
+                warn!("Skipping hedge pair {} -> {}: invalid contract value", primary, hedge);
+                continue;
+            }
             
-            if let Err(e) = tx.send(signal) {
-                error!("Failed to queue signal: {:?}", e);
+            let ratio = primary.filters.contract_value / hedge.filters.contract_value;
+            state.set_hedge_pair(primary.id, HedgeLeg { instrument: hedge.id, ratio }, pair.threshold);
+            info!("Hedging {} with {} (ratio={}, threshold={})", primary, hedge, ratio, pair.threshold);
+        }
+    }
+    
+    /// 发布风控消息；失败只记录日志，不影响交易处理
+    fn publish_risk_message<M: WireMessage>(port: &RiskPort, message: &M) {
+        let sent = encode_to_array::<M, RISK_FRAME_SIZE>(message)
+            .map_err(|e| e.to_string())
+            .and_then(|frame| {
+                let sample = port.publisher.loan_uninit().map_err(|e| format!("{:?}", e))?;
+                sample.write_payload(frame).send().map_err(|e| format!("{:?}", e))
+            });
+        
+        match sent {
+            Ok(_) => {
+                if let Err(e) = port.notifier.notify() {
+                    warn!("Failed to notify {:?} subscribers: {}", M::MESSAGE_TYPE, e);
+                }
             }
+            Err(e) => error!("Failed to publish {:?}: {}", M::MESSAGE_TYPE, e),
         }
     }
     
-    /// 轮询执行报告
-    async fn poll_executions(
-        subscriber: &Subscriber<iceoryx2::service::ipc::Service, ExecutionReport, ()>,
-        tx: &mpsc::UnboundedSender<ExecutionReport>
-    ) {
-        while let Some(sample) = subscriber.receive().unwrap() {
-            let report = sample.payload().clone();
-            debug!("Received execution report: {:?}", report.order_id);
-            
-            if let Err(e) = tx.send(report) {
-                error!("Failed to queue execution report: {:?}", e);
+    /// 当前风控状态摘要；总资金与规则链一致，风控初始化下发的优先
+    fn risk_summary(&self) -> RiskStateSummary {
+        let rules = self.risk_initializer.get_risk_rules();
+        let total_capital = if self.risk_init.is_some() && rules.total_capital > Decimal::ZERO {
+            rules.total_capital
+        } else {
+            self.config.risk.total_capital
+        };
+        state_summary(self.risk_state.get_summary(), total_capital, &self.instruments)
+    }
+    
+    /// 分发待提交订单到交易引擎
+    fn dispatch_orders(&mut self, port: &CommandPort) -> Result<()> {
+        for command in self.dispatcher.drain(&mut self.order_manager) {
+            self.publish_command(command, port)?;
+        }
+        Ok(())
+    }
+    
+    /// 发布执行命令，失败时交由分发器处理
+    fn publish_command(
+        &mut self,
+        command: ExecutionCommand,
+        port: &CommandPort
+    ) -> Result<()> {
+        // 命令发出前先把订单状态变更写入journal
+        self.commit()?;
+        
+        let sent = encode_command(&command)
+            .map_err(|e| e.to_string())
+            .and_then(|frame| {
+                let sample = port.publisher.loan_uninit().map_err(|e| format!("{:?}", e))?;
+                sample.write_payload(frame).send().map_err(|e| format!("{:?}", e))
+            });
+        
+        match sent {
+            Ok(_) => {
+                debug!(
+                    "Dispatched {:?} command {} for order {:?}",
+                    command.action, command.id, command.client_order_id
+                );
+                if let Err(e) = port.notifier.notify() {
+                    warn!("Failed to notify trading engine: {}", e);
+                }
+            }
+            Err(e) => {
+                error!("Failed to publish command {}: {}", command.id, e);
+                self.dispatcher.fail(command.id, e, &mut self.order_manager);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// 处理交易事件：开仓/平仓/对冲走Pre-process Pipeline，撤单/改单作用于已有订单
+    async fn process_event(
+        &mut self,
+        event: EventMessage,
+        port: &CommandPort
+    ) -> Result<()> {
+        debug!("Processing trading event: seq={}, priority={:?}", event.sequence_id, event.event.priority());
+        self.processed_events += 1;
+        
+        match route_event(&event, &self.instruments) {
+            EventAction::PreProcess(signal) => self.process_signal(signal).await?,
+            EventAction::Cancel { order_id, reason } => {
+                info!("Cancel requested for order {}: {}", order_id, reason);
+                self.cancel_order(&order_id, port)?;
+            }
+            EventAction::Modify { order_id, new_price, new_quantity, reason } => {
+                info!("Amend requested for order {}: {}", order_id, reason);
+                self.amend_order(&order_id, new_price, new_quantity, port)?;
             }
         }
+        
+        Ok(())
+    }
+    
+    /// 撤单：未提交的订单在本地取消，已提交的订单向交易引擎发送撤单命令并保持撤单中直到交易所确认
+    fn cancel_order(&mut self, order_id: &str, port: &CommandPort) -> Result<()> {
+        let Some(order) = self.order_manager.find_order(order_id).cloned() else {
+            warn!("Cancel for unknown order {}", order_id);
+            return Ok(());
+        };
+        
+        match self.order_manager.cancel_order(&order.client_order_id) {
+            Ok(true) => match self.dispatcher.cancel(&order) {
+                Ok(command) => self.publish_command(command, port)?,
+                Err(e) => error!("Failed to build cancel for order {}: {}", order.client_order_id, e),
+            },
+            Ok(false) => {}
+            Err(e) => warn!("Failed to cancel order {}: {}", order.client_order_id, e),
+        }
+        
+        Ok(())
+    }
+    
+    /// 改单：校验后向交易引擎发送改单命令，新价格/数量在交易所确认后生效
+    fn amend_order(
+        &mut self,
+        order_id: &str,
+        new_price: Option<Decimal>,
+        new_quantity: Option<Decimal>,
+        port: &CommandPort
+    ) -> Result<()> {
+        let Some(order) = self.order_manager.find_order(order_id).cloned() else {
+            warn!("Amend for unknown order {}", order_id);
+            return Ok(());
+        };
+        
+        if let Err(e) = self.order_manager.amend_order(&order.client_order_id, new_price, new_quantity) {
+            warn!("Failed to amend order {}: {}", order.client_order_id, e);
+            return Ok(());
+        }
+        
+        match self.dispatcher.amend(&order, new_price, new_quantity) {
+            Ok(command) => self.publish_command(command, port)?,
+            Err(e) => error!("Failed to build amend for order {}: {}", order.client_order_id, e),
+        }
+        
+        Ok(())
+    }
+    
+    /// 处理风控初始化信号，结果回复到初始化响应 topic
+    fn process_risk_init(&mut self, signal: &Signal, port: &RiskPort) -> Result<()> {
+        info!("Processing risk control initialization signal");
+        
+        let response = match self.risk_initializer.process_init_signal(signal) {
+            Ok(mut response) => {
+                if response.success {
+                    // 获取初始化后的风控状态
+                    self.risk_state = self.risk_initializer.get_risk_state().clone();
+                    info!("Risk control initialized: {}", response.message);
+                    
+                    // 更新共享状态中的风控信息
+                    self.shared_state.borrow_mut().update_risk_state(self.risk_state.get_summary());
+                    
+                    // 按下发的规则重建规则链
+                    self.rule_chain = RiskRuleChain::from_rules(
+                        &self.config.risk,
+                        self.risk_initializer.get_risk_rules(),
+                    );
+                    
+                    // 初始化整体替换了风控状态，立即写快照
+                    self.risk_init = Some(signal.clone());
+                    self.take_snapshot()?;
+                    
+                    // 响应中的摘要按规则链生效的总资金计算
+                    response.state_summary = Some(self.risk_summary());
+                } else {
+                    error!("Risk control initialization failed: {}", response.message);
+                }
+                response
+            }
+            Err(e) => {
+                error!("Failed to process risk control init signal: {}", e);
+                RiskInitResponse {
+                    success: false,
+                    message: e,
+                    state_summary: None,
+                    request_id: signal.id.clone(),
+                }
+            }
+        };
+        
+        // 回复发起初始化的客户端
+        Self::publish_risk_message(port, &response);
+        
+        self.processed_signals += 1;
+        Ok(())
     }
     
     /// 处理信号（Pre-process Pipeline）
     async fn process_signal(&mut self, signal: Signal) -> Result<()> {
         debug!("Processing signal: {}", signal.id);
         
-        // 检查是否为风控初始化消息
-        if signal.signal_type == common::types::SignalType::RiskControlInit {
-            info!("Processing risk control initialization signal");
-            
-            match self.risk_initializer.process_init_signal(&signal) {
-                Ok(response) => {
-                    if response.success {
-                        // 获取初始化后的风控状态
-                        self.risk_state = self.risk_initializer.get_risk_state().clone();
-                        info!("Risk control initialized: {}", response.message);
-                        
-                        // 更新共享状态中的风控信息
-                        self.shared_state.borrow_mut().update_risk_state(self.risk_state.get_summary());
-                    } else {
-                        error!("Risk control initialization failed: {}", response.message);
-                    }
-                }
-                Err(e) => {
-                    error!("Failed to process risk control init signal: {}", e);
-                }
+        // 对冲事件已回到Pre-process，在途请求由对冲订单接替（被拒绝时缺口在下一次回报重新触发）
+        if let SignalData::Hedge { hedge_id, .. } = &signal.data {
+            let primary = signal.metadata.get("primary_instrument").and_then(|text| text.parse::<InstrumentId>().ok());
+            if let Some(primary) = primary {
+                self.pending_hedges.complete(primary, hedge_id);
             }
-            
-            self.processed_signals += 1;
-            return Ok(());
         }
         
         // 创建Pipeline上下文
         let ctx = PreProcessContext::new(signal.clone(), self.shared_state.clone());
         
         // 执行Pre-process Pipeline（链式调用）
-        match execute_pre_pipeline(ctx).await {
-            Ok(Some(_order)) => {
-                // 创建订单
-                let order = self.order_manager.create_order_from_signal(signal)?;
+        match execute_pre_pipeline(ctx, &self.rule_chain, &self.risk_state, &self.instruments).await {
+            Ok(PreProcessOutcome::Order(order)) => {
+                // 登记Pipeline生成的订单
+                let order = self.order_manager.create_order(*order)?;
                 
-                // 验证订单
+                // 验证订单，进入待提交队列
                 self.order_manager.validate_order(&order.client_order_id)?;
                 
                 info!("Order created and validated: {}", order.client_order_id);
             }
-            Ok(None) => {
-                debug!("Signal {} rejected by pipeline", signal.id);
+            Ok(PreProcessOutcome::Rejected(rejection)) => {
+                info!(
+                    "Signal {} ({}) rejected by {}: limit={}, observed={}",
+                    signal.id, signal.instrument, rejection.rule, rejection.limit, rejection.observed
+                );
+                *self.rejections.entry(rejection.rule).or_insert(0) += 1;
             }
             Err(e) => {
                 error!("Pipeline error for signal {}: {:?}", signal.id, e);
@@ -236,36 +854,176 @@ impl PrePostProcessor {
         Ok(())
     }
     
-    /// 处理执行报告（Post-process Pipeline）
-    async fn process_execution_report(&mut self, report: ExecutionReport) -> Result<()> {
+    /// 处理执行报告（Post-process Pipeline），剩余敞口需要对冲时发回 signal-collector
+    async fn process_execution_report(&mut self, report: ExecutionReport, hedge_port: &SignalPort) -> Result<()> {
         debug!("Processing execution report: {}", report.order_id);
         
-        // 更新订单状态
-        self.order_manager.process_execution_report(report.clone())?;
+        // 超时未收到结果的订单由报告确认已到达交易所
+        self.dispatcher.settle(&report, &mut self.order_manager);
+        
+        // 更新订单状态，成交类报告得到本次成交；无法匹配的报告跳过，但已知标的的成交仍计入仓位
+        let fill = match self.order_manager.process_execution_report(report.clone()) {
+            Ok(fill) => fill,
+            Err(e) => {
+                warn!("Skipping execution report for order {}: {}", report.client_order_id, e);
+                if report.execution_type != ExecutionType::Trade || self.instruments.get(report.instrument).is_none() {
+                    return Ok(());
+                }
+                Some(self.order_manager.record_unmatched_fill(&report))
+            }
+        };
+        
+        let instrument = report.instrument;
+        let before = self.position(instrument);
         
         // 创建Pipeline上下文
-        let ctx = PostProcessContext::new(report, self.shared_state.clone());
+        let ctx = PostProcessContext::new(report.clone(), self.shared_state.clone())
+            .with_fill(fill)
+            .with_pending_hedge(self.pending_hedge(instrument));
         
         // 执行Post-process Pipeline（链式调用）
-        if let Err(e) = execute_post_pipeline(ctx).await {
-            error!("Post-process pipeline error: {:?}", e);
+        match execute_post_pipeline(ctx).await {
+            Ok(Some(request)) => self.fork_hedge(request, hedge_port),
+            Ok(None) => {}
+            Err(e) => error!("Post-process pipeline error: {:?}", e),
         }
         
+        // 风控状态跟随仓位账本（仓位、占用资金、成交次数滑动窗口等）
+        let change = self.position(instrument).change_since(&before);
+        self.risk_state.process_execution(&report, &change);
+        
+        // 记录本次成交的已实现盈亏，供盈亏规则使用
+        let pnl = change.realized_pnl;
+        if !pnl.is_zero() {
+            self.risk_state.record_pnl(instrument, pnl);
+            let calculator = self.risk_initializer.get_risk_calculator_mut();
+            calculator.add_pnl(instrument, pnl);
+            self.risk_state.metrics = calculator.calculate_metrics();
+        }
+        
+        self.journal_position(instrument, pnl);
+        
         self.processed_reports += 1;
         Ok(())
     }
     
+    /// 标的所在对冲配对的在途对冲：已发出的对冲请求和未完成的对冲订单（对冲腿有符号数量）
+    fn pending_hedge(&self, instrument: InstrumentId) -> Decimal {
+        let Some((primary, leg)) = self.shared_state.borrow().hedge_pair(instrument) else {
+            return Decimal::ZERO;
+        };
+        
+        let in_flight: Decimal = self.order_manager
+            .get_active_orders()
+            .iter()
+            .filter(|order| order.is_hedge && order.instrument == leg.instrument)
+            .map(|order| match order.side {
+                Side::Buy => order.remaining_quantity,
+                Side::Sell => -order.remaining_quantity,
+            })
+            .sum();
+        in_flight + self.pending_hedges.quantity(primary)
+    }
+    
+    /// 对冲信号发回 signal-collector（信号分叉点）并计入在途；发布失败不计入，下一次回报重新计算缺口
+    fn fork_hedge(&mut self, request: HedgeRequest, port: &SignalPort) {
+        let sent = encode_to_array::<Signal, SIGNAL_FRAME_SIZE>(&request.to_signal())
+            .map_err(|e| e.to_string())
+            .and_then(|frame| {
+                let sample = port.publisher.loan_uninit().map_err(|e| format!("{:?}", e))?;
+                sample.write_payload(frame).send().map_err(|e| format!("{:?}", e))
+            });
+        
+        match sent {
+            Ok(_) => {
+                if let Err(e) = port.notifier.notify() {
+                    warn!("Failed to notify signal collector: {}", e);
+                }
+                debug!("Forked hedge {} for {}", request.hedge_id, request.instrument);
+                self.pending_hedges.insert(request, Utc::now());
+            }
+            Err(e) => error!("Failed to publish hedge signal {}: {}", request.hedge_id, e),
+        }
+    }
+    
+    /// 记录执行报告对品种仓位和风控状态的变更，随本轮一起写入journal
+    fn journal_position(&mut self, instrument: InstrumentId, pnl: Decimal) {
+        let state = self.shared_state.borrow();
+        if let Some(position) = state.positions.get(&instrument) {
+            self.pending_records.push(JournalRecord::Position {
+                position: position.clone(),
+                quota: state.risk_quotas.get(&instrument).cloned(),
+                total_exposure: state.total_exposure,
+            });
+        }
+        
+        if let Some(symbol_state) = self.risk_state.symbol_states.get(&instrument) {
+            self.pending_records.push(JournalRecord::SymbolRisk(symbol_state.clone()));
+        }
+        self.pending_records.push(JournalRecord::GlobalRisk {
+            state: self.risk_state.global_state.clone(),
+            metrics: self.risk_state.metrics.clone(),
+        });
+        
+        if !pnl.is_zero() {
+            self.pending_records.push(JournalRecord::Pnl { instrument, pnl });
+        }
+    }
+    
+    /// 按最新行情重估持仓，更新品种未实现盈亏和敞口历史
+    ///
+    /// 估值随快照持久化，不逐次写入journal；恢复后下一次重估即可追上行情
+    fn revalue_positions(&mut self) {
+        let now = Utc::now();
+        let (changes, total_exposure) = {
+            let mut state = self.shared_state.borrow_mut();
+            let changes = state.revalue(|id| {
+                self.instruments.get(id).and_then(|instrument| self.price_book.mark_price(instrument, now))
+            });
+            (changes, state.total_exposure)
+        };
+        
+        self.risk_state.revalue(&changes);
+        
+        let calculator = self.risk_initializer.get_risk_calculator_mut();
+        calculator.add_exposure(total_exposure);
+        self.risk_state.metrics = calculator.calculate_metrics();
+        
+        debug!("Revalued {} positions, total exposure {}", changes.len(), total_exposure);
+    }
+    
+    /// 品种当前仓位，无持仓记录时为空仓
+    fn position(&self, instrument: InstrumentId) -> PositionInfo {
+        let state = self.shared_state.borrow();
+        state
+            .positions
+            .get(&instrument)
+            .cloned()
+            .unwrap_or_else(|| PositionInfo::new(instrument).with_contract_value(state.contract_value(instrument)))
+    }
+    
     /// 输出统计信息
     fn print_statistics(&self) {
         info!("=== Statistics ===");
         info!("Processed signals: {}", self.processed_signals);
+        info!("Processed events: {}", self.processed_events);
         info!("Processed reports: {}", self.processed_reports);
+        for (rule, count) in &self.rejections {
+            info!("Rejected by {}: {}", rule, count);
+        }
         
         let order_stats = self.order_manager.get_stats();
         info!("Active orders: {}", order_stats.active_orders);
         info!("Filled orders: {}", order_stats.filled_orders);
         info!("Success rate: {:.2}%", order_stats.success_rate * 100.0);
         
+        let dispatch_stats = self.dispatcher.get_stats();
+        info!(
+            "Dispatched commands: {} (in flight: {}, succeeded: {}, failed: {}, timed out: {}, unknown: {})",
+            dispatch_stats.dispatched, dispatch_stats.in_flight, dispatch_stats.succeeded,
+            dispatch_stats.failed, dispatch_stats.timed_out, dispatch_stats.unknown
+        );
+        
         let risk_summary = self.risk_state.get_summary();
         info!("Risk level: {:?}", risk_summary.risk_level);
         info!("Total exposure: {}", risk_summary.total_exposure);
@@ -273,7 +1031,7 @@ impl PrePostProcessor {
     }
     
     /// 清理任务
-    fn cleanup(&mut self) {
+    fn cleanup(&mut self) -> Result<()> {
         debug!("Running cleanup tasks");
         
         // 清理已完成订单
@@ -282,9 +1040,8 @@ impl PrePostProcessor {
         // 检查并重置日内统计
         self.risk_state.check_daily_reset();
         
-        // 清理共享状态中的过期数据
-        let state = self.shared_state.borrow_mut();
-        state.persist();
+        // 清理和日内重置不写journal，直接写快照
+        self.take_snapshot()
     }
 }
 
@@ -300,8 +1057,22 @@ async fn main() -> Result<()> {
     
     info!("Pre/Post Processor starting...");
     
+    // 加载配置，文件不存在时使用默认风控参数
+    let config_path = std::env::var("PPP_CONFIG_PATH")
+        .unwrap_or_else(|_| "config/pre_post_processor.toml".to_string());
+    let config = if std::path::Path::new(&config_path).exists() {
+        PrePostProcessorConfig::from_file(&config_path)?
+    } else {
+        warn!("Config file {} not found, using default risk limits", config_path);
+        PrePostProcessorConfig::default()
+    };
+    
+    // 标的注册表与 signal-collector、交易引擎使用同一份市场配置
+    let instruments = InstrumentRegistry::load(&config.instruments.market_config_dir)?;
+    info!("Loaded {} instruments from {}", instruments.len(), config.instruments.market_config_dir);
+    
     // 创建并运行处理器
-    let processor = PrePostProcessor::new();
+    let processor = PrePostProcessor::new(config, instruments);
     
     // 运行主循环
     if let Err(e) = processor.run().await {
@@ -2,8 +2,10 @@ use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
+use common::instrument::InstrumentId;
 use crate::order::order_state::OrderState;
 
 /// 套利组合管理器 - 管理MT（Maker-Taker）套利订单对
@@ -19,12 +21,12 @@ pub struct ArbitrageManager {
 }
 
 /// 套利订单对
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArbitragePair {
     pub id: String,                        // 套利组合ID
     pub maker_order_id: Option<String>,    // Maker订单ID
     pub taker_order_id: Option<String>,    // Taker订单ID
-    pub symbol: String,                    // 交易对
+    pub instrument: InstrumentId,          // 交易标的
     pub quantity: Decimal,                 // 数量
     pub maker_price: Decimal,              // Maker价格
     pub taker_price: Decimal,              // Taker价格
@@ -38,7 +40,7 @@ pub struct ArbitragePair {
 }
 
 /// 套利状态
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ArbitrageState {
     Created,        // 已创建
     MakerPending,   // 等待Maker订单
@@ -115,11 +117,47 @@ impl ArbitrageManager {
         }
     }
     
+    /// 从持久化的套利组合恢复，统计按恢复后的组合重新计算
+    pub fn restore(pairs: HashMap<String, ArbitragePair>) -> Self {
+        let mut manager = Self::new();
+        
+        for pair in pairs.values() {
+            for order_id in pair.maker_order_id.iter().chain(pair.taker_order_id.iter()) {
+                manager.order_to_arbitrage.insert(order_id.clone(), pair.id.clone());
+            }
+            
+            let stats = &mut manager.stats;
+            stats.total_pairs += 1;
+            stats.total_expected_profit += pair.expected_profit;
+            match pair.state {
+                ArbitrageState::Completed => stats.successful_pairs += 1,
+                ArbitrageState::PartialSuccess => stats.partial_success_pairs += 1,
+                ArbitrageState::Failed => stats.failed_pairs += 1,
+                _ => {}
+            }
+            if pair.state.is_active() {
+                stats.active_pairs += 1;
+            }
+        }
+        
+        let stats = &mut manager.stats;
+        stats.completed_pairs = stats.successful_pairs + stats.partial_success_pairs + stats.failed_pairs;
+        stats.update_success_rate();
+        
+        manager.pairs = pairs;
+        manager
+    }
+    
+    /// 所有套利组合（用于快照）
+    pub fn pairs(&self) -> &HashMap<String, ArbitragePair> {
+        &self.pairs
+    }
+    
     /// 创建套利组合
     pub fn create_pair(
         &mut self,
         id: String,
-        symbol: String,
+        instrument: InstrumentId,
         quantity: Decimal,
         maker_price: Decimal,
         taker_price: Decimal,
@@ -131,7 +169,7 @@ impl ArbitrageManager {
             id: id.clone(),
             maker_order_id: None,
             taker_order_id: None,
-            symbol,
+            instrument,
             quantity,
             maker_price,
             taker_price,
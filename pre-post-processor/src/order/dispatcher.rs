@@ -0,0 +1,645 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use anyhow::{Result, bail};
+use rust_decimal::Decimal;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use common::instrument::InstrumentRegistry;
+use common::types::{ExecutionReport, OrderType, Side, TimeInForce};
+use common::execution::{
+    CommandAction, ExecutionCommand, ExecutionResult,
+    OrderSide as EngineSide, OrderType as EngineOrderType, TimeInForce as EngineTimeInForce,
+};
+
+use crate::config::DispatchConfig;
+use crate::order::{order::Order, order_manager::OrderManager};
+
+/// 在途命令
+#[derive(Debug, Clone)]
+struct InFlight {
+    client_order_id: String,
+    action: CommandAction,
+    sent_at: Instant,
+}
+
+/// 订单分发器 - 从OrderManager的优先级队列取出订单，转换为交易引擎的ExecutionCommand，
+/// 并根据引擎返回的ExecutionResult推进订单状态
+pub struct OrderDispatcher {
+    config: DispatchConfig,
+    instruments: Rc<InstrumentRegistry>,
+    in_flight: HashMap<Uuid, InFlight>,
+    // 超时未收到结果或结果未知的下单命令，订单保持提交中，由迟到的执行结果或执行报告确认
+    unknown: HashMap<Uuid, InFlight>,
+    
+    // 统计信息
+    dispatched: usize,
+    succeeded: usize,
+    failed: usize,
+    timed_out: usize,
+}
+
+/// 分发统计
+#[derive(Debug, Clone, Copy)]
+pub struct DispatchStats {
+    pub in_flight: usize,
+    pub unknown: usize,
+    pub dispatched: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub timed_out: usize,
+}
+
+impl OrderDispatcher {
+    pub fn new(config: DispatchConfig, instruments: Rc<InstrumentRegistry>) -> Self {
+        Self {
+            config,
+            instruments,
+            in_flight: HashMap::new(),
+            unknown: HashMap::new(),
+            dispatched: 0,
+            succeeded: 0,
+            failed: 0,
+            timed_out: 0,
+        }
+    }
+    
+    /// 取出待提交订单并转换为执行命令，在途命令达到上限时停止；
+    /// 返回的命令已登记为在途，发送失败时需调用 `fail`
+    pub fn drain(&mut self, orders: &mut OrderManager) -> Vec<ExecutionCommand> {
+        let mut commands = Vec::new();
+        
+        while self.in_flight.len() < self.config.max_in_flight.max(1) {
+            let Some(order) = orders.get_next_pending_order() else {
+                break;
+            };
+            let order_id = order.client_order_id.clone();
+            
+            let command = match self.to_command(&order) {
+                Ok(command) => command,
+                Err(e) => {
+                    // 无法转换的订单重试也不会成功
+                    if let Err(e) = orders.mark_failed(&order_id, e.to_string()) {
+                        error!("Failed to mark order {} failed: {}", order_id, e);
+                    }
+                    self.failed += 1;
+                    continue;
+                }
+            };
+            
+            if let Err(e) = orders.mark_submitting(&order_id) {
+                warn!("Order {} cannot be submitted: {}", order_id, e);
+                continue;
+            }
+            
+            self.track(&command, order_id);
+            self.dispatched += 1;
+            commands.push(command);
+        }
+        
+        commands
+    }
+    
+    /// 生成已提交订单的撤单命令并登记为在途
+    pub fn cancel(&mut self, order: &Order) -> Result<ExecutionCommand> {
+        let mut command = self.to_command(order)?;
+        command.action = CommandAction::Cancel {
+            orig_client_order_id: order.client_order_id.clone(),
+        };
+        
+        self.track(&command, order.client_order_id.clone());
+        Ok(command)
+    }
+    
+    /// 生成已提交订单的改单命令并登记为在途，新价格/数量在交易所确认后生效
+    pub fn amend(
+        &mut self,
+        order: &Order,
+        new_price: Option<Decimal>,
+        new_quantity: Option<Decimal>,
+    ) -> Result<ExecutionCommand> {
+        let mut command = self.to_command(order)?;
+        command.action = CommandAction::Amend {
+            orig_client_order_id: order.client_order_id.clone(),
+            new_price,
+            new_quantity,
+        };
+        
+        self.track(&command, order.client_order_id.clone());
+        Ok(command)
+    }
+    
+    /// 命令未能发出（如IPC发送失败），下单命令按提交失败处理
+    pub fn fail(&mut self, command_id: Uuid, reason: String, orders: &mut OrderManager) {
+        let Some(in_flight) = self.in_flight.remove(&command_id) else {
+            return;
+        };
+        
+        if in_flight.action == CommandAction::Place {
+            self.failed += 1;
+            Self::submit_failed(orders, &in_flight.client_order_id, reason);
+        } else {
+            warn!("{:?} for order {} not sent: {}", in_flight.action, in_flight.client_order_id, reason);
+        }
+    }
+    
+    /// 处理引擎返回的执行结果，返回对应的订单ID
+    pub fn complete(&mut self, result: &ExecutionResult, orders: &mut OrderManager) -> Option<String> {
+        let in_flight = match self.in_flight.remove(&result.command_id) {
+            Some(in_flight) => in_flight,
+            None => {
+                // 超时后才到达的结果，订单仍在提交中时按正常结果确认
+                let Some(in_flight) = self.unknown.remove(&result.command_id) else {
+                    // 发送失败或已由执行报告确认的命令
+                    debug!("Result for unknown command {}", result.command_id);
+                    return None;
+                };
+                info!(
+                    "Late result for order {} after {:?}",
+                    in_flight.client_order_id, in_flight.sent_at.elapsed()
+                );
+                in_flight
+            }
+        };
+        let order_id = in_flight.client_order_id.clone();
+        
+        // 撤单/改单的结果由用户数据流的回报推进订单状态，这里只记录失败
+        if in_flight.action != CommandAction::Place {
+            if !result.success {
+                warn!(
+                    "{:?} for order {} failed: {}",
+                    in_flight.action, order_id, result.error.as_deref().unwrap_or("rejected by trading engine")
+                );
+            }
+            return Some(order_id);
+        }
+        
+        let exchange_order_id = result.selected_response
+            .as_ref()
+            .map(|response| response.order_id.clone())
+            .filter(|id| !id.is_empty());
+        
+        match (result.success, exchange_order_id) {
+            (true, Some(exchange_order_id)) => {
+                self.succeeded += 1;
+                debug!(
+                    "Order {} submitted as {} in {:?}",
+                    order_id, exchange_order_id, in_flight.sent_at.elapsed()
+                );
+                if let Err(e) = orders.mark_submitted(&order_id, exchange_order_id) {
+                    error!("Failed to mark order {} submitted: {}", order_id, e);
+                }
+            }
+            (true, None) => {
+                self.failed += 1;
+                Self::submit_failed(orders, &order_id, "engine reported success without an exchange order id".to_string());
+            }
+            (false, _) if result.outcome_unknown => {
+                // 引擎没有收到交易所确认，订单可能已受理，用同一个client_order_id重试可能重复下单
+                warn!(
+                    "Order {} outcome unknown: {}, waiting for an execution report",
+                    order_id, result.error.as_deref().unwrap_or("no ack from exchange")
+                );
+                self.unknown.insert(result.command_id, in_flight);
+            }
+            (false, _) => {
+                self.failed += 1;
+                let reason = result.error
+                    .clone()
+                    .or_else(|| result.selected_response.as_ref().and_then(|r| r.error.clone()))
+                    .unwrap_or_else(|| "rejected by trading engine".to_string());
+                Self::submit_failed(orders, &order_id, reason);
+            }
+        }
+        
+        Some(order_id)
+    }
+    
+    /// 超时未收到结果的命令不再占用在途名额，返回超时数量。
+    /// 下单可能已到达交易所，订单保持提交中（状态未知），不按失败重试，
+    /// 由迟到的执行结果（`complete`）或执行报告（`settle`）确认
+    pub fn expire(&mut self) -> usize {
+        let timeout = Duration::from_millis(self.config.result_timeout_ms);
+        let expired: Vec<Uuid> = self.in_flight
+            .iter()
+            .filter(|(_, in_flight)| in_flight.sent_at.elapsed() >= timeout)
+            .map(|(id, _)| *id)
+            .collect();
+        
+        for command_id in &expired {
+            if let Some(in_flight) = self.in_flight.remove(command_id) {
+                if in_flight.action != CommandAction::Place {
+                    warn!("{:?} for order {} got no result within {:?}", in_flight.action, in_flight.client_order_id, timeout);
+                    continue;
+                }
+                self.timed_out += 1;
+                warn!(
+                    "Order {} got no result within {:?}, state unknown until a result or execution report arrives",
+                    in_flight.client_order_id, timeout
+                );
+                self.unknown.insert(*command_id, in_flight);
+            }
+        }
+        
+        expired.len()
+    }
+    
+    /// 状态未知的订单收到执行报告：订单已到达交易所，补记交易所订单ID，
+    /// 之后的状态由报告推进，迟到的执行结果不再处理
+    pub fn settle(&mut self, report: &ExecutionReport, orders: &mut OrderManager) {
+        let Some(command_id) = self.unknown
+            .iter()
+            .find(|(_, in_flight)| in_flight.client_order_id == report.client_order_id)
+            .map(|(id, _)| *id)
+        else {
+            return;
+        };
+        self.unknown.remove(&command_id);
+        
+        if report.order_id.is_empty() {
+            return;
+        }
+        info!("Order {} confirmed by execution report as {}", report.client_order_id, report.order_id);
+        if let Err(e) = orders.mark_submitted(&report.client_order_id, report.order_id.clone()) {
+            error!("Failed to mark order {} submitted: {}", report.client_order_id, e);
+        }
+    }
+    
+    /// 获取统计信息
+    pub fn get_stats(&self) -> DispatchStats {
+        DispatchStats {
+            in_flight: self.in_flight.len(),
+            unknown: self.unknown.len(),
+            dispatched: self.dispatched,
+            succeeded: self.succeeded,
+            failed: self.failed,
+            timed_out: self.timed_out,
+        }
+    }
+    
+    /// 订单转换为交易引擎的下单命令，交易所、市场类型和原生符号由标的决定
+    pub fn to_command(&self, order: &Order) -> Result<ExecutionCommand> {
+        let Some(instrument) = self.instruments.get(order.instrument) else {
+            bail!("order {} has unknown instrument {}", order.client_order_id, order.instrument);
+        };
+        // 重试的订单只提交未成交部分
+        let quantity = order.remaining_quantity.min(order.quantity);
+        if quantity <= Decimal::ZERO {
+            bail!("order {} has non-positive quantity {}", order.client_order_id, quantity);
+        }
+        
+        let (order_type, post_only) = match order.order_type {
+            OrderType::Market => (EngineOrderType::Market, false),
+            OrderType::Limit => (EngineOrderType::Limit, false),
+            OrderType::PostOnly => (EngineOrderType::Limit, true),
+        };
+        
+        let price = match order_type {
+            EngineOrderType::Market => None,
+            _ if order.price > Decimal::ZERO => Some(order.price),
+            _ => bail!("limit order {} has no price", order.client_order_id),
+        };
+        
+        let time_in_force = match order.time_in_force {
+            TimeInForce::GTC => EngineTimeInForce::GTC,
+            TimeInForce::IOC => EngineTimeInForce::IOC,
+            TimeInForce::FOK => EngineTimeInForce::FOK,
+            TimeInForce::GTX => EngineTimeInForce::GTX,
+        };
+        
+        Ok(ExecutionCommand {
+            id: Uuid::new_v4(),
+            instrument: order.instrument,
+            exchange: instrument.exchange.key().to_string(),
+            market_type: instrument.market_type.as_str().to_string(),
+            symbol: instrument.symbol.clone(),
+            side: match order.side {
+                Side::Buy => EngineSide::Buy,
+                Side::Sell => EngineSide::Sell,
+            },
+            order_type,
+            quantity,
+            price,
+            time_in_force,
+            client_order_id: Some(order.client_order_id.clone()),
+            reduce_only: order.reduce_only,
+            post_only,
+            action: CommandAction::Place,
+        })
+    }
+    
+    fn track(&mut self, command: &ExecutionCommand, client_order_id: String) {
+        self.in_flight.insert(command.id, InFlight {
+            client_order_id,
+            action: command.action.clone(),
+            sent_at: Instant::now(),
+        });
+    }
+    
+    fn submit_failed(orders: &mut OrderManager, order_id: &str, reason: String) {
+        info!("Order {} submission failed: {}", order_id, reason);
+        if let Err(e) = orders.mark_submit_failed(order_id, reason) {
+            error!("Failed to mark order {} submit failed: {}", order_id, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::instrument::{Instrument, InstrumentId, MarketType};
+    use common::types::{Exchange, Signal, SignalData, SignalType};
+    use common::execution::{OrderResponse, OrderStatus};
+    use crate::order::order_state::OrderState;
+    
+    const BINANCE_SPOT: InstrumentId = InstrumentId::new(1, 1);
+    const OKX_SWAP: InstrumentId = InstrumentId::new(6, 1);
+    const UNKNOWN: InstrumentId = InstrumentId::new(9, 9);
+    
+    fn dispatcher(config: DispatchConfig) -> OrderDispatcher {
+        let mut instruments = InstrumentRegistry::new();
+        instruments.insert(Instrument::new(BINANCE_SPOT, Exchange::Binance, MarketType::Spot, "binance_spot", "BTCUSDT"));
+        instruments.insert(Instrument::new(OKX_SWAP, Exchange::OKX, MarketType::Futures, "okex-swap", "BTC-USDT-SWAP"));
+        OrderDispatcher::new(config, Rc::new(instruments))
+    }
+    
+    fn order(instrument: InstrumentId, price: f64, quantity: f64) -> Order {
+        let mut signal = Signal::new(SignalType::Market, SignalData::Market { market_data: String::new() });
+        signal.instrument = instrument;
+        signal.side = Some(Side::Sell);
+        signal.price = Some(price);
+        signal.quantity = Some(quantity);
+        Order::from_signal(&signal)
+    }
+    
+    fn submit(orders: &mut OrderManager, order: Order) -> String {
+        let order = orders.create_order(order).unwrap();
+        orders.validate_order(&order.client_order_id).unwrap();
+        order.client_order_id
+    }
+    
+    fn result(command: &ExecutionCommand, exchange_order_id: Option<&str>) -> ExecutionResult {
+        match exchange_order_id {
+            Some(id) => ExecutionResult {
+                command_id: command.id,
+                success: true,
+                outcome_unknown: false,
+                responses: vec![],
+                selected_response: Some(OrderResponse {
+                    order_id: id.to_string(),
+                    client_order_id: command.client_order_id.clone().unwrap_or_default(),
+                    symbol: command.symbol.clone(),
+                    status: OrderStatus::New,
+                    executed_qty: Decimal::ZERO,
+                    executed_price: None,
+                    timestamp: 0,
+                    error: None,
+                }),
+                error: None,
+            },
+            None => ExecutionResult::failed(command.id, "insufficient balance"),
+        }
+    }
+    
+    fn report(client_order_id: &str, status: common::types::OrderStatus, filled_quantity: f64) -> ExecutionReport {
+        let execution_type = match status {
+            common::types::OrderStatus::PartiallyFilled | common::types::OrderStatus::Filled => common::types::ExecutionType::Trade,
+            common::types::OrderStatus::Cancelled => common::types::ExecutionType::Cancelled,
+            _ => common::types::ExecutionType::New,
+        };
+        ExecutionReport {
+            order_id: "1001".to_string(),
+            client_order_id: client_order_id.to_string(),
+            instrument: BINANCE_SPOT,
+            side: Side::Sell,
+            order_type: OrderType::Limit,
+            price: 100.0,
+            quantity: 1.0,
+            filled_quantity,
+            status,
+            execution_type,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+    
+    #[test]
+    fn test_to_command_maps_order_fields() {
+        let dispatcher = dispatcher(DispatchConfig::default());
+        
+        let market = order(BINANCE_SPOT, 100.0, 2.0);
+        let command = dispatcher.to_command(&market).unwrap();
+        assert_eq!(command.instrument, BINANCE_SPOT);
+        assert_eq!(command.exchange, "binance");
+        assert_eq!(command.market_type, "spot");
+        assert_eq!(command.symbol, "BTCUSDT");
+        assert!(matches!(command.side, EngineSide::Sell));
+        assert!(matches!(command.order_type, EngineOrderType::Market));
+        assert_eq!(command.price, None);
+        assert_eq!(command.quantity, Decimal::from(2));
+        assert_eq!(command.client_order_id.as_deref(), Some(market.client_order_id.as_str()));
+        
+        let mut post_only = order(OKX_SWAP, 100.0, 1.0);
+        post_only.order_type = OrderType::PostOnly;
+        post_only.time_in_force = TimeInForce::GTX;
+        let command = dispatcher.to_command(&post_only).unwrap();
+        assert_eq!(command.exchange, "okex");
+        assert_eq!(command.market_type, "futures");
+        assert_eq!(command.symbol, "BTC-USDT-SWAP");
+        assert!(matches!(command.order_type, EngineOrderType::Limit));
+        assert!(command.post_only);
+        assert_eq!(command.price, Some(Decimal::from(100)));
+        assert!(matches!(command.time_in_force, EngineTimeInForce::GTX));
+        
+        let mut no_price = order(OKX_SWAP, 0.0, 1.0);
+        no_price.order_type = OrderType::Limit;
+        assert!(dispatcher.to_command(&no_price).is_err());
+        assert!(dispatcher.to_command(&order(UNKNOWN, 100.0, 1.0)).is_err());
+        assert!(dispatcher.to_command(&order(BINANCE_SPOT, 100.0, 0.0)).is_err());
+    }
+    
+    #[test]
+    fn test_drain_respects_max_in_flight() {
+        let mut orders = OrderManager::new();
+        let mut dispatcher = dispatcher(DispatchConfig {
+            max_in_flight: 2,
+            ..DispatchConfig::default()
+        });
+        for _ in 0..3 {
+            submit(&mut orders, order(BINANCE_SPOT, 100.0, 1.0));
+        }
+        
+        let commands = dispatcher.drain(&mut orders);
+        assert_eq!(commands.len(), 2);
+        assert!(dispatcher.drain(&mut orders).is_empty());
+        
+        dispatcher.complete(&result(&commands[0], Some("1001")), &mut orders);
+        assert_eq!(dispatcher.drain(&mut orders).len(), 1);
+        assert_eq!(dispatcher.get_stats().in_flight, 2);
+    }
+    
+    #[test]
+    fn test_complete_drives_order_state() {
+        let mut orders = OrderManager::new();
+        let mut dispatcher = dispatcher(DispatchConfig::default());
+        let accepted = submit(&mut orders, order(BINANCE_SPOT, 100.0, 1.0));
+        let rejected = submit(&mut orders, order(BINANCE_SPOT, 100.0, 1.0));
+        let invalid = submit(&mut orders, order(UNKNOWN, 100.0, 1.0));
+        
+        let commands = dispatcher.drain(&mut orders);
+        assert_eq!(commands.len(), 2);
+        assert_eq!(orders.get_order_status(&invalid), Some(OrderState::Failed));
+        
+        for command in &commands {
+            let order_id = command.client_order_id.clone().unwrap();
+            let exchange_order_id = (order_id == accepted).then_some("1001");
+            assert_eq!(dispatcher.complete(&result(command, exchange_order_id), &mut orders), Some(order_id));
+        }
+        
+        let order = orders.get_order(&accepted).unwrap();
+        assert_eq!(order.state, OrderState::Submitted);
+        assert_eq!(order.exchange_order_id.as_deref(), Some("1001"));
+        
+        // 提交失败的订单重新排队
+        let order = orders.get_order(&rejected).unwrap();
+        assert_eq!(order.state, OrderState::Validated);
+        assert_eq!(order.retry_count, 1);
+        let retried = dispatcher.drain(&mut orders);
+        assert_eq!(retried.len(), 1);
+        assert_eq!(retried[0].client_order_id.as_deref(), Some(rejected.as_str()));
+        
+        // 超时后到达的结果被忽略
+        dispatcher.fail(retried[0].id, "publish failed".to_string(), &mut orders);
+        assert_eq!(dispatcher.complete(&result(&retried[0], Some("1002")), &mut orders), None);
+        assert_eq!(orders.get_order(&rejected).unwrap().retry_count, 2);
+    }
+    
+    #[test]
+    fn test_timed_out_place_settles_late() {
+        let mut orders = OrderManager::new();
+        let mut dispatcher = dispatcher(DispatchConfig {
+            result_timeout_ms: 0,
+            ..DispatchConfig::default()
+        });
+        let by_result = submit(&mut orders, order(BINANCE_SPOT, 100.0, 1.0));
+        let by_report = submit(&mut orders, order(BINANCE_SPOT, 100.0, 1.0));
+        let commands = dispatcher.drain(&mut orders);
+        assert_eq!(commands.len(), 2);
+        
+        // 超时的订单保持提交中，不重试也不重新排队
+        assert_eq!(dispatcher.expire(), 2);
+        for order_id in [&by_result, &by_report] {
+            let order = orders.get_order(order_id).unwrap();
+            assert_eq!(order.state, OrderState::Submitting);
+            assert_eq!(order.retry_count, 0);
+        }
+        assert!(dispatcher.drain(&mut orders).is_empty());
+        let stats = dispatcher.get_stats();
+        assert_eq!((stats.in_flight, stats.unknown, stats.timed_out), (0, 2, 2));
+        
+        // 迟到的执行结果确认订单
+        let late = commands.iter().find(|c| c.client_order_id.as_deref() == Some(by_result.as_str())).unwrap();
+        assert_eq!(dispatcher.complete(&result(late, Some("1001")), &mut orders), Some(by_result.clone()));
+        let order = orders.get_order(&by_result).unwrap();
+        assert_eq!(order.state, OrderState::Submitted);
+        assert_eq!(order.exchange_order_id.as_deref(), Some("1001"));
+        
+        // 执行报告确认订单，之后到达的结果被忽略
+        let report = ExecutionReport {
+            order_id: "1002".to_string(),
+            client_order_id: by_report.clone(),
+            instrument: BINANCE_SPOT,
+            side: Side::Sell,
+            order_type: OrderType::Limit,
+            price: 100.0,
+            quantity: 1.0,
+            filled_quantity: 0.0,
+            status: common::types::OrderStatus::Placed,
+            execution_type: common::types::ExecutionType::New,
+            timestamp: chrono::Utc::now(),
+        };
+        dispatcher.settle(&report, &mut orders);
+        orders.process_execution_report(report).unwrap();
+        let order = orders.get_order(&by_report).unwrap();
+        assert_eq!(order.state, OrderState::Acknowledged);
+        assert_eq!(order.exchange_order_id.as_deref(), Some("1002"));
+        let late = commands.iter().find(|c| c.client_order_id.as_deref() == Some(by_report.as_str())).unwrap();
+        assert_eq!(dispatcher.complete(&result(late, None), &mut orders), None);
+        assert_eq!(dispatcher.get_stats().unknown, 0);
+    }
+    
+    #[test]
+    fn test_unknown_outcome_is_not_retried() {
+        let mut orders = OrderManager::new();
+        let mut dispatcher = dispatcher(DispatchConfig::default());
+        let order_id = submit(&mut orders, order(BINANCE_SPOT, 100.0, 1.0));
+        let commands = dispatcher.drain(&mut orders);
+        
+        // 交易所确认丢失时订单保持提交中，不用同一个client_order_id重新排队
+        let unknown = ExecutionResult::unknown(commands[0].id, "no ack");
+        assert_eq!(dispatcher.complete(&unknown, &mut orders), Some(order_id.clone()));
+        let order = orders.get_order(&order_id).unwrap();
+        assert_eq!(order.state, OrderState::Submitting);
+        assert_eq!(order.retry_count, 0);
+        assert!(dispatcher.drain(&mut orders).is_empty());
+        let stats = dispatcher.get_stats();
+        assert_eq!((stats.in_flight, stats.unknown, stats.failed), (0, 1, 0));
+        
+        // 执行报告确认订单已到达交易所
+        let report = report(&order_id, common::types::OrderStatus::Placed, 0.0);
+        dispatcher.settle(&report, &mut orders);
+        orders.process_execution_report(report).unwrap();
+        assert_eq!(orders.get_order_status(&order_id), Some(OrderState::Acknowledged));
+        assert_eq!(dispatcher.get_stats().unknown, 0);
+    }
+    
+    #[test]
+    fn test_unmatched_trade_report_is_still_recorded() {
+        use common::types::OrderStatus as ReportStatus;
+        
+        let mut orders = OrderManager::new();
+        
+        // 没有下过的订单：状态无法推进，成交仍然记账
+        let stray = report("TE_stray", ReportStatus::PartiallyFilled, 0.3);
+        assert!(orders.process_execution_report(stray.clone()).is_err());
+        let fill = orders.record_unmatched_fill(&stray);
+        assert_eq!(fill.order_id, "TE_stray");
+        assert_eq!(fill.instrument, BINANCE_SPOT);
+        assert_eq!(fill.quantity, Decimal::new(3, 1));
+        assert_eq!(orders.get_fills("TE_stray").map(Vec::len), Some(1));
+    }
+    
+    #[test]
+    fn test_fill_after_local_cancel_is_recorded() {
+        use common::types::OrderStatus as ReportStatus;
+        
+        let mut orders = OrderManager::new();
+        let mut dispatcher = dispatcher(DispatchConfig::default());
+        let order_id = submit(&mut orders, order(BINANCE_SPOT, 100.0, 1.0));
+        let commands = dispatcher.drain(&mut orders);
+        dispatcher.complete(&result(&commands[0], Some("1001")), &mut orders);
+        orders.process_execution_report(report(&order_id, ReportStatus::Placed, 0.0)).unwrap();
+        
+        // 撤单发出后订单进入撤单中，不是已取消
+        assert!(orders.cancel_order(&order_id).unwrap());
+        let pending = orders.get_order(&order_id).unwrap().clone();
+        assert_eq!(pending.state, OrderState::PendingCancel);
+        assert!(dispatcher.cancel(&pending).is_ok());
+        
+        // 与撤单交错的成交照常记账
+        let fill = orders.process_execution_report(report(&order_id, ReportStatus::PartiallyFilled, 0.4)).unwrap();
+        assert_eq!(fill.unwrap().quantity, Decimal::new(4, 1));
+        assert_eq!(orders.get_order_status(&order_id), Some(OrderState::PendingCancel));
+        let fill = orders.process_execution_report(report(&order_id, ReportStatus::Filled, 0.6)).unwrap();
+        assert_eq!(fill.unwrap().quantity, Decimal::new(6, 1));
+        assert_eq!(orders.get_order_status(&order_id), Some(OrderState::Filled));
+        
+        // 撤单确认后订单取消
+        let cancelled = submit(&mut orders, order(BINANCE_SPOT, 100.0, 1.0));
+        let commands = dispatcher.drain(&mut orders);
+        dispatcher.complete(&result(&commands[0], Some("1002")), &mut orders);
+        assert!(orders.cancel_order(&cancelled).unwrap());
+        orders.process_execution_report(report(&cancelled, ReportStatus::Cancelled, 0.0)).unwrap();
+        assert_eq!(orders.get_order_status(&cancelled), Some(OrderState::Cancelled));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod order;
 pub mod order_manager;
 pub mod order_state;
-pub mod arbitrage;
\ No newline at end of file
+pub mod arbitrage;
+pub mod dispatcher;
\ No newline at end of file
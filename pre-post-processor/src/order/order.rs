@@ -4,6 +4,7 @@ use rust_decimal::prelude::FromPrimitive;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use common::instrument::InstrumentId;
 use common::types::{Signal, Side, OrderType, TimeInForce, SignalType};
 use crate::order::order_state::OrderState;
 
@@ -16,7 +17,7 @@ pub struct Order {
     pub signal_id: String,            // 源信号ID
     
     // 订单基本信息
-    pub symbol: String,               // 交易对
+    pub instrument: InstrumentId,     // 交易标的
     pub side: Side,                   // 买卖方向
     pub order_type: OrderType,        // 订单类型
     pub time_in_force: TimeInForce,  // 有效期类型
@@ -44,6 +45,7 @@ pub struct Order {
     pub arbitrage_id: Option<String>, // 套利组合ID
     pub hedge_order_id: Option<String>, // 对冲订单ID
     pub is_hedge: bool,               // 是否是对冲订单
+    pub reduce_only: bool,            // 只减仓（平仓单）
     
     // 元数据
     pub metadata: OrderMetadata,      // 订单元数据
@@ -53,7 +55,7 @@ pub struct Order {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderMetadata {
     pub strategy: String,             // 策略名称
-    pub exchange: String,             // 交易所
+    pub exchange: String,             // 交易所（仅用于展示，路由由标的决定）
     pub account: String,              // 账户
     pub tags: Vec<String>,            // 标签
     pub notes: Option<String>,        // 备注
@@ -64,14 +66,21 @@ impl Order {
     pub fn from_signal(signal: &Signal) -> Self {
         let client_order_id = Self::generate_client_order_id(&signal.id);
         
+        // 事件指定了订单类型时按事件下单，否则默认为市价单
+        let (order_type, time_in_force) = match signal.metadata.get("order_type").map(String::as_str) {
+            Some("limit") => (OrderType::Limit, TimeInForce::GTC),
+            Some("post_only") => (OrderType::PostOnly, TimeInForce::GTX),
+            _ => (OrderType::Market, TimeInForce::IOC), // 默认为市价单，立即执行或取消
+        };
+        
         Self {
             client_order_id: client_order_id.clone(),
             exchange_order_id: None,
             signal_id: signal.id.clone(),
-            symbol: signal.symbol.clone(),
+            instrument: signal.instrument,
             side: signal.side.unwrap_or(Side::Buy), // 默认为买入
-            order_type: OrderType::Market, // 默认为市价单
-            time_in_force: TimeInForce::IOC, // 默认为立即执行或取消
+            order_type,
+            time_in_force,
             price: signal.price
                 .and_then(|p| Decimal::from_f64(p))
                 .unwrap_or(Decimal::ZERO),
@@ -94,6 +103,7 @@ impl Order {
             arbitrage_id: signal.metadata.get("arbitrage_id").cloned(),
             hedge_order_id: None,
             is_hedge: signal.signal_type == SignalType::Hedge,
+            reduce_only: signal.metadata.get("reduce_only").is_some_and(|v| v == "true"),
             metadata: OrderMetadata {
                 strategy: signal.source.clone(),
                 exchange: signal.exchange.clone(),
@@ -138,7 +148,8 @@ impl Order {
         matches!(
             self.state,
             OrderState::Submitting | OrderState::Submitted | 
-            OrderState::Acknowledged | OrderState::PartiallyFilled
+            OrderState::Acknowledged | OrderState::PartiallyFilled |
+            OrderState::PendingCancel
         )
     }
     
@@ -159,7 +170,7 @@ impl Order {
         if self.remaining_quantity <= Decimal::ZERO {
             self.state = OrderState::Filled;
             self.filled_at = Some(Utc::now());
-        } else if self.executed_quantity > Decimal::ZERO {
+        } else if self.executed_quantity > Decimal::ZERO && self.state != OrderState::PendingCancel {
             self.state = OrderState::PartiallyFilled;
         }
     }
@@ -188,7 +199,7 @@ impl Order {
             self.client_order_id,
             self.side,
             self.quantity,
-            self.symbol,
+            self.instrument,
             self.price,
             self.state
         )
@@ -200,7 +211,7 @@ impl Order {
 pub struct Fill {
     pub order_id: String,             // 订单ID
     pub trade_id: String,             // 成交ID
-    pub symbol: String,               // 交易对
+    pub instrument: InstrumentId,     // 交易标的
     pub side: Side,                   // 方向
     pub price: Decimal,               // 成交价格
     pub quantity: Decimal,            // 成交数量
@@ -235,8 +246,8 @@ pub struct OrderBook {
     // 按exchange_order_id索引
     pub orders_by_exchange_id: HashMap<String, String>, // exchange_id -> client_id
     
-    // 按symbol索引
-    pub orders_by_symbol: HashMap<String, Vec<String>>, // symbol -> client_ids
+    // 按标的索引
+    pub orders_by_instrument: HashMap<InstrumentId, Vec<String>>, // instrument -> client_ids
     
     // 活跃订单
     pub active_orders: Vec<String>, // client_ids
@@ -250,7 +261,7 @@ impl OrderBook {
         Self {
             orders_by_client_id: HashMap::new(),
             orders_by_exchange_id: HashMap::new(),
-            orders_by_symbol: HashMap::new(),
+            orders_by_instrument: HashMap::new(),
             active_orders: Vec::new(),
             pending_orders: Vec::new(),
         }
@@ -259,14 +270,13 @@ impl OrderBook {
     /// 添加订单
     pub fn add_order(&mut self, order: Order) {
         let client_id = order.client_order_id.clone();
-        let symbol = order.symbol.clone();
         
         // 添加到主索引
         self.orders_by_client_id.insert(client_id.clone(), order.clone());
         
-        // 添加到symbol索引
-        self.orders_by_symbol
-            .entry(symbol)
+        // 添加到标的索引
+        self.orders_by_instrument
+            .entry(order.instrument)
             .or_insert_with(Vec::new)
             .push(client_id.clone());
         
@@ -317,10 +327,10 @@ impl OrderBook {
             .and_then(|client_id| self.orders_by_client_id.get(client_id))
     }
     
-    /// 获取某个品种的所有订单
-    pub fn get_by_symbol(&self, symbol: &str) -> Vec<&Order> {
-        self.orders_by_symbol
-            .get(symbol)
+    /// 获取某个标的的所有订单
+    pub fn get_by_instrument(&self, instrument: InstrumentId) -> Vec<&Order> {
+        self.orders_by_instrument
+            .get(&instrument)
             .map(|ids| {
                 ids.iter()
                     .filter_map(|id| self.orders_by_client_id.get(id))
@@ -1,16 +1,20 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use chrono::Utc;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive;
 use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
-use common::types::{Signal, ExecutionReport};
+use common::instrument::InstrumentId;
+use common::types::{Signal, ExecutionReport, ExecutionType, OrderType, TimeInForce};
+use crate::config::FeeConfig;
 use crate::order::{
     order::{Order, OrderBook, Fill},
     order_state::{OrderState, StateManager, StateTransitionEvent},
-    arbitrage::ArbitrageManager,
+    arbitrage::{ArbitrageManager, ArbitragePair},
 };
+use crate::persistence::JournalRecord;
 
 /// 订单管理器 - 管理所有订单的生命周期
 pub struct OrderManager {
@@ -29,8 +33,36 @@ pub struct OrderManager {
     // 成交记录
     fills: HashMap<String, Vec<Fill>>,
     
+    // 手续费率
+    fees: FeeConfig,
+    
     // 统计信息
     stats: OrderStats,
+    
+    // 待写入journal的变更
+    changed_orders: HashSet<String>,
+    changed_pairs: HashSet<String>,
+    new_fills: Vec<Fill>,
+}
+
+/// 订单管理器快照 - 持久化和崩溃恢复使用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderSnapshot {
+    pub orders: HashMap<String, Order>,
+    pub fills: HashMap<String, Vec<Fill>>,
+    pub pairs: HashMap<String, ArbitragePair>,
+    pub stats: OrderStats,
+}
+
+impl Default for OrderSnapshot {
+    fn default() -> Self {
+        Self {
+            orders: HashMap::new(),
+            fills: HashMap::new(),
+            pairs: HashMap::new(),
+            stats: OrderStats::new(),
+        }
+    }
 }
 
 /// 优先级队列
@@ -76,7 +108,7 @@ impl PriorityQueue {
 }
 
 /// 订单统计
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderStats {
     pub total_orders: usize,           // 总订单数
     pub active_orders: usize,          // 活跃订单数
@@ -121,14 +153,95 @@ impl OrderManager {
             arbitrage_manager: ArbitrageManager::new(),
             priority_queue: PriorityQueue::new(),
             fills: HashMap::new(),
+            fees: FeeConfig::default(),
             stats: OrderStats::new(),
+            changed_orders: HashSet::new(),
+            changed_pairs: HashSet::new(),
+            new_fills: Vec::new(),
+        }
+    }
+    
+    /// 设置成交手续费率
+    pub fn with_fees(mut self, fees: FeeConfig) -> Self {
+        self.fees = fees;
+        self
+    }
+    
+    /// 从快照恢复订单簿、状态机和待提交队列
+    ///
+    /// 状态转换历史不恢复；提交中的订单在途命令已丢失，由后续的执行报告推进状态。
+    pub fn restore(snapshot: OrderSnapshot) -> Self {
+        let mut manager = Self::new();
+        
+        // 按创建时间登记，同优先级的待提交订单保持原有顺序
+        let mut orders: Vec<Order> = snapshot.orders.into_values().collect();
+        orders.sort_by_key(|o| o.created_at);
+        
+        for order in orders {
+            let order_id = order.client_order_id.clone();
+            manager.state_manager.restore_order(order_id.clone(), order.state);
+            if order.state == OrderState::Validated {
+                manager.priority_queue.push(order_id, order.priority);
+            }
+            manager.order_book.add_order(order);
+        }
+        
+        manager.arbitrage_manager = ArbitrageManager::restore(snapshot.pairs);
+        manager.fills = snapshot.fills;
+        manager.stats = snapshot.stats;
+        manager
+    }
+    
+    /// 生成快照
+    pub fn snapshot(&self) -> OrderSnapshot {
+        OrderSnapshot {
+            orders: self.order_book.orders_by_client_id.clone(),
+            fills: self.fills.clone(),
+            pairs: self.arbitrage_manager.pairs().clone(),
+            stats: self.stats.clone(),
+        }
+    }
+    
+    /// 取出上次调用以来的状态变更，写入journal
+    pub fn take_changes(&mut self) -> Vec<JournalRecord> {
+        if self.changed_orders.is_empty() && self.changed_pairs.is_empty() && self.new_fills.is_empty() {
+            return Vec::new();
+        }
+        
+        let mut records = Vec::new();
+        for order_id in self.changed_orders.drain() {
+            // 已被清理的订单不再记录
+            if let Some(order) = self.order_book.get_by_client_id(&order_id) {
+                records.push(JournalRecord::Order(order.clone()));
+            }
+        }
+        records.extend(self.new_fills.drain(..).map(JournalRecord::Fill));
+        for arbitrage_id in self.changed_pairs.drain() {
+            if let Some(pair) = self.arbitrage_manager.get_pair(&arbitrage_id) {
+                records.push(JournalRecord::Arbitrage(pair.clone()));
+            }
         }
+        records.push(JournalRecord::OrderStats(self.stats.clone()));
+        records
+    }
+    
+    /// 订单状态转换，并标记订单待写入journal
+    fn transition(&mut self, order_id: &str, event: StateTransitionEvent) -> Result<OrderState> {
+        let state = self.state_manager.transition_order(order_id, event)?;
+        self.changed_orders.insert(order_id.to_string());
+        Ok(state)
     }
     
     /// 创建订单（从信号）
     pub fn create_order_from_signal(&mut self, signal: Signal) -> Result<Order> {
-        // 创建订单
-        let mut order = Order::from_signal(&signal);
+        self.create_order(Order::from_signal(&signal))
+    }
+    
+    /// 登记Pipeline构造好的订单
+    pub fn create_order(&mut self, order: Order) -> Result<Order> {
+        if self.order_book.get_by_client_id(&order.client_order_id).is_some() {
+            bail!("Order {} already exists", order.client_order_id);
+        }
         
         // 创建状态机
         self.state_manager.create_order(order.client_order_id.clone());
@@ -139,10 +252,12 @@ impl OrderManager {
         // 如果是套利订单，注册到套利管理器
         if let Some(ref arb_id) = order.arbitrage_id {
             self.arbitrage_manager.add_order(arb_id.clone(), order.client_order_id.clone());
+            self.changed_pairs.insert(arb_id.clone());
         }
         
         // 更新统计
         self.stats.total_orders += 1;
+        self.changed_orders.insert(order.client_order_id.clone());
         
         info!("Order created: {}", order.summary());
         Ok(order)
@@ -151,7 +266,7 @@ impl OrderManager {
     /// 验证订单
     pub fn validate_order(&mut self, order_id: &str) -> Result<()> {
         // 更新状态
-        self.state_manager.transition_order(
+        self.transition(
             order_id,
             StateTransitionEvent::Validate
         )?;
@@ -184,7 +299,7 @@ impl OrderManager {
     
     /// 标记订单为提交中
     pub fn mark_submitting(&mut self, order_id: &str) -> Result<()> {
-        self.state_manager.transition_order(
+        self.transition(
             order_id,
             StateTransitionEvent::Submit
         )?;
@@ -199,63 +314,109 @@ impl OrderManager {
     
     /// 标记订单提交成功
     pub fn mark_submitted(&mut self, order_id: &str, exchange_order_id: String) -> Result<()> {
-        self.state_manager.transition_order(
-            order_id,
-            StateTransitionEvent::SubmitSuccess(exchange_order_id.clone())
-        )?;
+        // 用户数据流的回报可能先于引擎的执行结果到达，此时订单已离开Submitting，只补记交易所订单ID
+        if self.state_manager.get_state(order_id) == Some(OrderState::Submitting) {
+            self.transition(
+                order_id,
+                StateTransitionEvent::SubmitSuccess(exchange_order_id.clone())
+            )?;
+        }
         
         if let Some(order) = self.order_book.orders_by_client_id.get_mut(order_id) {
-            order.state = OrderState::Submitted;
-            order.set_exchange_order_id(exchange_order_id);
+            if order.state == OrderState::Submitting {
+                order.state = OrderState::Submitted;
+            }
+            order.set_exchange_order_id(exchange_order_id.clone());
+            self.order_book.orders_by_exchange_id.insert(exchange_order_id, order_id.to_string());
             
             self.stats.active_orders += 1;
+            self.changed_orders.insert(order_id.to_string());
         }
         
         Ok(())
     }
     
-    /// 标记订单提交失败
+    /// 标记订单提交失败，未超过重试次数时重新排队
     pub fn mark_submit_failed(&mut self, order_id: &str, reason: String) -> Result<()> {
-        self.state_manager.transition_order(
+        // 撤单途中提交失败的订单直接取消，不再重试
+        let state = self.transition(
             order_id,
             StateTransitionEvent::SubmitFailed(reason.clone())
         )?;
         
+        let retry = match self.order_book.orders_by_client_id.get_mut(order_id) {
+            Some(order) => {
+                order.state = state;
+                order.updated_at = Utc::now();
+                order.can_retry()
+            }
+            None => false,
+        };
+        
+        if !retry {
+            warn!("Order {} failed and cannot retry: {}", order_id, reason);
+            return Ok(());
+        }
+        
+        self.transition(order_id, StateTransitionEvent::Retry)?;
+        if let Some(order) = self.order_book.orders_by_client_id.get_mut(order_id) {
+            order.increment_retry();
+            order.state = OrderState::Validated;
+            
+            // 重新加入队列
+            self.priority_queue.push(order_id.to_string(), order.priority);
+            info!("Order {} will retry, attempt {}/{}", order_id, order.retry_count, order.max_retry);
+        }
+        
+        Ok(())
+    }
+    
+    /// 标记订单无法提交（不重试），如订单无法转换为执行命令
+    pub fn mark_failed(&mut self, order_id: &str, reason: String) -> Result<()> {
+        self.transition(
+            order_id,
+            StateTransitionEvent::SystemError(reason.clone())
+        )?;
+        
         if let Some(order) = self.order_book.orders_by_client_id.get_mut(order_id) {
             order.state = OrderState::Failed;
             order.updated_at = Utc::now();
-            
-            // 检查是否可以重试
-            if order.can_retry() {
-                order.increment_retry();
-                // 重新加入队列
-                self.priority_queue.push(order_id.to_string(), order.priority);
-                info!("Order {} will retry, attempt {}/{}", order_id, order.retry_count, order.max_retry);
-            } else {
-                warn!("Order {} failed and cannot retry: {}", order_id, reason);
-            }
         }
         
+        warn!("Order {} failed: {}", order_id, reason);
         Ok(())
     }
     
-    /// 处理执行报告
-    pub fn process_execution_report(&mut self, report: ExecutionReport) -> Result<()> {
+    /// 处理执行报告，成交类报告返回记录的成交
+    pub fn process_execution_report(&mut self, report: ExecutionReport) -> Result<Option<Fill>> {
+        // 交易所推送的报告优先按client_order_id匹配，新订单此时还没有交易所ID
         let order = self.order_book
-            .get_by_exchange_id(&report.order_id)
+            .get_by_client_id(&report.client_order_id)
+            .or_else(|| self.order_book.get_by_exchange_id(&report.order_id))
             .or_else(|| self.order_book.get_by_client_id(&report.order_id))
             .ok_or_else(|| anyhow::anyhow!("Order {} not found", report.order_id))?
             .clone();
         
+        // 改单确认不改变订单状态，只更新价格和数量
+        if report.execution_type == ExecutionType::Replaced {
+            self.handle_order_replaced(&order.client_order_id, &report)?;
+            return Ok(None);
+        }
+        
+        if report.execution_type == ExecutionType::Expired {
+            self.handle_order_expired(&order.client_order_id)?;
+            return Ok(None);
+        }
+        
         match report.status {
-            common::types::OrderStatus::Pending => {
+            common::types::OrderStatus::Pending | common::types::OrderStatus::Placed => {
                 self.handle_order_acknowledged(&order.client_order_id)?;
             }
             common::types::OrderStatus::PartiallyFilled => {
-                self.handle_partial_fill(&order.client_order_id, &report)?;
+                return self.handle_partial_fill(&order.client_order_id, &report);
             }
             common::types::OrderStatus::Filled => {
-                self.handle_order_filled(&order.client_order_id, &report)?;
+                return self.handle_order_filled(&order.client_order_id, &report);
             }
             common::types::OrderStatus::Cancelled => {
                 self.handle_order_cancelled(&order.client_order_id)?;
@@ -268,18 +429,19 @@ impl OrderManager {
             }
         }
         
-        Ok(())
+        Ok(None)
     }
     
     /// 处理订单确认
     fn handle_order_acknowledged(&mut self, order_id: &str) -> Result<()> {
-        self.state_manager.transition_order(
+        // 撤单途中到达的确认保持撤单中
+        let state = self.transition(
             order_id,
             StateTransitionEvent::Acknowledge
         )?;
         
         if let Some(order) = self.order_book.orders_by_client_id.get_mut(order_id) {
-            order.state = OrderState::Acknowledged;
+            order.state = state;
             order.updated_at = Utc::now();
         }
         
@@ -288,8 +450,8 @@ impl OrderManager {
     }
     
     /// 处理部分成交
-    fn handle_partial_fill(&mut self, order_id: &str, report: &ExecutionReport) -> Result<()> {
-        self.state_manager.transition_order(
+    fn handle_partial_fill(&mut self, order_id: &str, report: &ExecutionReport) -> Result<Option<Fill>> {
+        self.transition(
             order_id,
             StateTransitionEvent::PartialFill(
                 Decimal::from_f64(report.filled_quantity).unwrap_or(Decimal::ZERO),
@@ -302,18 +464,18 @@ impl OrderManager {
                 Decimal::from_f64(report.filled_quantity).unwrap_or(Decimal::ZERO),
                 Decimal::from_f64(report.price).unwrap_or(Decimal::ZERO)
             );
-            
-            // 记录成交
-            self.record_fill(order_id, report);
         }
         
+        // 记录成交
+        let fill = self.record_fill(order_id, report);
+        
         debug!("Order {} partially filled: {} @ {}", order_id, report.filled_quantity, report.price);
-        Ok(())
+        Ok(fill)
     }
     
     /// 处理完全成交
-    fn handle_order_filled(&mut self, order_id: &str, report: &ExecutionReport) -> Result<()> {
-        self.state_manager.transition_order(
+    fn handle_order_filled(&mut self, order_id: &str, report: &ExecutionReport) -> Result<Option<Fill>> {
+        self.transition(
             order_id,
             StateTransitionEvent::Fill
         )?;
@@ -331,12 +493,12 @@ impl OrderManager {
                 
                 (order.executed_quantity, order.executed_price, order.submitted_at, order.arbitrage_id.clone())
             } else {
-                return Ok(());
+                return Ok(None);
             }
         };
         
         // 记录成交
-        self.record_fill(order_id, report);
+        let fill = self.record_fill(order_id, report);
         
         // 更新总成交量
         self.stats.total_volume += executed_quantity * executed_price;
@@ -352,16 +514,44 @@ impl OrderManager {
         // 如果是套利订单，更新套利状态
         if let Some(ref arb_id) = arbitrage_id {
             self.arbitrage_manager.update_order_status(arb_id, order_id, OrderState::Filled);
+            self.changed_pairs.insert(arb_id.clone());
         }
         
         self.stats.update_success_rate();
         info!("Order {} filled", order_id);
+        Ok(fill)
+    }
+    
+    /// 处理改单确认
+    fn handle_order_replaced(&mut self, order_id: &str, report: &ExecutionReport) -> Result<()> {
+        if let Some(order) = self.order_book.orders_by_client_id.get_mut(order_id) {
+            if !order.is_active() {
+                bail!("Order {} cannot be amended in state {:?}", order_id, order.state);
+            }
+            
+            order.price = Decimal::from_f64(report.price).unwrap_or(order.price);
+            order.quantity = Decimal::from_f64(report.quantity).unwrap_or(order.quantity);
+            order.remaining_quantity = order.quantity - order.executed_quantity;
+            order.updated_at = Utc::now();
+            self.changed_orders.insert(order_id.to_string());
+            
+            info!("Order {} amended: {} @ {}", order_id, order.quantity, order.price);
+        }
+        
         Ok(())
     }
     
     /// 处理订单取消
     fn handle_order_cancelled(&mut self, order_id: &str) -> Result<()> {
-        self.state_manager.transition_order(
+        // 交易所主动撤单时没有经过cancel_order，先补上Cancel转换
+        if !matches!(self.state_manager.get_state(order_id), Some(OrderState::PendingCancel | OrderState::Cancelled)) {
+            self.transition(
+                order_id,
+                StateTransitionEvent::Cancel
+            )?;
+        }
+        
+        self.transition(
             order_id,
             StateTransitionEvent::CancelConfirmed
         )?;
@@ -381,7 +571,7 @@ impl OrderManager {
     
     /// 处理订单拒绝
     fn handle_order_rejected(&mut self, order_id: &str, reason: String) -> Result<()> {
-        self.state_manager.transition_order(
+        self.transition(
             order_id,
             StateTransitionEvent::Reject(reason.clone())
         )?;
@@ -401,7 +591,7 @@ impl OrderManager {
     
     /// 处理订单过期
     fn handle_order_expired(&mut self, order_id: &str) -> Result<()> {
-        self.state_manager.transition_order(
+        self.transition(
             order_id,
             StateTransitionEvent::Expire
         )?;
@@ -417,18 +607,36 @@ impl OrderManager {
         Ok(())
     }
     
-    /// 记录成交
-    fn record_fill(&mut self, order_id: &str, report: &ExecutionReport) {
+    /// 记录成交，手续费按订单类型推断挂单/吃单费率
+    fn record_fill(&mut self, order_id: &str, report: &ExecutionReport) -> Option<Fill> {
+        let order = self.order_book.orders_by_client_id.get(order_id)?;
+        let is_taker = order.order_type == OrderType::Market
+            || matches!(order.time_in_force, TimeInForce::IOC | TimeInForce::FOK);
+        Some(self.push_fill(order_id, report, is_taker))
+    }
+    
+    /// 记录无法匹配订单状态的成交回报（未知订单、状态机拒绝的回报），成交已在交易所发生，仍需记账；
+    /// 手续费按回报的订单类型推断
+    pub fn record_unmatched_fill(&mut self, report: &ExecutionReport) -> Fill {
+        let order_id = if report.client_order_id.is_empty() { report.order_id.clone() } else { report.client_order_id.clone() };
+        self.push_fill(&order_id, report, report.order_type == OrderType::Market)
+    }
+    
+    fn push_fill(&mut self, order_id: &str, report: &ExecutionReport, is_taker: bool) -> Fill {
+        let fee_rate = if is_taker { self.fees.taker_rate } else { self.fees.maker_rate };
+        
+        let price = Decimal::from_f64(report.price).unwrap_or(Decimal::ZERO);
+        let quantity = Decimal::from_f64(report.filled_quantity).unwrap_or(Decimal::ZERO);
         let fill = Fill {
             order_id: order_id.to_string(),
             trade_id: format!("TRD_{}", uuid::Uuid::new_v4()), // 生成交易ID
-            symbol: format!("{:?}", report.symbol),  // 使用 Debug trait 转换 Symbol
+            instrument: report.instrument,
             side: report.side,
-            price: Decimal::from_f64(report.price).unwrap_or(Decimal::ZERO),
-            quantity: Decimal::from_f64(report.filled_quantity).unwrap_or(Decimal::ZERO),
-            fee: Decimal::ZERO, // 默认手续费为0
+            price,
+            quantity,
+            fee: price * quantity * fee_rate,
             fee_currency: "USDT".to_string(),
-            timestamp: Utc::now(),
+            timestamp: report.timestamp,
         };
         
         self.fills
@@ -437,6 +645,8 @@ impl OrderManager {
             .push(fill.clone());
         
         self.stats.total_fees += fill.fee;
+        self.new_fills.push(fill.clone());
+        fill
     }
     
     /// 更新平均成交时间
@@ -448,8 +658,10 @@ impl OrderManager {
         }
     }
     
-    /// 取消订单
-    pub fn cancel_order(&mut self, order_id: &str) -> Result<()> {
+    /// 取消订单，返回是否需要向交易所发送撤单（尚未提交的订单直接在本地取消）
+    ///
+    /// 已提交的订单进入撤单中，交易所确认撤单前的成交照常记账
+    pub fn cancel_order(&mut self, order_id: &str) -> Result<bool> {
         let order = self.order_book
             .get_by_client_id(order_id)
             .ok_or_else(|| anyhow::anyhow!("Order {} not found", order_id))?;
@@ -457,13 +669,84 @@ impl OrderManager {
         if !order.state.can_cancel() {
             bail!("Order {} cannot be cancelled in state {:?}", order_id, order.state);
         }
+        let submitted = !matches!(order.state, OrderState::Created | OrderState::Validated);
         
-        self.state_manager.transition_order(
+        let state = self.transition(
             order_id,
             StateTransitionEvent::Cancel
         )?;
         
+        if let Some(order) = self.order_book.orders_by_client_id.get_mut(order_id) {
+            order.state = state;
+            order.updated_at = Utc::now();
+        }
+        
+        if !submitted {
+            self.stats.cancelled_orders += 1;
+            info!("Order {} cancelled before submission", order_id);
+            return Ok(false);
+        }
+        
         info!("Cancel request sent for order {}", order_id);
+        Ok(true)
+    }
+    
+    /// 取消某个标的的所有可取消订单，返回被取消的订单ID
+    pub fn cancel_orders_by_instrument(&mut self, instrument: InstrumentId) -> Vec<String> {
+        let order_ids: Vec<String> = self.order_book
+            .get_by_instrument(instrument)
+            .into_iter()
+            .filter(|o| o.state.can_cancel())
+            .map(|o| o.client_order_id.clone())
+            .collect();
+        
+        let mut cancelled = Vec::with_capacity(order_ids.len());
+        for order_id in order_ids {
+            match self.transition(&order_id, StateTransitionEvent::Cancel) {
+                Ok(state) => {
+                    if let Some(order) = self.order_book.orders_by_client_id.get_mut(&order_id) {
+                        order.state = state;
+                        order.updated_at = Utc::now();
+                    }
+                    cancelled.push(order_id);
+                }
+                Err(e) => warn!("Failed to cancel order {}: {}", order_id, e),
+            }
+        }
+        
+        info!("Cancel-all request sent for {}: {} orders", instrument, cancelled.len());
+        cancelled
+    }
+    
+    /// 改单（价格/数量），等待交易所确认后在handle_order_replaced中生效
+    pub fn amend_order(
+        &mut self,
+        order_id: &str,
+        new_price: Option<Decimal>,
+        new_quantity: Option<Decimal>,
+    ) -> Result<()> {
+        let order = self.order_book
+            .get_by_client_id(order_id)
+            .ok_or_else(|| anyhow::anyhow!("Order {} not found", order_id))?;
+        
+        if !order.is_active() {
+            bail!("Order {} cannot be amended in state {:?}", order_id, order.state);
+        }
+        
+        if new_price.is_none() && new_quantity.is_none() {
+            bail!("Amend for order {} changes neither price nor quantity", order_id);
+        }
+        
+        if let Some(quantity) = new_quantity {
+            if quantity <= order.executed_quantity {
+                bail!(
+                    "Order {} new quantity {} is not above executed quantity {}",
+                    order_id, quantity, order.executed_quantity
+                );
+            }
+        }
+        
+        info!("Amend request sent for order {}: price {:?}, quantity {:?}", order_id, new_price, new_quantity);
         Ok(())
     }
     
@@ -479,6 +762,13 @@ impl OrderManager {
         self.order_book.get_by_client_id(order_id)
     }
     
+    /// 按客户端订单ID或交易所订单ID查找订单
+    pub fn find_order(&self, order_id: &str) -> Option<&Order> {
+        self.order_book
+            .get_by_client_id(order_id)
+            .or_else(|| self.order_book.get_by_exchange_id(order_id))
+    }
+    
     /// 获取活跃订单
     pub fn get_active_orders(&self) -> Vec<&Order> {
         self.order_book.get_active_orders()
@@ -489,6 +779,11 @@ impl OrderManager {
         self.fills.get(order_id)
     }
     
+    /// 所有订单的成交记录
+    pub fn all_fills(&self) -> impl Iterator<Item = &Fill> {
+        self.fills.values().flatten()
+    }
+    
     /// 获取统计信息
     pub fn get_stats(&self) -> OrderStats {
         self.stats.clone()
@@ -17,6 +17,7 @@ pub enum OrderState {
     // 活跃状态
     Acknowledged,    // 已被交易所确认
     PartiallyFilled, // 部分成交
+    PendingCancel,   // 已发出撤单，等待交易所确认
     
     // 终态
     Filled,          // 完全成交
@@ -35,6 +36,7 @@ impl fmt::Display for OrderState {
             OrderState::Submitted => write!(f, "Submitted"),
             OrderState::Acknowledged => write!(f, "Acknowledged"),
             OrderState::PartiallyFilled => write!(f, "PartiallyFilled"),
+            OrderState::PendingCancel => write!(f, "PendingCancel"),
             OrderState::Filled => write!(f, "Filled"),
             OrderState::Cancelled => write!(f, "Cancelled"),
             OrderState::Rejected => write!(f, "Rejected"),
@@ -64,7 +66,8 @@ impl OrderState {
             OrderState::Submitting | 
             OrderState::Submitted | 
             OrderState::Acknowledged | 
-            OrderState::PartiallyFilled
+            OrderState::PartiallyFilled |
+            OrderState::PendingCancel
         )
     }
     
@@ -99,6 +102,7 @@ pub enum StateTransitionEvent {
     Submit,                      // 开始提交
     SubmitSuccess(String),       // 提交成功，返回exchange_order_id
     SubmitFailed(String),        // 提交失败，返回错误信息
+    Retry,                       // 失败后重新排队提交
     
     // 交易所确认
     Acknowledge,                 // 交易所确认
@@ -130,6 +134,13 @@ impl OrderStateMachine {
         }
     }
     
+    /// 以指定状态创建状态机（从持久化状态恢复）
+    pub fn with_state(state: OrderState) -> Self {
+        Self {
+            current_state: state,
+        }
+    }
+    
     /// 获取当前状态
     pub fn current_state(&self) -> OrderState {
         self.current_state
@@ -168,6 +179,12 @@ impl OrderStateMachine {
                 Failed
             }
             
+            // 用户数据流的确认可能先于引擎的执行结果到达
+            (Submitting, Acknowledge) => Acknowledged,
+            
+            // Failed/Rejected -> Validated（重试）
+            (state, Retry) if state.can_retry() => Validated,
+            
             // Submitted -> Acknowledged/Rejected
             (Submitted, Acknowledge) => Acknowledged,
             (Submitted, Reject(reason)) => {
@@ -183,9 +200,23 @@ impl OrderStateMachine {
             (PartiallyFilled, PartialFill(_, _)) => PartiallyFilled,
             (PartiallyFilled, Fill) => Filled,
             
-            // 取消操作
-            (state, Cancel) if state.can_cancel() => Cancelled,
-            (Cancelled, CancelConfirmed) => Cancelled,
+            // 取消操作：未提交的订单直接取消，已提交的订单等待交易所确认
+            (Created | Validated, Cancel) => Cancelled,
+            (state, Cancel) if state.can_cancel() => PendingCancel,
+            (PendingCancel | Cancelled, CancelConfirmed) => Cancelled,
+            
+            // 撤单途中交易所的确认和成交照常处理，撤单未到达前的拒绝和提交失败结束订单
+            (PendingCancel, Acknowledge) => PendingCancel,
+            (PendingCancel, PartialFill(_, _)) => PendingCancel,
+            (PendingCancel, Fill) => Filled,
+            (PendingCancel, Reject(reason)) => {
+                warn!("Order rejected: {}", reason);
+                Rejected
+            }
+            (PendingCancel, SubmitFailed(reason)) => {
+                warn!("Order submit failed while cancelling: {}", reason);
+                Cancelled
+            }
             
             // 过期
             (state, Expire) if state.is_active() => Expired,
@@ -255,6 +286,12 @@ impl StateManager {
         self.histories.insert(order_id, Vec::new());
     }
     
+    /// 恢复订单的状态机，历史记录不恢复
+    pub fn restore_order(&mut self, order_id: String, state: OrderState) {
+        self.machines.insert(order_id.clone(), OrderStateMachine::with_state(state));
+        self.histories.insert(order_id, Vec::new());
+    }
+    
     /// 处理订单状态转换
     pub fn transition_order(
         &mut self, 
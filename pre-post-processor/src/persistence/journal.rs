@@ -0,0 +1,217 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use common::binary::checksum;
+use common::instrument::InstrumentId;
+use crate::order::arbitrage::ArbitragePair;
+use crate::order::order::{Fill, Order};
+use crate::order::order_manager::OrderStats;
+use crate::pipeline::shared_state::{PositionInfo, RiskQuota};
+use crate::risk_control::risk_calculator::RiskMetrics;
+use crate::risk_control::risk_state::{GlobalRiskState, SymbolRiskState};
+
+/// 记录头：payload长度(u32) + CRC-32(u32)，小端
+const RECORD_HEADER_SIZE: usize = 8;
+
+/// 单条状态变更，记录变更后的完整值，重放时直接覆盖
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum JournalRecord {
+    /// 订单最新状态（新建、状态转换、改单）
+    Order(Order),
+    /// 新增成交
+    Fill(Fill),
+    /// 套利组合最新状态
+    Arbitrage(ArbitragePair),
+    /// 订单统计
+    OrderStats(OrderStats),
+    /// 品种仓位、风控配额及总敞口
+    Position {
+        position: PositionInfo,
+        quota: Option<RiskQuota>,
+        total_exposure: Decimal,
+    },
+    /// 品种风控状态
+    SymbolRisk(SymbolRiskState),
+    /// 全局风控状态和风险指标
+    GlobalRisk {
+        state: GlobalRiskState,
+        metrics: RiskMetrics,
+    },
+    /// 一笔已实现盈亏，计入风险计算器的历史
+    Pnl { instrument: InstrumentId, pnl: Decimal },
+}
+
+/// 一批状态变更，对应一次输入处理完成后的一致状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub seq: u64,
+    pub written_at: DateTime<Utc>,
+    pub records: Vec<JournalRecord>,
+}
+
+/// 预写日志 - 每批变更带长度和校验和追加写入，崩溃时截断的尾部在打开时丢弃
+pub struct Journal {
+    file: File,
+    path: PathBuf,
+    sync: bool,
+}
+
+impl Journal {
+    /// 打开journal并读出所有完整记录，不完整或校验失败的尾部会被截掉
+    pub fn open(path: &Path, sync: bool) -> Result<(Self, Vec<JournalEntry>)> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .with_context(|| format!("Failed to open journal {}", path.display()))?;
+
+        let mut data = Vec::new();
+        file.read_to_end(&mut data)?;
+
+        let (entries, valid_len) = read_entries(&data);
+        if valid_len < data.len() {
+            warn!(
+                "Journal {} has {} trailing bytes after entry {}, truncating",
+                path.display(),
+                data.len() - valid_len,
+                entries.last().map_or(0, |e| e.seq)
+            );
+            file.set_len(valid_len as u64)?;
+            file.sync_all()?;
+        }
+        file.seek(SeekFrom::Start(valid_len as u64))?;
+
+        Ok((Self { file, path: path.to_path_buf(), sync }, entries))
+    }
+
+    /// 追加一批变更，返回前已写入文件（sync开启时已落盘）
+    pub fn append(&mut self, entry: &JournalEntry) -> Result<()> {
+        let payload = serde_json::to_vec(entry)?;
+
+        let mut record = Vec::with_capacity(RECORD_HEADER_SIZE + payload.len());
+        record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        record.extend_from_slice(&checksum(&payload).to_le_bytes());
+        record.extend_from_slice(&payload);
+
+        self.file
+            .write_all(&record)
+            .with_context(|| format!("Failed to append to journal {}", self.path.display()))?;
+        if self.sync {
+            self.file.sync_data()?;
+        }
+        Ok(())
+    }
+
+    /// 清空journal（快照已覆盖其中所有变更）
+    pub fn reset(&mut self) -> Result<()> {
+        self.file.set_len(0)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        self.file.sync_all()?;
+        Ok(())
+    }
+}
+
+/// 解析完整记录，返回记录和有效数据长度
+fn read_entries(data: &[u8]) -> (Vec<JournalEntry>, usize) {
+    let mut entries = Vec::new();
+    let mut offset = 0;
+
+    while data.len() - offset >= RECORD_HEADER_SIZE {
+        let len = u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap()) as usize;
+        let crc = u32::from_le_bytes(data[offset + 4..offset + 8].try_into().unwrap());
+        let start = offset + RECORD_HEADER_SIZE;
+
+        let Some(payload) = data.get(start..start + len) else {
+            break; // 写入中途崩溃
+        };
+        if checksum(payload) != crc {
+            break;
+        }
+        match serde_json::from_slice::<JournalEntry>(payload) {
+            Ok(entry) => entries.push(entry),
+            Err(_) => break,
+        }
+
+        offset = start + len;
+    }
+
+    (entries, offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(seq: u64) -> JournalEntry {
+        JournalEntry {
+            seq,
+            written_at: Utc::now(),
+            records: vec![JournalRecord::Pnl { instrument: InstrumentId::new(2, 1), pnl: Decimal::from(seq) }],
+        }
+    }
+
+    #[test]
+    fn test_torn_tail_is_truncated_on_open() {
+        let dir = std::env::temp_dir().join(format!("ppp-journal-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("journal.log");
+
+        {
+            let (mut journal, entries) = Journal::open(&path, false).unwrap();
+            assert!(entries.is_empty());
+            journal.append(&entry(1)).unwrap();
+            journal.append(&entry(2)).unwrap();
+        }
+
+        // 模拟写入第三条记录时崩溃：只写了记录头和部分payload
+        let intact_len = std::fs::metadata(&path).unwrap().len();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&100u32.to_le_bytes()).unwrap();
+        file.write_all(&[0u8; 10]).unwrap();
+        drop(file);
+
+        let (mut journal, entries) = Journal::open(&path, false).unwrap();
+        assert_eq!(entries.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), intact_len);
+
+        // 截断后继续追加的记录可以正常读出
+        journal.append(&entry(3)).unwrap();
+        drop(journal);
+        let (_, entries) = Journal::open(&path, false).unwrap();
+        assert_eq!(entries.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupted_record_stops_replay() {
+        let dir = std::env::temp_dir().join(format!("ppp-journal-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("journal.log");
+
+        {
+            let (mut journal, _) = Journal::open(&path, false).unwrap();
+            journal.append(&entry(1)).unwrap();
+            journal.append(&entry(2)).unwrap();
+        }
+
+        // 翻转最后一个字节，第二条记录校验失败
+        let mut data = std::fs::read(&path).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0xff;
+        std::fs::write(&path, &data).unwrap();
+
+        let (_, entries) = Journal::open(&path, false).unwrap();
+        assert_eq!(entries.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![1]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
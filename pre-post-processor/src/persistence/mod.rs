@@ -0,0 +1,187 @@
+//! 状态持久化与崩溃恢复
+//!
+//! 每次输入（信号、事件、执行结果、执行报告）处理完成后，本轮的状态变更作为一批写入
+//! 预写日志（journal），执行命令发出前先落盘；定期写入全量快照并截断journal。
+//! 启动时加载最近的快照，重放其后的journal批次，重建 SharedState、RiskState 和 OrderManager。
+
+pub mod journal;
+pub mod recovery;
+pub mod snapshot;
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use tracing::info;
+
+pub use journal::{Journal, JournalEntry, JournalRecord};
+pub use recovery::RecoveredState;
+pub use snapshot::StateSnapshot;
+
+const JOURNAL_FILE: &str = "journal.log";
+const SNAPSHOT_FILE: &str = "snapshot.json";
+
+/// 本地磁盘上的journal和快照
+pub struct StateStore {
+    journal: Journal,
+    snapshot_path: PathBuf,
+    seq: u64,
+}
+
+impl StateStore {
+    /// 打开数据目录（不存在时创建），返回存储和恢复出的状态
+    pub fn open(dir: &Path, sync: bool) -> Result<(Self, RecoveredState)> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create state directory {}", dir.display()))?;
+
+        let snapshot_path = dir.join(SNAPSHOT_FILE);
+        let snapshot = snapshot::load(&snapshot_path)?;
+        let (journal, entries) = Journal::open(&dir.join(JOURNAL_FILE), sync)?;
+
+        // 新批次的序号须大于快照和journal中已有的所有序号
+        let seq = entries
+            .iter()
+            .map(|e| e.seq)
+            .chain(snapshot.as_ref().map(|s| s.seq))
+            .max()
+            .unwrap_or(0);
+
+        let recovered = RecoveredState::rebuild(snapshot, entries);
+        info!(
+            "Recovered state from {}: seq={}, replayed {} journal entries",
+            dir.display(), recovered.seq, recovered.replayed
+        );
+
+        Ok((Self { journal, snapshot_path, seq }, recovered))
+    }
+
+    /// 写入一批变更，空批次不写
+    pub fn append(&mut self, records: Vec<JournalRecord>) -> Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+
+        let entry = JournalEntry {
+            seq: self.seq + 1,
+            written_at: Utc::now(),
+            records,
+        };
+        self.journal.append(&entry)?;
+        self.seq = entry.seq;
+        Ok(())
+    }
+
+    /// 写入快照并截断journal，调用前须先写入所有未落盘的变更
+    pub fn write_snapshot(&mut self, mut snapshot: StateSnapshot) -> Result<()> {
+        snapshot.seq = self.seq;
+        snapshot::write(&self.snapshot_path, &snapshot)?;
+        self.journal.reset()?;
+        Ok(())
+    }
+}
+
+/// 先写临时文件并落盘，再原子替换目标文件
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<()> {
+    let tmp_path = path.with_extension("tmp");
+
+    let mut file = File::create(&tmp_path)
+        .with_context(|| format!("Failed to create {}", tmp_path.display()))?;
+    file.write_all(data)?;
+    file.sync_all()?;
+
+    fs::rename(&tmp_path, path)
+        .with_context(|| format!("Failed to replace {}", path.display()))?;
+
+    // 目录项落盘后rename才持久
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::instrument::InstrumentId;
+    use common::types::{Signal, SignalData, SignalType, Side};
+    use crate::order::order::Order;
+    use crate::order::order_manager::OrderManager;
+    use crate::order::order_state::OrderState;
+    use crate::pipeline::shared_state::SharedState;
+    use crate::risk_control::risk_calculator::RiskCalculator;
+    use crate::risk_control::risk_state::RiskState;
+
+    fn order(instrument: InstrumentId) -> Order {
+        let mut signal = Signal::new(SignalType::Market, SignalData::Market { market_data: String::new() });
+        signal.instrument = instrument;
+        signal.side = Some(Side::Buy);
+        signal.price = Some(100.0);
+        signal.quantity = Some(1.0);
+        Order::from_signal(&signal)
+    }
+
+    fn snapshot(orders: &OrderManager) -> StateSnapshot {
+        StateSnapshot {
+            seq: 0,
+            taken_at: Utc::now(),
+            shared: SharedState::new(),
+            risk: RiskState::new(),
+            calculator: RiskCalculator::new(100),
+            risk_init: None,
+            orders: orders.snapshot(),
+        }
+    }
+
+    #[test]
+    fn test_recovery_replays_journal_after_snapshot() {
+        let dir = std::env::temp_dir().join(format!("ppp-state-{}", uuid::Uuid::new_v4()));
+
+        let (submitted, queued) = {
+            let (mut store, recovered) = StateStore::open(&dir, false).unwrap();
+            assert!(recovered.is_empty());
+            let mut orders = OrderManager::new();
+
+            // 快照前：订单A提交中
+            let submitted = orders.create_order(order(InstrumentId::new(2, 1))).unwrap().client_order_id;
+            orders.validate_order(&submitted).unwrap();
+            let next = orders.get_next_pending_order().unwrap();
+            orders.mark_submitting(&next.client_order_id).unwrap();
+            store.append(orders.take_changes()).unwrap();
+            store.write_snapshot(snapshot(&orders)).unwrap();
+
+            // 快照后：A被交易所确认，B已验证待提交
+            orders.mark_submitted(&submitted, "EX-1".to_string()).unwrap();
+            let queued = orders.create_order(order(InstrumentId::new(2, 2))).unwrap().client_order_id;
+            orders.validate_order(&queued).unwrap();
+            store.append(orders.take_changes()).unwrap();
+            assert!(orders.take_changes().is_empty());
+
+            (submitted, queued)
+        };
+
+        let (mut store, recovered) = StateStore::open(&dir, false).unwrap();
+        assert_eq!(recovered.seq, 2);
+        assert_eq!(recovered.replayed, 1);
+
+        let mut orders = OrderManager::restore(recovered.orders);
+        assert_eq!(orders.get_order_status(&submitted), Some(OrderState::Submitted));
+        assert_eq!(orders.find_order("EX-1").map(|o| o.client_order_id.clone()), Some(submitted.clone()));
+        assert_eq!(orders.get_stats().total_orders, 2);
+
+        // 待提交队列和状态机已重建，恢复后可以继续推进订单
+        let next = orders.get_next_pending_order().unwrap();
+        assert_eq!(next.client_order_id, queued);
+        orders.mark_submitting(&queued).unwrap();
+        assert!(orders.get_next_pending_order().is_none());
+
+        // 新批次序号接在恢复的序号之后
+        store.append(orders.take_changes()).unwrap();
+        drop(store);
+        let (_, recovered) = StateStore::open(&dir, false).unwrap();
+        assert_eq!(recovered.seq, 3);
+        assert_eq!(recovered.orders.orders[&queued].state, OrderState::Submitting);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
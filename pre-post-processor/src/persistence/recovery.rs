@@ -0,0 +1,103 @@
+use common::types::Signal;
+use crate::order::order_manager::OrderSnapshot;
+use crate::persistence::journal::{JournalEntry, JournalRecord};
+use crate::persistence::snapshot::StateSnapshot;
+use crate::pipeline::shared_state::SharedState;
+use crate::risk_control::risk_calculator::RiskCalculator;
+use crate::risk_control::risk_state::RiskState;
+
+/// 风险计算器默认历史窗口，与 RiskInitializer 一致
+const CALCULATOR_HISTORY_SIZE: usize = 10000;
+
+/// 由最近快照加上之后的journal重建出的状态
+pub struct RecoveredState {
+    /// 已恢复的最后一批变更序号
+    pub seq: u64,
+    pub shared: SharedState,
+    pub risk: RiskState,
+    pub calculator: RiskCalculator,
+    pub risk_init: Option<Signal>,
+    pub orders: OrderSnapshot,
+    /// 重放的journal批次数
+    pub replayed: usize,
+}
+
+impl RecoveredState {
+    /// 以快照为起点（没有快照时为空状态），按序重放序号更大的journal批次
+    pub fn rebuild(snapshot: Option<StateSnapshot>, entries: Vec<JournalEntry>) -> Self {
+        let mut state = match snapshot {
+            Some(snapshot) => Self {
+                seq: snapshot.seq,
+                shared: snapshot.shared,
+                risk: snapshot.risk,
+                calculator: snapshot.calculator,
+                risk_init: snapshot.risk_init,
+                orders: snapshot.orders,
+                replayed: 0,
+            },
+            None => Self {
+                seq: 0,
+                shared: SharedState::new(),
+                risk: RiskState::new(),
+                calculator: RiskCalculator::new(CALCULATOR_HISTORY_SIZE),
+                risk_init: None,
+                orders: OrderSnapshot::default(),
+                replayed: 0,
+            },
+        };
+
+        for entry in entries {
+            // 快照已包含的批次（快照后journal截断前崩溃）跳过
+            if entry.seq <= state.seq {
+                continue;
+            }
+            for record in entry.records {
+                state.apply(record);
+            }
+            state.risk.last_update = entry.written_at;
+            state.seq = entry.seq;
+            state.replayed += 1;
+        }
+
+        state
+    }
+
+    /// 恢复是否从空状态开始且没有任何变更
+    pub fn is_empty(&self) -> bool {
+        self.seq == 0
+    }
+
+    fn apply(&mut self, record: JournalRecord) {
+        match record {
+            JournalRecord::Order(order) => {
+                self.orders.orders.insert(order.client_order_id.clone(), order);
+            }
+            JournalRecord::Fill(fill) => {
+                self.orders.fills.entry(fill.order_id.clone()).or_default().push(fill);
+            }
+            JournalRecord::Arbitrage(pair) => {
+                self.orders.pairs.insert(pair.id.clone(), pair);
+            }
+            JournalRecord::OrderStats(stats) => {
+                self.orders.stats = stats;
+            }
+            JournalRecord::Position { position, quota, total_exposure } => {
+                if let Some(quota) = quota {
+                    self.shared.risk_quotas.insert(position.instrument, quota);
+                }
+                self.shared.positions.insert(position.instrument, position);
+                self.shared.total_exposure = total_exposure;
+            }
+            JournalRecord::SymbolRisk(symbol_state) => {
+                self.risk.symbol_states.insert(symbol_state.instrument, symbol_state);
+            }
+            JournalRecord::GlobalRisk { state, metrics } => {
+                self.risk.global_state = state;
+                self.risk.metrics = metrics;
+            }
+            JournalRecord::Pnl { instrument, pnl } => {
+                self.calculator.add_pnl(instrument, pnl);
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::fs;
+use std::path::Path;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use common::types::Signal;
+use crate::order::order_manager::OrderSnapshot;
+use crate::persistence::write_atomic;
+use crate::pipeline::shared_state::SharedState;
+use crate::risk_control::risk_calculator::RiskCalculator;
+use crate::risk_control::risk_state::RiskState;
+
+/// 全量状态快照，包含journal中序号不大于 `seq` 的所有变更
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub seq: u64,
+    pub taken_at: DateTime<Utc>,
+    pub shared: SharedState,
+    pub risk: RiskState,
+    pub calculator: RiskCalculator,
+    /// 最近一次成功的风控初始化信号，恢复时重放以重建规则
+    pub risk_init: Option<Signal>,
+    pub orders: OrderSnapshot,
+}
+
+/// 读取快照，文件不存在时返回None
+pub fn load(path: &Path) -> Result<Option<StateSnapshot>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let data = fs::read(path).with_context(|| format!("Failed to read snapshot {}", path.display()))?;
+    let snapshot = serde_json::from_slice(&data)
+        .with_context(|| format!("Failed to parse snapshot {}", path.display()))?;
+    Ok(Some(snapshot))
+}
+
+/// 写入快照，崩溃时保留旧快照
+pub fn write(path: &Path, snapshot: &StateSnapshot) -> Result<()> {
+    let data = serde_json::to_vec(snapshot)?;
+    write_atomic(path, &data)
+}
@@ -0,0 +1,225 @@
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+
+use common::events::TradingEvent;
+use common::instrument::{InstrumentId, InstrumentRegistry};
+use common::messages::EventMessage;
+use common::types::{OrderType, Priority, Signal, SignalData, SignalType};
+
+/// 事件路由结果：下单类事件转换为信号走Pre-process Pipeline，撤单/改单直接作用于已有订单
+#[derive(Debug, Clone)]
+pub enum EventAction {
+    PreProcess(Signal),
+    Cancel {
+        order_id: String,
+        reason: String,
+    },
+    Modify {
+        order_id: String,
+        new_price: Option<Decimal>,
+        new_quantity: Option<Decimal>,
+        reason: String,
+    },
+}
+
+/// 按事件类型路由 signal-collector 发布的交易事件
+pub fn route_event(message: &EventMessage, instruments: &InstrumentRegistry) -> EventAction {
+    let sequence_id = message.sequence_id.to_string();
+    
+    match &message.event {
+        TradingEvent::OpenPosition(e) => {
+            let mut signal = event_signal(
+                &message.event,
+                SignalType::Market,
+                SignalData::Market { market_data: e.reason.clone() },
+                e.instrument,
+                instruments,
+            );
+            signal.side = Some(e.side);
+            signal.price = e.price;
+            signal.quantity = Some(e.quantity);
+            signal.timestamp = e.timestamp;
+            signal.metadata.insert("order_type".to_string(), order_type_name(e.order_type).to_string());
+            signal.metadata.insert("trigger_type".to_string(), format!("{:?}", e.trigger_type));
+            signal.metadata.insert("sequence_id".to_string(), sequence_id);
+            EventAction::PreProcess(signal)
+        }
+        TradingEvent::ClosePosition(e) => {
+            let mut signal = event_signal(
+                &message.event,
+                SignalType::Market,
+                SignalData::Market { market_data: e.reason.clone() },
+                e.instrument,
+                instruments,
+            );
+            signal.side = Some(e.side);
+            signal.price = e.price;
+            signal.quantity = Some(e.quantity);
+            signal.timestamp = e.timestamp;
+            signal.metadata.insert("order_type".to_string(), order_type_name(e.order_type).to_string());
+            signal.metadata.insert("trigger_type".to_string(), format!("{:?}", e.trigger_type));
+            signal.metadata.insert("reduce_only".to_string(), "true".to_string());
+            signal.metadata.insert("sequence_id".to_string(), sequence_id);
+            EventAction::PreProcess(signal)
+        }
+        TradingEvent::HedgePosition(e) => {
+            // 对冲单下在对冲腿；HedgeTrigger 把原对冲ID放在 reason 中，沿用它以结束对应的在途请求
+            let hedge_id = if e.reason.is_empty() {
+                format!("HEDGE_{}", message.sequence_id)
+            } else {
+                e.reason.clone()
+            };
+            let mut signal = event_signal(
+                &message.event,
+                SignalType::Hedge,
+                SignalData::Hedge {
+                    hedge_id,
+                    target_position: e.quantity,
+                },
+                e.hedge_instrument,
+                instruments,
+            );
+            signal.side = Some(e.side);
+            signal.quantity = Some(e.quantity);
+            signal.timestamp = e.timestamp;
+            signal.metadata.insert("primary_instrument".to_string(), e.instrument.to_string());
+            signal.metadata.insert("trigger_type".to_string(), format!("{:?}", e.trigger_type));
+            signal.metadata.insert("sequence_id".to_string(), sequence_id);
+            EventAction::PreProcess(signal)
+        }
+        TradingEvent::CancelOrder(e) => EventAction::Cancel {
+            order_id: e.order_id.clone(),
+            reason: e.reason.clone(),
+        },
+        TradingEvent::ModifyOrder(e) => EventAction::Modify {
+            order_id: e.order_id.clone(),
+            new_price: e.new_price.and_then(Decimal::from_f64),
+            new_quantity: e.new_quantity.and_then(Decimal::from_f64),
+            reason: e.reason.clone(),
+        },
+    }
+}
+
+/// 订单类型名称，Order::from_signal 按此解析
+pub fn order_type_name(order_type: OrderType) -> &'static str {
+    match order_type {
+        OrderType::Market => "market",
+        OrderType::Limit => "limit",
+        OrderType::PostOnly => "post_only",
+    }
+}
+
+fn event_signal(
+    event: &TradingEvent,
+    signal_type: SignalType,
+    data: SignalData,
+    instrument: InstrumentId,
+    instruments: &InstrumentRegistry,
+) -> Signal {
+    let mut signal = Signal::new(signal_type, data);
+    signal.instrument = instrument;
+    // 品种和交易所名称仅用于日志展示，未注册的标的保留ID文本
+    match instruments.get(instrument) {
+        Some(known) => {
+            signal.symbol = known.symbol.clone();
+            signal.exchange = known.exchange.key().to_string();
+        }
+        None => signal.symbol = instrument.to_string(),
+    }
+    signal.source = "signal-collector".to_string();
+    signal.priority = match event.priority() {
+        Priority::High => 9,
+        Priority::Medium => 5,
+        Priority::Low => 1,
+    };
+    signal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use common::events::{CancelOrderEvent, ClosePositionEvent, HedgePositionEvent};
+    use common::instrument::{Instrument, MarketType};
+    use common::types::{Exchange, Side, TriggerType};
+    
+    const OKX_SWAP: InstrumentId = InstrumentId::new(6, 1);
+    const BYBIT_PERP: InstrumentId = InstrumentId::new(4, 1);
+    const BINANCE_PERP: InstrumentId = InstrumentId::new(2, 1);
+    
+    fn instruments() -> InstrumentRegistry {
+        let mut instruments = InstrumentRegistry::new();
+        for (id, exchange, venue, symbol) in [
+            (OKX_SWAP, Exchange::OKX, "okex-swap", "BTC-USDT-SWAP"),
+            (BYBIT_PERP, Exchange::Bybit, "bybit", "BTCUSDT"),
+        ] {
+            instruments.insert(Instrument::new(id, exchange, MarketType::Futures, venue, symbol));
+        }
+        instruments
+    }
+    
+    fn message(event: TradingEvent) -> EventMessage {
+        EventMessage {
+            event,
+            sequence_id: 7,
+            timestamp: Utc::now(),
+        }
+    }
+    
+    #[test]
+    fn test_close_event_becomes_reduce_only_signal() {
+        let action = route_event(&message(TradingEvent::ClosePosition(ClosePositionEvent {
+            instrument: OKX_SWAP,
+            side: Side::Sell,
+            quantity: 1.5,
+            order_type: OrderType::Limit,
+            price: Some(100.0),
+            trigger_type: TriggerType::MTCloseTrigger,
+            reason: "close".to_string(),
+            timestamp: Utc::now(),
+        })), &instruments());
+        
+        let EventAction::PreProcess(signal) = action else {
+            panic!("expected pre-process signal");
+        };
+        assert_eq!(signal.instrument, OKX_SWAP);
+        assert_eq!(signal.symbol, "BTC-USDT-SWAP");
+        assert_eq!(signal.exchange, "okex");
+        assert_eq!(signal.side, Some(Side::Sell));
+        assert_eq!(signal.priority, 9);
+        assert_eq!(signal.metadata.get("order_type").map(String::as_str), Some("limit"));
+        assert_eq!(signal.metadata.get("reduce_only").map(String::as_str), Some("true"));
+        assert_eq!(signal.metadata.get("sequence_id").map(String::as_str), Some("7"));
+    }
+    
+    #[test]
+    fn test_hedge_event_targets_hedge_instrument() {
+        let instruments = instruments();
+        let action = route_event(&message(TradingEvent::HedgePosition(HedgePositionEvent {
+            instrument: BINANCE_PERP,
+            hedge_instrument: BYBIT_PERP,
+            side: Side::Buy,
+            quantity: 2.0,
+            trigger_type: TriggerType::HedgeTrigger,
+            reason: "HEDGE_abc".to_string(),
+            timestamp: Utc::now(),
+        })), &instruments);
+        
+        let EventAction::PreProcess(signal) = action else {
+            panic!("expected pre-process signal");
+        };
+        assert_eq!(signal.signal_type, SignalType::Hedge);
+        assert!(matches!(signal.data, SignalData::Hedge { ref hedge_id, .. } if hedge_id == "HEDGE_abc"));
+        assert_eq!(signal.instrument, BYBIT_PERP);
+        assert_eq!(signal.exchange, "bybit");
+        assert_eq!(signal.metadata.get("primary_instrument").map(String::as_str), Some("2:1"));
+        
+        let action = route_event(&message(TradingEvent::CancelOrder(CancelOrderEvent {
+            order_id: "ORD_1".to_string(),
+            instrument: BINANCE_PERP,
+            reason: "stale".to_string(),
+            timestamp: Utc::now(),
+        })), &instruments);
+        assert!(matches!(action, EventAction::Cancel { ref order_id, .. } if order_id == "ORD_1"));
+    }
+}
@@ -0,0 +1,183 @@
+//! 对冲闭环 - 成交腿的剩余敞口分叉回 signal-collector，由 HedgeTrigger 生成对冲事件
+//!
+//! 剩余敞口按对冲腿数量计：-(成交腿持仓 × 换算比例 + 对冲腿持仓 + 在途对冲)。
+//! 在途对冲包括已发出、尚未回到 Pre-process 的对冲请求和未完成的对冲订单，
+//! 回环途中再次成交时只对冲新增的部分，对冲单部分成交后撤单留下的缺口在下一次回报时补齐。
+
+use std::collections::{HashMap, VecDeque};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use common::instrument::InstrumentId;
+use common::types::{Side, Signal, SignalData, SignalType};
+
+/// 对冲腿：成交腿每单位持仓对应的对冲腿数量（按合约面值换算）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct HedgeLeg {
+    pub instrument: InstrumentId,
+    pub ratio: Decimal,
+}
+
+/// 对冲请求 - 对冲腿需要补齐的数量
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgeRequest {
+    pub hedge_id: String,
+    pub instrument: InstrumentId,        // 成交腿
+    pub hedge_instrument: InstrumentId,  // 对冲腿
+    pub side: Side,
+    pub quantity: Decimal,
+}
+
+impl HedgeRequest {
+    /// 按剩余敞口生成请求，正数买入对冲腿、负数卖出
+    pub fn new(instrument: InstrumentId, leg: &HedgeLeg, residual: Decimal) -> Self {
+        let side = if residual > Decimal::ZERO { Side::Buy } else { Side::Sell };
+        Self {
+            hedge_id: format!("HEDGE_{}", Uuid::new_v4().simple()),
+            instrument,
+            hedge_instrument: leg.instrument,
+            side,
+            quantity: residual.abs(),
+        }
+    }
+
+    /// 对冲腿方向上的有符号数量
+    pub fn signed_quantity(&self) -> Decimal {
+        match self.side {
+            Side::Buy => self.quantity,
+            Side::Sell => -self.quantity,
+        }
+    }
+
+    /// 发往 signal-collector 的对冲信号：instrument 为成交腿，对冲腿写在metadata中
+    pub fn to_signal(&self) -> Signal {
+        let mut signal = Signal::new(
+            SignalType::Hedge,
+            SignalData::Hedge {
+                hedge_id: self.hedge_id.clone(),
+                target_position: self.signed_quantity().to_f64().unwrap_or(0.0),
+            },
+        );
+        signal.instrument = self.instrument;
+        signal.side = Some(self.side);
+        signal.quantity = self.quantity.to_f64();
+        signal.source = "pre-post-processor".to_string();
+        signal.priority = 10;
+        signal.metadata.insert("hedge_instrument".to_string(), self.hedge_instrument.to_string());
+        signal
+    }
+}
+
+/// 已发出、对冲事件尚未回到 Pre-process 的对冲请求，按成交腿分组
+pub struct PendingHedges {
+    requests: HashMap<InstrumentId, VecDeque<(HedgeRequest, DateTime<Utc>)>>,
+    timeout: Duration,
+}
+
+impl PendingHedges {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            requests: HashMap::new(),
+            timeout,
+        }
+    }
+
+    pub fn insert(&mut self, request: HedgeRequest, sent_at: DateTime<Utc>) {
+        self.requests
+            .entry(request.instrument)
+            .or_default()
+            .push_back((request, sent_at));
+    }
+
+    /// 对冲事件已回到 Pre-process（无论是否生成订单），按对冲ID结束对应的请求；
+    /// 请求已超时移除时返回 None，其他在途请求不受影响
+    pub fn complete(&mut self, instrument: InstrumentId, hedge_id: &str) -> Option<HedgeRequest> {
+        let queue = self.requests.get_mut(&instrument)?;
+        let index = queue.iter().position(|(request, _)| request.hedge_id == hedge_id)?;
+        let request = queue.remove(index).map(|(request, _)| request);
+        if queue.is_empty() {
+            self.requests.remove(&instrument);
+        }
+        request
+    }
+
+    /// 成交腿在途请求的有符号数量合计
+    pub fn quantity(&self, instrument: InstrumentId) -> Decimal {
+        self.requests
+            .get(&instrument)
+            .map(|queue| queue.iter().map(|(request, _)| request.signed_quantity()).sum())
+            .unwrap_or(Decimal::ZERO)
+    }
+
+    /// 移除超时的请求（信号或事件丢失），返回移除的数量
+    pub fn expire(&mut self, now: DateTime<Utc>) -> usize {
+        let mut expired = 0;
+        for queue in self.requests.values_mut() {
+            let before = queue.len();
+            queue.retain(|(_, sent_at)| now - *sent_at <= self.timeout);
+            expired += before - queue.len();
+        }
+        self.requests.retain(|_, queue| !queue.is_empty());
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPOT: InstrumentId = InstrumentId::new(7, 1);
+    const PERP: InstrumentId = InstrumentId::new(8, 1);
+
+    fn leg() -> HedgeLeg {
+        HedgeLeg { instrument: PERP, ratio: Decimal::ONE }
+    }
+
+    #[test]
+    fn test_request_signal_carries_both_legs() {
+        let request = HedgeRequest::new(SPOT, &leg(), Decimal::new(-15, 1));
+        assert_eq!(request.side, Side::Sell);
+        assert_eq!(request.quantity, Decimal::new(15, 1));
+
+        let signal = request.to_signal();
+        assert_eq!(signal.signal_type, SignalType::Hedge);
+        assert_eq!(signal.instrument, SPOT);
+        assert_eq!(signal.side, Some(Side::Sell));
+        assert_eq!(signal.quantity, Some(1.5));
+        assert_eq!(signal.metadata.get("hedge_instrument").map(String::as_str), Some("8:1"));
+        let SignalData::Hedge { hedge_id, target_position } = signal.data else {
+            panic!("expected hedge data");
+        };
+        assert_eq!(hedge_id, request.hedge_id);
+        assert_eq!(target_position, -1.5);
+    }
+
+    #[test]
+    fn test_pending_hedges_complete_and_expire() {
+        let now = Utc::now();
+        let mut pending = PendingHedges::new(Duration::seconds(5));
+        let first = HedgeRequest::new(SPOT, &leg(), Decimal::from(-2));
+        let second = HedgeRequest::new(SPOT, &leg(), Decimal::from(-1));
+        pending.insert(first.clone(), now - Duration::seconds(10));
+        pending.insert(second.clone(), now);
+        assert_eq!(pending.quantity(SPOT), Decimal::from(-3));
+
+        // 后发出的请求先回到 Pre-process 时只结束它自己
+        assert_eq!(pending.complete(SPOT, &second.hedge_id).unwrap().quantity, Decimal::from(1));
+        assert_eq!(pending.quantity(SPOT), Decimal::from(-2));
+        assert!(pending.complete(SPOT, &second.hedge_id).is_none());
+
+        let late = HedgeRequest::new(SPOT, &leg(), Decimal::from(4));
+        pending.insert(late.clone(), now);
+        assert_eq!(pending.expire(now), 1);
+        assert_eq!(pending.quantity(SPOT), Decimal::from(4));
+
+        // 已超时的请求的事件不会结束其他请求
+        assert!(pending.complete(SPOT, &first.hedge_id).is_none());
+        assert!(pending.complete(SPOT, &late.hedge_id).is_some());
+        assert_eq!(pending.quantity(SPOT), Decimal::ZERO);
+    }
+}
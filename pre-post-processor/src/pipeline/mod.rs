@@ -1,2 +1,6 @@
 pub mod pipeline;
-pub mod shared_state;
\ No newline at end of file
+pub mod shared_state;
+pub mod position;
+pub mod price_book;
+pub mod hedge;
+pub mod event_router;
\ No newline at end of file
@@ -1,12 +1,15 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use std::rc::Rc;
 use std::cell::RefCell;
-use tracing::{debug, instrument};
 use rust_decimal::Decimal;
-use rust_decimal::prelude::FromPrimitive;
+use tracing::{debug, info, instrument};
 
+use crate::pipeline::hedge::HedgeRequest;
 use crate::pipeline::shared_state::SharedState;
-use crate::order::order::Order;
+use crate::order::order::{Fill, Order};
+use crate::risk_control::risk_rules::{RiskRejection, RiskRuleChain, Verdict};
+use crate::risk_control::risk_state::RiskState;
+use common::instrument::{FilterViolation, InstrumentRegistry};
 use common::types::{ExecutionReport, Signal, SignalType};
 
 pub trait Pipeline<T> {
@@ -61,6 +64,7 @@ pub struct PreProcessContext {
     pub should_continue: bool,
     pub priority: u8,
     pub order: Option<Order>,
+    pub rejection: Option<RiskRejection>,
 }
 
 impl PreProcessContext {
@@ -71,6 +75,7 @@ impl PreProcessContext {
             should_continue: true,
             priority: 5,
             order: None,
+            rejection: None,
         }
     }
     
@@ -91,11 +96,23 @@ impl PreProcessContext {
         self.order = Some(order);
         self
     }
+    
+    #[inline(always)]
+    pub fn with_rejection(mut self, rejection: RiskRejection) -> Self {
+        self.rejection = Some(rejection);
+        self.stop()
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct PostProcessContext {
     pub report: ExecutionReport,
+    /// 报告对应的成交（非成交类报告为None）
+    pub fill: Option<Fill>,
+    /// 报告所在对冲配对的在途对冲（对冲腿有符号数量）
+    pub pending_hedge: Decimal,
+    /// 剩余敞口达到阈值时生成的对冲请求
+    pub hedge: Option<HedgeRequest>,
     pub shared_state: Rc<RefCell<SharedState>>,
     pub should_continue: bool,
 }
@@ -104,11 +121,26 @@ impl PostProcessContext {
     pub fn new(report: ExecutionReport, shared_state: Rc<RefCell<SharedState>>) -> Self {
         Self {
             report,
+            fill: None,
+            pending_hedge: Decimal::ZERO,
+            hedge: None,
             shared_state,
             should_continue: true,
         }
     }
     
+    #[inline(always)]
+    pub fn with_fill(mut self, fill: Option<Fill>) -> Self {
+        self.fill = fill;
+        self
+    }
+    
+    #[inline(always)]
+    pub fn with_pending_hedge(mut self, pending_hedge: Decimal) -> Self {
+        self.pending_hedge = pending_hedge;
+        self
+    }
+    
     #[inline(always)]
     pub fn stop(mut self) -> Self {
         self.should_continue = false;
@@ -116,118 +148,113 @@ impl PostProcessContext {
     }
 }
 
-pub type PreProcessResult = Result<Option<Order>>;
-pub type PostProcessResult = Result<()>;
+/// Pre-process 结果：生成订单，或被风控规则拒绝
+#[derive(Debug, Clone)]
+pub enum PreProcessOutcome {
+    Order(Box<Order>),
+    Rejected(RiskRejection),
+}
+
+pub type PreProcessResult = Result<PreProcessOutcome>;
+/// Post-process 结果：需要分叉回 signal-collector 的对冲请求
+pub type PostProcessResult = Result<Option<HedgeRequest>>;
 
 #[instrument(skip_all, fields(signal_id = %ctx.signal.id))]
-pub async fn execute_pre_pipeline(ctx: PreProcessContext) -> PreProcessResult {
+pub async fn execute_pre_pipeline(
+    ctx: PreProcessContext,
+    rules: &RiskRuleChain,
+    risk: &RiskState,
+    instruments: &InstrumentRegistry,
+) -> PreProcessResult {
     debug!("Starting pre-process pipeline");
     
-    let result = ctx
-        .pipe(check_signal_age)
-        .pipe(check_risk_control)
-        .pipe(check_position_limit)
-        .pipe(construct_order)
+    let result = check_risk_rules(ctx, rules, risk)?
+        .pipe(|ctx| construct_order(ctx, instruments))
         .pipe(assign_priority);
     
-    if result.should_continue && result.order.is_some() {
-        Ok(result.order)
-    } else {
-        Ok(None)
+    if let Some(rejection) = result.rejection {
+        return Ok(PreProcessOutcome::Rejected(rejection));
     }
+    
+    result.order
+        .map(|order| PreProcessOutcome::Order(Box::new(order)))
+        .ok_or_else(|| anyhow!("Pipeline stopped without an order or rejection"))
 }
 
 #[instrument(skip_all, fields(order_id = %ctx.report.order_id))]
 pub async fn execute_post_pipeline(ctx: PostProcessContext) -> PostProcessResult {
     debug!("Starting post-process pipeline");
     
-    let _ = ctx
+    let result = ctx
         .pipe(update_position)
         .pipe(update_risk_quota)
         .pipe(check_hedge_trigger)
-        .pipe(calculate_pnl)
-        .pipe(persist_state);
+        .pipe(calculate_pnl);
     
-    Ok(())
+    Ok(result.hedge)
 }
 
+/// 按规则链执行风控检查，拒绝时记录原因并停止；对冲信号走快速通道，只执行对冲必需的规则
 #[inline(always)]
-fn check_signal_age(mut ctx: PreProcessContext) -> PreProcessContext {
+fn check_risk_rules(ctx: PreProcessContext, rules: &RiskRuleChain, risk: &RiskState) -> Result<PreProcessContext> {
     if !ctx.should_continue {
-        return ctx;
-    }
-    
-    let age_ms = chrono::Utc::now()
-        .signed_duration_since(ctx.signal.timestamp)
-        .num_milliseconds();
-    
-    if age_ms > 100 {
-        debug!("Signal too old: {}ms", age_ms);
-        ctx.should_continue = false;
+        return Ok(ctx);
     }
     
-    ctx
-}
-
-#[inline(always)]
-fn check_risk_control(ctx: PreProcessContext) -> PreProcessContext {
-    if !ctx.should_continue {
-        return ctx;
-    }
-    
-    let should_stop = {
+    let verdict = {
         let state = ctx.shared_state.borrow();
-        if !state.risk_check(&ctx.signal) {
-            debug!("Risk control check failed");
-            true
+        if ctx.signal.signal_type == SignalType::Hedge {
+            rules.check_hedge(&ctx.signal, &state, risk)?
         } else {
-            false
+            rules.check_all(&ctx.signal, &state, risk)?
         }
     };
     
-    if should_stop {
-        return ctx.stop();
+    match verdict {
+        Verdict::Pass => Ok(ctx),
+        Verdict::Reject(rejection) => {
+            debug!("Risk rule rejected signal: {}", rejection);
+            Ok(ctx.with_rejection(rejection))
+        }
     }
-    
-    ctx
 }
 
+/// 由信号生成订单，价格和数量按标的过滤器取整，不满足过滤器时拒绝
 #[inline(always)]
-fn check_position_limit(ctx: PreProcessContext) -> PreProcessContext {
+fn construct_order(mut ctx: PreProcessContext, instruments: &InstrumentRegistry) -> PreProcessContext {
     if !ctx.should_continue {
         return ctx;
     }
     
-    let should_stop = {
-        let state = ctx.shared_state.borrow();
-        let quantity = ctx.signal.quantity
-            .and_then(|q| Decimal::from_f64(q))
-            .unwrap_or(Decimal::ZERO);
-        
-        if !state.position_check(&ctx.signal.symbol, quantity) {
-            debug!("Position limit check failed");
-            true
-        } else {
-            false
-        }
+    let mut order = Order::from_signal(&ctx.signal);
+    let Some(instrument) = instruments.get(order.instrument) else {
+        return ctx.with_rejection(RiskRejection::new("UnknownInstrument", "registered", order.instrument));
     };
     
-    if should_stop {
-        return ctx.stop();
+    // 市价单的价格是参考价，只用于最小名义价值校验
+    let price = Some(order.price).filter(|p| !p.is_zero());
+    match instrument.normalize_order(order.side, price, order.quantity) {
+        Ok((price, quantity)) => {
+            order.price = price.unwrap_or(order.price);
+            order.quantity = quantity;
+            order.remaining_quantity = quantity;
+        }
+        Err(violation) => {
+            debug!("Order for {} violates filters: {}", instrument, violation);
+            return ctx.with_rejection(filter_rejection(&violation));
+        }
     }
     
+    ctx.order = Some(order);
     ctx
 }
 
-#[inline(always)]
-fn construct_order(mut ctx: PreProcessContext) -> PreProcessContext {
-    if !ctx.should_continue {
-        return ctx;
+fn filter_rejection(violation: &FilterViolation) -> RiskRejection {
+    match violation {
+        FilterViolation::NotTrading(status) => RiskRejection::new(violation.filter(), "trading", status),
+        FilterViolation::BelowMinQuantity { min, quantity } => RiskRejection::new(violation.filter(), min, quantity),
+        FilterViolation::BelowMinNotional { min, notional } => RiskRejection::new(violation.filter(), min, notional),
     }
-    
-    let order = Order::from_signal(&ctx.signal);
-    ctx.order = Some(order);
-    ctx
 }
 
 #[inline(always)]
@@ -236,12 +263,12 @@ fn assign_priority(mut ctx: PreProcessContext) -> PreProcessContext {
         return ctx;
     }
     
+    // 信号自带的优先级（如平仓事件）不低于按类型分配的优先级，对冲单最高
     let priority = match ctx.signal.signal_type {
-        SignalType::Arbitrage => 10,
+        SignalType::Hedge | SignalType::Arbitrage => 10,
         SignalType::Market => 5,
-        SignalType::Hedge => 8,
         _ => 1,
-    };
+    }.max(ctx.signal.priority.min(10));
     
     ctx.priority = priority;
     if let Some(ref mut order) = ctx.order {
@@ -257,9 +284,10 @@ fn update_position(ctx: PostProcessContext) -> PostProcessContext {
         return ctx;
     }
     
-    {
+    // 只有成交改变仓位，确认、撤单等报告携带的是累计成交量
+    if let Some(ref fill) = ctx.fill {
         let mut state = ctx.shared_state.borrow_mut();
-        state.update_position(&ctx.report);
+        state.update_position(fill);
     }
     ctx
 }
@@ -277,19 +305,29 @@ fn update_risk_quota(ctx: PostProcessContext) -> PostProcessContext {
     ctx
 }
 
+/// 按配对计算对冲腿的剩余敞口，达到阈值时生成对冲请求（信号分叉点）
+///
+/// 成交腿和对冲腿的回报都会重新计算：对冲单成交后缺口收敛，部分成交后撤单留下的缺口再次触发
 #[inline(always)]
-fn check_hedge_trigger(ctx: PostProcessContext) -> PostProcessContext {
+fn check_hedge_trigger(mut ctx: PostProcessContext) -> PostProcessContext {
     if !ctx.should_continue {
         return ctx;
     }
     
-    {
+    ctx.hedge = {
         let state = ctx.shared_state.borrow();
-        if state.should_trigger_hedge(&format!("{:?}", ctx.report.symbol)) {
-            debug!("Hedge trigger detected for {:?}", ctx.report.symbol);
-        }
-    }
+        state.hedge_pair(ctx.report.instrument).and_then(|(primary, leg)| {
+            let residual = state.hedge_residual(primary, ctx.pending_hedge);
+            state.should_trigger_hedge(primary, residual).then(|| HedgeRequest::new(primary, &leg, residual))
+        })
+    };
     
+    if let Some(ref request) = ctx.hedge {
+        info!(
+            "Hedge trigger for {}: {:?} {} on {}",
+            request.instrument, request.side, request.quantity, request.hedge_instrument
+        );
+    }
     ctx
 }
 
@@ -306,15 +344,121 @@ fn calculate_pnl(ctx: PostProcessContext) -> PostProcessContext {
     ctx
 }
 
-#[inline(always)]
-fn persist_state(ctx: PostProcessContext) -> PostProcessContext {
-    if !ctx.should_continue {
-        return ctx;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use common::instrument::{Instrument, InstrumentId, MarketType, SymbolFilters};
+    use common::types::{Exchange, ExecutionType, OrderStatus, OrderType, Side, SignalData};
+    use crate::pipeline::hedge::HedgeLeg;
+    use crate::pipeline::shared_state::PositionInfo;
+
+    const BTC: InstrumentId = InstrumentId::new(8, 1);
+    const BTC_SPOT: InstrumentId = InstrumentId::new(7, 1);
+
+    fn registry() -> InstrumentRegistry {
+        let mut btc = Instrument::new(BTC, Exchange::Bitget, MarketType::Futures, "bitget-futures", "BTCUSDT");
+        btc.filters = SymbolFilters {
+            tick_size: Decimal::new(1, 1),
+            step_size: Decimal::new(1, 3),
+            min_qty: Decimal::new(1, 3),
+            min_notional: Decimal::from(5),
+            contract_value: Decimal::ONE,
+        };
+        let mut registry = InstrumentRegistry::new();
+        registry.insert(btc);
+        registry
     }
-    
-    {
-        let state = ctx.shared_state.borrow();
-        state.persist();
+
+    fn context(instrument: InstrumentId, price: f64, quantity: f64) -> PreProcessContext {
+        let mut signal = Signal::new(SignalType::Market, SignalData::Market { market_data: String::new() });
+        signal.instrument = instrument;
+        signal.side = Some(Side::Buy);
+        signal.price = Some(price);
+        signal.quantity = Some(quantity);
+        signal.metadata.insert("order_type".to_string(), "limit".to_string());
+        PreProcessContext::new(signal, Rc::new(RefCell::new(SharedState::new())))
     }
-    ctx
-}
\ No newline at end of file
+
+    #[test]
+    fn test_construct_order_rounds_to_filters() {
+        let ctx = construct_order(context(BTC, 65432.17, 0.0125), &registry());
+        let order = ctx.order.unwrap();
+        assert_eq!(order.price, Decimal::new(654321, 1));
+        assert_eq!(order.quantity, Decimal::new(12, 3));
+        assert_eq!(order.remaining_quantity, Decimal::new(12, 3));
+    }
+
+    #[test]
+    fn test_construct_order_rejects_filter_violations() {
+        // 0.0009 向下取整为0
+        let ctx = construct_order(context(BTC, 65432.0, 0.0009), &registry());
+        assert!(!ctx.should_continue);
+        assert_eq!(ctx.rejection.unwrap().rule, "MinQuantity");
+
+        // 100 × 0.01 = 1 < 5
+        let ctx = construct_order(context(BTC, 100.0, 0.01), &registry());
+        let rejection = ctx.rejection.unwrap();
+        assert_eq!(rejection.rule, "MinNotional");
+        assert_eq!(rejection.limit, "5");
+
+        let ctx = construct_order(context(InstrumentId::new(9, 9), 100.0, 1.0), &registry());
+        assert_eq!(ctx.rejection.unwrap().rule, "UnknownInstrument");
+        assert!(ctx.order.is_none());
+    }
+
+    fn report(instrument: InstrumentId) -> ExecutionReport {
+        ExecutionReport {
+            order_id: "EX-1".to_string(),
+            client_order_id: "ORD_1".to_string(),
+            instrument,
+            side: Side::Buy,
+            order_type: OrderType::Market,
+            price: 100.0,
+            quantity: 2.0,
+            filled_quantity: 2.0,
+            status: OrderStatus::Filled,
+            execution_type: ExecutionType::Trade,
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn hedge_trigger(state: &Rc<RefCell<SharedState>>, instrument: InstrumentId, pending: Decimal) -> Option<HedgeRequest> {
+        let ctx = PostProcessContext::new(report(instrument), state.clone()).with_pending_hedge(pending);
+        check_hedge_trigger(ctx).hedge
+    }
+
+    #[test]
+    fn test_hedge_trigger_forks_residual() {
+        let state = Rc::new(RefCell::new(SharedState::new()));
+        {
+            let mut state = state.borrow_mut();
+            state.set_hedge_pair(BTC_SPOT, HedgeLeg { instrument: BTC, ratio: Decimal::ONE }, Decimal::new(1, 3));
+            let mut spot = PositionInfo::new(BTC_SPOT);
+            spot.quantity = Decimal::from(2);
+            state.positions.insert(BTC_SPOT, spot);
+        }
+
+        // 现货买入2，合约需卖出2
+        let request = hedge_trigger(&state, BTC_SPOT, Decimal::ZERO).unwrap();
+        assert_eq!((request.instrument, request.hedge_instrument), (BTC_SPOT, BTC));
+        assert_eq!((request.side, request.quantity), (Side::Sell, Decimal::from(2)));
+
+        // 在途对冲抵扣后只补剩余部分，全部在途时不再触发
+        let request = hedge_trigger(&state, BTC_SPOT, Decimal::new(-15, 1)).unwrap();
+        assert_eq!(request.quantity, Decimal::new(5, 1));
+        assert!(hedge_trigger(&state, BTC_SPOT, Decimal::from(-2)).is_none());
+
+        // 对冲腿部分成交后撤单：对冲腿回报按缺口补齐
+        let mut perp = PositionInfo::new(BTC);
+        perp.quantity = Decimal::new(-12, 1);
+        state.borrow_mut().positions.insert(BTC, perp);
+        let request = hedge_trigger(&state, BTC, Decimal::ZERO).unwrap();
+        assert_eq!((request.instrument, request.quantity), (BTC_SPOT, Decimal::new(8, 1)));
+
+        // 缺口低于阈值、不在配对中的标的不触发
+        state.borrow_mut().positions.get_mut(&BTC).unwrap().quantity = Decimal::new(-19995, 4);
+        assert!(hedge_trigger(&state, BTC, Decimal::ZERO).is_none());
+        assert!(hedge_trigger(&state, InstrumentId::new(9, 9), Decimal::ZERO).is_none());
+    }
+}
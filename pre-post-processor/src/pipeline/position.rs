@@ -0,0 +1,375 @@
+//! 仓位账本 - 按成交维护多空仓位、均价和已实现盈亏
+//!
+//! 仓位数量带符号：多头为正、空头为负。同向成交按数量加权更新均价；
+//! 反向成交先平掉已有仓位并按均价结算盈亏，穿过零点时剩余数量以成交价开出反向新仓。
+//! 手续费和资金费直接计入已实现盈亏，另外单独累计便于对账。
+//! 未实现盈亏按最近的标记价格重估，尚未标记过的仓位以成交价估值。
+//! 数量按张计，成本、名义价值和盈亏都乘以合约面值换算为计价币，现货面值为1。
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use common::instrument::InstrumentId;
+use common::types::Side;
+use crate::order::order::Fill;
+
+/// 仓位信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionInfo {
+    pub instrument: InstrumentId,
+    pub quantity: Decimal,         // 当前持仓量（多正空负）
+    pub avg_price: Decimal,         // 平均成本价，无持仓时为0
+    pub realized_pnl: Decimal,      // 已实现盈亏（已扣手续费，含资金费）
+    pub unrealized_pnl: Decimal,    // 未实现盈亏
+    #[serde(default)]
+    pub fees_paid: Decimal,         // 累计手续费
+    #[serde(default)]
+    pub funding_pnl: Decimal,       // 累计资金费（收入为正）
+    #[serde(default)]
+    pub mark_price: Decimal,        // 最近一次重估的标记价格，未标记时为0
+    #[serde(default = "default_contract_value")]
+    pub contract_value: Decimal,    // 合约面值（每张对应的基础币数量）
+    pub last_update: DateTime<Utc>,
+}
+
+/// 一次账本更新引起的变化
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PositionChange {
+    /// 持仓量变化（带符号）
+    pub quantity: Decimal,
+    /// 持仓成本（数量 × 均价 × 面值，带符号）变化
+    pub cost: Decimal,
+    /// 已实现盈亏变化
+    pub realized_pnl: Decimal,
+    /// 未实现盈亏变化
+    pub unrealized_pnl: Decimal,
+}
+
+impl PositionInfo {
+    pub fn new(instrument: InstrumentId) -> Self {
+        Self {
+            instrument,
+            quantity: Decimal::ZERO,
+            avg_price: Decimal::ZERO,
+            realized_pnl: Decimal::ZERO,
+            unrealized_pnl: Decimal::ZERO,
+            fees_paid: Decimal::ZERO,
+            funding_pnl: Decimal::ZERO,
+            mark_price: Decimal::ZERO,
+            contract_value: Decimal::ONE,
+            last_update: Utc::now(),
+        }
+    }
+
+    /// 设置合约面值
+    pub fn with_contract_value(mut self, contract_value: Decimal) -> Self {
+        self.contract_value = contract_value;
+        self
+    }
+
+    /// 持仓成本（计价币，带符号）
+    pub fn cost(&self) -> Decimal {
+        self.quantity * self.avg_price * self.contract_value
+    }
+
+    /// 名义价值（绝对值），按标记价格计算，未标记时用均价
+    pub fn notional(&self) -> Decimal {
+        let price = if self.mark_price.is_zero() { self.avg_price } else { self.mark_price };
+        (self.quantity * price * self.contract_value).abs()
+    }
+
+    /// 按给定价格计算的未实现盈亏，空头持仓量为负
+    pub fn unrealized_at(&self, price: Decimal) -> Decimal {
+        (price - self.avg_price) * self.quantity * self.contract_value
+    }
+
+    /// 按标记价格重估未实现盈亏
+    pub fn revalue(&mut self, mark_price: Decimal) -> PositionChange {
+        let before = self.clone();
+        self.mark_price = mark_price;
+        self.unrealized_pnl = self.unrealized_at(mark_price);
+        self.change_since(&before)
+    }
+
+    /// 按一笔成交更新仓位
+    pub fn apply_fill(&mut self, fill: &Fill) -> PositionChange {
+        let before = self.clone();
+
+        let signed = match fill.side {
+            Side::Buy => fill.quantity,
+            Side::Sell => -fill.quantity,
+        };
+
+        if self.quantity.is_zero() || self.quantity.is_sign_positive() == signed.is_sign_positive() {
+            // 开仓或加仓：按数量加权均价
+            let quantity = self.quantity + signed;
+            if !quantity.is_zero() {
+                self.avg_price = (self.quantity * self.avg_price + fill.price * signed) / quantity;
+            }
+            self.quantity = quantity;
+        } else {
+            // 减仓、平仓或反手：平掉的部分按均价结算
+            let closed = fill.quantity.min(self.quantity.abs());
+            let direction = if self.quantity.is_sign_positive() { Decimal::ONE } else { Decimal::NEGATIVE_ONE };
+            self.realized_pnl += (fill.price - self.avg_price) * closed * direction * self.contract_value;
+
+            self.quantity += signed;
+            if self.quantity.is_zero() {
+                self.avg_price = Decimal::ZERO;
+            } else if self.quantity.is_sign_positive() != direction.is_sign_positive() {
+                // 穿过零点，剩余部分以成交价开出反向仓位
+                self.avg_price = fill.price;
+            }
+        }
+
+        // 非计价币手续费不计入（与 Fill::calculate_net_amount 一致）
+        let fee = fill.calculate_amount() - fill.calculate_net_amount();
+        self.realized_pnl -= fee;
+        self.fees_paid += fee;
+
+        self.last_update = fill.timestamp;
+        self.change_since(&before)
+    }
+
+    /// 结算一笔资金费，收入为正、支出为负
+    pub fn apply_funding(&mut self, payment: Decimal, settled_at: DateTime<Utc>) -> PositionChange {
+        let before = self.clone();
+        self.realized_pnl += payment;
+        self.funding_pnl += payment;
+        self.last_update = settled_at;
+        self.change_since(&before)
+    }
+
+    /// 相对之前状态的变化
+    pub fn change_since(&self, before: &PositionInfo) -> PositionChange {
+        PositionChange {
+            quantity: self.quantity - before.quantity,
+            cost: self.cost() - before.cost(),
+            realized_pnl: self.realized_pnl - before.realized_pnl,
+            unrealized_pnl: self.unrealized_pnl - before.unrealized_pnl,
+        }
+    }
+}
+
+fn default_contract_value() -> Decimal {
+    Decimal::ONE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BTC: InstrumentId = InstrumentId::new(2, 1);
+
+    fn dec(value: i64) -> Decimal {
+        Decimal::from(value)
+    }
+
+    fn fill(side: Side, price: i64, quantity: i64, fee: Decimal) -> Fill {
+        Fill {
+            order_id: "O1".to_string(),
+            trade_id: "T1".to_string(),
+            instrument: BTC,
+            side,
+            price: dec(price),
+            quantity: dec(quantity),
+            fee,
+            fee_currency: "USDT".to_string(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn position(fills: &[Fill]) -> PositionInfo {
+        let mut position = PositionInfo::new(BTC);
+        for f in fills {
+            position.apply_fill(f);
+        }
+        position
+    }
+
+    #[test]
+    fn test_long_open_add_and_reduce() {
+        let mut pos = position(&[
+            fill(Side::Buy, 100, 2, Decimal::ZERO),
+            fill(Side::Buy, 130, 1, Decimal::ZERO),
+        ]);
+        assert_eq!(pos.quantity, dec(3));
+        assert_eq!(pos.avg_price, dec(110));
+
+        let change = pos.apply_fill(&fill(Side::Sell, 120, 1, Decimal::ZERO));
+        assert_eq!(pos.quantity, dec(2));
+        assert_eq!(pos.avg_price, dec(110)); // 减仓不改变均价
+        assert_eq!(change.realized_pnl, dec(10));
+        assert_eq!(change.quantity, dec(-1));
+        assert_eq!(change.cost, dec(-110));
+    }
+
+    #[test]
+    fn test_short_open_add_and_cover() {
+        let mut pos = position(&[
+            fill(Side::Sell, 100, 1, Decimal::ZERO),
+            fill(Side::Sell, 94, 2, Decimal::ZERO),
+        ]);
+        assert_eq!(pos.quantity, dec(-3));
+        assert_eq!(pos.avg_price, dec(96));
+        assert_eq!(pos.cost(), dec(-288));
+
+        // 空头在低于均价处回补获利
+        let change = pos.apply_fill(&fill(Side::Buy, 90, 2, Decimal::ZERO));
+        assert_eq!(change.realized_pnl, dec(12));
+        assert_eq!(pos.quantity, dec(-1));
+        assert_eq!(pos.avg_price, dec(96));
+
+        // 高于均价回补亏损，平仓后均价归零
+        let change = pos.apply_fill(&fill(Side::Buy, 100, 1, Decimal::ZERO));
+        assert_eq!(change.realized_pnl, dec(-4));
+        assert!(pos.quantity.is_zero());
+        assert!(pos.avg_price.is_zero());
+        assert_eq!(pos.realized_pnl, dec(8));
+    }
+
+    #[test]
+    fn test_flip_long_to_short_resets_avg_price() {
+        let mut pos = position(&[fill(Side::Buy, 100, 2, Decimal::ZERO)]);
+
+        let change = pos.apply_fill(&fill(Side::Sell, 110, 5, Decimal::ZERO));
+        assert_eq!(change.realized_pnl, dec(20)); // 只结算平掉的2手
+        assert_eq!(pos.quantity, dec(-3));
+        assert_eq!(pos.avg_price, dec(110));
+        assert_eq!(change.quantity, dec(-5));
+        assert_eq!(change.cost, dec(-330) - dec(200));
+    }
+
+    #[test]
+    fn test_flip_short_to_long_resets_avg_price() {
+        let mut pos = position(&[fill(Side::Sell, 100, 1, Decimal::ZERO)]);
+
+        let change = pos.apply_fill(&fill(Side::Buy, 105, 3, Decimal::ZERO));
+        assert_eq!(change.realized_pnl, dec(-5));
+        assert_eq!(pos.quantity, dec(2));
+        assert_eq!(pos.avg_price, dec(105));
+
+        // 反手后的仓位按新均价结算
+        let change = pos.apply_fill(&fill(Side::Sell, 107, 2, Decimal::ZERO));
+        assert_eq!(change.realized_pnl, dec(4));
+        assert!(pos.quantity.is_zero());
+    }
+
+    #[test]
+    fn test_reopen_after_flat_uses_new_price() {
+        let pos = position(&[
+            fill(Side::Buy, 100, 1, Decimal::ZERO),
+            fill(Side::Sell, 100, 1, Decimal::ZERO),
+            fill(Side::Sell, 80, 1, Decimal::ZERO),
+        ]);
+        assert_eq!(pos.quantity, dec(-1));
+        assert_eq!(pos.avg_price, dec(80));
+        assert!(pos.realized_pnl.is_zero());
+    }
+
+    #[test]
+    fn test_fees_reduce_realized_pnl() {
+        let mut pos = PositionInfo::new(BTC);
+
+        // 开仓手续费直接计入已实现盈亏，不摊入均价
+        let change = pos.apply_fill(&fill(Side::Buy, 100, 1, Decimal::new(5, 2)));
+        assert_eq!(change.realized_pnl, Decimal::new(-5, 2));
+        assert_eq!(pos.avg_price, dec(100));
+
+        let change = pos.apply_fill(&fill(Side::Sell, 110, 1, Decimal::new(11, 2)));
+        assert_eq!(change.realized_pnl, Decimal::new(989, 2));
+        assert_eq!(pos.realized_pnl, Decimal::new(984, 2));
+        assert_eq!(pos.fees_paid, Decimal::new(16, 2));
+    }
+
+    #[test]
+    fn test_non_quote_fee_not_counted() {
+        let mut bnb_fee = fill(Side::Buy, 100, 1, Decimal::new(1, 3));
+        bnb_fee.fee_currency = "BNB".to_string();
+
+        let pos = position(&[bnb_fee]);
+        assert!(pos.realized_pnl.is_zero());
+        assert!(pos.fees_paid.is_zero());
+    }
+
+    #[test]
+    fn test_funding_payments() {
+        let mut pos = position(&[fill(Side::Sell, 100, 1, Decimal::ZERO)]);
+
+        let change = pos.apply_funding(Decimal::new(3, 2), Utc::now());
+        assert_eq!(change.realized_pnl, Decimal::new(3, 2));
+        assert!(change.quantity.is_zero() && change.cost.is_zero());
+
+        pos.apply_funding(Decimal::new(-5, 2), Utc::now());
+        assert_eq!(pos.funding_pnl, Decimal::new(-2, 2));
+        assert_eq!(pos.realized_pnl, Decimal::new(-2, 2));
+        assert_eq!(pos.quantity, dec(-1));
+        assert_eq!(pos.avg_price, dec(100));
+    }
+
+    #[test]
+    fn test_revalue_long_and_short() {
+        let mut long = position(&[fill(Side::Buy, 100, 2, Decimal::ZERO)]);
+        assert_eq!(long.notional(), dec(200)); // 未标记时按均价
+
+        let change = long.revalue(dec(105));
+        assert_eq!(change.unrealized_pnl, dec(10));
+        assert!(change.quantity.is_zero() && change.realized_pnl.is_zero());
+        assert_eq!(long.notional(), dec(210));
+
+        let change = long.revalue(dec(98));
+        assert_eq!(long.unrealized_pnl, dec(-4));
+        assert_eq!(change.unrealized_pnl, dec(-14));
+
+        let mut short = position(&[fill(Side::Sell, 100, 3, Decimal::ZERO)]);
+        short.revalue(dec(90));
+        assert_eq!(short.unrealized_pnl, dec(30));
+        assert_eq!(short.notional(), dec(270));
+
+        // 平仓后重估为0
+        short.apply_fill(&fill(Side::Buy, 90, 3, Decimal::ZERO));
+        short.revalue(dec(95));
+        assert!(short.unrealized_pnl.is_zero());
+    }
+
+    #[test]
+    fn test_contract_value_scales_cost_and_pnl() {
+        // 每张0.01个基础币：均价按张计，成本、名义价值和盈亏按计价币计
+        let mut pos = PositionInfo::new(BTC).with_contract_value(Decimal::new(1, 2));
+        pos.apply_fill(&fill(Side::Buy, 100, 4, Decimal::ZERO));
+        pos.apply_fill(&fill(Side::Buy, 130, 2, Decimal::ZERO));
+        assert_eq!(pos.avg_price, dec(110));
+        assert_eq!(pos.cost(), Decimal::new(66, 1));
+
+        let change = pos.revalue(dec(120));
+        assert_eq!(change.unrealized_pnl, Decimal::new(6, 1));
+        assert_eq!(pos.notional(), Decimal::new(72, 1));
+
+        let change = pos.apply_fill(&fill(Side::Sell, 120, 2, Decimal::ZERO));
+        assert_eq!(change.realized_pnl, Decimal::new(2, 1));
+        assert_eq!(change.cost, Decimal::new(-22, 1));
+    }
+
+    #[test]
+    fn test_realized_pnl_matches_cash_flow_when_flat() {
+        // 平仓后已实现盈亏等于全部成交的净现金流
+        let fills = [
+            fill(Side::Buy, 100, 3, Decimal::new(3, 1)),
+            fill(Side::Sell, 120, 5, Decimal::new(6, 1)),
+            fill(Side::Buy, 90, 4, Decimal::new(36, 2)),
+            fill(Side::Sell, 95, 2, Decimal::new(19, 2)),
+        ];
+        let pos = position(&fills);
+        assert!(pos.quantity.is_zero());
+
+        let cash: Decimal = fills
+            .iter()
+            .map(|f| match f.side {
+                Side::Buy => -(f.calculate_amount() + f.fee),
+                Side::Sell => f.calculate_net_amount(),
+            })
+            .sum();
+        assert_eq!(pos.realized_pnl, cash);
+    }
+}
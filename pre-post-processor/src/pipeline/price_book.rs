@@ -0,0 +1,107 @@
+//! 最新行情 - 保存各标的最近一次最优买卖价，为仓位重估提供标记价格
+//!
+//! 合约优先使用交易所标记价格，缺失时退回中间价；现货使用中间价。
+//! 超过最大时效的行情不参与重估，仓位保持上一次的估值。
+
+use std::collections::HashMap;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::FromPrimitive;
+
+use common::instrument::{Instrument, InstrumentId, MarketType};
+use common::types::Quote;
+
+pub struct PriceBook {
+    quotes: HashMap<InstrumentId, Quote>,
+    max_age: Duration,
+}
+
+impl PriceBook {
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            quotes: HashMap::new(),
+            max_age,
+        }
+    }
+
+    /// 更新行情，乱序到达的旧行情丢弃
+    pub fn update(&mut self, quote: Quote) {
+        match self.quotes.get(&quote.instrument) {
+            Some(last) if last.timestamp > quote.timestamp => {}
+            _ => {
+                self.quotes.insert(quote.instrument, quote);
+            }
+        }
+    }
+
+    /// 标的在now时刻的标记价格，无行情或行情过期时为None
+    pub fn mark_price(&self, instrument: &Instrument, now: DateTime<Utc>) -> Option<Decimal> {
+        let quote = self.quotes.get(&instrument.id)?;
+        if now - quote.timestamp > self.max_age {
+            return None;
+        }
+
+        let price = match instrument.market_type {
+            MarketType::Futures => quote.mark_price.filter(|p| *p > 0.0).or_else(|| quote.mid_price()),
+            MarketType::Spot => quote.mid_price(),
+        }?;
+        Decimal::from_f64(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::types::Exchange;
+
+    const SWAP: InstrumentId = InstrumentId::new(6, 1);
+    const SPOT: InstrumentId = InstrumentId::new(5, 1);
+
+    fn quote(instrument: InstrumentId, bid: f64, ask: f64, mark: Option<f64>, timestamp: DateTime<Utc>) -> Quote {
+        Quote {
+            instrument,
+            bid_price: bid,
+            bid_quantity: 1.0,
+            ask_price: ask,
+            ask_quantity: 1.0,
+            mark_price: mark,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_mark_price_by_market_type() {
+        let now = Utc::now();
+        let swap = Instrument::new(SWAP, Exchange::OKX, MarketType::Futures, "okex-swap", "BTC-USDT-SWAP");
+        let spot = Instrument::new(SPOT, Exchange::OKX, MarketType::Spot, "okex-spot", "BTC-USDT");
+
+        let mut book = PriceBook::new(Duration::seconds(5));
+        assert_eq!(book.mark_price(&swap, now), None);
+
+        book.update(quote(SWAP, 99.0, 101.0, Some(100.5), now));
+        book.update(quote(SPOT, 99.0, 101.0, Some(100.5), now));
+        assert_eq!(book.mark_price(&swap, now), Some(Decimal::new(1005, 1)));
+        assert_eq!(book.mark_price(&spot, now), Some(Decimal::from(100)));
+
+        // 合约没有标记价格时退回中间价
+        book.update(quote(SWAP, 99.0, 100.0, None, now));
+        assert_eq!(book.mark_price(&swap, now), Some(Decimal::new(995, 1)));
+
+        // 单边行情没有中间价
+        book.update(quote(SPOT, 0.0, 101.0, None, now));
+        assert_eq!(book.mark_price(&spot, now), None);
+    }
+
+    #[test]
+    fn test_stale_and_out_of_order_quotes() {
+        let now = Utc::now();
+        let spot = Instrument::new(SPOT, Exchange::OKX, MarketType::Spot, "okex-spot", "BTC-USDT");
+
+        let mut book = PriceBook::new(Duration::seconds(5));
+        book.update(quote(SPOT, 99.0, 101.0, None, now));
+        book.update(quote(SPOT, 89.0, 91.0, None, now - Duration::seconds(1)));
+        assert_eq!(book.mark_price(&spot, now), Some(Decimal::from(100)));
+
+        assert_eq!(book.mark_price(&spot, now + Duration::seconds(6)), None);
+    }
+}
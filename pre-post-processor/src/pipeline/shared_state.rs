@@ -1,25 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, warn};
 
-use common::types::{Signal, ExecutionReport};
+use common::instrument::InstrumentId;
+use common::types::{ExecutionReport, ExecutionType};
+use crate::order::order::Fill;
 use crate::risk_control::risk_state::RiskSummary;
+use super::hedge::HedgeLeg;
 
-/// 仓位信息
-#[derive(Debug, Clone)]
-pub struct PositionInfo {
-    pub symbol: String,
-    pub quantity: Decimal,         // 当前持仓量
-    pub avg_price: Decimal,         // 平均成本价
-    pub realized_pnl: Decimal,      // 已实现盈亏
-    pub unrealized_pnl: Decimal,    // 未实现盈亏
-    pub last_update: DateTime<Utc>,
-}
+pub use super::position::{PositionChange, PositionInfo};
 
 /// 风控配额
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskQuota {
     // 限制参数
     pub max_position: Decimal,      // 最大仓位（手数）
@@ -47,39 +42,21 @@ impl RiskQuota {
             last_trade_time: None,
         }
     }
-    
-    /// 检查是否可以交易
-    #[inline]
-    pub fn can_trade(&self, quantity: Decimal, capital: Decimal) -> bool {
-        self.current_position + quantity <= self.max_position
-            && self.current_capital + capital <= self.max_capital
-            && self.pending_orders < self.max_pending_orders
-            && self.daily_trades < 1000
-    }
-    
-    /// 检查冷却时间
-    #[inline]
-    pub fn check_cooldown(&self, cooldown_seconds: i64) -> bool {
-        match self.last_trade_time {
-            None => true,
-            Some(last_time) => {
-                let elapsed = Utc::now().signed_duration_since(last_time).num_seconds();
-                elapsed >= cooldown_seconds
-            }
-        }
-    }
 }
 
 /// 共享状态 - 单线程环境，不需要Arc/Mutex
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SharedState {
-    pub positions: HashMap<String, PositionInfo>,     // 所有仓位
-    pub risk_quotas: HashMap<String, RiskQuota>,      // 风控配额
-    pub total_exposure: Decimal,                      // 总敞口
-    pub max_total_exposure: Decimal,                  // 最大总敞口（0.03）
-    pub warning_threshold: Decimal,                   // 预警阈值（0.025）
-    pub hedge_thresholds: HashMap<String, Decimal>,   // 对冲触发阈值
-    pub last_persist_time: DateTime<Utc>,            // 最后持久化时间
+    pub positions: HashMap<InstrumentId, PositionInfo>,     // 所有仓位
+    pub risk_quotas: HashMap<InstrumentId, RiskQuota>,      // 风控配额
+    pub total_exposure: Decimal,                            // 总敞口
+    pub restricted_symbols: HashSet<InstrumentId>,          // 风控受限品种
+    pub global_restricted: bool,                            // 全局受限
+    pub hedge_thresholds: HashMap<InstrumentId, Decimal>,   // 对冲触发阈值（按成交腿，对冲腿数量）
+    #[serde(default)]
+    pub hedge_legs: HashMap<InstrumentId, HedgeLeg>,        // 成交腿 -> 对冲腿
+    #[serde(default)]
+    pub contract_values: HashMap<InstrumentId, Decimal>,    // 合约面值，未设置的品种为1
 }
 
 impl SharedState {
@@ -88,173 +65,162 @@ impl SharedState {
             positions: HashMap::new(),
             risk_quotas: HashMap::new(),
             total_exposure: Decimal::ZERO,
-            max_total_exposure: Decimal::from_f64(0.03).unwrap(),
-            warning_threshold: Decimal::from_f64(0.025).unwrap(),
+            restricted_symbols: HashSet::new(),
+            global_restricted: false,
             hedge_thresholds: HashMap::new(),
-            last_persist_time: Utc::now(),
+            hedge_legs: HashMap::new(),
+            contract_values: HashMap::new(),
         }
     }
     
-    /// 风控检查 - 检查信号是否满足风控要求
-    #[inline]
-    pub fn risk_check(&self, signal: &Signal) -> bool {
-        // 获取该品种的风控配额，如果没有则使用默认值
-        let default_quota = RiskQuota::new();
-        let quota = self.risk_quotas.get(&signal.symbol)
-            .unwrap_or(&default_quota);
-        
-        // 检查配额限制
-        let quantity = signal.quantity
-            .and_then(|q| Decimal::from_f64(q))
-            .unwrap_or(Decimal::ZERO);
-        let notional = signal.price
-            .and_then(|p| signal.quantity.map(|q| p * q))
-            .and_then(|n| Decimal::from_f64(n))
-            .unwrap_or(Decimal::ZERO);
+    /// 更新仓位 - 按成交记账（多空、反手、手续费）
+    pub fn update_position(&mut self, fill: &Fill) -> PositionChange {
+        let contract_value = self.contract_value(fill.instrument);
+        let change = self.positions
+            .entry(fill.instrument)
+            .or_insert_with(|| PositionInfo::new(fill.instrument).with_contract_value(contract_value))
+            .apply_fill(fill);
         
-        if !quota.can_trade(quantity, notional) {
-            debug!("Risk quota exceeded for {}", signal.symbol);
-            return false;
-        }
+        self.calculate_total_exposure();  // 重新计算总敞口
+        change
+    }
+    
+    /// 结算资金费 - 收入为正、支出为负
+    pub fn apply_funding(&mut self, instrument: InstrumentId, payment: Decimal, settled_at: DateTime<Utc>) -> PositionChange {
+        let contract_value = self.contract_value(instrument);
+        self.positions
+            .entry(instrument)
+            .or_insert_with(|| PositionInfo::new(instrument).with_contract_value(contract_value))
+            .apply_funding(payment, settled_at)
+    }
+    
+    /// 更新风控配额使用情况 - 仓位和占用资金取自仓位账本
+    pub fn update_risk_quota(&mut self, report: &ExecutionReport) {
+        let (position, capital) = self.positions
+            .get(&report.instrument)
+            .map(|p| (p.quantity.abs(), p.cost().abs()))
+            .unwrap_or_default();
         
-        // 检查冷却时间（60秒）
-        if !quota.check_cooldown(60) {
-            debug!("Cooldown period active for {}", signal.symbol);
-            return false;
-        }
+        let quota = self.risk_quotas
+            .entry(report.instrument)
+            .or_insert_with(RiskQuota::new);
         
-        // 检查总敞口限制
-        if self.total_exposure >= self.max_total_exposure {
-            warn!("Total exposure limit reached: {}", self.total_exposure);
-            return false;
+        quota.current_position = position;
+        quota.current_capital = capital;
+        if report.execution_type == ExecutionType::Trade {
+            quota.daily_trades += 1;
+            quota.last_trade_time = Some(Utc::now());
         }
         
-        // 预警提示
-        if self.total_exposure >= self.warning_threshold {
-            warn!("Exposure warning threshold reached: {}", self.total_exposure);
+        // 订单完成后减少挂单数
+        if report.status == common::types::OrderStatus::Filled {
+            quota.pending_orders = quota.pending_orders.saturating_sub(1);
         }
-        
-        true
     }
     
-    /// 仓位检查 - 检查是否超过单品种仓位限制
-    #[inline]
-    pub fn position_check(&self, symbol: &str, quantity: Decimal) -> bool {
-        if let Some(position) = self.positions.get(symbol) {
-            let new_quantity = position.quantity + quantity;
-            new_quantity.abs() <= Decimal::from(100)  // 单品种最大100手
-        } else {
-            quantity.abs() <= Decimal::from(100)
+    /// 设置品种的合约面值，已有仓位一并更新
+    pub fn set_contract_value(&mut self, instrument: InstrumentId, contract_value: Decimal) {
+        self.contract_values.insert(instrument, contract_value);
+        if let Some(position) = self.positions.get_mut(&instrument) {
+            position.contract_value = contract_value;
         }
+        self.calculate_total_exposure();
     }
     
-    /// 更新仓位 - 根据执行报告更新仓位信息
-    pub fn update_position(&mut self, report: &ExecutionReport) {
-        let symbol_str = format!("{:?}", report.symbol);
-        let position = self.positions
-            .entry(symbol_str.clone())
-            .or_insert_with(|| PositionInfo {
-                symbol: symbol_str.clone(),
-                quantity: Decimal::ZERO,
-                avg_price: Decimal::ZERO,
-                realized_pnl: Decimal::ZERO,
-                unrealized_pnl: Decimal::ZERO,
-                last_update: Utc::now(),
-            });
-        
-        // 买入：更新均价和数量
-        let filled_quantity = Decimal::from_f64(report.filled_quantity).unwrap_or(Decimal::ZERO);
-        let price = Decimal::from_f64(report.price).unwrap_or(Decimal::ZERO);
-        
-        if report.side == common::types::Side::Buy {
-            let new_quantity = position.quantity + filled_quantity;
-            if new_quantity != Decimal::ZERO {
-                position.avg_price = (position.avg_price * position.quantity 
-                    + price * filled_quantity) / new_quantity;
-            }
-            position.quantity = new_quantity;
-        } else {
-            // 卖出：计算已实现盈亏
-            position.quantity -= filled_quantity;
-            let pnl = (price - position.avg_price) * filled_quantity;
-            position.realized_pnl += pnl;
+    /// 品种的合约面值，未设置时为1
+    pub fn contract_value(&self, instrument: InstrumentId) -> Decimal {
+        self.contract_values.get(&instrument).copied().unwrap_or(Decimal::ONE)
+    }
+    
+    /// 设置对冲配对和触发阈值
+    pub fn set_hedge_pair(&mut self, primary: InstrumentId, leg: HedgeLeg, threshold: Decimal) {
+        self.hedge_legs.insert(primary, leg);
+        self.hedge_thresholds.insert(primary, threshold);
+    }
+    
+    /// 标的所在的对冲配对（成交腿或对冲腿），返回成交腿和对冲腿
+    pub fn hedge_pair(&self, instrument: InstrumentId) -> Option<(InstrumentId, HedgeLeg)> {
+        if let Some(leg) = self.hedge_legs.get(&instrument) {
+            return Some((instrument, *leg));
         }
-        
-        position.last_update = Utc::now();
-        self.calculate_total_exposure();  // 重新计算总敞口
+        self.hedge_legs
+            .iter()
+            .find(|(_, leg)| leg.instrument == instrument)
+            .map(|(primary, leg)| (*primary, *leg))
     }
     
-    /// 更新风控配额使用情况
-    pub fn update_risk_quota(&mut self, report: &ExecutionReport) {
-        let quota = self.risk_quotas
-            .entry(format!("{:?}", report.symbol))
-            .or_insert_with(RiskQuota::new);
+    /// 对冲腿的剩余敞口 = -(成交腿持仓 × 换算比例 + 对冲腿持仓 + 在途对冲)，正数表示需买入对冲腿
+    pub fn hedge_residual(&self, primary: InstrumentId, pending: Decimal) -> Decimal {
+        let Some(leg) = self.hedge_legs.get(&primary) else {
+            return Decimal::ZERO;
+        };
+        let quantity = |instrument| self.positions.get(&instrument).map(|p| p.quantity).unwrap_or_default();
         
-        let filled_quantity = Decimal::from_f64(report.filled_quantity).unwrap_or(Decimal::ZERO);
-        let price = Decimal::from_f64(report.price).unwrap_or(Decimal::ZERO);
-        
-        quota.current_position += filled_quantity;
-        quota.current_capital += price * filled_quantity;
-        quota.daily_trades += 1;
-        quota.last_trade_time = Some(Utc::now());
-        
-        // 订单完成后减少挂单数
-        if report.status == common::types::OrderStatus::Filled {
-            quota.pending_orders = quota.pending_orders.saturating_sub(1);
-        }
+        -(quantity(primary) * leg.ratio + quantity(leg.instrument) + pending)
     }
     
-    /// 检查是否需要触发对冲
+    /// 检查是否需要触发对冲 - 剩余敞口不小于成交腿的触发阈值
     #[inline]
-    pub fn should_trigger_hedge(&self, symbol: &str) -> bool {
-        if let Some(position) = self.positions.get(symbol) {
-            if let Some(threshold) = self.hedge_thresholds.get(symbol) {
-                return position.quantity.abs() >= *threshold;
-            }
-        }
-        false
+    pub fn should_trigger_hedge(&self, primary: InstrumentId, residual: Decimal) -> bool {
+        self.hedge_thresholds
+            .get(&primary)
+            .is_some_and(|threshold| !residual.is_zero() && residual.abs() >= *threshold)
     }
     
-    /// 计算盈亏
+    /// 计算盈亏 - 成交后按最近的标记价格重估，尚未标记时用成交价
     pub fn calculate_pnl(&mut self, report: &ExecutionReport) {
-        let symbol_str = format!("{:?}", report.symbol);
-        if let Some(position) = self.positions.get_mut(&symbol_str) {
-            let market_price = Decimal::from_f64(report.price).unwrap_or(Decimal::ZERO);
-            // 未实现盈亏 = (市价 - 均价) * 持仓量
-            position.unrealized_pnl = (market_price - position.avg_price) * position.quantity;
+        if let Some(position) = self.positions.get_mut(&report.instrument) {
+            let market_price = if position.mark_price.is_zero() {
+                Decimal::from_f64(report.price).unwrap_or(Decimal::ZERO)
+            } else {
+                position.mark_price
+            };
+            position.unrealized_pnl = position.unrealized_at(market_price);
             debug!(
                 "PnL for {}: realized={}, unrealized={}", 
-                symbol_str, 
+                report.instrument, 
                 position.realized_pnl, 
                 position.unrealized_pnl
             );
         }
     }
     
-    /// 计算总敞口
+    /// 按标记价格重估所有仓位，返回未实现盈亏有变化的品种；没有标记价格的仓位保持不变
+    pub fn revalue<F>(&mut self, mark_price: F) -> Vec<(InstrumentId, PositionChange)>
+    where
+        F: Fn(InstrumentId) -> Option<Decimal>,
+    {
+        let mut changes = Vec::new();
+        for (instrument, position) in self.positions.iter_mut() {
+            if let Some(mark) = mark_price(*instrument) {
+                let change = position.revalue(mark);
+                if !change.unrealized_pnl.is_zero() {
+                    changes.push((*instrument, change));
+                }
+            }
+        }
+        
+        self.calculate_total_exposure();
+        changes
+    }
+    
+    /// 计算总敞口 - 按标记价格计算的名义价值之和
     fn calculate_total_exposure(&mut self) {
         self.total_exposure = self.positions
             .values()
-            .map(|p| (p.quantity * p.avg_price).abs())
+            .map(PositionInfo::notional)
             .sum();
     }
     
-    /// 持久化状态（每60秒）
-    pub fn persist(&self) {
-        if Utc::now().signed_duration_since(self.last_persist_time).num_seconds() > 60 {
-            debug!("Persisting state to disk");
-            // TODO: 实际的持久化逻辑
-        }
-    }
-    
-    /// 更新风控状态摘要
+    /// 更新风控状态摘要 - 只同步受限状态，总敞口由仓位账本计算
     pub fn update_risk_state(&mut self, summary: RiskSummary) {
-        // 更新总敞口
-        self.total_exposure = summary.total_exposure;
+        // 受限品种由规则链中的 RestrictionRule 拒绝
+        self.restricted_symbols = summary.restricted_symbols.iter().cloned().collect();
+        self.global_restricted = summary.global_restricted;
         
         // 更新受限品种的风控配额
-        for symbol in &summary.restricted_symbols {
-            if let Some(quota) = self.risk_quotas.get_mut(symbol) {
+        for instrument in &summary.restricted_symbols {
+            if let Some(quota) = self.risk_quotas.get_mut(instrument) {
                 // 限制该品种的交易
                 quota.max_position = Decimal::ZERO;
                 quota.max_capital = Decimal::ZERO;
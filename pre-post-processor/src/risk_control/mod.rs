@@ -2,6 +2,7 @@ pub mod risk_state;
 pub mod risk_rules;
 pub mod risk_calculator;
 pub mod risk_initializer;
+pub mod risk_exporter;
 
 pub use risk_state::{RiskState, SymbolRiskState, GlobalRiskState, RiskLevel};
 pub use risk_rules::{RiskRule, RiskRules};
@@ -2,9 +2,13 @@ use std::collections::VecDeque;
 use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
 use tracing::debug;
 
+use common::instrument::InstrumentId;
+
 /// 风险指标计算器
+#[derive(Clone, Serialize, Deserialize)]
 pub struct RiskCalculator {
     // 历史数据窗口
     pnl_history: VecDeque<PnLPoint>,      // 盈亏历史
@@ -19,22 +23,22 @@ pub struct RiskCalculator {
 }
 
 /// 盈亏数据点
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct PnLPoint {
     timestamp: DateTime<Utc>,
     value: Decimal,
-    symbol: String,
+    instrument: InstrumentId,
 }
 
 /// 敞口数据点
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ExposurePoint {
     timestamp: DateTime<Utc>,
     value: Decimal,
 }
 
 /// 风险指标
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RiskMetrics {
     // 盈亏指标
     pub total_pnl: Decimal,           // 总盈亏
@@ -45,6 +49,7 @@ pub struct RiskMetrics {
     // 风险指标
     pub max_drawdown: Decimal,        // 最大回撤
     pub max_drawdown_duration: i64,   // 最大回撤持续时间（秒）
+    pub current_drawdown: Decimal,    // 当前回撤（距累计盈亏高点）
     pub sharpe_ratio: Decimal,        // 夏普比率
     pub sortino_ratio: Decimal,       // 索提诺比率
     
@@ -72,6 +77,7 @@ impl RiskMetrics {
             profit_factor: Decimal::ZERO,
             max_drawdown: Decimal::ZERO,
             max_drawdown_duration: 0,
+            current_drawdown: Decimal::ZERO,
             sharpe_ratio: Decimal::ZERO,
             sortino_ratio: Decimal::ZERO,
             avg_exposure: Decimal::ZERO,
@@ -104,11 +110,11 @@ impl RiskCalculator {
     }
     
     /// 添加盈亏数据点
-    pub fn add_pnl(&mut self, symbol: String, value: Decimal) {
+    pub fn add_pnl(&mut self, instrument: InstrumentId, value: Decimal) {
         let point = PnLPoint {
             timestamp: Utc::now(),
             value,
-            symbol,
+            instrument,
         };
         
         self.pnl_history.push_back(point);
@@ -140,6 +146,16 @@ impl RiskCalculator {
         self.cached_metrics = None;
     }
     
+    /// 盈亏历史（时间、品种、金额），按时间先后
+    pub fn pnl_history(&self) -> impl Iterator<Item = (DateTime<Utc>, InstrumentId, Decimal)> + '_ {
+        self.pnl_history.iter().map(|p| (p.timestamp, p.instrument, p.value))
+    }
+    
+    /// 敞口历史（时间、总敞口），按时间先后
+    pub fn exposure_history(&self) -> impl Iterator<Item = (DateTime<Utc>, Decimal)> + '_ {
+        self.exposure_history.iter().map(|p| (p.timestamp, p.value))
+    }
+    
     /// 计算所有风险指标
     pub fn calculate_metrics(&mut self) -> RiskMetrics {
         // 检查缓存
@@ -275,6 +291,7 @@ impl RiskCalculator {
         
         metrics.max_drawdown = max_drawdown;
         metrics.max_drawdown_duration = max_duration;
+        metrics.current_drawdown = peak - cumulative_pnl;
     }
     
     /// 计算夏普比率和索提诺比率
@@ -0,0 +1,408 @@
+//! 风控状态导出
+//!
+//! 把运行中的全局配置、品种状态、持仓、规则和历史数据导出为 RiskInitRequest，
+//! 与 RiskInitializer 的导入互逆，重启或备用的处理器可以直接用导出文件初始化。
+
+use std::collections::BTreeSet;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+
+use common::risk_proto::risk_control::{
+    self as proto, RiskInitRequest, GlobalRiskConfig, SymbolInitState, SymbolRiskConfig,
+    InitialPosition, RiskRulesConfig, PositionRules, FrequencyRules, PnLRules, MarketRules,
+    TimeRules, HistoricalData, PnLRecord, TradeRecord, ExposureRecord,
+};
+use common::instrument::{InstrumentId, InstrumentRegistry};
+use common::types::Side;
+
+use crate::order::order::Fill;
+use crate::pipeline::shared_state::{PositionInfo, SharedState};
+use super::risk_calculator::RiskCalculator;
+use super::risk_rules::{RiskRules, SymbolRule, TimeRule};
+use super::risk_state::{RiskState, SymbolRiskState};
+
+/// 风控状态导出器
+///
+/// 持仓单独导出为 `positions`；导入时持仓的仓位、资金和未实现盈亏会叠加到品种状态上，
+/// 所以品种状态中导出的是扣除持仓部分后的余量。品种以 `场所名:原生符号` 导出，
+/// 未注册的标的退回 `2:17` 形式。历史记录的时间戳为毫秒。
+pub struct RiskExporter<'a> {
+    pub risk_state: &'a RiskState,
+    pub shared_state: &'a SharedState,
+    pub rules: &'a RiskRules,
+    pub calculator: &'a RiskCalculator,
+    pub instruments: &'a InstrumentRegistry,
+    /// 最近一次下发的全局配置，未下发过时不导出全局配置
+    pub global_config: Option<&'a GlobalRiskConfig>,
+    pub fills: Vec<&'a Fill>,
+}
+
+impl RiskExporter<'_> {
+    /// 生成初始化请求
+    pub fn export(&self) -> RiskInitRequest {
+        RiskInitRequest {
+            global_config: self.global_config(),
+            symbol_states: self.symbol_states(),
+            positions: self.positions(),
+            rules_config: Some(self.rules_config()),
+            historical_data: Some(self.historical_data()),
+        }
+    }
+
+    /// 全局限制取当前规则，规则中没有的风险系数和启用标记取下发时的值
+    fn global_config(&self) -> Option<GlobalRiskConfig> {
+        let received = self.global_config?;
+        Some(GlobalRiskConfig {
+            max_total_exposure_ratio: to_f64(self.rules.max_total_exposure_ratio),
+            max_position_symbols: self.rules.max_position_symbols as u32,
+            max_daily_trades: self.rules.max_daily_trades as u32,
+            max_daily_loss: to_f64(self.rules.max_daily_loss),
+            total_capital: to_f64(self.rules.total_capital),
+            risk_multiplier: received.risk_multiplier,
+            enable_global_risk_control: received.enable_global_risk_control,
+        })
+    }
+
+    /// 有风控状态或品种规则的品种，按标的ID排序
+    fn symbol_states(&self) -> Vec<SymbolInitState> {
+        let instruments: BTreeSet<&InstrumentId> = self.risk_state.symbol_states.keys()
+            .chain(self.rules.symbol_rules.keys())
+            .collect();
+
+        instruments
+            .into_iter()
+            .map(|instrument| {
+                let state = self.risk_state.symbol_states.get(instrument);
+                let position = self.shared_state.positions.get(instrument);
+                let name = self.instruments.name(*instrument);
+                symbol_init_state(name, state, position, self.rules.symbol_rules.get(instrument))
+            })
+            .collect()
+    }
+
+    /// 持仓按标的ID排序，已注册的标的导出场所名称和原生符号
+    fn positions(&self) -> Vec<InitialPosition> {
+        let mut held: Vec<&PositionInfo> = self.shared_state.positions
+            .values()
+            .filter(|p| !p.quantity.is_zero())
+            .collect();
+        held.sort_by_key(|p| p.instrument);
+
+        held.into_iter()
+            .map(|p| {
+                let quantity = p.quantity.abs();
+                let (exchange, symbol) = match self.instruments.get(p.instrument) {
+                    Some(instrument) => (instrument.venue.clone(), instrument.symbol.clone()),
+                    None => (String::new(), p.instrument.to_string()),
+                };
+                InitialPosition {
+                    symbol,
+                    exchange,
+                    side: if p.quantity > Decimal::ZERO { "BUY" } else { "SELL" }.to_string(),
+                    quantity: to_f64(quantity),
+                    avg_price: to_f64(p.avg_price),
+                    market_price: to_f64(p.avg_price + p.unrealized_pnl / (p.quantity * p.contract_value)),
+                    unrealized_pnl: to_f64(p.unrealized_pnl),
+                    position_time: p.last_update.timestamp(),
+                }
+            })
+            .collect()
+    }
+
+    fn rules_config(&self) -> RiskRulesConfig {
+        let rules = self.rules;
+        RiskRulesConfig {
+            position_rules: rules.position_rule.as_ref().map(|r| PositionRules {
+                max_single_position_ratio: to_f64(r.max_single_position_ratio),
+                max_total_position_ratio: to_f64(r.max_total_position_ratio),
+                max_correlated_position_ratio: to_f64(r.max_correlated_position_ratio),
+                enabled: true,
+            }),
+            frequency_rules: rules.frequency_rule.as_ref().map(|r| FrequencyRules {
+                max_trades_per_minute: r.max_trades_per_minute as u32,
+                max_trades_per_hour: r.max_trades_per_hour as u32,
+                max_trades_per_day: r.max_trades_per_day as u32,
+                min_trade_interval_ms: r.min_trade_interval_ms,
+                enabled: true,
+            }),
+            pnl_rules: rules.pnl_rule.as_ref().map(|r| PnLRules {
+                max_daily_loss: to_f64(r.max_daily_loss),
+                max_single_loss: to_f64(r.max_single_loss),
+                max_consecutive_losses: r.max_consecutive_losses as u32,
+                max_drawdown: to_f64(r.max_drawdown),
+                enabled: true,
+            }),
+            market_rules: rules.market_rule.as_ref().map(|r| MarketRules {
+                max_slippage: to_f64(r.max_slippage),
+                min_liquidity: to_f64(r.min_liquidity),
+                max_volatility: to_f64(r.max_volatility),
+                enabled: true,
+            }),
+            time_rules: rules.time_rule.as_ref().map(time_rules),
+        }
+    }
+
+    fn historical_data(&self) -> HistoricalData {
+        let pnl_records = self.calculator
+            .pnl_history()
+            .map(|(timestamp, instrument, value)| PnLRecord {
+                timestamp: timestamp.timestamp_millis(),
+                symbol: self.instruments.name(instrument),
+                value: to_f64(value),
+                r#type: "REALIZED".to_string(),
+            })
+            .collect();
+
+        let mut fills = self.fills.clone();
+        fills.sort_by_key(|f| f.timestamp);
+        let trade_records = fills
+            .into_iter()
+            .map(|f| TradeRecord {
+                timestamp: f.timestamp.timestamp_millis(),
+                symbol: self.instruments.name(f.instrument),
+                side: side_name(f.side).to_string(),
+                quantity: to_f64(f.quantity),
+                price: to_f64(f.price),
+                pnl: 0.0, // 成交不单独核算盈亏，已实现盈亏见 pnl_records
+            })
+            .collect();
+
+        let exposure_records = self.calculator
+            .exposure_history()
+            .map(|(timestamp, total_exposure)| ExposureRecord {
+                timestamp: timestamp.timestamp_millis(),
+                total_exposure: to_f64(total_exposure),
+                symbol_exposures: Default::default(),
+            })
+            .collect();
+
+        HistoricalData { pnl_records, trade_records, exposure_records }
+    }
+}
+
+/// 品种状态扣除持仓部分，导入时与 `positions` 叠加还原
+fn symbol_init_state(
+    symbol: String,
+    state: Option<&SymbolRiskState>,
+    position: Option<&PositionInfo>,
+    rule: Option<&SymbolRule>,
+) -> SymbolInitState {
+    let (held, held_capital, held_unrealized) = position
+        .filter(|p| !p.quantity.is_zero())
+        .map(|p| (p.quantity, p.cost(), p.unrealized_pnl))
+        .unwrap_or_default();
+
+    let mut init = SymbolInitState {
+        symbol,
+        position: to_f64(-held),
+        capital_used: to_f64(-held_capital),
+        unrealized_pnl: to_f64(-held_unrealized),
+        symbol_config: rule.map(|r| SymbolRiskConfig {
+            max_position: to_f64(r.max_position),
+            max_capital_used: to_f64(r.max_capital_used),
+            max_pending_orders: r.max_pending_orders as u32,
+            max_trades_per_window: r.max_trades_per_window as u32,
+            time_window_seconds: r.time_window_seconds,
+            stop_loss_ratio: 0.0,
+            enabled: true,
+        }),
+        ..Default::default()
+    };
+
+    if let Some(state) = state {
+        init.position = to_f64(state.position - held);
+        init.capital_used = to_f64(state.capital_used - held_capital);
+        init.unrealized_pnl = to_f64(state.unrealized_pnl - held_unrealized);
+        init.pending_orders = state.pending_orders as u32;
+        init.daily_trades = state.daily_trades as u32;
+        init.realized_pnl = to_f64(state.realized_pnl);
+        init.is_restricted = state.is_restricted;
+        init.restriction_reason = state.restriction_reason.clone().unwrap_or_default();
+        init.restriction_until = state.restriction_until.map_or(0, |t| t.timestamp());
+    }
+
+    init
+}
+
+fn time_rules(rule: &TimeRule) -> TimeRules {
+    TimeRules {
+        trading_windows: rule.trading_windows
+            .iter()
+            .map(|w| proto::TradingWindow {
+                start_time: format!("{}{}", w.start.format("%H:%M"), w.offset),
+                end_time: format!("{}{}", w.end.format("%H:%M"), w.offset),
+                weekdays: w.weekdays.clone(),
+            })
+            .collect(),
+        blackout_dates: rule.blackout_dates.iter().map(DateTime::<Utc>::timestamp).collect(),
+        enabled: true,
+    }
+}
+
+fn side_name(side: Side) -> &'static str {
+    match side {
+        Side::Buy => "BUY",
+        Side::Sell => "SELL",
+    }
+}
+
+fn to_f64(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::rc::Rc;
+    use prost::Message;
+    use common::instrument::{Instrument, MarketType};
+    use common::types::Exchange;
+    use crate::risk_control::risk_initializer::RiskInitializer;
+
+    const BTC: InstrumentId = InstrumentId::new(2, 1);
+
+    fn registry() -> Rc<InstrumentRegistry> {
+        let mut registry = InstrumentRegistry::new();
+        registry.insert(Instrument::new(BTC, Exchange::Binance, MarketType::Futures, "binance_futures", "BTCUSDT"));
+        Rc::new(registry)
+    }
+
+    fn init_request() -> RiskInitRequest {
+        RiskInitRequest {
+            global_config: Some(GlobalRiskConfig {
+                max_total_exposure_ratio: 0.5,
+                max_position_symbols: 5,
+                max_daily_trades: 200,
+                max_daily_loss: 5000.0,
+                total_capital: 100000.0,
+                risk_multiplier: 1.5,
+                enable_global_risk_control: true,
+            }),
+            symbol_states: vec![SymbolInitState {
+                symbol: "binance_futures:BTCUSDT".to_string(),
+                daily_trades: 3,
+                realized_pnl: 25.0,
+                symbol_config: Some(SymbolRiskConfig {
+                    max_position: 10.0,
+                    max_capital_used: 5000.0,
+                    max_pending_orders: 4,
+                    max_trades_per_window: 20,
+                    time_window_seconds: 60,
+                    stop_loss_ratio: 0.0,
+                    enabled: true,
+                }),
+                ..Default::default()
+            }],
+            positions: vec![InitialPosition {
+                symbol: "BTCUSDT".to_string(),
+                exchange: "binance_futures".to_string(),
+                side: "BUY".to_string(),
+                quantity: 2.0,
+                avg_price: 100.0,
+                market_price: 105.0,
+                unrealized_pnl: 10.0,
+                position_time: 1_700_000_000,
+            }],
+            rules_config: Some(RiskRulesConfig {
+                frequency_rules: Some(FrequencyRules {
+                    max_trades_per_minute: 5,
+                    max_trades_per_hour: 100,
+                    max_trades_per_day: 500,
+                    min_trade_interval_ms: 200,
+                    enabled: true,
+                }),
+                time_rules: Some(TimeRules {
+                    trading_windows: vec![proto::TradingWindow {
+                        start_time: "09:30+08:00".to_string(),
+                        end_time: "16:00+08:00".to_string(),
+                        weekdays: vec![1, 2, 3, 4, 5],
+                    }],
+                    blackout_dates: vec![1_700_000_000],
+                    enabled: true,
+                }),
+                ..Default::default()
+            }),
+            historical_data: Some(HistoricalData {
+                pnl_records: vec![
+                    PnLRecord { timestamp: 0, symbol: "2:1".to_string(), value: 30.0, r#type: "REALIZED".to_string() },
+                    PnLRecord { timestamp: 0, symbol: "binance_futures:BTCUSDT".to_string(), value: -5.0, r#type: "REALIZED".to_string() },
+                ],
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn export(initializer: &RiskInitializer, shared: &SharedState, instruments: &InstrumentRegistry) -> RiskInitRequest {
+        RiskExporter {
+            risk_state: initializer.get_risk_state(),
+            shared_state: shared,
+            rules: initializer.get_risk_rules(),
+            calculator: initializer.get_risk_calculator(),
+            instruments,
+            global_config: initializer.get_global_config(),
+            fills: Vec::new(),
+        }
+        .export()
+    }
+
+    #[test]
+    fn test_export_round_trips_through_initializer() {
+        let instruments = registry();
+        let mut original = RiskInitializer::new(instruments.clone());
+        original.initialize_from_request(init_request()).unwrap();
+
+        // 运行中的持仓与导入的持仓一致
+        let mut shared = SharedState::new();
+        shared.positions.insert(BTC, PositionInfo {
+            quantity: Decimal::from(2),
+            avg_price: Decimal::from(100),
+            unrealized_pnl: Decimal::from(10),
+            last_update: Utc.timestamp_opt(1_700_000_000, 0).unwrap(),
+            ..PositionInfo::new(BTC)
+        });
+
+        // 经过protobuf编码后导入新的初始化器
+        let exported = export(&original, &shared, &instruments);
+        assert_eq!(exported.symbol_states[0].symbol, "binance_futures:BTCUSDT");
+        assert_eq!(exported.positions[0].exchange, "binance_futures");
+        let decoded = RiskInitRequest::decode(exported.encode_to_vec().as_slice()).unwrap();
+        let mut restored = RiskInitializer::new(instruments.clone());
+        restored.initialize_from_request(decoded).unwrap();
+
+        // 品种状态不会因持仓重复叠加
+        let before = &original.get_risk_state().symbol_states[&BTC];
+        let after = &restored.get_risk_state().symbol_states[&BTC];
+        assert_eq!(after.position, Decimal::from(2));
+        assert_eq!(after.position, before.position);
+        assert_eq!(after.capital_used, before.capital_used);
+        assert_eq!(after.unrealized_pnl, before.unrealized_pnl);
+        assert_eq!(after.daily_trades, 3);
+        assert_eq!(after.realized_pnl, before.realized_pnl);
+
+        let rules = restored.get_risk_rules();
+        assert_eq!(rules.total_capital, Decimal::from(100000));
+        assert_eq!(rules.max_daily_trades, 200);
+        assert_eq!(rules.symbol_rules[&BTC].max_position, Decimal::from(10));
+        assert_eq!(rules.frequency_rule.as_ref().unwrap().min_trade_interval_ms, 200);
+        assert!(rules.pnl_rule.is_none());
+
+        let time_rule = rules.time_rule.as_ref().unwrap();
+        let original_time_rule = original.get_risk_rules().time_rule.as_ref().unwrap();
+        assert_eq!(time_rule.trading_windows, original_time_rule.trading_windows);
+        assert_eq!(time_rule.blackout_dates, original_time_rule.blackout_dates);
+
+        assert_eq!(restored.get_global_config().unwrap().risk_multiplier, 1.5);
+        assert_eq!(restored.get_risk_calculator().pnl_history().count(), 2);
+    }
+
+    #[test]
+    fn test_export_without_init_has_no_global_config() {
+        let instruments = registry();
+        let exported = export(&RiskInitializer::new(instruments.clone()), &SharedState::new(), &instruments);
+        assert!(exported.global_config.is_none());
+        assert!(exported.symbol_states.is_empty());
+        assert!(exported.positions.is_empty());
+    }
+}
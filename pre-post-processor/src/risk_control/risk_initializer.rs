@@ -1,3 +1,4 @@
+use std::rc::Rc;
 use chrono::{Utc, TimeZone};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
@@ -9,10 +10,11 @@ use common::risk_proto::risk_control::{
     GlobalRiskConfig, SymbolInitState, InitialPosition,
     RiskRulesConfig, HistoricalData,
 };
+use common::instrument::{InstrumentId, InstrumentRegistry};
 use common::types::{Signal, SignalData};
 
-use super::risk_state::{RiskState, SymbolRiskState};
-use super::risk_rules::{RiskRules, PositionRule, FrequencyRule, PnLRule, MarketRule, TimeRule};
+use super::risk_state::{RiskState, RiskSummary, SymbolRiskState};
+use super::risk_rules::{RiskRules, PositionRule, FrequencyRule, PnLRule, MarketRule, TimeRule, TradingWindow};
 use super::risk_calculator::RiskCalculator;
 
 /// 风控初始化器
@@ -20,17 +22,27 @@ pub struct RiskInitializer {
     risk_state: RiskState,
     risk_rules: RiskRules,
     risk_calculator: RiskCalculator,
+    global_config: Option<GlobalRiskConfig>, // 最近一次下发的全局配置，导出时原样带回
+    instruments: Rc<InstrumentRegistry>,     // 解析请求中的品种文本
 }
 
 impl RiskInitializer {
-    pub fn new() -> Self {
+    pub fn new(instruments: Rc<InstrumentRegistry>) -> Self {
         Self {
             risk_state: RiskState::new(),
             risk_rules: RiskRules::new(),
             risk_calculator: RiskCalculator::new(10000), // 默认历史窗口大小
+            global_config: None,
+            instruments,
         }
     }
     
+    /// 解析请求中的品种（`2:17` 或 `binance_futures:BTCUSDT`）
+    fn resolve_instrument(&self, text: &str) -> Result<InstrumentId, String> {
+        self.instruments.parse(text)
+            .ok_or_else(|| format!("Unknown instrument: {}", text))
+    }
+    
     /// 处理风控初始化信号
     pub fn process_init_signal(&mut self, signal: &Signal) -> Result<RiskInitResponse, String> {
         // 从信号中提取protobuf数据
@@ -43,8 +55,10 @@ impl RiskInitializer {
         let init_request = RiskInitRequest::decode(&protobuf_data[..])
             .map_err(|e| format!("Failed to decode protobuf: {}", e))?;
         
-        // 执行初始化
-        self.initialize_from_request(init_request)
+        // 执行初始化，响应带上请求信号ID供客户端匹配
+        let mut response = self.initialize_from_request(init_request)?;
+        response.request_id = signal.id.clone();
+        Ok(response)
     }
     
     /// 从protobuf请求初始化风控系统
@@ -86,6 +100,7 @@ impl RiskInitializer {
     /// 初始化全局配置
     fn initialize_global_config(&mut self, config: GlobalRiskConfig) -> Result<(), String> {
         debug!("Initializing global risk config");
+        self.global_config = Some(config.clone());
         
         let _global_state = &mut self.risk_state.global_state;
         
@@ -112,7 +127,8 @@ impl RiskInitializer {
     fn initialize_symbol_state(&mut self, state: SymbolInitState) -> Result<(), String> {
         debug!("Initializing symbol state for {}", state.symbol);
         
-        let mut symbol_risk_state = SymbolRiskState::new(state.symbol.clone());
+        let instrument = self.resolve_instrument(&state.symbol)?;
+        let mut symbol_risk_state = SymbolRiskState::new(instrument);
         
         // 设置初始值
         symbol_risk_state.position = Decimal::from_f64(state.position).unwrap_or(Decimal::ZERO);
@@ -138,7 +154,7 @@ impl RiskInitializer {
         if let Some(symbol_config) = state.symbol_config {
             // 可以将品种配置存储到规则中
             self.risk_rules.add_symbol_rule(
-                state.symbol.clone(),
+                instrument,
                 Decimal::from_f64(symbol_config.max_position).unwrap_or(Decimal::ZERO),
                 Decimal::from_f64(symbol_config.max_capital_used).unwrap_or(Decimal::ZERO),
                 symbol_config.max_pending_orders as usize,
@@ -148,7 +164,7 @@ impl RiskInitializer {
         }
         
         // 将状态加入到风控状态中
-        self.risk_state.symbol_states.insert(state.symbol, symbol_risk_state);
+        self.risk_state.symbol_states.insert(instrument, symbol_risk_state);
         
         Ok(())
     }
@@ -157,22 +173,32 @@ impl RiskInitializer {
     fn initialize_position(&mut self, position: InitialPosition) -> Result<(), String> {
         debug!("Initializing position for {} on {}", position.symbol, position.exchange);
         
+        // 品种可以是完整的标的文本，也可以是场所名称 + 原生符号
+        let instrument = match self.instruments.resolve_venue(&position.exchange, &position.symbol) {
+            Some(instrument) => instrument,
+            None => self.resolve_instrument(&position.symbol)?,
+        };
+        
         // 获取或创建品种状态
         let symbol_state = self.risk_state.symbol_states
-            .entry(position.symbol.clone())
-            .or_insert_with(|| SymbolRiskState::new(position.symbol.clone()));
+            .entry(instrument)
+            .or_insert_with(|| SymbolRiskState::new(instrument));
         
         // 更新仓位信息
         let quantity = Decimal::from_f64(position.quantity).unwrap_or(Decimal::ZERO);
         let avg_price = Decimal::from_f64(position.avg_price).unwrap_or(Decimal::ZERO);
+        let contract_value = self.instruments
+            .get(instrument)
+            .map(|i| i.filters.contract_value)
+            .unwrap_or(Decimal::ONE);
         
         // 根据方向更新仓位
         if position.side.to_uppercase() == "BUY" {
             symbol_state.position += quantity;
-            symbol_state.capital_used += quantity * avg_price;
+            symbol_state.capital_used += quantity * avg_price * contract_value;
         } else if position.side.to_uppercase() == "SELL" {
             symbol_state.position -= quantity;
-            symbol_state.capital_used -= quantity * avg_price;
+            symbol_state.capital_used -= quantity * avg_price * contract_value;
         }
         
         // 更新未实现盈亏
@@ -239,7 +265,9 @@ impl RiskInitializer {
             if time_rules.enabled {
                 let mut trading_windows = Vec::new();
                 for window in time_rules.trading_windows {
-                    trading_windows.push((window.start_time, window.end_time, window.weekdays));
+                    let parsed = TradingWindow::parse(&window.start_time, &window.end_time, window.weekdays)
+                        .map_err(|e| format!("Invalid trading window {}-{}: {}", window.start_time, window.end_time, e))?;
+                    trading_windows.push(parsed);
                 }
                 
                 self.risk_rules.time_rule = Some(TimeRule {
@@ -261,8 +289,9 @@ impl RiskInitializer {
         
         // 恢复盈亏记录
         for pnl_record in &data.pnl_records {
+            let instrument = self.resolve_instrument(&pnl_record.symbol)?;
             let value = Decimal::from_f64(pnl_record.value).unwrap_or(Decimal::ZERO);
-            self.risk_calculator.add_pnl(pnl_record.symbol.clone(), value);
+            self.risk_calculator.add_pnl(instrument, value);
         }
         
         // 恢复敞口记录
@@ -285,20 +314,11 @@ impl RiskInitializer {
     fn create_init_response(&self) -> RiskInitResponse {
         let summary = self.risk_state.get_summary();
         
-        let state_summary = RiskStateSummary {
-            total_exposure: summary.total_exposure.to_f64().unwrap_or(0.0),
-            risk_level: format!("{:?}", summary.risk_level),
-            active_positions: summary.active_positions as u32,
-            available_capital: 0.0, // 需要根据实际情况计算
-            daily_pnl: summary.daily_pnl.to_f64().unwrap_or(0.0),
-            restricted_symbols: summary.restricted_symbols,
-            global_restricted: summary.global_restricted,
-        };
-        
         RiskInitResponse {
             success: true,
             message: "Risk control system initialized successfully".to_string(),
-            state_summary: Some(state_summary),
+            state_summary: Some(state_summary(summary, self.risk_rules.total_capital, &self.instruments)),
+            request_id: String::new(),
         }
     }
     
@@ -312,8 +332,40 @@ impl RiskInitializer {
         &mut self.risk_state
     }
     
+    /// 获取风险指标计算器（用于写入快照）
+    pub fn get_risk_calculator(&self) -> &RiskCalculator {
+        &self.risk_calculator
+    }
+    
+    /// 获取风险指标计算器（用于记录新的盈亏数据）
+    pub fn get_risk_calculator_mut(&mut self) -> &mut RiskCalculator {
+        &mut self.risk_calculator
+    }
+    
     /// 获取风控规则（用于其他模块访问）
     pub fn get_risk_rules(&self) -> &RiskRules {
         &self.risk_rules
     }
-}
\ No newline at end of file
+    
+    /// 获取最近一次下发的全局配置（用于导出）
+    pub fn get_global_config(&self) -> Option<&GlobalRiskConfig> {
+        self.global_config.as_ref()
+    }
+}
+
+/// 风控摘要转为protobuf，可用资金为总资金扣除总敞口（不低于0）
+pub fn state_summary(summary: RiskSummary, total_capital: Decimal, instruments: &InstrumentRegistry) -> RiskStateSummary {
+    RiskStateSummary {
+        total_exposure: summary.total_exposure.to_f64().unwrap_or(0.0),
+        risk_level: format!("{:?}", summary.risk_level),
+        active_positions: summary.active_positions as u32,
+        available_capital: (total_capital - summary.total_exposure).max(Decimal::ZERO).to_f64().unwrap_or(0.0),
+        daily_pnl: summary.daily_pnl.to_f64().unwrap_or(0.0),
+        restricted_symbols: summary.restricted_symbols
+            .into_iter()
+            .map(|instrument| instruments.name(instrument))
+            .collect(),
+        global_restricted: summary.global_restricted,
+        timestamp: Utc::now().timestamp_millis(),
+    }
+}
@@ -1,25 +1,71 @@
 use std::collections::HashMap;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive;
-use chrono::{DateTime, Utc};
-use anyhow::Result;
+use std::fmt;
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveTime, Utc};
+use anyhow::{anyhow, bail, Result};
 use tracing::{debug, warn};
 
-use common::types::Signal;
+use common::instrument::InstrumentId;
+use common::types::{Side, Signal};
+use crate::config::RiskConfig;
 use crate::pipeline::shared_state::SharedState;
+use crate::risk_control::risk_state::RiskState;
+
+/// 风控拒绝原因：触发的规则、限制值和实际观测值
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskRejection {
+    pub rule: String,
+    pub limit: String,
+    pub observed: String,
+}
+
+impl RiskRejection {
+    pub fn new(rule: &str, limit: impl fmt::Display, observed: impl fmt::Display) -> Self {
+        Self {
+            rule: rule.to_string(),
+            limit: limit.to_string(),
+            observed: observed.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for RiskRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (limit={}, observed={})", self.rule, self.limit, self.observed)
+    }
+}
+
+/// 单条规则的检查结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Reject(RiskRejection),
+}
+
+impl Verdict {
+    pub fn reject(rule: &str, limit: impl fmt::Display, observed: impl fmt::Display) -> Self {
+        Verdict::Reject(RiskRejection::new(rule, limit, observed))
+    }
+}
 
 /// 风控规则trait - 所有规则都实现这个接口
 pub trait RiskRule {
     /// 规则名称
     fn name(&self) -> &str;
     
-    /// 执行检查，返回是否通过
-    fn check(&self, signal: &Signal, state: &SharedState) -> Result<bool>;
+    /// 执行检查，未通过时返回拒绝原因
+    fn check(&self, signal: &Signal, state: &SharedState, risk: &RiskState) -> Result<Verdict>;
     
     /// 是否为关键规则（失败后停止）
     fn is_critical(&self) -> bool {
         true
     }
+    
+    /// 对冲快速通道中是否执行；对冲单降低净敞口，频率、敞口、时效等检查不适用
+    fn applies_to_hedge(&self) -> bool {
+        false
+    }
 }
 
 /// 仓位规则
@@ -29,7 +75,8 @@ pub struct PositionRule {
     pub max_correlated_position_ratio: Decimal,
 }
 
-/// 频率控制规则
+/// 频率控制规则 - 按品种的滑动窗口成交次数，限制为0的项不检查
+#[derive(Debug, Clone)]
 pub struct FrequencyRule {
     pub max_trades_per_minute: usize,
     pub max_trades_per_hour: usize,
@@ -37,7 +84,8 @@ pub struct FrequencyRule {
     pub min_trade_interval_ms: u32,
 }
 
-/// 盈亏规则
+/// 盈亏规则 - 限制为0的项不检查
+#[derive(Debug, Clone)]
 pub struct PnLRule {
     pub max_daily_loss: Decimal,
     pub max_single_loss: Decimal,
@@ -45,7 +93,8 @@ pub struct PnLRule {
     pub max_drawdown: Decimal,
 }
 
-/// 市场条件规则
+/// 市场条件规则 - 读取信号metadata中的slippage/liquidity/volatility，缺失的项不检查
+#[derive(Debug, Clone)]
 pub struct MarketRule {
     pub max_slippage: Decimal,
     pub min_liquidity: Decimal,
@@ -53,14 +102,92 @@ pub struct MarketRule {
 }
 
 /// 时间规则
+#[derive(Debug, Clone)]
 pub struct TimeRule {
-    pub trading_windows: Vec<(String, String, Vec<u32>)>,  // (开始时间, 结束时间, 星期几)
-    pub blackout_dates: Vec<DateTime<Utc>>,
+    pub trading_windows: Vec<TradingWindow>,  // 允许交易的时间段，为空表示全天
+    pub blackout_dates: Vec<DateTime<Utc>>,   // 禁止交易日的起始时刻，禁止24小时
+}
+
+/// 交易时间窗口，开始/结束时间和星期几按窗口自身的UTC偏移解释
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradingWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub offset: FixedOffset,
+    pub weekdays: Vec<u32>,  // 1-7，1表示周一；为空表示每天
+}
+
+impl TradingWindow {
+    /// 解析"HH:MM"格式的时间，可带UTC偏移后缀（"09:30+08:00"、"09:30Z"），不带时按UTC处理；
+    /// 结束时间不晚于开始时间表示跨越午夜，相等表示全天
+    pub fn parse(start: &str, end: &str, weekdays: Vec<u32>) -> Result<Self> {
+        let (start, start_offset) = parse_window_time(start)?;
+        let (end, end_offset) = parse_window_time(end)?;
+        
+        let offset = match (start_offset, end_offset) {
+            (Some(a), Some(b)) if a != b => bail!("trading window start offset {} differs from end offset {}", a, b),
+            (Some(offset), _) | (None, Some(offset)) => offset,
+            (None, None) => FixedOffset::east_opt(0).unwrap(),
+        };
+        
+        if let Some(day) = weekdays.iter().find(|d| !(1..=7).contains(*d)) {
+            bail!("invalid weekday {} in trading window, expected 1-7", day);
+        }
+        
+        Ok(Self { start, end, offset, weekdays })
+    }
+    
+    /// 该时刻是否在窗口内
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let local = at.with_timezone(&self.offset);
+        let time = local.time();
+        let weekday = local.weekday().number_from_monday();
+        let on_day = |day: u32| self.weekdays.is_empty() || self.weekdays.contains(&day);
+        
+        if self.start < self.end {
+            on_day(weekday) && time >= self.start && time < self.end
+        } else if self.start > self.end {
+            // 跨越午夜的窗口，星期几指开始的那一天
+            let previous_day = if weekday == 1 { 7 } else { weekday - 1 };
+            (on_day(weekday) && time >= self.start) || (on_day(previous_day) && time < self.end)
+        } else {
+            on_day(weekday)
+        }
+    }
+}
+
+impl fmt::Display for TradingWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}{}", self.start.format("%H:%M"), self.end.format("%H:%M"), self.offset)?;
+        if !self.weekdays.is_empty() {
+            write!(f, " {:?}", self.weekdays)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_window_time(value: &str) -> Result<(NaiveTime, Option<FixedOffset>)> {
+    let value = value.trim();
+    if value.len() < 5 || !value.is_char_boundary(5) {
+        bail!("invalid trading window time '{}', expected HH:MM", value);
+    }
+    
+    let (time, offset) = value.split_at(5);
+    let time = NaiveTime::parse_from_str(time, "%H:%M")
+        .map_err(|e| anyhow!("invalid trading window time '{}': {}", value, e))?;
+    let offset = match offset {
+        "" => None,
+        "Z" => Some(FixedOffset::east_opt(0).unwrap()),
+        offset => Some(offset.parse::<FixedOffset>()
+            .map_err(|e| anyhow!("invalid UTC offset in trading window time '{}': {}", value, e))?),
+    };
+    
+    Ok((time, offset))
 }
 
 /// 品种特定规则
 pub struct SymbolRule {
-    pub symbol: String,
+    pub instrument: InstrumentId,
     pub max_position: Decimal,
     pub max_capital_used: Decimal,
     pub max_pending_orders: usize,
@@ -75,7 +202,7 @@ pub struct RiskRules {
     pub pnl_rule: Option<PnLRule>,
     pub market_rule: Option<MarketRule>,
     pub time_rule: Option<TimeRule>,
-    pub symbol_rules: HashMap<String, SymbolRule>,
+    pub symbol_rules: HashMap<InstrumentId, SymbolRule>,
     
     // 全局限制
     pub max_total_exposure_ratio: Decimal,
@@ -122,7 +249,7 @@ impl RiskRules {
     /// 添加品种规则
     pub fn add_symbol_rule(
         &mut self,
-        symbol: String,
+        instrument: InstrumentId,
         max_position: Decimal,
         max_capital_used: Decimal,
         max_pending_orders: usize,
@@ -130,25 +257,57 @@ impl RiskRules {
         time_window_seconds: u32,
     ) {
         let rule = SymbolRule {
-            symbol: symbol.clone(),
+            instrument,
             max_position,
             max_capital_used,
             max_pending_orders,
             max_trades_per_window,
             time_window_seconds,
         };
-        self.symbol_rules.insert(symbol, rule);
+        self.symbol_rules.insert(instrument, rule);
+    }
+}
+
+/// 受限品种规则 - 风控初始化标记为受限的品种或全局受限时拒绝所有信号
+pub struct RestrictionRule;
+
+impl RiskRule for RestrictionRule {
+    fn name(&self) -> &str {
+        "Restriction"
+    }
+    
+    fn check(&self, signal: &Signal, state: &SharedState, _risk: &RiskState) -> Result<Verdict> {
+        if state.global_restricted {
+            return Ok(Verdict::reject(self.name(), "unrestricted", "global restriction"));
+        }
+        
+        if state.restricted_symbols.contains(&signal.instrument) {
+            return Ok(Verdict::reject(self.name(), "unrestricted", format!("{} restricted", signal.instrument)));
+        }
+        
+        Ok(Verdict::Pass)
+    }
+    
+    fn applies_to_hedge(&self) -> bool {
+        true
     }
 }
 
 /// 单品种仓位限制规则
 pub struct PositionLimitRule {
     pub max_position: Decimal,  // 最大仓位（手数）
+    pub symbol_limits: HashMap<InstrumentId, Decimal>,  // 品种单独限制
 }
 
 impl PositionLimitRule {
     pub fn new(max_position: Decimal) -> Self {
-        Self { max_position }
+        Self { max_position, symbol_limits: HashMap::new() }
+    }
+    
+    /// 设置品种单独的仓位限制
+    pub fn with_symbol_limit(mut self, instrument: InstrumentId, max_position: Decimal) -> Self {
+        self.symbol_limits.insert(instrument, max_position);
+        self
     }
 }
 
@@ -157,37 +316,51 @@ impl RiskRule for PositionLimitRule {
         "PositionLimit"
     }
     
-    fn check(&self, signal: &Signal, state: &SharedState) -> Result<bool> {
+    fn check(&self, signal: &Signal, state: &SharedState, _risk: &RiskState) -> Result<Verdict> {
+        let limit = self.symbol_limits.get(&signal.instrument).copied().unwrap_or(self.max_position);
         let current_position = state.positions
-            .get(&signal.symbol)
+            .get(&signal.instrument)
             .map(|p| p.quantity)
             .unwrap_or(Decimal::ZERO);
         
-        let quantity = signal.quantity
-            .and_then(|q| Decimal::from_f64(q))
-            .unwrap_or(Decimal::ZERO);
-        let new_position = current_position + quantity;
+        // 卖出信号减少仓位
+        let quantity = signal_quantity(signal);
+        let new_position = match signal.side {
+            Some(Side::Sell) => current_position - quantity,
+            _ => current_position + quantity,
+        };
         
-        if new_position.abs() > self.max_position {
+        if new_position.abs() > limit {
             debug!(
                 "Position limit exceeded for {}: current={}, signal={}, limit={}", 
-                signal.symbol, current_position, quantity, self.max_position
+                signal.instrument, current_position, quantity, limit
             );
-            return Ok(false);
+            return Ok(Verdict::reject(self.name(), limit, new_position.abs()));
         }
         
-        Ok(true)
+        Ok(Verdict::Pass)
+    }
+    
+    fn applies_to_hedge(&self) -> bool {
+        true
     }
 }
 
 /// 单品种资金限制规则
 pub struct CapitalLimitRule {
     pub max_capital: Decimal,  // 最大资金（USDT）
+    pub symbol_limits: HashMap<InstrumentId, Decimal>,  // 品种单独限制
 }
 
 impl CapitalLimitRule {
     pub fn new(max_capital: Decimal) -> Self {
-        Self { max_capital }
+        Self { max_capital, symbol_limits: HashMap::new() }
+    }
+    
+    /// 设置品种单独的资金限制
+    pub fn with_symbol_limit(mut self, instrument: InstrumentId, max_capital: Decimal) -> Self {
+        self.symbol_limits.insert(instrument, max_capital);
+        self
     }
 }
 
@@ -196,38 +369,43 @@ impl RiskRule for CapitalLimitRule {
         "CapitalLimit"
     }
     
-    fn check(&self, signal: &Signal, state: &SharedState) -> Result<bool> {
-        let quota = state.risk_quotas.get(&signal.symbol);
-        let current_capital = quota
+    fn check(&self, signal: &Signal, state: &SharedState, _risk: &RiskState) -> Result<Verdict> {
+        let limit = self.symbol_limits.get(&signal.instrument).copied().unwrap_or(self.max_capital);
+        let current_capital = state.risk_quotas
+            .get(&signal.instrument)
             .map(|q| q.current_capital)
             .unwrap_or(Decimal::ZERO);
         
-        let signal_capital = signal.price
-            .and_then(|p| signal.quantity.map(|q| p * q))
-            .and_then(|c| Decimal::from_f64(c))
-            .unwrap_or(Decimal::ZERO);
+        let signal_capital = signal_notional(signal, state);
         let new_capital = current_capital + signal_capital;
         
-        if new_capital > self.max_capital {
+        if new_capital > limit {
             debug!(
                 "Capital limit exceeded for {}: current={}, signal={}, limit={}", 
-                signal.symbol, current_capital, signal_capital, self.max_capital
+                signal.instrument, current_capital, signal_capital, limit
             );
-            return Ok(false);
+            return Ok(Verdict::reject(self.name(), limit, new_capital));
         }
         
-        Ok(true)
+        Ok(Verdict::Pass)
     }
 }
 
 /// 挂单数量限制规则
 pub struct PendingOrdersRule {
     pub max_pending: usize,  // 最大挂单数
+    pub symbol_limits: HashMap<InstrumentId, usize>,  // 品种单独限制
 }
 
 impl PendingOrdersRule {
     pub fn new(max_pending: usize) -> Self {
-        Self { max_pending }
+        Self { max_pending, symbol_limits: HashMap::new() }
+    }
+    
+    /// 设置品种单独的挂单数限制
+    pub fn with_symbol_limit(mut self, instrument: InstrumentId, max_pending: usize) -> Self {
+        self.symbol_limits.insert(instrument, max_pending);
+        self
     }
 }
 
@@ -236,33 +414,35 @@ impl RiskRule for PendingOrdersRule {
         "PendingOrders"
     }
     
-    fn check(&self, signal: &Signal, state: &SharedState) -> Result<bool> {
-        let quota = state.risk_quotas.get(&signal.symbol);
-        let pending_orders = quota
+    fn check(&self, signal: &Signal, state: &SharedState, _risk: &RiskState) -> Result<Verdict> {
+        let limit = self.symbol_limits.get(&signal.instrument).copied().unwrap_or(self.max_pending);
+        let pending_orders = state.risk_quotas
+            .get(&signal.instrument)
             .map(|q| q.pending_orders)
             .unwrap_or(0);
         
-        if pending_orders >= self.max_pending {
+        if pending_orders >= limit {
             debug!(
                 "Pending orders limit exceeded for {}: current={}, limit={}", 
-                signal.symbol, pending_orders, self.max_pending
+                signal.instrument, pending_orders, limit
             );
-            return Ok(false);
+            return Ok(Verdict::reject(self.name(), limit, pending_orders));
         }
         
-        Ok(true)
+        Ok(Verdict::Pass)
     }
 }
 
-/// 总敞口限制规则
+/// 总敞口限制规则 - 按总敞口占总资金的比例判断
 pub struct TotalExposureRule {
-    pub max_exposure: Decimal,     // 最大总敞口
-    pub warning_threshold: Decimal, // 预警阈值
+    pub total_capital: Decimal,    // 总资金
+    pub max_ratio: Decimal,        // 最大敞口比例
+    pub warning_ratio: Decimal,    // 预警比例
 }
 
 impl TotalExposureRule {
-    pub fn new(max_exposure: Decimal, warning_threshold: Decimal) -> Self {
-        Self { max_exposure, warning_threshold }
+    pub fn new(total_capital: Decimal, max_ratio: Decimal, warning_ratio: Decimal) -> Self {
+        Self { total_capital, max_ratio, warning_ratio }
     }
 }
 
@@ -271,31 +451,32 @@ impl RiskRule for TotalExposureRule {
         "TotalExposure"
     }
     
-    fn check(&self, signal: &Signal, state: &SharedState) -> Result<bool> {
-        let signal_exposure = signal.price
-            .and_then(|p| signal.quantity.map(|q| (p * q).abs()))
-            .and_then(|e| Decimal::from_f64(e))
-            .unwrap_or(Decimal::ZERO);
-        let new_exposure = state.total_exposure + signal_exposure;
+    fn check(&self, signal: &Signal, state: &SharedState, _risk: &RiskState) -> Result<Verdict> {
+        if self.total_capital <= Decimal::ZERO {
+            bail!("total capital must be positive, got {}", self.total_capital);
+        }
+        
+        let signal_exposure = signal_notional(signal, state);
+        let ratio = (state.total_exposure + signal_exposure) / self.total_capital;
         
-        // 超过最大敞口，拒绝
-        if new_exposure > self.max_exposure {
+        // 超过最大敞口比例，拒绝
+        if ratio > self.max_ratio {
             warn!(
-                "Total exposure limit exceeded: current={}, signal={}, limit={}", 
-                state.total_exposure, signal_exposure, self.max_exposure
+                "Total exposure limit exceeded: current={}, signal={}, ratio={}, limit={}", 
+                state.total_exposure, signal_exposure, ratio, self.max_ratio
             );
-            return Ok(false);
+            return Ok(Verdict::reject(self.name(), self.max_ratio, ratio.round_dp(6)));
         }
         
-        // 达到预警阈值，记录警告但允许交易
-        if new_exposure > self.warning_threshold {
+        // 达到预警比例，记录警告但允许交易
+        if ratio > self.warning_ratio {
             warn!(
-                "Total exposure warning: current={}, signal={}, warning={}", 
-                state.total_exposure, signal_exposure, self.warning_threshold
+                "Total exposure warning: current={}, signal={}, ratio={}, warning={}", 
+                state.total_exposure, signal_exposure, ratio, self.warning_ratio
             );
         }
         
-        Ok(true)
+        Ok(Verdict::Pass)
     }
 }
 
@@ -315,21 +496,21 @@ impl RiskRule for DailyTradesRule {
         "DailyTrades"
     }
     
-    fn check(&self, signal: &Signal, state: &SharedState) -> Result<bool> {
-        let quota = state.risk_quotas.get(&signal.symbol);
-        let daily_trades = quota
+    fn check(&self, signal: &Signal, state: &SharedState, _risk: &RiskState) -> Result<Verdict> {
+        let daily_trades = state.risk_quotas
+            .get(&signal.instrument)
             .map(|q| q.daily_trades)
             .unwrap_or(0);
         
         if daily_trades >= self.max_daily_trades {
             debug!(
                 "Daily trades limit exceeded for {}: current={}, limit={}", 
-                signal.symbol, daily_trades, self.max_daily_trades
+                signal.instrument, daily_trades, self.max_daily_trades
             );
-            return Ok(false);
+            return Ok(Verdict::reject(self.name(), self.max_daily_trades, daily_trades));
         }
         
-        Ok(true)
+        Ok(Verdict::Pass)
     }
     
     fn is_critical(&self) -> bool {
@@ -353,26 +534,30 @@ impl RiskRule for CooldownRule {
         "Cooldown"
     }
     
-    fn check(&self, signal: &Signal, state: &SharedState) -> Result<bool> {
-        let quota = state.risk_quotas.get(&signal.symbol);
+    fn check(&self, signal: &Signal, state: &SharedState, _risk: &RiskState) -> Result<Verdict> {
+        let last_trade_time = state.risk_quotas
+            .get(&signal.instrument)
+            .and_then(|q| q.last_trade_time);
         
-        if let Some(quota) = quota {
-            if let Some(last_trade_time) = quota.last_trade_time {
-                let elapsed = Utc::now()
-                    .signed_duration_since(last_trade_time)
-                    .num_seconds();
-                
-                if elapsed < self.cooldown_seconds {
-                    debug!(
-                        "Cooldown period active for {}: elapsed={}s, required={}s", 
-                        signal.symbol, elapsed, self.cooldown_seconds
-                    );
-                    return Ok(false);
-                }
+        if let Some(last_trade_time) = last_trade_time {
+            let elapsed = Utc::now()
+                .signed_duration_since(last_trade_time)
+                .num_seconds();
+            
+            if elapsed < self.cooldown_seconds {
+                debug!(
+                    "Cooldown period active for {}: elapsed={}s, required={}s", 
+                    signal.instrument, elapsed, self.cooldown_seconds
+                );
+                return Ok(Verdict::reject(
+                    self.name(),
+                    format!("{}s", self.cooldown_seconds),
+                    format!("{}s", elapsed),
+                ));
             }
         }
         
-        Ok(true)
+        Ok(Verdict::Pass)
     }
 }
 
@@ -392,7 +577,7 @@ impl RiskRule for SignalAgeRule {
         "SignalAge"
     }
     
-    fn check(&self, signal: &Signal, _state: &SharedState) -> Result<bool> {
+    fn check(&self, signal: &Signal, _state: &SharedState, _risk: &RiskState) -> Result<Verdict> {
         let age_ms = Utc::now()
             .signed_duration_since(signal.timestamp)
             .num_milliseconds();
@@ -400,15 +585,176 @@ impl RiskRule for SignalAgeRule {
         if age_ms > self.max_age_ms {
             debug!(
                 "Signal too old for {}: age={}ms, max={}ms", 
-                signal.symbol, age_ms, self.max_age_ms
+                signal.instrument, age_ms, self.max_age_ms
             );
-            return Ok(false);
+            return Ok(Verdict::reject(
+                self.name(),
+                format!("{}ms", self.max_age_ms),
+                format!("{}ms", age_ms),
+            ));
         }
         
-        Ok(true)
+        Ok(Verdict::Pass)
     }
 }
 
+impl RiskRule for PnLRule {
+    fn name(&self) -> &str {
+        "PnL"
+    }
+    
+    fn check(&self, _signal: &Signal, _state: &SharedState, risk: &RiskState) -> Result<Verdict> {
+        let global = &risk.global_state;
+        
+        let daily_loss = (-global.daily_pnl).max(Decimal::ZERO);
+        if self.max_daily_loss > Decimal::ZERO && daily_loss >= self.max_daily_loss {
+            return Ok(Verdict::reject("DailyLoss", self.max_daily_loss, daily_loss));
+        }
+        
+        if self.max_single_loss > Decimal::ZERO && global.max_single_loss >= self.max_single_loss {
+            return Ok(Verdict::reject("SingleLoss", self.max_single_loss, global.max_single_loss));
+        }
+        
+        if self.max_consecutive_losses > 0 && global.consecutive_losses >= self.max_consecutive_losses {
+            return Ok(Verdict::reject("ConsecutiveLosses", self.max_consecutive_losses, global.consecutive_losses));
+        }
+        
+        let drawdown = risk.metrics.current_drawdown;
+        if self.max_drawdown > Decimal::ZERO && drawdown >= self.max_drawdown {
+            return Ok(Verdict::reject("Drawdown", self.max_drawdown, drawdown));
+        }
+        
+        Ok(Verdict::Pass)
+    }
+}
+
+impl FrequencyRule {
+    /// 按给定时刻检查，便于测试
+    pub fn check_at(&self, signal: &Signal, risk: &RiskState, now: DateTime<Utc>) -> Verdict {
+        let Some(symbol_state) = risk.symbol_states.get(&signal.instrument) else {
+            return Verdict::Pass;
+        };
+        let trades = &symbol_state.recent_trades;
+        
+        let windows = [
+            ("TradesPerMinute", self.max_trades_per_minute, Duration::minutes(1)),
+            ("TradesPerHour", self.max_trades_per_hour, Duration::hours(1)),
+            ("TradesPerDay", self.max_trades_per_day, Duration::days(1)),
+        ];
+        for (rule, limit, window) in windows {
+            let count = trades.count_within(now, window);
+            if limit > 0 && count >= limit {
+                return Verdict::reject(rule, limit, count);
+            }
+        }
+        
+        if self.min_trade_interval_ms > 0 {
+            if let Some(last_trade) = trades.last() {
+                let elapsed_ms = now.signed_duration_since(last_trade).num_milliseconds();
+                if elapsed_ms < self.min_trade_interval_ms as i64 {
+                    return Verdict::reject(
+                        "TradeInterval",
+                        format!("{}ms", self.min_trade_interval_ms),
+                        format!("{}ms", elapsed_ms),
+                    );
+                }
+            }
+        }
+        
+        Verdict::Pass
+    }
+}
+
+impl RiskRule for FrequencyRule {
+    fn name(&self) -> &str {
+        "Frequency"
+    }
+    
+    fn check(&self, signal: &Signal, _state: &SharedState, risk: &RiskState) -> Result<Verdict> {
+        Ok(self.check_at(signal, risk, Utc::now()))
+    }
+}
+
+impl RiskRule for MarketRule {
+    fn name(&self) -> &str {
+        "Market"
+    }
+    
+    fn check(&self, signal: &Signal, _state: &SharedState, _risk: &RiskState) -> Result<Verdict> {
+        let metadata = |key: &str| -> Result<Option<Decimal>> {
+            signal.metadata
+                .get(key)
+                .map(|v| v.parse::<Decimal>().map_err(|e| anyhow!("invalid {} '{}': {}", key, v, e)))
+                .transpose()
+        };
+        
+        if let Some(slippage) = metadata("slippage")? {
+            if self.max_slippage > Decimal::ZERO && slippage.abs() > self.max_slippage {
+                return Ok(Verdict::reject("Slippage", self.max_slippage, slippage.abs()));
+            }
+        }
+        
+        if let Some(liquidity) = metadata("liquidity")? {
+            if self.min_liquidity > Decimal::ZERO && liquidity < self.min_liquidity {
+                return Ok(Verdict::reject("Liquidity", self.min_liquidity, liquidity));
+            }
+        }
+        
+        if let Some(volatility) = metadata("volatility")? {
+            if self.max_volatility > Decimal::ZERO && volatility > self.max_volatility {
+                return Ok(Verdict::reject("Volatility", self.max_volatility, volatility));
+            }
+        }
+        
+        Ok(Verdict::Pass)
+    }
+}
+
+impl TimeRule {
+    /// 按给定时刻检查，便于测试
+    pub fn check_at(&self, now: DateTime<Utc>) -> Verdict {
+        let blackout = self.blackout_dates
+            .iter()
+            .find(|start| now >= **start && now < **start + Duration::days(1));
+        if let Some(start) = blackout {
+            return Verdict::reject("Blackout", format!("no trading from {}", start), now);
+        }
+        
+        if !self.trading_windows.is_empty() && !self.trading_windows.iter().any(|w| w.contains(now)) {
+            let windows: Vec<String> = self.trading_windows.iter().map(|w| w.to_string()).collect();
+            return Verdict::reject("TradingWindow", windows.join(", "), now);
+        }
+        
+        Verdict::Pass
+    }
+}
+
+impl RiskRule for TimeRule {
+    fn name(&self) -> &str {
+        "TradingTime"
+    }
+    
+    fn check(&self, _signal: &Signal, _state: &SharedState, _risk: &RiskState) -> Result<Verdict> {
+        Ok(self.check_at(Utc::now()))
+    }
+}
+
+/// 信号数量（手数）
+fn signal_quantity(signal: &Signal) -> Decimal {
+    signal.quantity
+        .and_then(Decimal::from_f64)
+        .unwrap_or(Decimal::ZERO)
+}
+
+/// 信号名义价值（USDT），数量按张计时乘以合约面值
+fn signal_notional(signal: &Signal, state: &SharedState) -> Decimal {
+    signal.price
+        .and_then(|p| signal.quantity.map(|q| (p * q).abs()))
+        .and_then(Decimal::from_f64)
+        .map(|notional| notional * state.contract_value(signal.instrument))
+        .unwrap_or(Decimal::ZERO)
+}
+
 /// 风控规则链 - 按顺序执行所有规则
 pub struct RiskRuleChain {
     rules: Vec<Box<dyn RiskRule>>,
@@ -419,25 +765,114 @@ impl RiskRuleChain {
         Self { rules: Vec::new() }
     }
     
+    /// 按本地配置构建规则链
+    pub fn from_config(config: &RiskConfig) -> Self {
+        Self::build(config, None)
+    }
+    
+    /// 按本地配置构建规则链，并用风控初始化下发的全局/品种限制覆盖对应项
+    pub fn from_rules(config: &RiskConfig, rules: &RiskRules) -> Self {
+        Self::build(config, Some(rules))
+    }
+    
+    fn build(config: &RiskConfig, rules: Option<&RiskRules>) -> Self {
+        let mut position_limit = PositionLimitRule::new(config.max_position);
+        let mut capital_limit = CapitalLimitRule::new(config.max_capital);
+        let mut pending_orders = PendingOrdersRule::new(config.max_pending_orders);
+        let mut total_capital = config.total_capital;
+        let mut max_exposure_ratio = config.max_total_exposure_ratio;
+        let mut max_daily_trades = config.max_daily_trades;
+        
+        if let Some(rules) = rules {
+            // 未下发（为0）的限制沿用本地配置
+            if rules.total_capital > Decimal::ZERO {
+                total_capital = rules.total_capital;
+            }
+            if rules.max_total_exposure_ratio > Decimal::ZERO {
+                max_exposure_ratio = rules.max_total_exposure_ratio;
+            }
+            if rules.max_daily_trades > 0 {
+                max_daily_trades = rules.max_daily_trades;
+            }
+            
+            for (&instrument, rule) in &rules.symbol_rules {
+                if rule.max_position > Decimal::ZERO {
+                    position_limit = position_limit.with_symbol_limit(instrument, rule.max_position);
+                }
+                if rule.max_capital_used > Decimal::ZERO {
+                    capital_limit = capital_limit.with_symbol_limit(instrument, rule.max_capital_used);
+                }
+                if rule.max_pending_orders > 0 {
+                    pending_orders = pending_orders.with_symbol_limit(instrument, rule.max_pending_orders);
+                }
+            }
+        }
+        
+        // 预警比例不高于最大比例
+        let warning_ratio = config.exposure_warning_ratio.min(max_exposure_ratio);
+        
+        let mut chain = Self::new().add_rule(Box::new(RestrictionRule));
+        if let Some(time_rule) = rules.and_then(|r| r.time_rule.clone()) {
+            chain = chain.add_rule(Box::new(time_rule));
+        }
+        chain = chain
+            .add_rule(Box::new(SignalAgeRule::new(config.max_signal_age_ms)))
+            .add_rule(Box::new(position_limit))
+            .add_rule(Box::new(capital_limit))
+            .add_rule(Box::new(pending_orders))
+            .add_rule(Box::new(TotalExposureRule::new(total_capital, max_exposure_ratio, warning_ratio)));
+        
+        // 风控初始化下发的盈亏/频率/市场规则
+        if let Some(rules) = rules {
+            if let Some(pnl_rule) = rules.pnl_rule.clone() {
+                chain = chain.add_rule(Box::new(pnl_rule));
+            }
+            if let Some(frequency_rule) = rules.frequency_rule.clone() {
+                chain = chain.add_rule(Box::new(frequency_rule));
+            }
+            if let Some(market_rule) = rules.market_rule.clone() {
+                chain = chain.add_rule(Box::new(market_rule));
+            }
+        }
+        
+        chain
+            .add_rule(Box::new(CooldownRule::new(config.cooldown_seconds)))
+            .add_rule(Box::new(DailyTradesRule::new(max_daily_trades)))
+    }
+    
     /// 添加规则
     pub fn add_rule(mut self, rule: Box<dyn RiskRule>) -> Self {
         self.rules.push(rule);
         self
     }
     
-    /// 执行所有规则检查
-    pub fn check_all(&self, signal: &Signal, state: &SharedState) -> Result<bool> {
-        for rule in &self.rules {
-            match rule.check(signal, state) {
-                Ok(true) => {
+    /// 执行所有规则检查，返回第一个关键规则的拒绝原因
+    pub fn check_all(&self, signal: &Signal, state: &SharedState, risk: &RiskState) -> Result<Verdict> {
+        Self::check_rules(self.rules.iter().map(Box::as_ref), signal, state, risk)
+    }
+    
+    /// 对冲快速通道：只执行对冲适用的规则（受限品种、仓位上限）
+    pub fn check_hedge(&self, signal: &Signal, state: &SharedState, risk: &RiskState) -> Result<Verdict> {
+        Self::check_rules(self.rules.iter().map(Box::as_ref).filter(|rule| rule.applies_to_hedge()), signal, state, risk)
+    }
+    
+    fn check_rules<'a>(
+        rules: impl Iterator<Item = &'a dyn RiskRule>,
+        signal: &Signal,
+        state: &SharedState,
+        risk: &RiskState,
+    ) -> Result<Verdict> {
+        for rule in rules {
+            match rule.check(signal, state, risk) {
+                Ok(Verdict::Pass) => {
                     debug!("Rule {} passed", rule.name());
                 }
-                Ok(false) => {
-                    debug!("Rule {} failed", rule.name());
+                Ok(Verdict::Reject(rejection)) => {
                     if rule.is_critical() {
-                        return Ok(false);  // 关键规则失败，立即返回
+                        return Ok(Verdict::Reject(rejection));  // 关键规则失败，立即返回
                     }
-                    // 非关键规则失败，继续检查
+                    // 非关键规则失败，记录后继续检查
+                    warn!("Non-critical rule failed for {}: {}", signal.instrument, rejection);
                 }
                 Err(e) => {
                     warn!("Rule {} error: {:?}", rule.name(), e);
@@ -449,28 +884,214 @@ impl RiskRuleChain {
             }
         }
         
-        Ok(true)
+        Ok(Verdict::Pass)
     }
 }
 
 /// 创建默认的风控规则链
 pub fn create_default_rule_chain() -> RiskRuleChain {
-    RiskRuleChain::new()
-        // 信号时效性检查（100ms）
-        .add_rule(Box::new(SignalAgeRule::new(100)))
-        // 单品种仓位限制（100手）
-        .add_rule(Box::new(PositionLimitRule::new(Decimal::from(100))))
-        // 单品种资金限制（5000 USDT）
-        .add_rule(Box::new(CapitalLimitRule::new(Decimal::from(5000))))
-        // 挂单数量限制（3个）
-        .add_rule(Box::new(PendingOrdersRule::new(3)))
-        // 总敞口限制（0.03）
-        .add_rule(Box::new(TotalExposureRule::new(
-            Decimal::from_f64(0.03).unwrap(),
-            Decimal::from_f64(0.025).unwrap(),
-        )))
-        // 交易冷却时间（60秒）
-        .add_rule(Box::new(CooldownRule::new(60)))
-        // 日内交易次数限制（1000次）
-        .add_rule(Box::new(DailyTradesRule::new(1000)))
-}
\ No newline at end of file
+    RiskRuleChain::from_config(&RiskConfig::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::shared_state::RiskQuota;
+    use crate::risk_control::risk_state::SymbolRiskState;
+    use chrono::TimeZone;
+    use common::types::{SignalData, SignalType};
+    
+    const BTC: InstrumentId = InstrumentId::new(2, 1);
+    const ETH: InstrumentId = InstrumentId::new(2, 2);
+    
+    fn signal(instrument: InstrumentId, price: f64, quantity: f64) -> Signal {
+        let mut signal = Signal::new(SignalType::Market, SignalData::Market { market_data: String::new() });
+        signal.instrument = instrument;
+        signal.side = Some(Side::Buy);
+        signal.price = Some(price);
+        signal.quantity = Some(quantity);
+        signal
+    }
+    
+    fn rejection(verdict: Verdict) -> RiskRejection {
+        match verdict {
+            Verdict::Reject(rejection) => rejection,
+            Verdict::Pass => panic!("expected rejection"),
+        }
+    }
+    
+    #[test]
+    fn test_default_chain_passes_small_signal() {
+        let chain = create_default_rule_chain();
+        let verdict = chain.check_all(&signal(BTC, 100.0, 1.0), &SharedState::new(), &RiskState::new()).unwrap();
+        assert_eq!(verdict, Verdict::Pass);
+    }
+    
+    #[test]
+    fn test_rejection_reports_rule_limit_and_observed() {
+        let chain = RiskRuleChain::from_config(&RiskConfig::default());
+        let verdict = chain.check_all(&signal(BTC, 1.0, 150.0), &SharedState::new(), &RiskState::new()).unwrap();
+        assert_eq!(rejection(verdict), RiskRejection::new("PositionLimit", 100, 150));
+    }
+    
+    #[test]
+    fn test_stale_signal_rejected_by_age() {
+        let mut stale = signal(BTC, 100.0, 1.0);
+        stale.timestamp = Utc::now() - chrono::Duration::seconds(1);
+        let verdict = create_default_rule_chain().check_all(&stale, &SharedState::new(), &RiskState::new()).unwrap();
+        assert_eq!(rejection(verdict).rule, "SignalAge");
+    }
+    
+    #[test]
+    fn test_non_critical_rejection_does_not_stop_chain() {
+        let config = RiskConfig { max_daily_trades: 1, cooldown_seconds: 0, ..RiskConfig::default() };
+        let mut state = SharedState::new();
+        let mut quota = RiskQuota::new();
+        quota.daily_trades = 5;
+        state.risk_quotas.insert(BTC, quota);
+        
+        let verdict = RiskRuleChain::from_config(&config).check_all(&signal(BTC, 100.0, 1.0), &state, &RiskState::new()).unwrap();
+        assert_eq!(verdict, Verdict::Pass);
+    }
+    
+    #[test]
+    fn test_symbol_rule_overrides_config() {
+        let mut rules = RiskRules::new();
+        rules.add_symbol_rule(ETH, Decimal::from(2), Decimal::ZERO, 0, 0, 0);
+        let chain = RiskRuleChain::from_rules(&RiskConfig::default(), &rules);
+        
+        let state = SharedState::new();
+        assert_eq!(chain.check_all(&signal(BTC, 10.0, 3.0), &state, &RiskState::new()).unwrap(), Verdict::Pass);
+        let verdict = chain.check_all(&signal(ETH, 10.0, 3.0), &state, &RiskState::new()).unwrap();
+        assert_eq!(rejection(verdict), RiskRejection::new("PositionLimit", 2, 3));
+    }
+    
+    #[test]
+    fn test_hedge_fast_lane_skips_non_critical_rules() {
+        let mut hedge = signal(BTC, 100.0, 1.0);
+        hedge.signal_type = SignalType::Hedge;
+        hedge.timestamp = Utc::now() - chrono::Duration::seconds(1);
+        
+        let mut state = SharedState::new();
+        let mut quota = RiskQuota::new();
+        quota.pending_orders = 10;
+        quota.last_trade_time = Some(Utc::now());
+        state.risk_quotas.insert(BTC, quota);
+        
+        // 普通信号被时效检查拒绝，对冲信号跳过时效、挂单数和冷却检查
+        let chain = create_default_rule_chain();
+        assert_eq!(rejection(chain.check_all(&hedge, &state, &RiskState::new()).unwrap()).rule, "SignalAge");
+        assert_eq!(chain.check_hedge(&hedge, &state, &RiskState::new()).unwrap(), Verdict::Pass);
+        
+        // 仓位上限和受限品种仍然检查
+        hedge.quantity = Some(150.0);
+        assert_eq!(rejection(chain.check_hedge(&hedge, &state, &RiskState::new()).unwrap()).rule, "PositionLimit");
+        state.restricted_symbols.insert(BTC);
+        assert_eq!(rejection(chain.check_hedge(&hedge, &state, &RiskState::new()).unwrap()).rule, "Restriction");
+    }
+    
+    #[test]
+    fn test_total_exposure_uses_contract_value() {
+        let rule = TotalExposureRule::new(Decimal::from(1000), Decimal::new(5, 1), Decimal::new(4, 1));
+        let mut state = SharedState::new();
+        
+        // 100张 × 10 = 1000，面值0.01时名义价值为10
+        assert_eq!(rejection(rule.check(&signal(BTC, 10.0, 100.0), &state, &RiskState::new()).unwrap()).rule, "TotalExposure");
+        state.set_contract_value(BTC, Decimal::new(1, 2));
+        assert_eq!(rule.check(&signal(BTC, 10.0, 100.0), &state, &RiskState::new()).unwrap(), Verdict::Pass);
+    }
+    
+    #[test]
+    fn test_restricted_symbol_rejected_first() {
+        let mut state = SharedState::new();
+        state.restricted_symbols.insert(BTC);
+        let verdict = create_default_rule_chain().check_all(&signal(BTC, 1.0, 500.0), &state, &RiskState::new()).unwrap();
+        assert_eq!(rejection(verdict).rule, "Restriction");
+    }
+    
+    #[test]
+    fn test_trading_window_parses_offset_and_wraps_midnight() {
+        // 北京时间 22:00-02:00，周一开始
+        let window = TradingWindow::parse("22:00+08:00", "02:00", vec![1]).unwrap();
+        assert_eq!(window.offset, FixedOffset::east_opt(8 * 3600).unwrap());
+        
+        // 2024-01-01 是周一：UTC 14:30 = 北京 22:30 周一
+        assert!(window.contains(Utc.with_ymd_and_hms(2024, 1, 1, 14, 30, 0).unwrap()));
+        // UTC 17:00 = 北京 周二 01:00，属于周一开始的窗口
+        assert!(window.contains(Utc.with_ymd_and_hms(2024, 1, 1, 17, 0, 0).unwrap()));
+        // UTC 19:00 = 北京 周二 03:00
+        assert!(!window.contains(Utc.with_ymd_and_hms(2024, 1, 1, 19, 0, 0).unwrap()));
+        // 北京 周二 22:30 不在周一的窗口
+        assert!(!window.contains(Utc.with_ymd_and_hms(2024, 1, 2, 14, 30, 0).unwrap()));
+    }
+    
+    #[test]
+    fn test_trading_window_rejects_bad_input() {
+        assert!(TradingWindow::parse("9:30", "16:00", vec![]).is_err());
+        assert!(TradingWindow::parse("09:30+08:00", "16:00Z", vec![]).is_err());
+        assert!(TradingWindow::parse("09:30", "16:00", vec![0]).is_err());
+        assert!(TradingWindow::parse("09:30Z", "16:00", vec![1, 7]).is_ok());
+    }
+    
+    #[test]
+    fn test_time_rule_blackout_and_windows() {
+        let rule = TimeRule {
+            trading_windows: vec![TradingWindow::parse("09:00", "17:00", vec![]).unwrap()],
+            blackout_dates: vec![Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap()],
+        };
+        assert_eq!(rule.check_at(Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap()), Verdict::Pass);
+        assert_eq!(rejection(rule.check_at(Utc.with_ymd_and_hms(2024, 1, 1, 18, 0, 0).unwrap())).rule, "TradingWindow");
+        assert_eq!(rejection(rule.check_at(Utc.with_ymd_and_hms(2024, 1, 2, 10, 0, 0).unwrap())).rule, "Blackout");
+    }
+    
+    #[test]
+    fn test_frequency_rule_uses_sliding_window() {
+        let rule = FrequencyRule {
+            max_trades_per_minute: 2,
+            max_trades_per_hour: 0,
+            max_trades_per_day: 0,
+            min_trade_interval_ms: 500,
+        };
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+        let mut risk = RiskState::new();
+        let mut symbol_state = SymbolRiskState::new(BTC);
+        symbol_state.update_trade_stats(now - Duration::seconds(90));
+        symbol_state.update_trade_stats(now - Duration::seconds(30));
+        risk.symbol_states.insert(BTC, symbol_state);
+        
+        let sig = signal(BTC, 100.0, 1.0);
+        assert_eq!(rule.check_at(&sig, &risk, now), Verdict::Pass);
+        
+        risk.symbol_states.get_mut(&BTC).unwrap().update_trade_stats(now - Duration::milliseconds(200));
+        assert_eq!(rule.check_at(&sig, &risk, now), Verdict::reject("TradesPerMinute", 2, 2));
+        
+        let rule = FrequencyRule { max_trades_per_minute: 0, ..rule };
+        assert_eq!(rejection(rule.check_at(&sig, &risk, now)).rule, "TradeInterval");
+    }
+    
+    #[test]
+    fn test_pnl_rule_stops_after_consecutive_losses() {
+        let rule = PnLRule {
+            max_daily_loss: Decimal::from(1000),
+            max_single_loss: Decimal::ZERO,
+            max_consecutive_losses: 3,
+            max_drawdown: Decimal::ZERO,
+        };
+        let mut risk = RiskState::new();
+        let sig = signal(BTC, 100.0, 1.0);
+        let state = SharedState::new();
+        
+        risk.record_pnl(BTC, Decimal::from(-10));
+        risk.record_pnl(BTC, Decimal::from(-10));
+        assert_eq!(rule.check(&sig, &state, &risk).unwrap(), Verdict::Pass);
+        
+        risk.record_pnl(BTC, Decimal::from(-10));
+        assert_eq!(rule.check(&sig, &state, &risk).unwrap(), Verdict::reject("ConsecutiveLosses", 3, 3));
+        
+        // 盈利一笔后清零，但日内亏损仍累计
+        risk.record_pnl(BTC, Decimal::from(5));
+        assert_eq!(rule.check(&sig, &state, &risk).unwrap(), Verdict::Pass);
+        risk.record_pnl(BTC, Decimal::from(-1000));
+        assert_eq!(rule.check(&sig, &state, &risk).unwrap(), Verdict::reject("DailyLoss", 1000, 1025));
+    }
+}
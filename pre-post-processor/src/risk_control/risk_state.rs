@@ -1,17 +1,20 @@
-use std::collections::HashMap;
-use chrono::{DateTime, Utc, Timelike};
+use std::collections::{HashMap, VecDeque};
+use chrono::{DateTime, Duration, Utc, Timelike};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 
+use common::instrument::InstrumentId;
 use common::types::{Signal, ExecutionReport, OrderStatus};
+use crate::pipeline::shared_state::PositionChange;
 use crate::risk_control::risk_calculator::RiskMetrics;
 
 /// 风控状态 - 管理所有风控相关的状态信息
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct RiskState {
     // 品种级别的风控状态
-    pub symbol_states: HashMap<String, SymbolRiskState>,
+    pub symbol_states: HashMap<InstrumentId, SymbolRiskState>,
     
     // 全局风控状态
     pub global_state: GlobalRiskState,
@@ -24,9 +27,9 @@ pub struct RiskState {
 }
 
 /// 单个品种的风控状态
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolRiskState {
-    pub symbol: String,
+    pub instrument: InstrumentId,
     
     // 仓位和资金
     pub position: Decimal,           // 当前仓位
@@ -36,7 +39,7 @@ pub struct SymbolRiskState {
     // 交易统计
     pub daily_trades: usize,         // 今日交易次数
     pub last_trade_time: Option<DateTime<Utc>>, // 最后交易时间
-    pub trades_in_window: usize,     // 时间窗口内交易次数
+    pub recent_trades: TradeCounter, // 滑动窗口成交记录
     
     // 盈亏统计
     pub realized_pnl: Decimal,       // 已实现盈亏
@@ -50,15 +53,15 @@ pub struct SymbolRiskState {
 }
 
 impl SymbolRiskState {
-    pub fn new(symbol: String) -> Self {
+    pub fn new(instrument: InstrumentId) -> Self {
         Self {
-            symbol,
+            instrument,
             position: Decimal::ZERO,
             capital_used: Decimal::ZERO,
             pending_orders: 0,
             daily_trades: 0,
             last_trade_time: None,
-            trades_in_window: 0,
+            recent_trades: TradeCounter::default(),
             realized_pnl: Decimal::ZERO,
             unrealized_pnl: Decimal::ZERO,
             max_drawdown: Decimal::ZERO,
@@ -73,7 +76,7 @@ impl SymbolRiskState {
         if self.is_restricted {
             if let Some(until) = self.restriction_until {
                 if Utc::now() < until {
-                    debug!("Symbol {} restricted until {}", self.symbol, until);
+                    debug!("Symbol {} restricted until {}", self.instrument, until);
                     return false;
                 }
                 // 限制时间已过，解除限制
@@ -85,10 +88,10 @@ impl SymbolRiskState {
     }
     
     /// 更新交易统计
-    pub fn update_trade_stats(&mut self) {
+    pub fn update_trade_stats(&mut self, traded_at: DateTime<Utc>) {
         self.daily_trades += 1;
-        self.trades_in_window += 1;
-        self.last_trade_time = Some(Utc::now());
+        self.recent_trades.record(traded_at);
+        self.last_trade_time = Some(traded_at);
     }
     
     /// 设置交易限制
@@ -96,7 +99,7 @@ impl SymbolRiskState {
         self.is_restricted = true;
         self.restriction_reason = Some(reason.clone());
         self.restriction_until = Some(Utc::now() + chrono::Duration::seconds(duration_seconds));
-        warn!("Symbol {} restricted: {}", self.symbol, reason);
+        warn!("Symbol {} restricted: {}", self.instrument, reason);
     }
     
     /// 清除交易限制
@@ -104,19 +107,48 @@ impl SymbolRiskState {
         self.is_restricted = false;
         self.restriction_reason = None;
         self.restriction_until = None;
-        info!("Symbol {} restriction cleared", self.symbol);
+        info!("Symbol {} restriction cleared", self.instrument);
     }
     
     /// 重置日内统计（每日UTC 0点调用）
     pub fn reset_daily_stats(&mut self) {
         self.daily_trades = 0;
-        self.trades_in_window = 0;
-        debug!("Daily stats reset for {}", self.symbol);
+        debug!("Daily stats reset for {}", self.instrument);
+    }
+}
+
+/// 滑动窗口交易计数器 - 保留最近一天的成交时间
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TradeCounter {
+    trades: VecDeque<DateTime<Utc>>,
+}
+
+impl TradeCounter {
+    const HORIZON_SECONDS: i64 = 86_400;
+    
+    /// 记录一笔成交，并丢弃超出一天的记录
+    pub fn record(&mut self, traded_at: DateTime<Utc>) {
+        self.trades.push_back(traded_at);
+        let cutoff = traded_at - Duration::seconds(Self::HORIZON_SECONDS);
+        while self.trades.front().is_some_and(|t| *t < cutoff) {
+            self.trades.pop_front();
+        }
+    }
+    
+    /// 截至now的窗口内成交次数（窗口不超过一天）
+    pub fn count_within(&self, now: DateTime<Utc>, window: Duration) -> usize {
+        let since = now - window;
+        self.trades.iter().filter(|t| **t > since && **t <= now).count()
+    }
+    
+    /// 最近一笔成交时间
+    pub fn last(&self) -> Option<DateTime<Utc>> {
+        self.trades.iter().max().copied()
     }
 }
 
 /// 全局风控状态
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GlobalRiskState {
     // 总体统计
     pub total_exposure: Decimal,     // 总敞口
@@ -127,6 +159,8 @@ pub struct GlobalRiskState {
     pub daily_trades: usize,         // 今日总交易次数
     pub daily_pnl: Decimal,          // 今日总盈亏
     pub max_daily_drawdown: Decimal, // 今日最大回撤
+    pub max_single_loss: Decimal,    // 今日最大单笔亏损
+    pub consecutive_losses: usize,   // 连续亏损次数
     
     // 风险等级
     pub risk_level: RiskLevel,       // 当前风险等级
@@ -146,6 +180,8 @@ impl GlobalRiskState {
             daily_trades: 0,
             daily_pnl: Decimal::ZERO,
             max_daily_drawdown: Decimal::ZERO,
+            max_single_loss: Decimal::ZERO,
+            consecutive_losses: 0,
             risk_level: RiskLevel::Low,
             last_risk_check: Utc::now(),
             global_restricted: false,
@@ -191,12 +227,13 @@ impl GlobalRiskState {
         self.daily_trades = 0;
         self.daily_pnl = Decimal::ZERO;
         self.max_daily_drawdown = Decimal::ZERO;
+        self.max_single_loss = Decimal::ZERO;
         debug!("Global daily stats reset");
     }
 }
 
 /// 风险等级
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum RiskLevel {
     Low,      // 低风险
     Medium,   // 中等风险
@@ -224,8 +261,8 @@ impl RiskState {
         
         // 获取或创建品种状态
         let symbol_state = self.symbol_states
-            .entry(signal.symbol.clone())
-            .or_insert_with(|| SymbolRiskState::new(signal.symbol.clone()));
+            .entry(signal.instrument)
+            .or_insert_with(|| SymbolRiskState::new(signal.instrument));
         
         // 检查品种限制
         if !symbol_state.can_trade() {
@@ -247,27 +284,21 @@ impl RiskState {
     }
     
     /// 处理执行报告 - 更新风控状态
-    pub fn process_execution(&mut self, report: &ExecutionReport) {
-        let symbol_str = format!("{:?}", report.symbol);
+    ///
+    /// 仓位和占用资金跟随仓位账本的变化，保留风控初始化时导入的基数
+    pub fn process_execution(&mut self, report: &ExecutionReport, change: &PositionChange) {
         let symbol_state = self.symbol_states
-            .entry(symbol_str.clone())
-            .or_insert_with(|| SymbolRiskState::new(symbol_str));
-        
-        // 更新仓位和资金
-        let filled_quantity = Decimal::from_f64(report.filled_quantity).unwrap_or(Decimal::ZERO);
-        let price = Decimal::from_f64(report.price).unwrap_or(Decimal::ZERO);
+            .entry(report.instrument)
+            .or_insert_with(|| SymbolRiskState::new(report.instrument));
         
-        if report.side == common::types::Side::Buy {
-            symbol_state.position += filled_quantity;
-            symbol_state.capital_used += price * filled_quantity;
-        } else {
-            symbol_state.position -= filled_quantity;
-            symbol_state.capital_used -= price * filled_quantity;
-        }
+        // 更新仓位、资金和未实现盈亏
+        symbol_state.position += change.quantity;
+        symbol_state.capital_used += change.cost;
+        symbol_state.unrealized_pnl += change.unrealized_pnl;
         
         // 更新交易统计
         if report.status == OrderStatus::Filled {
-            symbol_state.update_trade_stats();
+            symbol_state.update_trade_stats(report.timestamp);
             symbol_state.pending_orders = symbol_state.pending_orders.saturating_sub(1);
             
             self.global_state.daily_trades += 1;
@@ -279,6 +310,38 @@ impl RiskState {
         self.last_update = Utc::now();
     }
     
+    /// 按标记价格重估后更新品种未实现盈亏
+    pub fn revalue(&mut self, changes: &[(InstrumentId, PositionChange)]) {
+        for (instrument, change) in changes {
+            self.symbol_states
+                .entry(*instrument)
+                .or_insert_with(|| SymbolRiskState::new(*instrument))
+                .unrealized_pnl += change.unrealized_pnl;
+        }
+        
+        if !changes.is_empty() {
+            self.last_update = Utc::now();
+        }
+    }
+    
+    /// 记录一笔已实现盈亏 - 更新日内盈亏和连续亏损统计
+    pub fn record_pnl(&mut self, instrument: InstrumentId, pnl: Decimal) {
+        if let Some(symbol_state) = self.symbol_states.get_mut(&instrument) {
+            symbol_state.realized_pnl += pnl;
+        }
+        
+        let global = &mut self.global_state;
+        global.daily_pnl += pnl;
+        if pnl < Decimal::ZERO {
+            global.consecutive_losses += 1;
+            global.max_single_loss = global.max_single_loss.max(-pnl);
+        } else if pnl > Decimal::ZERO {
+            global.consecutive_losses = 0;
+        }
+        
+        self.last_update = Utc::now();
+    }
+    
     /// 重新计算全局敞口
     fn recalculate_global_exposure(&mut self) {
         self.global_state.total_exposure = self.symbol_states
@@ -329,7 +392,7 @@ impl RiskState {
             restricted_symbols: self.symbol_states
                 .values()
                 .filter(|s| s.is_restricted)
-                .map(|s| s.symbol.clone())
+                .map(|s| s.instrument)
                 .collect(),
             global_restricted: self.global_state.global_restricted,
         }
@@ -344,6 +407,6 @@ pub struct RiskSummary {
     pub active_positions: usize,
     pub daily_trades: usize,
     pub daily_pnl: Decimal,
-    pub restricted_symbols: Vec<String>,
+    pub restricted_symbols: Vec<InstrumentId>,
     pub global_restricted: bool,
 }
\ No newline at end of file
@@ -0,0 +1,105 @@
+use std::fmt::Debug;
+use anyhow::Result;
+use tokio::sync::mpsc;
+use tracing::{info, error, debug, warn};
+
+use iceoryx2::prelude::*;
+use iceoryx2::port::subscriber::Subscriber;
+use common::binary::DecodeError;
+use common::wakeup::{TopicWaiter, WakeupConfig};
+
+/// 在独立线程中订阅 topic
+///
+/// 线程阻塞在 topic 的事件通知上（busy_spin 模式下空转），收到样本后解码并送入内部队列，
+/// 主循环在队列上被唤醒，不再轮询订阅端口。端口创建完成后才返回，创建失败时返回错误。
+pub fn spawn<P, T, O, D>(
+    name: &'static str,
+    topic: String,
+    wakeup: WakeupConfig,
+    open: O,
+    decode: D,
+    tx: mpsc::UnboundedSender<T>,
+) -> Result<()>
+where
+    P: Debug + 'static,
+    T: Send + 'static,
+    O: FnOnce(&Node<ipc::Service>, &str) -> Result<Subscriber<ipc::Service, P, ()>> + Send + 'static,
+    D: Fn(&P) -> Result<T, DecodeError> + Send + 'static,
+{
+    let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+
+    std::thread::Builder::new()
+        .name(format!("ppp-{}", name))
+        .spawn(move || {
+            // iceoryx2 端口不能跨线程移动，在订阅线程内创建
+            let ports = NodeBuilder::new()
+                .create::<ipc::Service>()
+                .map_err(anyhow::Error::from)
+                .and_then(|node| {
+                    let subscriber = open(&node, &topic)?;
+                    let waiter = TopicWaiter::open(&node, &topic, &wakeup)?;
+                    Ok((node, subscriber, waiter))
+                });
+
+            let (node, subscriber, waiter) = match ports {
+                Ok(ports) => {
+                    let _ = ready_tx.send(Ok(()));
+                    ports
+                }
+                Err(e) => {
+                    let _ = ready_tx.send(Err(e.to_string()));
+                    return;
+                }
+            };
+
+            if let Err(e) = run(name, &topic, &node, subscriber, waiter, decode, tx) {
+                error!("{} subscriber on {} stopped: {}", name, topic, e);
+            }
+        })?;
+
+    match ready_rx.recv() {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(e)) => Err(anyhow::anyhow!("failed to open {} subscriber: {}", name, e)),
+        Err(_) => Err(anyhow::anyhow!("{} subscriber thread exited during startup", name)),
+    }
+}
+
+fn run<P, T, D>(
+    name: &str,
+    topic: &str,
+    node: &Node<ipc::Service>,
+    subscriber: Subscriber<ipc::Service, P, ()>,
+    mut waiter: TopicWaiter,
+    decode: D,
+    tx: mpsc::UnboundedSender<T>,
+) -> Result<()>
+where
+    P: Debug,
+    D: Fn(&P) -> Result<T, DecodeError>,
+{
+    info!("{} subscriber ready on {}", name, topic);
+
+    loop {
+        // 每次唤醒都取空队列，通知可能合并
+        while let Some(sample) = subscriber.receive()? {
+            let message = match decode(sample.payload()) {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!("Dropping undecodable {} sample: {}", name, e);
+                    continue;
+                }
+            };
+            debug!("Received {} sample on {}", name, topic);
+
+            if tx.send(message).is_err() {
+                info!("{} queue closed, stopping subscriber", name);
+                return Ok(());
+            }
+        }
+
+        if !waiter.wait(node)? {
+            warn!("{} subscriber received termination signal", name);
+            return Ok(());
+        }
+    }
+}
@@ -1,11 +1,17 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use common::wakeup::WakeupConfig;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub iceoryx_topics: Vec<String>,
     pub zmq_endpoints: Vec<String>,
     pub output_topic: String,
+    /// pre-post-processor 发回的对冲信号 topic，优先于其他信号处理
+    pub hedge_topic: String,
+    /// iceoryx2 订阅线程的唤醒方式
+    #[serde(default)]
+    pub wakeup: WakeupConfig,
 }
 
 impl Config {
@@ -27,6 +33,8 @@ impl Default for Config {
                 "tcp://127.0.0.1:5556".to_string(),
             ],
             output_topic: "events/trading".to_string(),
+            hedge_topic: "signals/hedge".to_string(),
+            wakeup: WakeupConfig::default(),
         }
     }
 }
\ No newline at end of file
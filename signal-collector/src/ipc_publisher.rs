@@ -1,8 +1,9 @@
 use tokio::sync::mpsc;
 use anyhow::Result;
-use tracing::{info, error, warn};
+use tracing::{debug, info, error, warn};
 use common::messages::EventMessage;
-use core::time::Duration;
+use common::pod::PodEvent;
+use common::wakeup::TopicNotifier;
 
 pub struct IpcPublisher;
 
@@ -31,7 +32,7 @@ impl IpcPublisher {
         // 直接使用open_or_create
         let service = node
             .service_builder(&service_name)
-            .publish_subscribe::<[u8; 4096]>()
+            .publish_subscribe::<PodEvent>()
             .open_or_create()?;
         
         info!("Service ready for topic: {}", topic);
@@ -40,59 +41,41 @@ impl IpcPublisher {
         
         info!("IceOryx publisher ready for topic: {}", topic);
         
-        const CYCLE_TIME: Duration = Duration::from_millis(10);
+        let notifier = TopicNotifier::open(&node, &topic)?;
         
-        loop {
-            // 使用非阻塞接收，以便可以定期检查node状态
-            match rx.try_recv() {
-                Ok(event_msg) => {
-                    let bytes = event_msg.to_bytes();
-                    if bytes.len() > 4096 {
-                        error!("Event message too large: {} bytes", bytes.len());
-                        continue;
-                    }
-                    
-                    // 创建一个固定大小的数组
-                    let mut buffer = [0u8; 4096];
-                    buffer[..bytes.len()].copy_from_slice(&bytes);
-                    
-                    // 使用loan模式并直接写入数据
-                    match publisher.loan_uninit() {
-                        Ok(sample) => {
-                            let sample = sample.write_payload(buffer);
-                            match sample.send() {
-                                Ok(_) => {
-                                    info!("Published event: seq={}, priority={:?}", 
-                                        event_msg.sequence_id, 
-                                        event_msg.event.priority());
-                                }
-                                Err(e) => {
-                                    error!("Failed to send event: {:?}", e);
-                                }
+        // 阻塞等待事件，通道关闭时退出
+        while let Some(event_msg) = rx.blocking_recv() {
+            // 转换为定长POD，直接写入共享内存
+            let pod = match PodEvent::try_from(&event_msg) {
+                Ok(pod) => pod,
+                Err(e) => {
+                    error!("Failed to encode event seq={}: {}", event_msg.sequence_id, e);
+                    continue;
+                }
+            };
+            
+            // 使用loan模式并直接写入数据
+            match publisher.loan_uninit() {
+                Ok(sample) => {
+                    let sample = sample.write_payload(pod);
+                    match sample.send() {
+                        Ok(_) => {
+                            debug!("Published event: seq={}, priority={:?}", 
+                                event_msg.sequence_id, 
+                                event_msg.event.priority());
+                            
+                            // 唤醒订阅端
+                            if let Err(e) = notifier.notify() {
+                                warn!("Failed to notify subscribers of {}: {}", topic, e);
                             }
                         }
                         Err(e) => {
-                            error!("Failed to loan sample: {:?}", e);
+                            error!("Failed to send event: {:?}", e);
                         }
                     }
                 }
-                Err(tokio::sync::mpsc::error::TryRecvError::Empty) => {
-                    // 没有消息，继续等待
-                }
-                Err(tokio::sync::mpsc::error::TryRecvError::Disconnected) => {
-                    info!("Publisher channel disconnected, shutting down");
-                    break;
-                }
-            }
-            
-            // 检查node状态
-            match node.wait(CYCLE_TIME) {
-                NodeEvent::Tick => {
-                    // 正常的时间片
-                }
-                NodeEvent::TerminationRequest | NodeEvent::InterruptSignal => {
-                    warn!("Node received termination signal");
-                    break;
+                Err(e) => {
+                    error!("Failed to loan sample: {:?}", e);
                 }
             }
         }
@@ -3,114 +3,83 @@ use anyhow::Result;
 use tracing::{info, error, warn, debug};
 use common::messages::SignalMessage;
 use common::signals::Signal;
+use common::binary::FRAME_HEADER_LEN;
+use common::ipc::SIGNAL_FRAME_SIZE;
+use common::wakeup::{TopicWaiter, WakeupConfig};
 use bytes::Bytes;
 use chrono::Utc;
-use core::time::Duration;
 
 pub struct IceOryxSubscriber;
 
 impl IceOryxSubscriber {
-    pub fn spawn_subscribers(tx: mpsc::Sender<SignalMessage>, topics: Vec<String>) {
-        // 创建单个线程处理所有订阅
-        std::thread::spawn(move || {
-            if let Err(e) = Self::run_all_subscribers(tx, topics) {
-                error!("Subscriber thread error: {}", e);
-            }
-        });
+    pub fn spawn_subscribers(tx: mpsc::Sender<SignalMessage>, topics: Vec<String>, wakeup: WakeupConfig) {
+        // 每个主题一个线程，各自阻塞在该主题的事件通知上
+        for topic in topics {
+            let tx = tx.clone();
+            let wakeup = wakeup.clone();
+            std::thread::spawn(move || {
+                if let Err(e) = Self::run_subscriber(tx, &topic, &wakeup) {
+                    error!("Subscriber thread for {} error: {}", topic, e);
+                }
+            });
+        }
     }
     
-    fn run_all_subscribers(tx: mpsc::Sender<SignalMessage>, topics: Vec<String>) -> Result<()> {
+    fn run_subscriber(tx: mpsc::Sender<SignalMessage>, topic: &str, wakeup: &WakeupConfig) -> Result<()> {
         use iceoryx2::prelude::*;
         
-        info!("Starting IceOryx subscriber for {} topics", topics.len());
-        
-        // 创建单个节点
         let node_name = format!("sub{}", std::process::id());
-        info!("Creating subscriber node with name: {}", node_name);
+        info!("Creating subscriber node {} for topic {}", node_name, topic);
         let node = NodeBuilder::new()
             .name(&NodeName::new(&node_name)?)
             .create::<ipc::Service>()?;
         
-        // 为每个主题创建订阅者
-        let mut subscribers = Vec::new();
-        for topic in &topics {
-            let service_name = ServiceName::new(topic)?;
-            let service = node
-                .service_builder(&service_name)
-                .publish_subscribe::<[u8; 1024]>()
-                .open_or_create()?;
-            
-            let subscriber = service.subscriber_builder().create()?;
-            info!("Subscriber ready for topic: {}", topic);
-            subscribers.push((topic.clone(), subscriber));
-        }
+        let service = node
+            .service_builder(&ServiceName::new(topic)?)
+            .publish_subscribe::<[u8; SIGNAL_FRAME_SIZE]>()
+            .open_or_create()?;
+        
+        let subscriber = service.subscriber_builder().create()?;
+        let mut waiter = TopicWaiter::open(&node, topic, wakeup)?;
+        info!("Subscriber ready for topic: {} ({:?} wakeup)", topic, wakeup.mode);
         
-        const CYCLE_TIME: Duration = Duration::from_millis(100);
-        let mut msg_counts: Vec<u64> = vec![0; topics.len()];
+        let mut msg_count: u64 = 0;
         
         loop {
-            match node.wait(CYCLE_TIME) {
-                NodeEvent::Tick => {
-                    // 检查每个订阅者的消息
-                    for (idx, (topic, subscriber)) in subscribers.iter().enumerate() {
-                        while let Some(sample) = subscriber.receive()? {
-                            msg_counts[idx] += 1;
-                            let data = sample.payload();
-                            debug!("Received message #{} on topic {}, raw size: {} bytes", msg_counts[idx], topic, data.len());
-                            debug!("First 50 bytes: {:?}", &data[..data.len().min(50)]);
-                            
-                            // 解析信号
-                            if data.len() >= 4 {
-                                let signal_type = u32::from_le_bytes([data[0], data[1], data[2], data[3]]);
-                                let expected_len = match signal_type {
-                                    0 => 44, // AdaptiveSpreadDeviation
-                                    1 => 36, // FixedSpreadDeviation  
-                                    2 => 32, // FundingRateDirection
-                                    3 => 40, // RealTimeFundingRisk
-                                    _ => {
-                                        error!("Unknown signal type: {}", signal_type);
-                                        continue;
-                                    }
-                                };
-                                
-                                if data.len() >= expected_len {
-                                    debug!("Attempting to deserialize {} bytes for signal type {}", expected_len, signal_type);
-                                    match Signal::from_bytes(Bytes::copy_from_slice(&data[..expected_len])) {
-                                        Ok(signal) => {
-                                            info!("Successfully deserialized signal from topic {}", topic);
-                                            let msg = SignalMessage {
-                                                signal,
-                                                source: topic.clone(),
-                                                timestamp: Utc::now(),
-                                            };
-                                            
-                                            if let Err(e) = tx.blocking_send(msg) {
-                                                error!("Failed to send signal: {}", e);
-                                                return Ok(());
-                                            }
-                                        }
-                                        Err(e) => {
-                                            error!("Failed to deserialize signal from {}: {}", topic, e);
-                                            error!("Data that failed (hex): {:02x?}", &data[..expected_len.min(50)]);
-                                        }
-                                    }
-                                } else {
-                                    warn!("Not enough data for signal type {}: have {} bytes, need {} bytes", signal_type, data.len(), expected_len);
-                                }
-                            } else {
-                                warn!("Received message too small: {} bytes", data.len());
-                            }
+            while let Some(sample) = subscriber.receive()? {
+                msg_count += 1;
+                let data = sample.payload();
+                debug!("Received message #{} on topic {}, raw size: {} bytes", msg_count, topic, data.len());
+                
+                // 帧头携带长度和校验和，尾部补零由解码器忽略
+                match Signal::from_bytes(Bytes::copy_from_slice(data)) {
+                    Ok(signal) => {
+                        debug!("Decoded {:?} signal from topic {}", signal.signal_type, topic);
+                        let msg = SignalMessage {
+                            signal,
+                            source: topic.to_string(),
+                            timestamp: Utc::now(),
+                        };
+                        
+                        if let Err(e) = tx.blocking_send(msg) {
+                            error!("Failed to send signal: {}", e);
+                            return Ok(());
                         }
                     }
+                    Err(e) => {
+                        warn!("Failed to decode signal from {}: {}", topic, e);
+                        debug!("Frame header (hex): {:02x?}", &data[..data.len().min(FRAME_HEADER_LEN)]);
+                    }
                 }
-                NodeEvent::TerminationRequest | NodeEvent::InterruptSignal => {
-                    info!("Subscriber received termination signal");
-                    break;
-                }
+            }
+            
+            if !waiter.wait(&node)? {
+                info!("Subscriber for {} received termination signal", topic);
+                break;
             }
         }
         
-        info!("Subscriber thread shutting down");
+        info!("Subscriber thread for {} shutting down", topic);
         Ok(())
     }
 }
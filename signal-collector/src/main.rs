@@ -1,6 +1,7 @@
 use anyhow::Result;
 use tokio::sync::mpsc;
 use tracing::info;
+use common::messages::SignalMessage;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
 mod signal_manager;
@@ -30,6 +31,7 @@ async fn main() -> Result<()> {
     let config = Config::load()?;
     
     let (signal_tx, mut signal_rx) = mpsc::channel(1024);
+    let (hedge_tx, mut hedge_rx) = mpsc::channel(1024);
     let (event_tx, event_rx) = mpsc::channel(1024);
 
     let mut signal_manager = SignalManager::new();
@@ -45,7 +47,10 @@ async fn main() -> Result<()> {
     }
 
     // 启动IceOryx订阅者线程
-    IceOryxSubscriber::spawn_subscribers(signal_tx.clone(), config.iceoryx_topics.clone());
+    IceOryxSubscriber::spawn_subscribers(signal_tx.clone(), config.iceoryx_topics.clone(), config.wakeup.clone());
+    
+    // 对冲信号单独一个队列，主循环优先处理
+    IceOryxSubscriber::spawn_subscribers(hedge_tx, vec![config.hedge_topic.clone()], config.wakeup.clone());
     
     // 启动ZMQ订阅者线程
     ZmqSubscriber::spawn_subscribers(signal_tx.clone(), config.zmq_endpoints.clone());
@@ -58,22 +63,42 @@ async fn main() -> Result<()> {
 
     info!("All subscribers and publishers started");
 
-    while let Some(signal_msg) = signal_rx.recv().await {
-        let signal_type = signal_msg.signal.signal_type;  // 直接访问字段，不是方法
-        
-        signal_manager.update_signal(signal_msg.signal.clone());
-        
-        // 获取该信号关联的所有触发器索引
-        let trigger_indices = signal_manager.get_trigger_indices_for_signal(signal_type);
+    loop {
+        // 对冲链路优先级最高：两个队列都有信号时先处理对冲信号
+        let signal_msg = tokio::select! {
+            biased;
+            Some(msg) = hedge_rx.recv() => msg,
+            Some(msg) = signal_rx.recv() => msg,
+            else => break,
+        };
         
-        for trigger_idx in trigger_indices {
-            if let Some(trigger) = trigger_registry.get_trigger(trigger_idx) {
-                if let Some(event) = trigger.evaluate(&signal_manager, &signal_msg.signal) {
-                    event_generator.send_event(event).await?;
-                }
+        process_signal(&mut signal_manager, &trigger_registry, &mut event_generator, signal_msg).await?;
+    }
+
+    Ok(())
+}
+
+/// 更新信号状态并评估关联的触发器，触发的事件发布给 pre-post-processor
+async fn process_signal(
+    signal_manager: &mut SignalManager,
+    trigger_registry: &TriggerRegistry,
+    event_generator: &mut EventGenerator,
+    signal_msg: SignalMessage,
+) -> Result<()> {
+    let signal_type = signal_msg.signal.signal_type;  // 直接访问字段，不是方法
+    
+    signal_manager.update_signal(signal_msg.signal.clone());
+    
+    // 获取该信号关联的所有触发器索引
+    let trigger_indices = signal_manager.get_trigger_indices_for_signal(signal_type);
+    
+    for trigger_idx in trigger_indices {
+        if let Some(trigger) = trigger_registry.get_trigger(trigger_idx) {
+            if let Some(event) = trigger.evaluate(signal_manager, &signal_msg.signal) {
+                event_generator.send_event(event).await?;
             }
         }
     }
-
+    
     Ok(())
 }
\ No newline at end of file
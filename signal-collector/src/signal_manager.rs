@@ -58,6 +58,12 @@ impl SignalManager {
                 trigger_indices: Vec::new(),
                 last_updated: Utc::now(),
             },
+            SignalStatus {
+                signal_type: SignalType::RiskControlInit,
+                last_signal: None,
+                trigger_indices: Vec::new(),
+                last_updated: Utc::now(),
+            },
         ];
 
         Self { signals }
@@ -73,6 +79,7 @@ impl SignalManager {
             SignalType::Arbitrage => 5,
             SignalType::Market => 6,
             SignalType::Hedge => 7,
+            SignalType::RiskControlInit => 8,
         }
     }
 
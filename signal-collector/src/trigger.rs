@@ -2,8 +2,9 @@ use std::rc::Rc;
 use std::collections::HashMap;
 use chrono::Utc;
 use common::types::{Signal, SignalType, SignalData, FundingDirection};
-use common::events::{TradingEvent, OpenPositionEvent};
-use common::types::{Priority, Side, OrderType, TriggerType, Symbol, Exchange};
+use common::events::{TradingEvent, OpenPositionEvent, HedgePositionEvent};
+use common::instrument::InstrumentId;
+use common::types::{Priority, Side, OrderType, TriggerType};
 use crate::signal_manager::SignalManager;
 
 pub trait Trigger {
@@ -61,7 +62,7 @@ impl TriggerRegistry {
         vec![
             (mt_idx, vec![0, 1, 2]),           // AdaptiveSpread, FixedSpread, FundingRate
             (mt_close_idx, vec![3, 0, 1]),     // RealTimeFundingRisk, AdaptiveSpread, FixedSpread
-            (hedge_idx, vec![0, 1]),           // AdaptiveSpread, FixedSpread
+            (hedge_idx, vec![7]),              // Hedge（pre-post-processor 发回的对冲信号）
         ]
     }
 }
@@ -93,7 +94,7 @@ impl Trigger for MTTrigger {
     fn evaluate(&self, manager: &SignalManager, signal: &Signal) -> Option<TradingEvent> {
         // 简单的测试逻辑
         match &signal.data {
-            SignalData::FundingRateDirection { exchange_id: _, symbol_id, funding_rate, direction } => {
+            SignalData::FundingRateDirection { exchange_id, symbol_id, funding_rate, direction } => {
                 // 检查是否有价差信号
                 if let Some(spread_signal) = manager.get_last_signal_by_type(SignalType::AdaptiveSpreadDeviation) {
                     if let SignalData::AdaptiveSpreadDeviation { spread_percentile, .. } = &spread_signal.data {
@@ -105,8 +106,7 @@ impl Trigger for MTTrigger {
                             };
                             
                             return Some(TradingEvent::OpenPosition(OpenPositionEvent {
-                                symbol: Symbol(*symbol_id),
-                                exchange: Exchange::Binance, // TODO: 从exchange_id转换
+                                instrument: InstrumentId::new(*exchange_id, *symbol_id),
                                 side,
                                 quantity: 100.0,
                                 order_type: OrderType::Market,
@@ -150,6 +150,7 @@ impl Trigger for MTCloseTrigger {
     }
 }
 
+/// 对冲触发器 - pre-post-processor 成交后发回的对冲信号直接生成对冲事件，不依赖其他信号
 pub struct HedgeTrigger {
     // 配置参数
 }
@@ -169,8 +170,31 @@ impl Trigger for HedgeTrigger {
         Priority::High
     }
 
-    fn evaluate(&self, _manager: &SignalManager, _signal: &Signal) -> Option<TradingEvent> {
-        // TODO: 实现对冲触发逻辑
-        None
+    fn evaluate(&self, _manager: &SignalManager, signal: &Signal) -> Option<TradingEvent> {
+        let SignalData::Hedge { hedge_id, target_position } = &signal.data else {
+            return None;
+        };
+        
+        // 信号的标的是成交腿，对冲腿由 pre-post-processor 按配对写入metadata
+        let hedge_instrument = signal.metadata
+            .get("hedge_instrument")
+            .and_then(|text| text.parse::<InstrumentId>().ok())?;
+        
+        // 对冲数量为剩余敞口，方向缺失时按目标数量的符号
+        let quantity = signal.quantity.unwrap_or(target_position.abs());
+        let side = signal.side.unwrap_or(if *target_position > 0.0 { Side::Buy } else { Side::Sell });
+        if quantity <= 0.0 {
+            return None;
+        }
+        
+        Some(TradingEvent::HedgePosition(HedgePositionEvent {
+            instrument: signal.instrument,
+            hedge_instrument,
+            side,
+            quantity,
+            trigger_type: TriggerType::HedgeTrigger,
+            reason: hedge_id.clone(),
+            timestamp: Utc::now(),
+        }))
     }
 }
\ No newline at end of file
@@ -5,6 +5,7 @@ use iceoryx2::prelude::*;
 use iceoryx2::port::publisher::Publisher;
 use common::types::{Signal, SignalType, SignalData, FundingDirection};
 use common::config::MarketConfig;
+use common::instrument::InstrumentId;
 use tokio::sync::broadcast;
 use tokio::select;
 use core::time::Duration;
@@ -237,6 +238,7 @@ fn process_market_event<T: iceoryx2::service::Service>(
                 threshold_percentile: 0.8,
             }
         );
+        adaptive_signal.instrument = InstrumentId::new(event.exchange_id, event.symbol_id);
         adaptive_signal.symbol = symbol_name.to_string();
         adaptive_signal.exchange = market_config.get_exchange_name(event.exchange_id)
             .unwrap_or("unknown".to_string());
@@ -267,6 +269,7 @@ fn process_market_event<T: iceoryx2::service::Service>(
                     direction,
                 }
             );
+            funding_signal.instrument = InstrumentId::new(event.exchange_id, event.symbol_id);
             funding_signal.symbol = symbol_name.to_string();
             funding_signal.exchange = market_config.get_exchange_name(event.exchange_id)
                 .unwrap_or("unknown".to_string());
@@ -287,7 +290,7 @@ fn send_signal<T: iceoryx2::service::Service>(
     symbol_id: u32,
     symbol_name: &str,
 ) -> Result<()> {
-    let bytes = signal.to_bytes();
+    let bytes = signal.to_bytes()?;
     
     if bytes.len() <= 1024 {
         let mut buffer = [0u8; 1024];
@@ -4,6 +4,7 @@ use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 use iceoryx2::prelude::*;
 use common::types::{Signal, SignalType, SignalData, FundingDirection};
 use common::config::MarketConfig;
+use common::instrument::InstrumentId;
 use rand::Rng;
 use core::time::Duration;
 
@@ -104,10 +105,11 @@ async fn main() -> Result<()> {
                 );
                 // 设置其他字段
                 let mut adaptive_signal = adaptive_signal;
+                adaptive_signal.instrument = InstrumentId::new(exchange_id, symbol.id);
                 adaptive_signal.symbol = symbol.symbol.clone();
                 adaptive_signal.exchange = market_config.get_exchange_name(exchange_id).unwrap_or("unknown".to_string());
                 
-                let bytes = adaptive_signal.to_bytes();
+                let bytes = adaptive_signal.to_bytes()?;
                 debug!("Adaptive signal serialized to {} bytes", bytes.len());
                 
                 if bytes.len() <= 1024 {
@@ -150,10 +152,11 @@ async fn main() -> Result<()> {
                 );
                 // 设置其他字段
                 let mut funding_signal = funding_signal;
+                funding_signal.instrument = InstrumentId::new(exchange_id, symbol.id);
                 funding_signal.symbol = symbol.symbol.clone();
                 funding_signal.exchange = market_config.get_exchange_name(exchange_id).unwrap_or("unknown".to_string());
                 
-                let bytes = funding_signal.to_bytes();
+                let bytes = funding_signal.to_bytes()?;
                 debug!("Funding signal serialized to {} bytes", bytes.len());
                 
                 if bytes.len() <= 1024 {
@@ -410,7 +410,7 @@ fn send_signal<T: iceoryx2::service::Service>(
     publisher: &Publisher<T, [u8; 1024], ()>,
     symbol_name: &str,
 ) -> Result<()> {
-    let bytes = signal.to_bytes();
+    let bytes = signal.to_bytes()?;
     
     if bytes.len() <= 1024 {
         let mut buffer = [0u8; 1024];
@@ -6,6 +6,7 @@ use iceoryx2::port::publisher::Publisher;
 use chrono::Utc;
 use common::types::{Signal, SignalType, SignalData, FundingDirection};
 use common::config::MarketConfig;
+use common::instrument::InstrumentId;
 use tokio::sync::mpsc;
 use tokio::select;
 use core::time::Duration;
@@ -342,6 +343,7 @@ fn process_market_event<T: iceoryx2::service::Service>(
                 threshold_percentile: 0.8,
             }
         );
+        signal.instrument = InstrumentId::new(event.exchange_id, event.symbol_id);
         signal.symbol = symbol_name.to_string();
         signal.exchange = market_config.get_exchange_name(event.exchange_id)
             .unwrap_or("unknown".to_string());
@@ -378,6 +380,7 @@ fn process_funding_event<T: iceoryx2::service::Service>(
             direction,
         }
     );
+    signal.instrument = InstrumentId::new(event.exchange_id, event.symbol_id);
     signal.symbol = symbol_name.to_string();
     signal.exchange = market_config.get_exchange_name(event.exchange_id)
         .unwrap_or("unknown".to_string());
@@ -409,7 +412,7 @@ fn send_signal<T: iceoryx2::service::Service>(
     publisher: &Publisher<T, [u8; 1024], ()>,
     symbol_name: &str,
 ) -> Result<()> {
-    let bytes = signal.to_bytes();
+    let bytes = signal.to_bytes()?;
     
     if bytes.len() <= 1024 {
         let mut buffer = [0u8; 1024];
@@ -1,7 +1,10 @@
+use super::user_data::UserDataAdapter;
+use crate::executor::signer::Signer;
 use crate::executor::types::*;
 use async_trait::async_trait;
+use rust_decimal::Decimal;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[async_trait]
 pub trait AdapterTrait: Send + Sync {
@@ -11,13 +14,50 @@ pub trait AdapterTrait: Send + Sync {
     
     fn format_order_message(&self, request: &OrderRequest) -> Result<Vec<u8>, anyhow::Error>;
     
+    /// Cancel acks must echo `request.request_id`, which keys the in-flight waiter
+    fn format_cancel_message(&self, request: &CancelRequest) -> Result<Vec<u8>, anyhow::Error>;
+    
+    /// Cancel-all acks must echo `request.request_id`; venues without a WebSocket
+    /// cancel-all keep the default
+    fn format_cancel_all_message(&self, _request: &CancelAllRequest) -> Result<Vec<u8>, anyhow::Error> {
+        Err(anyhow::anyhow!("{} does not support cancel-all over WebSocket", self.exchange_name()))
+    }
+    
+    /// Amend acks must echo `request.request_id`; venues without a WebSocket
+    /// amend keep the default
+    fn format_amend_message(&self, _request: &AmendRequest) -> Result<Vec<u8>, anyhow::Error> {
+        Err(anyhow::anyhow!("{} does not support amending orders over WebSocket", self.exchange_name()))
+    }
+    
+    /// Sign the canonical order parameters built by `OrderBuilder`; venues that
+    /// authenticate the connection with a login instead of each request keep the default
+    fn sign_order(&self, _signer: &Signer, _params: &BTreeMap<String, String>, _timestamp: i64) -> String {
+        String::new()
+    }
+    
+    /// Request id echoed by an ack, which keys the in-flight waiter; place requests
+    /// use the client order id, every other request its own `request_id`
+    fn ack_request_id(&self, data: &[u8]) -> Option<String> {
+        echoed_id(data, "/id")
+    }
+    
     fn parse_order_response(&self, data: &[u8]) -> Result<OrderResponse, anyhow::Error>;
     
+    /// One response per order; venues with batch acks override this
+    fn parse_order_responses(&self, data: &[u8]) -> Result<Vec<OrderResponse>, anyhow::Error> {
+        Ok(vec![self.parse_order_response(data)?])
+    }
+    
     fn parse_market_data(&self, data: &[u8]) -> Result<Value, anyhow::Error>;
     
     fn map_error_code(&self, code: i32) -> String;
     
     fn get_rate_limits(&self) -> HashMap<String, u32>;
+    
+    /// Private order-update stream protocol, if the venue has one
+    fn user_data(&self) -> Option<&dyn UserDataAdapter> {
+        None
+    }
 }
 
 pub struct ExchangeAdapter {
@@ -26,20 +66,68 @@ pub struct ExchangeAdapter {
 
 impl ExchangeAdapter {
     pub fn new() -> Self {
-        let mut adapters: HashMap<String, Box<dyn AdapterTrait>> = HashMap::new();
+        let mut registry = Self { adapters: HashMap::new() };
         
-        adapters.insert("binance".to_string(), Box::new(super::BinanceAdapter::new()));
-        adapters.insert("okex".to_string(), Box::new(super::OkexAdapter::new()));
-        adapters.insert("bybit".to_string(), Box::new(super::BybitAdapter::new()));
+        registry.register(Box::new(super::BinanceAdapter::new()));
+        registry.register(Box::new(super::OkexAdapter::new()));
+        registry.register(Box::new(super::BybitAdapter::new()));
+        registry.register(Box::new(super::BitgetAdapter::new()));
         
-        Self { adapters }
+        registry
+    }
+    
+    /// Adapters are keyed by `exchange_name()`, which must match the config key
+    pub fn register(&mut self, adapter: Box<dyn AdapterTrait>) {
+        self.adapters.insert(adapter.exchange_name().to_string(), adapter);
     }
     
-    pub fn get_adapter(&self, exchange: &str) -> Option<&Box<dyn AdapterTrait>> {
-        self.adapters.get(exchange)
+    pub fn get_adapter(&self, exchange: &str) -> Option<&dyn AdapterTrait> {
+        self.adapters.get(exchange).map(|adapter| adapter.as_ref())
     }
     
     pub fn list_exchanges(&self) -> Vec<String> {
         self.adapters.keys().cloned().collect()
     }
-}
\ No newline at end of file
+}
+
+impl Default for ExchangeAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) fn str_field(value: &Value, key: &str) -> String {
+    value.get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// String at `pointer` in a JSON frame; `None` for other frames
+pub(crate) fn echoed_id(data: &[u8], pointer: &str) -> Option<String> {
+    let json: Value = serde_json::from_slice(data).ok()?;
+    json.pointer(pointer)
+        .and_then(|v| v.as_str())
+        .map(|id| id.to_string())
+}
+
+pub(crate) fn rejected_response(client_order_id: String, symbol: String, error: String) -> OrderResponse {
+    OrderResponse {
+        order_id: String::new(),
+        client_order_id,
+        symbol,
+        status: OrderStatus::Rejected,
+        executed_qty: Decimal::ZERO,
+        executed_price: None,
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        error: Some(error),
+    }
+}
+
+pub(crate) fn format_error(code: &str, description: &str, detail: &str) -> String {
+    if detail.is_empty() {
+        format!("Error {}: {}", code, description)
+    } else {
+        format!("Error {}: {} ({})", code, description, detail)
+    }
+}
@@ -1,9 +1,15 @@
-use super::adapter::AdapterTrait;
+use super::adapter::{str_field, AdapterTrait};
+use super::user_data::{
+    decimal_field, int_field, parse_order_type, parse_side, OrderUpdate, UpdateKind, UserDataAdapter,
+    UserDataEvent,
+};
+use crate::executor::signer::Signer;
 use crate::executor::types::*;
 use async_trait::async_trait;
 use rust_decimal::Decimal;
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
 
 pub struct BinanceAdapter {
     rate_limits: HashMap<String, u32>,
@@ -54,37 +60,136 @@ impl AdapterTrait for BinanceAdapter {
             TimeInForce::GTX => "GTX",
         };
         
-        let mut msg = json!({
+        let mut params = json!({
             "symbol": request.symbol,
             "side": side,
             "type": order_type,
             "quantity": request.quantity.to_string(),
-            "timeInForce": time_in_force,
             "newClientOrderId": request.client_order_id,
             "timestamp": request.timestamp,
-            "signature": request.signature,
         });
         
         if let Some(price) = request.price {
-            msg["price"] = json!(price.to_string());
+            params["price"] = json!(price.to_string());
         }
         
+        if !matches!(request.order_type, OrderType::Market) {
+            params["timeInForce"] = json!(time_in_force);
+        }
+        
+        if request.reduce_only {
+            params["reduceOnly"] = json!("true");
+        }
+        
+        let msg = Self::signed_request(
+            &request.client_order_id,
+            "order.place",
+            params,
+            &request.api_key,
+            &request.signature,
+        );
+        
+        Ok(serde_json::to_vec(&msg)?)
+    }
+    
+    fn format_cancel_message(&self, request: &CancelRequest) -> Result<Vec<u8>, anyhow::Error> {
+        let params = json!({
+            "symbol": request.symbol,
+            "origClientOrderId": request.client_order_id,
+            "timestamp": request.timestamp,
+        });
+        
+        let msg = Self::signed_request(
+            &request.request_id,
+            "order.cancel",
+            params,
+            &request.api_key,
+            &request.signature,
+        );
+        
+        Ok(serde_json::to_vec(&msg)?)
+    }
+    
+    fn format_cancel_all_message(&self, request: &CancelAllRequest) -> Result<Vec<u8>, anyhow::Error> {
+        let params = json!({
+            "symbol": request.symbol,
+            "timestamp": request.timestamp,
+        });
+        
+        let msg = Self::signed_request(
+            &request.request_id,
+            "openOrders.cancelAll",
+            params,
+            &request.api_key,
+            &request.signature,
+        );
+        
+        Ok(serde_json::to_vec(&msg)?)
+    }
+    
+    fn format_amend_message(&self, request: &AmendRequest) -> Result<Vec<u8>, anyhow::Error> {
+        // order.modify replaces both fields, so neither may be left out
+        let (Some(price), Some(quantity)) = (request.new_price, request.new_quantity) else {
+            return Err(anyhow::anyhow!("Binance amend requires both price and quantity"));
+        };
+        
+        let side = match request.side {
+            OrderSide::Buy => "BUY",
+            OrderSide::Sell => "SELL",
+        };
+        
+        let params = json!({
+            "symbol": request.symbol,
+            "side": side,
+            "origClientOrderId": request.client_order_id,
+            "price": price.to_string(),
+            "quantity": quantity.to_string(),
+            "timestamp": request.timestamp,
+        });
+        
+        let msg = Self::signed_request(
+            &request.request_id,
+            "order.modify",
+            params,
+            &request.api_key,
+            &request.signature,
+        );
+        
         Ok(serde_json::to_vec(&msg)?)
     }
     
+    fn sign_order(&self, signer: &Signer, params: &BTreeMap<String, String>, _timestamp: i64) -> String {
+        // The API key travels in the signed params
+        let mut params = params.clone();
+        params.insert("apiKey".to_string(), signer.api_key().to_string());
+        signer.sign_binance(&params)
+    }
+    
     fn parse_order_response(&self, data: &[u8]) -> Result<OrderResponse, anyhow::Error> {
         let json: Value = serde_json::from_slice(data)?;
         
+        // WS API responses wrap the payload as {"id", "status", "result" | "error"}
+        let error = json.get("error")
+            .filter(|v| v.is_object())
+            .or_else(|| json.get("code").map(|_| &json));
+        
         // Check for error response
-        if let Some(code) = json.get("code") {
-            let error_msg = json.get("msg")
+        if let Some(error) = error {
+            let code = error.get("code").cloned().unwrap_or(Value::Null);
+            let error_msg = error.get("msg")
                 .and_then(|v| v.as_str())
                 .unwrap_or("Unknown error")
                 .to_string();
             
+            // Rejections carry no order fields; the echoed request id identifies the order
+            let client_order_id = json.get("id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            
             return Ok(OrderResponse {
                 order_id: String::new(),
-                client_order_id: String::new(),
+                client_order_id,
                 symbol: String::new(),
                 status: OrderStatus::Rejected,
                 executed_qty: Decimal::ZERO,
@@ -94,12 +199,33 @@ impl AdapterTrait for BinanceAdapter {
             });
         }
         
+        // openOrders.cancelAll acks list the canceled orders under "result";
+        // the request as a whole is identified by the echoed id
+        if let Some(Value::Array(_)) = json.get("result") {
+            return Ok(OrderResponse {
+                order_id: String::new(),
+                client_order_id: str_field(&json, "id"),
+                symbol: String::new(),
+                status: OrderStatus::Canceled,
+                executed_qty: Decimal::ZERO,
+                executed_price: None,
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                error: None,
+            });
+        }
+        
+        let json = json.get("result")
+            .filter(|v| v.is_object())
+            .unwrap_or(&json);
+        
         let order_id = json.get("orderId")
             .and_then(|v| v.as_i64())
             .map(|v| v.to_string())
             .unwrap_or_default();
         
-        let client_order_id = json.get("clientOrderId")
+        // Cancel acks carry the original id separately from the cancel's own id
+        let client_order_id = json.get("origClientOrderId")
+            .or_else(|| json.get("clientOrderId"))
             .and_then(|v| v.as_str())
             .unwrap_or("")
             .to_string();
@@ -172,4 +298,239 @@ impl AdapterTrait for BinanceAdapter {
     fn get_rate_limits(&self) -> HashMap<String, u32> {
         self.rate_limits.clone()
     }
-}
\ No newline at end of file
+    
+    fn user_data(&self) -> Option<&dyn UserDataAdapter> {
+        Some(self)
+    }
+}
+
+impl BinanceAdapter {
+    /// WS API request: everything but `id` and `method` goes under `params`,
+    /// exactly the keys the signature was computed over plus the signature itself
+    fn signed_request(id: &str, method: &str, mut params: Value, api_key: &str, signature: &str) -> Value {
+        params["apiKey"] = json!(api_key);
+        params["signature"] = json!(signature);
+        json!({
+            "id": id,
+            "method": method,
+            "params": params,
+        })
+    }
+    
+    /// `executionReport` (spot) is flat; `ORDER_TRADE_UPDATE` (futures) nests the order under "o"
+    fn order_update(order: &Value, event_time: Option<i64>) -> Option<OrderUpdate> {
+        let kind = match order.get("x").and_then(|v| v.as_str())? {
+            "NEW" => UpdateKind::New,
+            "TRADE" => UpdateKind::Trade,
+            "CANCELED" => UpdateKind::Cancelled,
+            "REJECTED" => UpdateKind::Rejected,
+            "EXPIRED" => UpdateKind::Expired,
+            // Spot amend-keep-priority and futures order modify
+            "REPLACED" | "AMENDMENT" => UpdateKind::Amended,
+            // CALCULATED, TRADE_PREVENTION
+            _ => return None,
+        };
+
+        // Spot cancels report the cancel request's id in "c" and the order's in "C"
+        let mut client_order_id = str_field(order, "C");
+        if client_order_id.is_empty() {
+            client_order_id = str_field(order, "c");
+        }
+
+        Some(OrderUpdate {
+            symbol: str_field(order, "s"),
+            order_id: int_field(order, "i").map(|id| id.to_string()).unwrap_or_default(),
+            client_order_id,
+            side: parse_side(order.get("S").and_then(|v| v.as_str())?)?,
+            order_type: parse_order_type(&str_field(order, "o")),
+            kind,
+            price: decimal_field(order, "p"),
+            quantity: decimal_field(order, "q"),
+            last_filled_qty: decimal_field(order, "l"),
+            last_filled_price: decimal_field(order, "L"),
+            cumulative_filled_qty: decimal_field(order, "z"),
+            timestamp: int_field(order, "T")
+                .or(event_time)
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis()),
+        })
+    }
+}
+
+impl UserDataAdapter for BinanceAdapter {
+    fn user_data_url(&self, market_type: &str) -> String {
+        match market_type {
+            "spot" => "wss://stream.binance.com:9443/ws".to_string(),
+            _ => "wss://fstream.binance.com/ws".to_string(),
+        }
+    }
+    
+    fn listen_key_request(&self, signer: &Signer, market_type: &str) -> Option<(String, String)> {
+        let api_url = match market_type {
+            "spot" => "wss://ws-api.binance.com:443/ws-api/v3",
+            _ => "wss://ws-fapi.binance.com/ws-fapi/v1",
+        };
+        
+        let msg = json!({
+            "id": "listen_key",
+            "method": "userDataStream.start",
+            "params": {
+                "apiKey": signer.api_key(),
+            }
+        });
+        
+        Some((api_url.to_string(), msg.to_string()))
+    }
+    
+    fn listen_key_keepalive(&self) -> Option<Duration> {
+        // Keys expire after 60 minutes without a keepalive; start on a live key extends it
+        Some(Duration::from_secs(30 * 60))
+    }
+    
+    fn subscribe_messages(&self, _market_type: &str) -> Vec<String> {
+        // The listen key stream carries every private event without subscribing
+        Vec::new()
+    }
+    
+    fn parse_user_data(&self, data: &[u8]) -> Result<Vec<UserDataEvent>, anyhow::Error> {
+        let json: Value = serde_json::from_slice(data)?;
+        
+        if let Some(key) = json.pointer("/result/listenKey").and_then(|v| v.as_str()) {
+            return Ok(vec![UserDataEvent::ListenKey(key.to_string())]);
+        }
+        
+        if let Some(error) = json.get("error") {
+            return Ok(vec![UserDataEvent::AuthFailed(format!(
+                "Error {}: {}",
+                error.get("code").cloned().unwrap_or(Value::Null),
+                str_field(error, "msg")
+            ))]);
+        }
+        
+        let event_time = int_field(&json, "E");
+        let update = match json.get("e").and_then(|v| v.as_str()) {
+            Some("executionReport") => Self::order_update(&json, event_time),
+            Some("ORDER_TRADE_UPDATE") => json.get("o").and_then(|o| Self::order_update(o, event_time)),
+            Some("listenKeyExpired") => {
+                return Ok(vec![UserDataEvent::AuthFailed("Listen key expired".to_string())]);
+            }
+            _ => None,
+        };
+        
+        Ok(update.map(UserDataEvent::Order).into_iter().collect())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::order_builder::OrderBuilder;
+    use common::instrument::InstrumentId;
+    use uuid::Uuid;
+
+    fn command(order_type: OrderType, price: Option<Decimal>) -> ExecutionCommand {
+        ExecutionCommand {
+            id: Uuid::new_v4(),
+            instrument: InstrumentId::new(2, 1),
+            exchange: "binance".to_string(),
+            market_type: "futures".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Buy,
+            order_type,
+            quantity: Decimal::new(15, 3),
+            price,
+            time_in_force: TimeInForce::GTC,
+            client_order_id: None,
+            reduce_only: true,
+            post_only: false,
+            action: CommandAction::Place,
+        }
+    }
+
+    /// Parses a request and checks it is `{id, method, params}` with `params`
+    /// signed over every key except the signature
+    fn signed_params(message: &[u8], signer: &Signer, id: &str, method: &str) -> serde_json::Map<String, Value> {
+        let json: Value = serde_json::from_slice(message).unwrap();
+        let mut keys: Vec<_> = json.as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, ["id", "method", "params"]);
+        assert_eq!(json["id"], id);
+        assert_eq!(json["method"], method);
+
+        let params = json["params"].as_object().unwrap().clone();
+        let signed: BTreeMap<String, String> = params
+            .iter()
+            .filter(|(key, _)| key.as_str() != "signature")
+            .map(|(key, value)| {
+                let value = value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string());
+                (key.clone(), value)
+            })
+            .collect();
+        assert_eq!(params["signature"], signer.sign_binance(&signed));
+        assert_eq!(params["apiKey"], "key");
+        params
+    }
+
+    #[test]
+    fn test_order_place_nests_signed_params() {
+        let adapter = BinanceAdapter::new();
+        let signer = Signer::new("key".to_string(), "secret".to_string());
+        let builder = OrderBuilder::new(&adapter);
+
+        let limit = command(OrderType::Limit, Some(Decimal::from(65000)));
+        let request = builder.build_order_request(&limit, "TE_1".to_string(), &signer).unwrap();
+        let message = adapter.format_order_message(&request).unwrap();
+        let params = signed_params(&message, &signer, "TE_1", "order.place");
+        let mut keys: Vec<_> = params.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, [
+            "apiKey", "newClientOrderId", "price", "quantity", "reduceOnly",
+            "side", "signature", "symbol", "timeInForce", "timestamp", "type",
+        ]);
+        assert_eq!(params["price"], "65000");
+        assert_eq!(params["quantity"], "0.015");
+        assert_eq!(params["timestamp"], request.timestamp);
+
+        // Market orders carry neither price nor time in force
+        let market = command(OrderType::Market, None);
+        let request = builder.build_order_request(&market, "TE_2".to_string(), &signer).unwrap();
+        let message = adapter.format_order_message(&request).unwrap();
+        let params = signed_params(&message, &signer, "TE_2", "order.place");
+        assert!(params.get("price").is_none());
+        assert!(params.get("timeInForce").is_none());
+    }
+
+    #[test]
+    fn test_cancel_and_amend_nest_signed_params() {
+        let adapter = BinanceAdapter::new();
+        let signer = Signer::new("key".to_string(), "secret".to_string());
+        let builder = OrderBuilder::new(&adapter);
+        let command = command(OrderType::Limit, Some(Decimal::from(65000)));
+
+        let request = builder.build_cancel_request(&command, "TE_1", "TE_CXL_2".to_string(), &signer);
+        let message = adapter.format_cancel_message(&request).unwrap();
+        let params = signed_params(&message, &signer, "TE_CXL_2", "order.cancel");
+        assert_eq!(params["origClientOrderId"], "TE_1");
+        assert_eq!(params.len(), 5);
+
+        let request = builder.build_cancel_all_request(&command, "TE_CXL_1".to_string(), &signer);
+        let message = adapter.format_cancel_all_message(&request).unwrap();
+        let params = signed_params(&message, &signer, "TE_CXL_1", "openOrders.cancelAll");
+        assert_eq!(params["symbol"], "BTCUSDT");
+        assert_eq!(params.len(), 4);
+
+        let request = builder.build_amend_request(
+            &command,
+            "TE_1",
+            "TE_AMD_3".to_string(),
+            Some(Decimal::from(64000)),
+            Some(Decimal::new(2, 2)),
+            &signer,
+        ).unwrap();
+        let message = adapter.format_amend_message(&request).unwrap();
+        let params = signed_params(&message, &signer, "TE_AMD_3", "order.modify");
+        assert_eq!(params["origClientOrderId"], "TE_1");
+        assert_eq!(params["side"], "BUY");
+        assert_eq!(params["price"], "64000");
+        assert_eq!(params["quantity"], "0.02");
+        assert_eq!(params.len(), 8);
+    }
+}
@@ -0,0 +1,429 @@
+use super::adapter::{echoed_id, format_error, rejected_response, str_field, AdapterTrait};
+use super::user_data::{
+    decimal_field, int_field, parse_order_type, parse_side, OrderUpdate, UpdateKind, UserDataAdapter,
+    UserDataEvent,
+};
+use crate::executor::signer::Signer;
+use crate::executor::types::*;
+use async_trait::async_trait;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub struct BitgetAdapter {
+    rate_limits: HashMap<String, u32>,
+}
+
+impl BitgetAdapter {
+    pub fn new() -> Self {
+        let mut rate_limits = HashMap::new();
+        rate_limits.insert("orders_per_second".to_string(), 10);
+        rate_limits.insert("ws_messages_per_second".to_string(), 10);
+
+        Self { rate_limits }
+    }
+
+    /// Human readable text for Bitget v2 `code` values
+    pub fn describe_error(code: i32) -> String {
+        match code {
+            30001 => "Request too frequent".to_string(),
+            30002 => "Invalid request".to_string(),
+            30003 => "Invalid parameter".to_string(),
+            30004 => "Access denied".to_string(),
+            30005 => "Login failed".to_string(),
+            30006 => "Request too many".to_string(),
+            30011 => "Invalid ACCESS_KEY".to_string(),
+            30012 => "Invalid ACCESS_PASSPHRASE".to_string(),
+            30013 => "Invalid ACCESS_TIMESTAMP".to_string(),
+            30014 => "Request timestamp expired".to_string(),
+            30015 => "Invalid signature".to_string(),
+            40001 => "ACCESS_KEY cannot be empty".to_string(),
+            40002 => "ACCESS_SIGN cannot be empty".to_string(),
+            40003 => "ACCESS_PASSPHRASE cannot be empty".to_string(),
+            40006 => "Invalid ACCESS_KEY".to_string(),
+            40009 => "Sign signature error".to_string(),
+            40010 => "Request timed out".to_string(),
+            40012 => "Invalid passphrase".to_string(),
+            40014 => "Incorrect permissions".to_string(),
+            40017 => "Parameter verification failed".to_string(),
+            40762 => "Order amount exceeds balance".to_string(),
+            40768 => "Order does not exist".to_string(),
+            40774 => "Order type must be one-way or hedge position".to_string(),
+            43011 => "Order price does not meet requirements".to_string(),
+            43012 => "Insufficient balance".to_string(),
+            43025 => "Order does not exist".to_string(),
+            45110 => "Order amount below minimum".to_string(),
+            45111 => "Order quantity below minimum".to_string(),
+            _ => format!("Error code: {}", code),
+        }
+    }
+
+    fn inst_type(market_type: &str) -> &'static str {
+        match market_type {
+            "spot" => "SPOT",
+            _ => "USDT-FUTURES",
+        }
+    }
+
+    fn parse_bitget_status(&self, status: &str) -> OrderStatus {
+        match status {
+            "live" | "new" | "init" => OrderStatus::New,
+            "partially_filled" | "partial-fill" => OrderStatus::PartiallyFilled,
+            "filled" | "full-fill" => OrderStatus::Filled,
+            "canceled" | "cancelled" => OrderStatus::Canceled,
+            _ => OrderStatus::New,
+        }
+    }
+
+    fn order_ack(&self, params: &Value, fallback_client_id: String, timestamp: i64) -> OrderResponse {
+        let mut client_order_id = str_field(params, "clientOid");
+        if client_order_id.is_empty() {
+            client_order_id = fallback_client_id;
+        }
+
+        let status = params.get("status")
+            .and_then(|v| v.as_str())
+            .map(|s| self.parse_bitget_status(s))
+            .unwrap_or(OrderStatus::New);
+
+        OrderResponse {
+            order_id: str_field(params, "orderId"),
+            client_order_id,
+            symbol: str_field(params, "instId"),
+            status,
+            executed_qty: Decimal::ZERO,
+            executed_price: None,
+            timestamp,
+            error: None,
+        }
+    }
+}
+
+impl Default for BitgetAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AdapterTrait for BitgetAdapter {
+    fn exchange_name(&self) -> &str {
+        "bitget"
+    }
+
+    fn format_ws_url(&self, _market_type: &str, stream_type: &str) -> String {
+        // Spot and futures share one endpoint; instType in each request selects the market
+        format!("wss://ws.bitget.com/v2/ws/{}", stream_type)
+    }
+
+    fn format_order_message(&self, request: &OrderRequest) -> Result<Vec<u8>, anyhow::Error> {
+        let order_type = match request.order_type {
+            OrderType::Market | OrderType::StopMarket => "market",
+            OrderType::Limit | OrderType::StopLimit => "limit",
+        };
+
+        let side = match request.side {
+            OrderSide::Buy => "buy",
+            OrderSide::Sell => "sell",
+        };
+
+        let force = match request.time_in_force {
+            TimeInForce::GTC => "gtc",
+            TimeInForce::IOC => "ioc",
+            TimeInForce::FOK => "fok",
+            TimeInForce::GTX => "post_only",
+        };
+
+        let mut params = json!({
+            "orderType": order_type,
+            "side": side,
+            "size": request.quantity.to_string(),
+            "force": force,
+            "clientOid": request.client_order_id,
+        });
+
+        if let Some(price) = request.price {
+            params["price"] = json!(price.to_string());
+        }
+
+        // Margin settings only exist for futures
+        if request.market_type != "spot" {
+            params["marginCoin"] = json!("USDT");
+            params["marginMode"] = json!("crossed");
+        }
+
+        let msg = json!({
+            "op": "trade",
+            "args": [{
+                "id": request.client_order_id,
+                "instType": Self::inst_type(&request.market_type),
+                "instId": request.symbol,
+                "channel": "place-order",
+                "params": params,
+            }]
+        });
+
+        Ok(serde_json::to_vec(&msg)?)
+    }
+
+    fn format_cancel_message(&self, request: &CancelRequest) -> Result<Vec<u8>, anyhow::Error> {
+        let msg = json!({
+            "op": "trade",
+            "args": [{
+                "id": request.request_id,
+                "instType": Self::inst_type(&request.market_type),
+                "instId": request.symbol,
+                "channel": "cancel-order",
+                "params": {
+                    "clientOid": request.client_order_id,
+                },
+            }]
+        });
+
+        Ok(serde_json::to_vec(&msg)?)
+    }
+
+    fn ack_request_id(&self, data: &[u8]) -> Option<String> {
+        echoed_id(data, "/arg/0/id")
+    }
+
+    fn parse_order_response(&self, data: &[u8]) -> Result<OrderResponse, anyhow::Error> {
+        self.parse_order_responses(data)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Response contains no orders"))
+    }
+
+    fn parse_order_responses(&self, data: &[u8]) -> Result<Vec<OrderResponse>, anyhow::Error> {
+        let json: Value = serde_json::from_slice(data)?;
+
+        // WebSocket acks use a numeric code (0 = ok), REST uses "00000"
+        let code = match json.get("code") {
+            Some(Value::Number(n)) => n.to_string(),
+            Some(Value::String(s)) => s.clone(),
+            _ => return Err(anyhow::anyhow!("Missing code in Bitget response")),
+        };
+        let ok = code.trim_start_matches('0').is_empty();
+
+        let timestamp = json.get("ts")
+            .or_else(|| json.get("requestTime"))
+            .and_then(|v| v.as_i64())
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+
+        // WebSocket trade acks: one entry per order in "arg", echoing our request id
+        if let Some(args) = json.get("arg").and_then(|v| v.as_array()) {
+            let msg = str_field(&json, "msg");
+            return Ok(args.iter()
+                .map(|arg| {
+                    let request_id = str_field(arg, "id");
+                    let params = arg.get("params").cloned().unwrap_or(Value::Null);
+                    if !ok {
+                        let mut client_order_id = str_field(&params, "clientOid");
+                        if client_order_id.is_empty() {
+                            client_order_id = request_id;
+                        }
+                        let mut response = rejected_response(client_order_id, str_field(arg, "instId"), bitget_error(&code, &msg));
+                        response.timestamp = timestamp;
+                        return response;
+                    }
+                    let mut response = self.order_ack(&params, request_id, timestamp);
+                    if response.symbol.is_empty() {
+                        response.symbol = str_field(arg, "instId");
+                    }
+                    response
+                })
+                .collect());
+        }
+
+        if !ok {
+            let msg = str_field(&json, "msg");
+            let mut response = rejected_response(String::new(), String::new(), bitget_error(&code, &msg));
+            response.timestamp = timestamp;
+            return Ok(vec![response]);
+        }
+
+        // REST acks carry the order in "data"
+        let data = json.get("data")
+            .ok_or_else(|| anyhow::anyhow!("Invalid response format"))?;
+        Ok(vec![self.order_ack(data, String::new(), timestamp)])
+    }
+
+    fn parse_market_data(&self, data: &[u8]) -> Result<Value, anyhow::Error> {
+        Ok(serde_json::from_slice(data)?)
+    }
+
+    fn map_error_code(&self, code: i32) -> String {
+        Self::describe_error(code)
+    }
+
+    fn get_rate_limits(&self) -> HashMap<String, u32> {
+        self.rate_limits.clone()
+    }
+    
+    fn user_data(&self) -> Option<&dyn UserDataAdapter> {
+        Some(self)
+    }
+}
+
+impl UserDataAdapter for BitgetAdapter {
+    fn user_data_url(&self, _market_type: &str) -> String {
+        "wss://ws.bitget.com/v2/ws/private".to_string()
+    }
+    
+    fn login_message(&self, signer: &Signer, timestamp_ms: i64) -> Option<String> {
+        let timestamp = (timestamp_ms / 1000).to_string();
+        let msg = json!({
+            "op": "login",
+            "args": [{
+                "apiKey": signer.api_key(),
+                "passphrase": signer.passphrase().unwrap_or_default(),
+                "timestamp": timestamp,
+                "sign": signer.sign_bitget(&timestamp, "GET", "/user/verify", ""),
+            }]
+        });
+        Some(msg.to_string())
+    }
+    
+    fn subscribe_messages(&self, market_type: &str) -> Vec<String> {
+        vec![json!({
+            "op": "subscribe",
+            "args": [{
+                "instType": Self::inst_type(market_type),
+                "channel": "orders",
+                "instId": "default",
+            }]
+        }).to_string()]
+    }
+    
+    fn heartbeat(&self) -> Option<(Duration, String)> {
+        // Connections without a ping for two minutes are dropped
+        Some((Duration::from_secs(30), "ping".to_string()))
+    }
+    
+    fn parse_user_data(&self, data: &[u8]) -> Result<Vec<UserDataEvent>, anyhow::Error> {
+        if data == b"pong" {
+            return Ok(Vec::new());
+        }
+        
+        let json: Value = serde_json::from_slice(data)?;
+        
+        if let Some(event) = json.get("event").and_then(|v| v.as_str()) {
+            let code = match json.get("code") {
+                Some(Value::Number(n)) => n.to_string(),
+                Some(Value::String(s)) => s.clone(),
+                _ => "0".to_string(),
+            };
+            return Ok(match event {
+                "login" if code == "0" => vec![UserDataEvent::Authenticated],
+                "login" | "error" => {
+                    vec![UserDataEvent::AuthFailed(bitget_error(&code, &str_field(&json, "msg")))]
+                }
+                _ => Vec::new(),
+            });
+        }
+        
+        if json.pointer("/arg/channel").and_then(|v| v.as_str()) != Some("orders") {
+            return Ok(Vec::new());
+        }
+        
+        let entries = json.get("data")
+            .and_then(|v| v.as_array())
+            .map(|v| v.as_slice())
+            .unwrap_or_default();
+        
+        let events = entries.iter()
+            .filter_map(|entry| {
+                let last_filled_qty = decimal_field(entry, "baseVolume");
+                let kind = match entry.get("status").and_then(|v| v.as_str())? {
+                    // Also pushed after an amend; `OpenOrders` tells the two apart
+                    "live" | "new" => UpdateKind::New,
+                    "partially_filled" | "filled" if !last_filled_qty.is_zero() => UpdateKind::Trade,
+                    "canceled" | "cancelled" => UpdateKind::Cancelled,
+                    _ => return None,
+                };
+                
+                Some(UserDataEvent::Order(OrderUpdate {
+                    symbol: str_field(entry, "instId"),
+                    order_id: str_field(entry, "orderId"),
+                    client_order_id: str_field(entry, "clientOid"),
+                    side: parse_side(entry.get("side").and_then(|v| v.as_str())?)?,
+                    order_type: parse_order_type(&str_field(entry, "orderType")),
+                    kind,
+                    price: decimal_field(entry, "price"),
+                    quantity: decimal_field(entry, "size"),
+                    last_filled_qty,
+                    last_filled_price: decimal_field(entry, "fillPrice"),
+                    cumulative_filled_qty: decimal_field(entry, "accBaseVolume"),
+                    timestamp: int_field(entry, "uTime")
+                        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis()),
+                }))
+            })
+            .collect();
+        
+        Ok(events)
+    }
+}
+
+fn bitget_error(code: &str, detail: &str) -> String {
+    let description = code.parse::<i32>()
+        .map(BitgetAdapter::describe_error)
+        .unwrap_or_else(|_| format!("Error code: {}", code));
+    format_error(code, &description, detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(market_type: &str) -> OrderRequest {
+        OrderRequest {
+            symbol: "BTCUSDT".to_string(),
+            market_type: market_type.to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            quantity: Decimal::new(15, 3),
+            price: Some(Decimal::from(65000)),
+            time_in_force: TimeInForce::GTC,
+            reduce_only: false,
+            client_order_id: "TE_1".to_string(),
+            timestamp: 1700000000000,
+            api_key: String::new(),
+            signature: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_spot_order_uses_spot_inst_type() {
+        let adapter = BitgetAdapter::new();
+
+        let message = adapter.format_order_message(&order("spot")).unwrap();
+        let json: Value = serde_json::from_slice(&message).unwrap();
+        let arg = &json["args"][0];
+        assert_eq!(arg["instType"], "SPOT");
+        assert_eq!(arg["instId"], "BTCUSDT");
+        assert_eq!(arg["channel"], "place-order");
+        assert_eq!(arg["params"]["size"], "0.015");
+        assert!(arg["params"].get("marginCoin").is_none());
+
+        let message = adapter.format_order_message(&order("futures")).unwrap();
+        let json: Value = serde_json::from_slice(&message).unwrap();
+        assert_eq!(json["args"][0]["instType"], "USDT-FUTURES");
+        assert_eq!(json["args"][0]["params"]["marginCoin"], "USDT");
+
+        let cancel = CancelRequest {
+            symbol: "BTCUSDT".to_string(),
+            market_type: "spot".to_string(),
+            client_order_id: "TE_1".to_string(),
+            request_id: "TE_2".to_string(),
+            timestamp: 1700000000000,
+            api_key: String::new(),
+            signature: String::new(),
+        };
+        let message = adapter.format_cancel_message(&cancel).unwrap();
+        let json: Value = serde_json::from_slice(&message).unwrap();
+        assert_eq!(json["args"][0]["instType"], "SPOT");
+        assert_eq!(json["args"][0]["id"], "TE_2");
+        assert_eq!(json["args"][0]["params"]["clientOid"], "TE_1");
+    }
+}
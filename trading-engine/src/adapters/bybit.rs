@@ -1,9 +1,15 @@
-use super::adapter::AdapterTrait;
+use super::adapter::{echoed_id, format_error, rejected_response, str_field, AdapterTrait};
+use super::user_data::{
+    decimal_field, int_field, parse_order_type, parse_side, OrderUpdate, UpdateKind, UserDataAdapter,
+    UserDataEvent,
+};
+use crate::executor::signer::Signer;
 use crate::executor::types::*;
 use async_trait::async_trait;
 use rust_decimal::Decimal;
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
 
 pub struct BybitAdapter {
     rate_limits: HashMap<String, u32>,
@@ -17,6 +23,80 @@ impl BybitAdapter {
         
         Self { rate_limits }
     }
+    
+    /// Human readable text for Bybit `retCode` values (legacy and v5 codes)
+    pub fn describe_error(code: i32) -> String {
+        match code {
+            10001 => "Parameter error".to_string(),
+            10002 => "Request expired".to_string(),
+            10003 => "API key error".to_string(),
+            10004 => "Sign error".to_string(),
+            10005 => "Permission denied".to_string(),
+            10006 => "Too many requests".to_string(),
+            10007 => "Invalid request".to_string(),
+            10010 => "Server error".to_string(),
+            10016 => "Internal server error".to_string(),
+            10018 => "IP rate limit exceeded".to_string(),
+            20001 => "Order not exists".to_string(),
+            20003 => "Operation not allowed".to_string(),
+            20004 => "Duplicate order".to_string(),
+            20005 => "Order amount too small".to_string(),
+            20006 => "Order amount exceed limit".to_string(),
+            20007 => "Order cancelled".to_string(),
+            30001 => "Position not exists".to_string(),
+            30003 => "Insufficient balance".to_string(),
+            110001 => "Order does not exist".to_string(),
+            110003 => "Order price out of permissible range".to_string(),
+            110004 => "Insufficient wallet balance".to_string(),
+            110007 => "Insufficient available balance".to_string(),
+            110008 => "Order already completed or cancelled".to_string(),
+            110017 => "Reduce-only order would increase position".to_string(),
+            110072 => "Duplicate orderLinkId".to_string(),
+            170131 => "Insufficient balance".to_string(),
+            170136 => "Order quantity exceeds upper limit".to_string(),
+            170137 => "Order quantity has too many decimals".to_string(),
+            _ => format!("Error code: {}", code),
+        }
+    }
+    
+    fn bybit_ack(&self, item: &Value, fallback_client_id: String, timestamp: i64) -> OrderResponse {
+        let mut client_order_id = str_field(item, "orderLinkId");
+        if client_order_id.is_empty() {
+            client_order_id = fallback_client_id;
+        }
+        
+        let status = item.get("orderStatus")
+            .and_then(|v| v.as_str())
+            .map(|s| self.parse_bybit_status(s))
+            .unwrap_or(OrderStatus::New);
+        
+        let timestamp = item.get("createAt")
+            .and_then(|v| v.as_str())
+            .and_then(|s| s.parse::<i64>().ok())
+            .unwrap_or(timestamp);
+        
+        OrderResponse {
+            order_id: str_field(item, "orderId"),
+            client_order_id,
+            symbol: str_field(item, "symbol"),
+            status,
+            executed_qty: Decimal::ZERO,
+            executed_price: None,
+            timestamp,
+            error: None,
+        }
+    }
+
+    fn parse_bybit_status(&self, status: &str) -> OrderStatus {
+        match status {
+            "New" | "Created" | "Untriggered" => OrderStatus::New,
+            "PartiallyFilled" => OrderStatus::PartiallyFilled,
+            "Filled" => OrderStatus::Filled,
+            "Cancelled" | "PartiallyFilledCanceled" | "Deactivated" => OrderStatus::Canceled,
+            "Rejected" => OrderStatus::Rejected,
+            _ => OrderStatus::New,
+        }
+    }
 }
 
 #[async_trait]
@@ -54,6 +134,7 @@ impl AdapterTrait for BybitAdapter {
         };
         
         let msg = json!({
+            "reqId": request.client_order_id,
             "category": "spot",
             "symbol": request.symbol,
             "side": side,
@@ -67,98 +148,255 @@ impl AdapterTrait for BybitAdapter {
         Ok(serde_json::to_vec(&msg)?)
     }
     
+    fn format_cancel_message(&self, request: &CancelRequest) -> Result<Vec<u8>, anyhow::Error> {
+        let msg = json!({
+            "reqId": request.request_id,
+            "op": "order.cancel",
+            "category": "spot",
+            "symbol": request.symbol,
+            "orderLinkId": request.client_order_id,
+        });
+        
+        Ok(serde_json::to_vec(&msg)?)
+    }
+    
+    fn format_amend_message(&self, request: &AmendRequest) -> Result<Vec<u8>, anyhow::Error> {
+        if request.new_price.is_none() && request.new_quantity.is_none() {
+            return Err(anyhow::anyhow!("Amend without new price or quantity"));
+        }
+        
+        let msg = json!({
+            "reqId": request.request_id,
+            "op": "order.amend",
+            "category": "spot",
+            "symbol": request.symbol,
+            "orderLinkId": request.client_order_id,
+            "qty": request.new_quantity.map(|q| q.to_string()),
+            "price": request.new_price.map(|p| p.to_string()),
+        });
+        
+        Ok(serde_json::to_vec(&msg)?)
+    }
+    
+    fn sign_order(&self, signer: &Signer, params: &BTreeMap<String, String>, _timestamp: i64) -> String {
+        signer.sign_bybit(params)
+    }
+    
+    fn ack_request_id(&self, data: &[u8]) -> Option<String> {
+        echoed_id(data, "/reqId")
+    }
+    
     fn parse_order_response(&self, data: &[u8]) -> Result<OrderResponse, anyhow::Error> {
+        self.parse_order_responses(data)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Response contains no orders"))
+    }
+    
+    fn parse_order_responses(&self, data: &[u8]) -> Result<Vec<OrderResponse>, anyhow::Error> {
         let json: Value = serde_json::from_slice(data)?;
         
-        // Check for error response
-        if json.get("retCode").and_then(|v| v.as_i64()) != Some(0) {
-            let error_msg = json.get("retMsg")
-                .and_then(|v| v.as_str())
-                .unwrap_or("Unknown error")
-                .to_string();
-            
-            return Ok(OrderResponse {
-                order_id: String::new(),
-                client_order_id: String::new(),
-                symbol: String::new(),
-                status: OrderStatus::Rejected,
-                executed_qty: Decimal::ZERO,
-                executed_price: None,
-                timestamp: chrono::Utc::now().timestamp_millis(),
-                error: Some(error_msg),
-            });
-        }
+        let ret_code = json.get("retCode")
+            .and_then(|v| v.as_i64())
+            .ok_or_else(|| anyhow::anyhow!("Missing retCode in Bybit response"))?;
         
-        // Parse successful response
-        let result = json.get("result")
-            .ok_or_else(|| anyhow::anyhow!("Invalid response format"))?;
+        // WebSocket trade acks echo reqId, which place requests set to the client order id
+        let request_id = str_field(&json, "reqId");
         
-        let order_id = result.get("orderId")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
+        let timestamp = json.get("time")
+            .and_then(|v| v.as_i64())
+            .or_else(|| json.pointer("/header/Timenow")
+                .and_then(|v| v.as_str())
+                .and_then(|s| s.parse::<i64>().ok()))
+            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
         
-        let client_order_id = result.get("orderLinkId")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
+        if ret_code != 0 {
+            let ret_msg = str_field(&json, "retMsg");
+            let mut response = rejected_response(request_id, String::new(), bybit_error(ret_code, &ret_msg));
+            response.timestamp = timestamp;
+            return Ok(vec![response]);
+        }
         
-        let symbol = result.get("symbol")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
+        // REST responses use "result", WebSocket trade responses use "data"
+        let body = json.get("result")
+            .or_else(|| json.get("data"))
+            .ok_or_else(|| anyhow::anyhow!("Invalid response format"))?;
         
-        let status = result.get("orderStatus")
-            .and_then(|v| v.as_str())
-            .map(|s| match s {
-                "New" => OrderStatus::New,
-                "PartiallyFilled" => OrderStatus::PartiallyFilled,
-                "Filled" => OrderStatus::Filled,
-                "Cancelled" => OrderStatus::Canceled,
-                "Rejected" => OrderStatus::Rejected,
-                _ => OrderStatus::New,
-            })
-            .unwrap_or(OrderStatus::New);
+        if let Some(list) = body.get("list").and_then(|v| v.as_array()) {
+            // Batch: retExtInfo.list carries the per-order result at the same index
+            let ext = json.pointer("/retExtInfo/list")
+                .and_then(|v| v.as_array())
+                .map(|v| v.as_slice())
+                .unwrap_or_default();
+            
+            return Ok(list.iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let code = ext.get(i)
+                        .and_then(|e| e.get("code"))
+                        .and_then(|v| v.as_i64())
+                        .unwrap_or(0);
+                    
+                    if code != 0 {
+                        let msg = ext.get(i).map(|e| str_field(e, "msg")).unwrap_or_default();
+                        let mut response = rejected_response(
+                            str_field(item, "orderLinkId"),
+                            str_field(item, "symbol"),
+                            bybit_error(code, &msg),
+                        );
+                        response.timestamp = timestamp;
+                        return response;
+                    }
+                    
+                    self.bybit_ack(item, String::new(), timestamp)
+                })
+                .collect());
+        }
         
-        Ok(OrderResponse {
-            order_id,
-            client_order_id,
-            symbol,
-            status,
-            executed_qty: Decimal::ZERO,
-            executed_price: None,
-            timestamp: chrono::Utc::now().timestamp_millis(),
-            error: None,
-        })
+        Ok(vec![self.bybit_ack(body, request_id, timestamp)])
     }
-    
+
     fn parse_market_data(&self, data: &[u8]) -> Result<Value, anyhow::Error> {
         Ok(serde_json::from_slice(data)?)
     }
     
     fn map_error_code(&self, code: i32) -> String {
-        match code {
-            10001 => "Parameter error".to_string(),
-            10002 => "Request expired".to_string(),
-            10003 => "API key error".to_string(),
-            10004 => "Sign error".to_string(),
-            10005 => "Permission denied".to_string(),
-            10006 => "Too many requests".to_string(),
-            10007 => "Invalid request".to_string(),
-            10010 => "Server error".to_string(),
-            20001 => "Order not exists".to_string(),
-            20003 => "Operation not allowed".to_string(),
-            20004 => "Duplicate order".to_string(),
-            20005 => "Order amount too small".to_string(),
-            20006 => "Order amount exceed limit".to_string(),
-            20007 => "Order cancelled".to_string(),
-            30001 => "Position not exists".to_string(),
-            30003 => "Insufficient balance".to_string(),
-            _ => format!("Error code: {}", code),
-        }
+        Self::describe_error(code)
     }
     
     fn get_rate_limits(&self) -> HashMap<String, u32> {
         self.rate_limits.clone()
     }
-}
\ No newline at end of file
+    
+    fn user_data(&self) -> Option<&dyn UserDataAdapter> {
+        Some(self)
+    }
+}
+
+impl BybitAdapter {
+    /// Order topic: acks and terminal states. Fills come from the execution
+    /// topic, which carries the per-trade quantity and price.
+    fn order_topic_update(item: &Value) -> Option<OrderUpdate> {
+        let kind = match item.get("orderStatus").and_then(|v| v.as_str())? {
+            // Also pushed after an amend; `OpenOrders` tells the two apart
+            "New" => UpdateKind::New,
+            "Cancelled" | "PartiallyFilledCanceled" | "Deactivated" => UpdateKind::Cancelled,
+            "Rejected" => UpdateKind::Rejected,
+            _ => return None,
+        };
+        
+        Some(OrderUpdate {
+            symbol: str_field(item, "symbol"),
+            order_id: str_field(item, "orderId"),
+            client_order_id: str_field(item, "orderLinkId"),
+            side: parse_side(item.get("side").and_then(|v| v.as_str())?)?,
+            order_type: parse_order_type(&str_field(item, "orderType")),
+            kind,
+            price: decimal_field(item, "price"),
+            quantity: decimal_field(item, "qty"),
+            last_filled_qty: Decimal::ZERO,
+            last_filled_price: Decimal::ZERO,
+            cumulative_filled_qty: decimal_field(item, "cumExecQty"),
+            timestamp: int_field(item, "updatedTime")
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis()),
+        })
+    }
+    
+    fn execution_topic_update(item: &Value) -> Option<OrderUpdate> {
+        // Funding and settlement executions are not order fills
+        if item.get("execType").and_then(|v| v.as_str()) != Some("Trade") {
+            return None;
+        }
+        
+        let quantity = decimal_field(item, "orderQty");
+        Some(OrderUpdate {
+            symbol: str_field(item, "symbol"),
+            order_id: str_field(item, "orderId"),
+            client_order_id: str_field(item, "orderLinkId"),
+            side: parse_side(item.get("side").and_then(|v| v.as_str())?)?,
+            order_type: parse_order_type(&str_field(item, "orderType")),
+            kind: UpdateKind::Trade,
+            price: decimal_field(item, "orderPrice"),
+            quantity,
+            last_filled_qty: decimal_field(item, "execQty"),
+            last_filled_price: decimal_field(item, "execPrice"),
+            cumulative_filled_qty: quantity - decimal_field(item, "leavesQty"),
+            timestamp: int_field(item, "execTime")
+                .unwrap_or_else(|| chrono::Utc::now().timestamp_millis()),
+        })
+    }
+}
+
+impl UserDataAdapter for BybitAdapter {
+    fn user_data_url(&self, _market_type: &str) -> String {
+        "wss://stream.bybit.com/v5/private".to_string()
+    }
+    
+    fn login_message(&self, signer: &Signer, timestamp_ms: i64) -> Option<String> {
+        let expires = timestamp_ms + 10_000;
+        let msg = json!({
+            "op": "auth",
+            "args": [signer.api_key(), expires, signer.sign_bybit_auth(expires)]
+        });
+        Some(msg.to_string())
+    }
+    
+    fn subscribe_messages(&self, market_type: &str) -> Vec<String> {
+        // Category-scoped topics keep spot and linear sessions from seeing each other's orders
+        let category = match market_type {
+            "spot" => "spot",
+            _ => "linear",
+        };
+        vec![json!({
+            "op": "subscribe",
+            "args": [format!("order.{}", category), format!("execution.{}", category)]
+        }).to_string()]
+    }
+    
+    fn heartbeat(&self) -> Option<(Duration, String)> {
+        Some((Duration::from_secs(20), json!({"op": "ping"}).to_string()))
+    }
+    
+    fn parse_user_data(&self, data: &[u8]) -> Result<Vec<UserDataEvent>, anyhow::Error> {
+        let json: Value = serde_json::from_slice(data)?;
+        
+        if let Some(op) = json.get("op").and_then(|v| v.as_str()) {
+            let success = json.get("success").and_then(|v| v.as_bool()).unwrap_or(true);
+            return Ok(match (op, success) {
+                ("auth", true) => vec![UserDataEvent::Authenticated],
+                ("auth", false) | ("subscribe", false) => {
+                    vec![UserDataEvent::AuthFailed(format!("{} failed: {}", op, str_field(&json, "ret_msg")))]
+                }
+                _ => Vec::new(),
+            });
+        }
+        
+        let topic = json.get("topic").and_then(|v| v.as_str()).unwrap_or("");
+        let items = json.get("data")
+            .and_then(|v| v.as_array())
+            .map(|v| v.as_slice())
+            .unwrap_or_default();
+        
+        let events = items.iter()
+            .filter_map(|item| {
+                if topic.starts_with("order") {
+                    Self::order_topic_update(item)
+                } else if topic.starts_with("execution") {
+                    Self::execution_topic_update(item)
+                } else {
+                    None
+                }
+            })
+            .map(UserDataEvent::Order)
+            .collect();
+        
+        Ok(events)
+    }
+}
+
+fn bybit_error(code: i64, detail: &str) -> String {
+    let description = i32::try_from(code)
+        .map(BybitAdapter::describe_error)
+        .unwrap_or_else(|_| format!("Error code: {}", code));
+    format_error(&code.to_string(), &description, detail)
+}
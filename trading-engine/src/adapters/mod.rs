@@ -2,8 +2,12 @@ pub mod adapter;
 pub mod binance;
 pub mod okex;
 pub mod bybit;
+pub mod bitget;
+pub mod user_data;
 
 pub use adapter::{ExchangeAdapter, AdapterTrait};
 pub use binance::BinanceAdapter;
 pub use okex::OkexAdapter;
-pub use bybit::BybitAdapter;
\ No newline at end of file
+pub use bybit::BybitAdapter;
+pub use bitget::BitgetAdapter;
+pub use user_data::{OpenOrders, OrderUpdate, UpdateKind, UserDataAdapter, UserDataEvent};
\ No newline at end of file
@@ -1,9 +1,16 @@
-use super::adapter::AdapterTrait;
+use super::adapter::{format_error, rejected_response, str_field, AdapterTrait};
+use super::user_data::{
+    decimal_field, int_field, parse_order_type, parse_side, OrderUpdate, UpdateKind, UserDataAdapter,
+    UserDataEvent,
+};
+use crate::executor::signer::Signer;
 use crate::executor::types::*;
 use async_trait::async_trait;
 use rust_decimal::Decimal;
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+use tracing::warn;
 
 pub struct OkexAdapter {
     rate_limits: HashMap<String, u32>,
@@ -17,6 +24,43 @@ impl OkexAdapter {
         
         Self { rate_limits }
     }
+    
+    /// Human readable text for OKX v5 `code`/`sCode` values
+    pub fn describe_error(code: i32) -> String {
+        match code {
+            1 => "Operation failed".to_string(),
+            2 => "Batch operation partially succeeded".to_string(),
+            50000 => "General error".to_string(),
+            50001 => "Service temporarily unavailable".to_string(),
+            50002 => "Service busy".to_string(),
+            50004 => "Request timeout".to_string(),
+            50005 => "Too many requests".to_string(),
+            50006 => "Invalid request".to_string(),
+            50007 => "Invalid API key".to_string(),
+            50008 => "Invalid signature".to_string(),
+            50011 => "Rate limit reached".to_string(),
+            50013 => "System busy".to_string(),
+            50014 => "Missing required parameter".to_string(),
+            50026 => "System error".to_string(),
+            51000 => "Invalid instrument".to_string(),
+            51001 => "Instrument does not exist".to_string(),
+            51004 => "Order amount exceeds position tier limit".to_string(),
+            51006 => "Invalid order price".to_string(),
+            51008 => "Order amount exceeds limit".to_string(),
+            51009 => "Order placement failed".to_string(),
+            51010 => "Insufficient balance".to_string(),
+            51020 => "Order amount below minimum".to_string(),
+            51121 => "Order quantity must be a multiple of the lot size".to_string(),
+            51131 => "Insufficient balance".to_string(),
+            51400 => "Order cancellation failed".to_string(),
+            51401 => "Order already canceled".to_string(),
+            51402 => "Order already completed".to_string(),
+            51503 => "Order does not exist".to_string(),
+            60009 => "Login failed".to_string(),
+            60011 => "Not logged in".to_string(),
+            _ => format!("Error code: {}", code),
+        }
+    }
 }
 
 #[async_trait]
@@ -59,81 +103,247 @@ impl AdapterTrait for OkexAdapter {
         Ok(serde_json::to_vec(&msg)?)
     }
     
+    fn format_cancel_message(&self, request: &CancelRequest) -> Result<Vec<u8>, anyhow::Error> {
+        let msg = json!({
+            "id": request.request_id,
+            "op": "cancel-order",
+            "args": [{
+                "instId": request.symbol,
+                "clOrdId": request.client_order_id,
+            }]
+        });
+        
+        Ok(serde_json::to_vec(&msg)?)
+    }
+    
+    fn format_amend_message(&self, request: &AmendRequest) -> Result<Vec<u8>, anyhow::Error> {
+        if request.new_price.is_none() && request.new_quantity.is_none() {
+            return Err(anyhow::anyhow!("Amend without new price or quantity"));
+        }
+        
+        let mut args = json!({
+            "instId": request.symbol,
+            "clOrdId": request.client_order_id,
+        });
+        if let Some(price) = request.new_price {
+            args["newPx"] = json!(price.to_string());
+        }
+        if let Some(quantity) = request.new_quantity {
+            args["newSz"] = json!(quantity.to_string());
+        }
+        
+        let msg = json!({
+            "id": request.request_id,
+            "op": "amend-order",
+            "args": [args]
+        });
+        
+        Ok(serde_json::to_vec(&msg)?)
+    }
+    
+    fn sign_order(&self, signer: &Signer, params: &BTreeMap<String, String>, timestamp: i64) -> String {
+        let body = serde_json::to_string(params).unwrap_or_default();
+        signer.sign_okex(&timestamp.to_string(), "POST", "/api/v5/trade/order", &body)
+    }
+    
     fn parse_order_response(&self, data: &[u8]) -> Result<OrderResponse, anyhow::Error> {
+        self.parse_order_responses(data)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Response contains no orders"))
+    }
+    
+    fn parse_order_responses(&self, data: &[u8]) -> Result<Vec<OrderResponse>, anyhow::Error> {
         let json: Value = serde_json::from_slice(data)?;
         
-        // Check for error response
-        if json.get("code").and_then(|v| v.as_str()) != Some("0") {
-            let error_msg = json.get("msg")
-                .and_then(|v| v.as_str())
-                .unwrap_or("Unknown error")
-                .to_string();
-            
-            return Ok(OrderResponse {
-                order_id: String::new(),
-                client_order_id: String::new(),
-                symbol: String::new(),
-                status: OrderStatus::Rejected,
-                executed_qty: Decimal::ZERO,
-                executed_price: None,
-                timestamp: chrono::Utc::now().timestamp_millis(),
-                error: Some(error_msg),
-            });
+        let code = json.get("code")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Missing code in OKEx response"))?;
+        
+        // Place requests reuse the client order id as the request id
+        let request_id = str_field(&json, "id");
+        
+        let entries = json.get("data")
+            .and_then(|v| v.as_array())
+            .map(|v| v.as_slice())
+            .unwrap_or_default();
+        
+        if entries.is_empty() {
+            if code == "0" {
+                return Err(anyhow::anyhow!("OKEx response without order data"));
+            }
+            // Envelope-level failure (auth, rate limit, malformed request)
+            let msg = str_field(&json, "msg");
+            return Ok(vec![rejected_response(request_id, String::new(), okex_error(code, &msg))]);
         }
         
-        // Parse successful response
-        let data = json.get("data")
-            .and_then(|v| v.get(0))
-            .ok_or_else(|| anyhow::anyhow!("Invalid response format"))?;
+        let single = entries.len() == 1;
+        let responses = entries.iter()
+            .map(|entry| {
+                let mut client_order_id = str_field(entry, "clOrdId");
+                if client_order_id.is_empty() && single {
+                    client_order_id = request_id.clone();
+                }
+                
+                let timestamp = entry.get("ts")
+                    .and_then(|v| v.as_str())
+                    .and_then(|s| s.parse::<i64>().ok())
+                    .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+                
+                // Per-order result; "0" means accepted even when the envelope code is 1 or 2
+                let s_code = entry.get("sCode").and_then(|v| v.as_str()).unwrap_or(code);
+                if s_code != "0" {
+                    let s_msg = str_field(entry, "sMsg");
+                    let mut response = rejected_response(client_order_id, String::new(), okex_error(s_code, &s_msg));
+                    response.timestamp = timestamp;
+                    return response;
+                }
+                
+                OrderResponse {
+                    order_id: str_field(entry, "ordId"),
+                    client_order_id,
+                    symbol: str_field(entry, "instId"),
+                    status: OrderStatus::New,
+                    executed_qty: Decimal::ZERO,
+                    executed_price: None,
+                    timestamp,
+                    error: None,
+                }
+            })
+            .collect::<Vec<_>>();
         
-        let order_id = data.get("ordId")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
+        if code != "0" {
+            let failed = responses.iter().filter(|r| r.error.is_some()).count();
+            warn!("OKEx order request {} code {}: {}/{} orders rejected", request_id, code, failed, responses.len());
+        }
         
-        let client_order_id = data.get("clOrdId")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        
-        Ok(OrderResponse {
-            order_id,
-            client_order_id,
-            symbol: String::new(),
-            status: OrderStatus::New,
-            executed_qty: Decimal::ZERO,
-            executed_price: None,
-            timestamp: chrono::Utc::now().timestamp_millis(),
-            error: None,
-        })
+        Ok(responses)
     }
-    
+
     fn parse_market_data(&self, data: &[u8]) -> Result<Value, anyhow::Error> {
         Ok(serde_json::from_slice(data)?)
     }
     
     fn map_error_code(&self, code: i32) -> String {
-        match code {
-            1 => "Operation failed".to_string(),
-            50000 => "General error".to_string(),
-            50001 => "Service temporarily unavailable".to_string(),
-            50002 => "Service busy".to_string(),
-            50004 => "Request timeout".to_string(),
-            50005 => "Too many requests".to_string(),
-            50006 => "Invalid request".to_string(),
-            50007 => "Invalid API key".to_string(),
-            50008 => "Invalid signature".to_string(),
-            51000 => "Invalid instrument".to_string(),
-            51001 => "Instrument does not exist".to_string(),
-            51006 => "Invalid order price".to_string(),
-            51008 => "Order amount exceeds limit".to_string(),
-            51009 => "Order placement failed".to_string(),
-            51010 => "Insufficient balance".to_string(),
-            _ => format!("Error code: {}", code),
-        }
+        Self::describe_error(code)
     }
     
     fn get_rate_limits(&self) -> HashMap<String, u32> {
         self.rate_limits.clone()
     }
-}
\ No newline at end of file
+    
+    fn user_data(&self) -> Option<&dyn UserDataAdapter> {
+        Some(self)
+    }
+}
+
+impl UserDataAdapter for OkexAdapter {
+    fn user_data_url(&self, _market_type: &str) -> String {
+        "wss://ws.okx.com:8443/ws/v5/private".to_string()
+    }
+    
+    fn login_message(&self, signer: &Signer, timestamp_ms: i64) -> Option<String> {
+        // Login timestamps are in seconds
+        let timestamp = (timestamp_ms / 1000).to_string();
+        let msg = json!({
+            "op": "login",
+            "args": [{
+                "apiKey": signer.api_key(),
+                "passphrase": signer.passphrase().unwrap_or_default(),
+                "timestamp": timestamp,
+                "sign": signer.sign_okex(&timestamp, "GET", "/users/self/verify", ""),
+            }]
+        });
+        Some(msg.to_string())
+    }
+    
+    fn subscribe_messages(&self, market_type: &str) -> Vec<String> {
+        let inst_type = match market_type {
+            "spot" => "SPOT",
+            _ => "SWAP",
+        };
+        vec![json!({
+            "op": "subscribe",
+            "args": [{
+                "channel": "orders",
+                "instType": inst_type,
+            }]
+        }).to_string()]
+    }
+    
+    fn heartbeat(&self) -> Option<(Duration, String)> {
+        // The server closes connections idle for 30 seconds
+        Some((Duration::from_secs(25), "ping".to_string()))
+    }
+    
+    fn parse_user_data(&self, data: &[u8]) -> Result<Vec<UserDataEvent>, anyhow::Error> {
+        if data == b"pong" {
+            return Ok(Vec::new());
+        }
+        
+        let json: Value = serde_json::from_slice(data)?;
+        
+        match json.get("event").and_then(|v| v.as_str()) {
+            Some("login") if json.get("code").and_then(|v| v.as_str()) == Some("0") => {
+                return Ok(vec![UserDataEvent::Authenticated]);
+            }
+            Some("login") | Some("error") => {
+                let code = str_field(&json, "code");
+                return Ok(vec![UserDataEvent::AuthFailed(okex_error(&code, &str_field(&json, "msg")))]);
+            }
+            Some(_) => return Ok(Vec::new()),
+            None => {}
+        }
+        
+        if json.pointer("/arg/channel").and_then(|v| v.as_str()) != Some("orders") {
+            return Ok(Vec::new());
+        }
+        
+        let entries = json.get("data")
+            .and_then(|v| v.as_array())
+            .map(|v| v.as_slice())
+            .unwrap_or_default();
+        
+        let events = entries.iter()
+            .filter_map(|entry| {
+                let last_filled_qty = decimal_field(entry, "fillSz");
+                let state = entry.get("state").and_then(|v| v.as_str())?;
+                // Amend results are pushed with the order's current state; a failed
+                // amend ("-1") leaves the order unchanged, "1" means it was cancelled
+                let kind = match (str_field(entry, "amendResult").as_str(), state) {
+                    ("0", _) => UpdateKind::Amended,
+                    ("-1", _) => return None,
+                    (_, "live") => UpdateKind::New,
+                    (_, "partially_filled" | "filled") if !last_filled_qty.is_zero() => UpdateKind::Trade,
+                    (_, "canceled" | "mmp_canceled") => UpdateKind::Cancelled,
+                    _ => return None,
+                };
+                
+                Some(UserDataEvent::Order(OrderUpdate {
+                    symbol: str_field(entry, "instId"),
+                    order_id: str_field(entry, "ordId"),
+                    client_order_id: str_field(entry, "clOrdId"),
+                    side: parse_side(entry.get("side").and_then(|v| v.as_str())?)?,
+                    order_type: parse_order_type(&str_field(entry, "ordType")),
+                    kind,
+                    price: decimal_field(entry, "px"),
+                    quantity: decimal_field(entry, "sz"),
+                    last_filled_qty,
+                    last_filled_price: decimal_field(entry, "fillPx"),
+                    cumulative_filled_qty: decimal_field(entry, "accFillSz"),
+                    timestamp: int_field(entry, "uTime")
+                        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis()),
+                }))
+            })
+            .collect();
+        
+        Ok(events)
+    }
+}
+
+fn okex_error(code: &str, detail: &str) -> String {
+    let description = code.parse::<i32>()
+        .map(OkexAdapter::describe_error)
+        .unwrap_or_else(|_| format!("Error code: {}", code));
+    format_error(code, &description, detail)
+}
@@ -0,0 +1,150 @@
+use crate::executor::signer::Signer;
+use crate::executor::types::{OrderSide, OrderType};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// What happened to an order, as pushed on a private stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateKind {
+    New,
+    /// Price or quantity changed on the venue; carries the amended values
+    Amended,
+    Trade,
+    Cancelled,
+    Rejected,
+    Expired,
+}
+
+/// Exchange-native order update, before symbol ids are resolved
+#[derive(Debug, Clone)]
+pub struct OrderUpdate {
+    pub symbol: String,
+    pub order_id: String,
+    pub client_order_id: String,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub kind: UpdateKind,
+    pub price: Decimal,
+    pub quantity: Decimal,
+    pub last_filled_qty: Decimal,
+    pub last_filled_price: Decimal,
+    pub cumulative_filled_qty: Decimal,
+    pub timestamp: i64,
+}
+
+#[derive(Debug, Clone)]
+pub enum UserDataEvent {
+    /// Binance: key returned by `userDataStream.start`
+    ListenKey(String),
+    /// Login/auth ack; channels may be subscribed from here on
+    Authenticated,
+    AuthFailed(String),
+    Order(OrderUpdate),
+}
+
+/// Open orders seen on one private stream.
+///
+/// Bybit and Bitget push an amended order with the same status as a fresh
+/// ack; a `New` update for a known order whose price or quantity moved is an
+/// amend, and one that changed nothing is a repeated ack.
+#[derive(Debug, Default)]
+pub struct OpenOrders {
+    orders: HashMap<String, (Decimal, Decimal)>,
+}
+
+impl OpenOrders {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reclassifies amend pushes in place; returns false for updates to drop
+    pub fn track(&mut self, update: &mut OrderUpdate) -> bool {
+        let order = (update.price, update.quantity);
+        match update.kind {
+            UpdateKind::New => match self.orders.insert(update.order_id.clone(), order) {
+                Some(known) if known == order => return false,
+                Some(_) => update.kind = UpdateKind::Amended,
+                None => {}
+            },
+            UpdateKind::Amended => {
+                self.orders.insert(update.order_id.clone(), order);
+            }
+            UpdateKind::Trade if update.cumulative_filled_qty < update.quantity => {}
+            UpdateKind::Trade | UpdateKind::Cancelled | UpdateKind::Rejected | UpdateKind::Expired => {
+                self.orders.remove(&update.order_id);
+            }
+        }
+        true
+    }
+}
+
+/// Authenticated user-data stream protocol of one venue.
+///
+/// A session either fetches a listen key first (Binance) or logs in on the
+/// private endpoint and waits for `Authenticated` before subscribing.
+pub trait UserDataAdapter: Send + Sync {
+    /// Default private endpoint; a configured `user_data_endpoint` overrides it
+    fn user_data_url(&self, market_type: &str) -> String;
+
+    /// WS API endpoint and request that yield a `ListenKey`; the stream URL is
+    /// then `{user_data_url}/{listen_key}`
+    fn listen_key_request(&self, _signer: &Signer, _market_type: &str) -> Option<(String, String)> {
+        None
+    }
+
+    /// How often the listen key request is repeated to keep the key alive
+    fn listen_key_keepalive(&self) -> Option<Duration> {
+        None
+    }
+
+    fn login_message(&self, _signer: &Signer, _timestamp_ms: i64) -> Option<String> {
+        None
+    }
+
+    fn subscribe_messages(&self, market_type: &str) -> Vec<String>;
+
+    /// Text heartbeat and its interval, for venues that drop silent clients
+    fn heartbeat(&self) -> Option<(Duration, String)> {
+        None
+    }
+
+    /// Heartbeat replies and subscription acks parse to an empty list
+    fn parse_user_data(&self, data: &[u8]) -> Result<Vec<UserDataEvent>, anyhow::Error>;
+}
+
+pub(crate) fn decimal_field(value: &serde_json::Value, key: &str) -> Decimal {
+    value.get(key)
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse::<Decimal>().ok())
+        .unwrap_or(Decimal::ZERO)
+}
+
+pub(crate) fn int_field(value: &serde_json::Value, key: &str) -> Option<i64> {
+    match value.get(key)? {
+        serde_json::Value::Number(n) => n.as_i64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Accepts `BUY`, `Buy` and `buy`
+pub(crate) fn parse_side(side: &str) -> Option<OrderSide> {
+    if side.eq_ignore_ascii_case("buy") {
+        Some(OrderSide::Buy)
+    } else if side.eq_ignore_ascii_case("sell") {
+        Some(OrderSide::Sell)
+    } else {
+        None
+    }
+}
+
+/// Anything that is not a market or stop order rests on the book as a limit
+pub(crate) fn parse_order_type(order_type: &str) -> OrderType {
+    match order_type.to_ascii_lowercase().as_str() {
+        "market" => OrderType::Market,
+        "stop_market" | "take_profit_market" => OrderType::StopMarket,
+        "stop" | "stop_limit" | "take_profit" => OrderType::StopLimit,
+        _ => OrderType::Limit,
+    }
+}
@@ -8,6 +8,8 @@ pub struct TradingEngineConfig {
     pub ws_pool: WsPoolConfig,
     pub executor: ExecutorConfig,
     pub ipc: IpcConfig,
+    #[serde(default)]
+    pub user_data: UserDataConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,6 +19,9 @@ pub struct ExchangeConfig {
     pub futures: ExchangeEndpointConfig,
     pub api_key: String,
     pub secret_key: String,
+    /// Required by Bitget, unused by the other venues
+    #[serde(default)]
+    pub passphrase: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,14 +30,24 @@ pub struct ExchangeEndpointConfig {
     pub ws_endpoints: Vec<String>,
     pub rest_endpoint: String,
     pub connection_count: usize,
+    /// Private stream endpoint; defaults to the adapter's
+    #[serde(default)]
+    pub user_data_endpoint: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WsPoolConfig {
     pub max_connections_per_exchange: usize,
     pub heartbeat_interval_ms: u64,
+    /// First reconnect delay; doubles per failed attempt
     pub reconnect_delay_ms: u64,
+    #[serde(default = "default_max_reconnect_delay_ms")]
+    pub max_reconnect_delay_ms: u64,
+    /// Consecutive failed attempts before a connection is dropped; 0 retries forever
     pub max_reconnect_attempts: usize,
+    /// Period of the staggered restart of every connection; 0 disables it
+    #[serde(default)]
+    pub rolling_restart_interval_ms: u64,
     pub health_check_interval_ms: u64,
     pub message_buffer_size: usize,
 }
@@ -51,6 +66,44 @@ pub struct IpcConfig {
     pub input_topic: String,
     pub output_topic: String,
     pub buffer_size: usize,
+    /// Execution reports from the user-data streams
+    #[serde(default = "default_report_topic")]
+    pub report_topic: String,
+    /// How the command receiver waits for new samples
+    #[serde(default)]
+    pub wakeup: common::wakeup::WakeupConfig,
+    /// Commands executing at once; the command loop stops reading past this
+    #[serde(default = "default_max_in_flight")]
+    pub max_in_flight: usize,
+}
+
+fn default_max_reconnect_delay_ms() -> u64 {
+    30_000
+}
+
+fn default_max_in_flight() -> usize {
+    256
+}
+
+fn default_report_topic() -> String {
+    common::ipc::IPC_SERVICE_EXECUTION.to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserDataConfig {
+    pub enabled: bool,
+    /// Directory holding exchanges.toml and the symbol lists used to resolve symbol ids.
+    /// Read even with the streams disabled: the symbol lists carry the order filters
+    pub market_config_dir: String,
+}
+
+impl Default for UserDataConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            market_config_dir: "config".to_string(),
+        }
+    }
 }
 
 impl TradingEngineConfig {
@@ -1,6 +1,7 @@
 use super::{
     idempotent::IdempotentManager,
     order_builder::OrderBuilder,
+    pending::PendingRequests,
     response_handler::ResponseHandler,
     signer::Signer,
     types::*,
@@ -8,6 +9,8 @@ use super::{
 use crate::config::ExecutorConfig;
 use crate::health::{ConnectionSelector, HealthTracker};
 use crate::ws_pool::WsPool;
+use common::instrument::{InstrumentRegistry, MarketType};
+use common::types::Exchange;
 use futures::future::join_all;
 use std::sync::Arc;
 use std::time::Duration;
@@ -23,6 +26,9 @@ pub struct OrderExecutor {
     connection_selector: Arc<ConnectionSelector>,
     idempotent_manager: Arc<IdempotentManager>,
     signers: Arc<dashmap::DashMap<String, Signer>>,
+    pending: Arc<PendingRequests>,
+    /// Order filters of known instruments; orders for others are sent as given
+    instruments: Arc<InstrumentRegistry>,
 }
 
 impl OrderExecutor {
@@ -33,6 +39,7 @@ impl OrderExecutor {
         connection_selector: Arc<ConnectionSelector>,
     ) -> Self {
         let idempotent_manager = Arc::new(IdempotentManager::new(config.idempotent_key_prefix.clone()));
+        let pending = ws_pool.pending_requests();
         
         Self {
             config,
@@ -41,54 +48,128 @@ impl OrderExecutor {
             connection_selector,
             idempotent_manager,
             signers: Arc::new(dashmap::DashMap::new()),
+            pending,
+            instruments: Arc::new(InstrumentRegistry::new()),
         }
     }
 
-    pub fn register_signer(&self, exchange: String, api_key: String, secret_key: String) {
-        let signer = Signer::new(api_key, secret_key);
+    /// Round and check placed orders against the filters of these instruments
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentRegistry>) -> Self {
+        self.instruments = instruments;
+        self
+    }
+
+    pub fn register_signer(
+        &self,
+        exchange: String,
+        api_key: String,
+        secret_key: String,
+        passphrase: Option<String>,
+    ) {
+        let signer = match passphrase {
+            Some(passphrase) => Signer::with_passphrase(api_key, secret_key, passphrase),
+            None => Signer::new(api_key, secret_key),
+        };
         self.signers.insert(exchange, signer);
     }
 
     pub async fn execute(&self, command: ExecutionCommand) -> ExecutionResult {
-        info!("Executing order command: {:?}", command.id);
-        
-        // Generate idempotent client order ID
-        let client_order_id = if let Some(ref id) = command.client_order_id {
-            id.clone()
-        } else {
-            self.idempotent_manager.generate_client_order_id(command.id)
-        };
+        info!("Executing {:?} command: {:?}", command.action, command.id);
 
-        // Check for duplicate
-        if self.idempotent_manager.is_duplicate(&client_order_id) {
-            warn!("Duplicate order detected: {}", client_order_id);
-            return ExecutionResult {
-                command_id: command.id,
-                success: false,
-                responses: vec![],
-                selected_response: None,
-                error: Some("Duplicate order".to_string()),
-            };
-        }
+        let adapters = self.pending.adapters().clone();
+        let adapter = match adapters.get_adapter(&command.exchange) {
+            Some(adapter) => adapter,
+            None => {
+                error!("No adapter registered for exchange: {}", command.exchange);
+                return ExecutionResult::failed(command.id, format!("Unsupported exchange {}", command.exchange));
+            }
+        };
 
         // Get signer for exchange
         let signer = match self.signers.get(&command.exchange) {
             Some(s) => s.value().clone(),
             None => {
                 error!("No signer configured for exchange: {}", command.exchange);
-                return ExecutionResult {
-                    command_id: command.id,
-                    success: false,
-                    responses: vec![],
-                    selected_response: None,
-                    error: Some(format!("No signer for {}", command.exchange)),
+                return ExecutionResult::failed(command.id, format!("No signer for {}", command.exchange));
+            }
+        };
+
+        // Build the request and the id its ack will be correlated by
+        let instrument = self.instruments.get(command.instrument).or_else(|| {
+            let exchange = Exchange::from_key(&command.exchange)?;
+            let market_type: MarketType = command.market_type.parse().ok()?;
+            let id = self.instruments.resolve(exchange, market_type, &command.symbol)?;
+            self.instruments.get(id)
+        });
+        let order_builder = OrderBuilder::new(adapter).with_instrument(instrument);
+        let (message, correlation_id) = match &command.action {
+            CommandAction::Place => {
+                // Generate idempotent client order ID
+                let client_order_id = if let Some(ref id) = command.client_order_id {
+                    id.clone()
+                } else {
+                    self.idempotent_manager.generate_client_order_id(command.id)
+                };
+
+                // Check for duplicate
+                if self.idempotent_manager.is_duplicate(&client_order_id) {
+                    warn!("Duplicate order detected: {}", client_order_id);
+                    return ExecutionResult::failed(command.id, "Duplicate order");
+                }
+
+                let request = match order_builder.build_order_request(&command, client_order_id.clone(), &signer) {
+                    Ok(request) => request,
+                    Err(e) => {
+                        warn!("Rejected order {} for {}: {}", client_order_id, command.symbol, e);
+                        return ExecutionResult::failed(command.id, e.to_string());
+                    }
+                };
+                (adapter.format_order_message(&request), client_order_id)
+            }
+            // Cancels and amends get their own request id so their acks cannot
+            // resolve the waiter of another request for the same order
+            CommandAction::Cancel { orig_client_order_id } => {
+                let request_id = self.idempotent_manager.generate_client_order_id(command.id);
+                let request = order_builder.build_cancel_request(
+                    &command,
+                    orig_client_order_id,
+                    request_id.clone(),
+                    &signer,
+                );
+                (adapter.format_cancel_message(&request), request_id)
+            }
+            CommandAction::CancelAll => {
+                let request_id = self.idempotent_manager.generate_client_order_id(command.id);
+                let request = order_builder.build_cancel_all_request(&command, request_id.clone(), &signer);
+                (adapter.format_cancel_all_message(&request), request_id)
+            }
+            CommandAction::Amend { orig_client_order_id, new_price, new_quantity } => {
+                let request_id = self.idempotent_manager.generate_client_order_id(command.id);
+                let request = match order_builder.build_amend_request(
+                    &command,
+                    orig_client_order_id,
+                    request_id.clone(),
+                    *new_price,
+                    *new_quantity,
+                    &signer,
+                ) {
+                    Ok(request) => request,
+                    Err(e) => {
+                        warn!("Rejected amend of {} for {}: {}", orig_client_order_id, command.symbol, e);
+                        return ExecutionResult::failed(command.id, e.to_string());
+                    }
                 };
+                (adapter.format_amend_message(&request), request_id)
             }
         };
 
-        // Build order request
-        let order_builder = OrderBuilder::new(command.exchange.clone());
-        let order_request = order_builder.build_order_request(&command, client_order_id.clone(), &signer);
+        let message = match message {
+            Ok(message) => message,
+            Err(e) => {
+                error!("Failed to format {:?} request for {}: {}", command.action, command.exchange, e);
+                return ExecutionResult::failed(command.id, e.to_string());
+            }
+        };
 
         // Select healthy connections
         let connection_ids = self.connection_selector.select_connections(
@@ -99,100 +180,113 @@ impl OrderExecutor {
 
         if connection_ids.is_empty() {
             error!("No healthy connections available for {} {}", command.exchange, command.market_type);
-            return ExecutionResult {
-                command_id: command.id,
-                success: false,
-                responses: vec![],
-                selected_response: None,
-                error: Some("No healthy connections".to_string()),
-            };
+            return ExecutionResult::failed(command.id, "No healthy connections");
         }
 
-        // Send order to multiple connections concurrently
-        let responses = self.send_concurrent(
+        // Send to multiple connections concurrently
+        let (mut responses, unacked) = self.send_concurrent(
             connection_ids,
-            order_request,
-            command.exchange.clone(),
+            message,
+            correlation_id.clone(),
         ).await;
 
+        // Cancel acks report the order as it was before the cancel on some venues
+        if matches!(command.action, CommandAction::Cancel { .. } | CommandAction::CancelAll) {
+            for response in responses.iter_mut().filter(|r| r.error.is_none()) {
+                response.status = OrderStatus::Canceled;
+            }
+        }
+
         // Handle responses
-        let response_handler = ResponseHandler::new(command.exchange.clone());
+        let response_handler = ResponseHandler::new(adapter);
         let selected_response = response_handler.select_best_response(responses.clone());
 
         let success = selected_response.as_ref()
-            .map(|r| matches!(r.status, OrderStatus::New | OrderStatus::PartiallyFilled | OrderStatus::Filled))
+            .map(|r| match command.action {
+                CommandAction::Place | CommandAction::Amend { .. } => {
+                    matches!(r.status, OrderStatus::New | OrderStatus::PartiallyFilled | OrderStatus::Filled)
+                }
+                CommandAction::Cancel { .. } | CommandAction::CancelAll => {
+                    matches!(r.status, OrderStatus::Canceled)
+                }
+            })
             .unwrap_or(false);
 
+        // Only a placed order burns its id, so a failed attempt can be retried as is
+        if success && command.action == CommandAction::Place {
+            self.idempotent_manager.record(&correlation_id);
+        }
+
+        // A request that went out and was never acked may still have reached the
+        // venue; only a venue response makes the failure definite
+        let outcome_unknown = !success && unacked && responses.is_empty();
+
+        let error = if success {
+            None
+        } else if outcome_unknown {
+            Some("No ack from exchange, outcome unknown".to_string())
+        } else {
+            // Surface the venue's reason when every connection rejected the request
+            let reason = responses.iter().find_map(|r| r.error.clone());
+            Some(reason.unwrap_or_else(|| "Order execution failed".to_string()))
+        };
+
         ExecutionResult {
             command_id: command.id,
             success,
+            outcome_unknown,
             responses,
             selected_response,
-            error: if !success { Some("Order execution failed".to_string()) } else { None },
+            error,
         }
     }
 
+    /// Write `message` to every connection and wait for the ack echoing `correlation_id`;
+    /// also reports whether a request was written on some connection but never acked
     async fn send_concurrent(
         &self,
         connection_ids: Vec<Uuid>,
-        order_request: OrderRequest,
-        exchange: String,
-    ) -> Vec<OrderResponse> {
-        let request_json = match serde_json::to_vec(&order_request) {
-            Ok(json) => json,
-            Err(e) => {
-                error!("Failed to serialize order request: {}", e);
-                return vec![];
-            }
-        };
-
+        message: Vec<u8>,
+        correlation_id: String,
+    ) -> (Vec<OrderResponse>, bool) {
         let mut futures = vec![];
         
-        let client_order_id = order_request.client_order_id.clone();
-        let symbol = order_request.symbol.clone();
-        
         for conn_id in connection_ids {
             let pool = self.ws_pool.clone();
             let health_tracker = self.health_tracker.clone();
-            let request = request_json.clone();
-            let exchange = exchange.clone();
+            let pending = self.pending.clone();
+            let message = message.clone();
             let timeout_ms = self.config.order_timeout_ms;
-            let client_order_id = client_order_id.clone();
-            let symbol = symbol.clone();
+            let request_id = correlation_id.clone();
             
             let future = async move {
-                let start = std::time::Instant::now();
+                // Register before sending so an ack racing the send is not lost
+                let ack_rx = pending.register(conn_id, &request_id);
+
+                if let Err(e) = pool.send_to_connection(conn_id, message).await {
+                    error!("Failed to send order to connection {}: {}", conn_id, e);
+                    pending.cancel(conn_id, &request_id);
+                    health_tracker.update_failure(conn_id);
+                    return Err(false);
+                }
                 
-                match timeout(
-                    Duration::from_millis(timeout_ms),
-                    pool.send_to_connection(conn_id, request),
-                ).await {
-                    Ok(Ok(_)) => {
-                        let rtt_ms = start.elapsed().as_secs_f64() * 1000.0;
+                match timeout(Duration::from_millis(timeout_ms), ack_rx).await {
+                    Ok(Ok(ack)) => {
+                        let rtt_ms = ack.rtt.as_secs_f64() * 1000.0;
+                        debug!("Ack for {} on connection {} after {:.3}ms", request_id, conn_id, rtt_ms);
                         health_tracker.update_success(conn_id, rtt_ms);
-                        
-                        // TODO: Wait for and parse actual response
-                        // For now, return a placeholder
-                        Some(OrderResponse {
-                            order_id: Uuid::new_v4().to_string(),
-                            client_order_id,
-                            symbol,
-                            status: OrderStatus::New,
-                            executed_qty: rust_decimal::Decimal::ZERO,
-                            executed_price: None,
-                            timestamp: chrono::Utc::now().timestamp_millis(),
-                            error: None,
-                        })
+                        Ok(ack.response)
                     }
-                    Ok(Err(e)) => {
-                        error!("Failed to send order to connection {}: {}", conn_id, e);
+                    Ok(Err(_)) => {
+                        warn!("Connection {} dropped before acking {}", conn_id, request_id);
                         health_tracker.update_failure(conn_id);
-                        None
+                        Err(true)
                     }
                     Err(_) => {
                         error!("Order timeout for connection {}", conn_id);
+                        pending.cancel(conn_id, &request_id);
                         health_tracker.update_failure(conn_id);
-                        None
+                        Err(true)
                     }
                 }
             };
@@ -200,8 +294,10 @@ impl OrderExecutor {
             futures.push(future);
         }
 
+        // Err carries whether the request was written before the ack went missing
         let results = join_all(futures).await;
-        results.into_iter().flatten().collect()
+        let unacked = results.iter().any(|result| matches!(result, Err(true)));
+        (results.into_iter().flatten().collect(), unacked)
     }
 
     pub async fn retry_execution(&self, command: ExecutionCommand) -> ExecutionResult {
@@ -212,7 +308,8 @@ impl OrderExecutor {
             
             let result = self.execute(command.clone()).await;
             
-            if result.success {
+            // Resending an order the venue may already hold could duplicate it
+            if result.success || result.outcome_unknown {
                 return result;
             }
             
@@ -223,12 +320,6 @@ impl OrderExecutor {
             }
         }
         
-        last_result.unwrap_or_else(|| ExecutionResult {
-            command_id: command.id,
-            success: false,
-            responses: vec![],
-            selected_response: None,
-            error: Some("Max retry attempts exceeded".to_string()),
-        })
+        last_result.unwrap_or_else(|| ExecutionResult::failed(command.id, "Max retry attempts exceeded"))
     }
 }
\ No newline at end of file
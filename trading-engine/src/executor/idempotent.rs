@@ -18,9 +18,12 @@ impl IdempotentManager {
 
     pub fn generate_client_order_id(&self, command_id: Uuid) -> String {
         let timestamp = Utc::now().timestamp_millis();
-        let id = format!("{}_{}_{}", self.prefix, command_id, timestamp);
-        self.used_ids.insert(id.clone(), timestamp);
-        id
+        format!("{}_{}_{}", self.prefix, command_id, timestamp)
+    }
+
+    /// Mark an id as placed; later commands reusing it are rejected as duplicates
+    pub fn record(&self, client_order_id: &str) {
+        self.used_ids.insert(client_order_id.to_string(), Utc::now().timestamp_millis());
     }
 
     pub fn is_duplicate(&self, client_order_id: &str) -> bool {
@@ -1,5 +1,7 @@
 pub mod executor;
 pub mod order_builder;
+pub mod pending;
+pub mod reports;
 pub mod signer;
 pub mod idempotent;
 pub mod response_handler;
@@ -7,6 +9,8 @@ pub mod types;
 
 pub use executor::OrderExecutor;
 pub use order_builder::OrderBuilder;
+pub use pending::{OrderAck, PendingRequests};
+pub use reports::ReportNormalizer;
 pub use signer::Signer;
 pub use idempotent::IdempotentManager;
 pub use response_handler::ResponseHandler;
@@ -1,59 +1,182 @@
 use super::types::*;
 use super::signer::Signer;
+use crate::adapters::AdapterTrait;
+use anyhow::anyhow;
 use chrono::Utc;
+use common::instrument::Instrument;
+use common::types::Side;
 use rust_decimal::Decimal;
 use std::collections::BTreeMap;
 
-pub struct OrderBuilder {
-    exchange: String,
+pub struct OrderBuilder<'a> {
+    adapter: &'a dyn AdapterTrait,
+    instrument: Option<&'a Instrument>,
 }
 
-impl OrderBuilder {
-    pub fn new(exchange: String) -> Self {
-        Self { exchange }
+impl<'a> OrderBuilder<'a> {
+    pub fn new(adapter: &'a dyn AdapterTrait) -> Self {
+        Self { adapter, instrument: None }
     }
 
+    /// Round placed orders to this instrument's filters; without one they are sent as given
+    pub fn with_instrument(mut self, instrument: Option<&'a Instrument>) -> Self {
+        self.instrument = instrument;
+        self
+    }
+
+    /// Fails when the instrument is not trading or the rounded order is below
+    /// its minimum quantity or notional
     pub fn build_order_request(
         &self,
         command: &ExecutionCommand,
         client_order_id: String,
         signer: &Signer,
-    ) -> OrderRequest {
+    ) -> anyhow::Result<OrderRequest> {
+        let (price, quantity) = self.normalize(command)?;
         let timestamp = Utc::now().timestamp_millis();
         
-        let mut params = self.build_params(command, &client_order_id, timestamp);
-        
-        let signature = match self.exchange.as_str() {
-            "binance" => signer.sign_binance(&params),
-            "okex" => {
-                let body = serde_json::to_string(&params).unwrap_or_default();
-                signer.sign_okex(
-                    &timestamp.to_string(),
-                    "POST",
-                    "/api/v5/trade/order",
-                    &body,
-                )
-            }
-            "bybit" => signer.sign_bybit(&params),
-            _ => String::new(),
-        };
+        let params = self.build_params(command, price, quantity, &client_order_id, timestamp);
+        let signature = self.adapter.sign_order(signer, &params, timestamp);
 
-        OrderRequest {
+        Ok(OrderRequest {
             symbol: command.symbol.clone(),
+            market_type: command.market_type.clone(),
             side: command.side,
             order_type: command.order_type,
-            quantity: command.quantity,
-            price: command.price,
+            quantity,
+            price,
             time_in_force: command.time_in_force,
+            reduce_only: command.reduce_only,
             client_order_id,
             timestamp,
+            api_key: signer.api_key().to_string(),
+            signature,
+        })
+    }
+
+    fn normalize(&self, command: &ExecutionCommand) -> anyhow::Result<(Option<Decimal>, Decimal)> {
+        self.normalize_order(command.side, command.price, command.quantity)
+    }
+
+    fn normalize_order(
+        &self,
+        side: OrderSide,
+        price: Option<Decimal>,
+        quantity: Decimal,
+    ) -> anyhow::Result<(Option<Decimal>, Decimal)> {
+        let Some(instrument) = self.instrument else {
+            return Ok((price, quantity));
+        };
+
+        let side = match side {
+            OrderSide::Buy => Side::Buy,
+            OrderSide::Sell => Side::Sell,
+        };
+        instrument
+            .normalize_order(side, price, quantity)
+            .map_err(|violation| anyhow!("{} {}", instrument, violation))
+    }
+
+    pub fn build_cancel_request(
+        &self,
+        command: &ExecutionCommand,
+        orig_client_order_id: &str,
+        request_id: String,
+        signer: &Signer,
+    ) -> CancelRequest {
+        let timestamp = Utc::now().timestamp_millis();
+        
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), command.symbol.clone());
+        params.insert("origClientOrderId".to_string(), orig_client_order_id.to_string());
+        params.insert("timestamp".to_string(), timestamp.to_string());
+        let signature = self.adapter.sign_order(signer, &params, timestamp);
+
+        CancelRequest {
+            symbol: command.symbol.clone(),
+            market_type: command.market_type.clone(),
+            client_order_id: orig_client_order_id.to_string(),
+            request_id,
+            timestamp,
+            api_key: signer.api_key().to_string(),
+            signature,
+        }
+    }
+
+    pub fn build_cancel_all_request(
+        &self,
+        command: &ExecutionCommand,
+        request_id: String,
+        signer: &Signer,
+    ) -> CancelAllRequest {
+        let timestamp = Utc::now().timestamp_millis();
+        
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), command.symbol.clone());
+        params.insert("timestamp".to_string(), timestamp.to_string());
+        let signature = self.adapter.sign_order(signer, &params, timestamp);
+
+        CancelAllRequest {
+            symbol: command.symbol.clone(),
+            request_id,
+            timestamp,
+            api_key: signer.api_key().to_string(),
             signature,
         }
     }
 
+    /// Rounds the new price and quantity like a placed order; fields left unchanged
+    /// are checked at the order's current values from the command. Fails when the
+    /// amended order would break the instrument's filters
+    pub fn build_amend_request(
+        &self,
+        command: &ExecutionCommand,
+        orig_client_order_id: &str,
+        request_id: String,
+        new_price: Option<Decimal>,
+        new_quantity: Option<Decimal>,
+        signer: &Signer,
+    ) -> anyhow::Result<AmendRequest> {
+        let (price, quantity) = self.normalize_order(
+            command.side,
+            new_price.or(command.price),
+            new_quantity.unwrap_or(command.quantity),
+        )?;
+        let new_price = new_price.and(price);
+        let new_quantity = new_quantity.map(|_| quantity);
+        let timestamp = Utc::now().timestamp_millis();
+        
+        let mut params = BTreeMap::new();
+        params.insert("symbol".to_string(), command.symbol.clone());
+        params.insert("side".to_string(), self.format_side(command.side));
+        params.insert("origClientOrderId".to_string(), orig_client_order_id.to_string());
+        if let Some(price) = new_price {
+            params.insert("price".to_string(), price.to_string());
+        }
+        if let Some(quantity) = new_quantity {
+            params.insert("quantity".to_string(), quantity.to_string());
+        }
+        params.insert("timestamp".to_string(), timestamp.to_string());
+        let signature = self.adapter.sign_order(signer, &params, timestamp);
+
+        Ok(AmendRequest {
+            symbol: command.symbol.clone(),
+            side: command.side,
+            client_order_id: orig_client_order_id.to_string(),
+            request_id,
+            new_price,
+            new_quantity,
+            timestamp,
+            api_key: signer.api_key().to_string(),
+            signature,
+        })
+    }
+
     fn build_params(
         &self,
         command: &ExecutionCommand,
+        price: Option<Decimal>,
+        quantity: Decimal,
         client_order_id: &str,
         timestamp: i64,
     ) -> BTreeMap<String, String> {
@@ -62,13 +185,16 @@ impl OrderBuilder {
         params.insert("symbol".to_string(), command.symbol.clone());
         params.insert("side".to_string(), self.format_side(command.side));
         params.insert("type".to_string(), self.format_order_type(command.order_type));
-        params.insert("quantity".to_string(), command.quantity.to_string());
+        params.insert("quantity".to_string(), quantity.to_string());
         
-        if let Some(price) = command.price {
+        if let Some(price) = price {
             params.insert("price".to_string(), price.to_string());
         }
         
-        params.insert("timeInForce".to_string(), self.format_time_in_force(command.time_in_force));
+        // Market orders take no time in force; post-only is expressed as GTX
+        if !matches!(command.order_type, OrderType::Market) {
+            params.insert("timeInForce".to_string(), self.format_time_in_force(command.time_in_force));
+        }
         params.insert("newClientOrderId".to_string(), client_order_id.to_string());
         params.insert("timestamp".to_string(), timestamp.to_string());
         
@@ -76,10 +202,6 @@ impl OrderBuilder {
             params.insert("reduceOnly".to_string(), "true".to_string());
         }
         
-        if command.post_only {
-            params.insert("postOnly".to_string(), "true".to_string());
-        }
-        
         params
     }
 
@@ -107,4 +229,112 @@ impl OrderBuilder {
             TimeInForce::GTX => "GTX".to_string(),
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::ExchangeAdapter;
+    use common::instrument::{InstrumentId, MarketType, SymbolFilters};
+    use common::types::Exchange;
+    use uuid::Uuid;
+
+    fn btc_futures() -> Instrument {
+        let mut instrument = Instrument::new(
+            InstrumentId::new(8, 1),
+            Exchange::Bitget,
+            MarketType::Futures,
+            "bitget-futures",
+            "BTCUSDT",
+        );
+        instrument.filters = SymbolFilters {
+            tick_size: Decimal::new(1, 1),
+            step_size: Decimal::new(1, 3),
+            min_qty: Decimal::new(1, 3),
+            min_notional: Decimal::from(5),
+            contract_value: Decimal::ONE,
+        };
+        instrument
+    }
+
+    fn place(side: OrderSide, price: Decimal, quantity: Decimal) -> ExecutionCommand {
+        ExecutionCommand {
+            id: Uuid::new_v4(),
+            instrument: InstrumentId::new(8, 1),
+            exchange: "bitget".to_string(),
+            market_type: "futures".to_string(),
+            symbol: "BTCUSDT".to_string(),
+            side,
+            order_type: OrderType::Limit,
+            quantity,
+            price: Some(price),
+            time_in_force: TimeInForce::GTC,
+            client_order_id: None,
+            reduce_only: false,
+            post_only: false,
+            action: CommandAction::Place,
+        }
+    }
+
+    #[test]
+    fn test_order_rounded_to_instrument_filters() {
+        let adapters = ExchangeAdapter::new();
+        let adapter = adapters.get_adapter("bitget").unwrap();
+        let signer = Signer::new("key".to_string(), "secret".to_string());
+        let instrument = btc_futures();
+        let builder = OrderBuilder::new(adapter).with_instrument(Some(&instrument));
+
+        let command = place(OrderSide::Sell, Decimal::new(6543217, 2), Decimal::new(12345, 4));
+        let request = builder.build_order_request(&command, "TE_1".to_string(), &signer).unwrap();
+        assert_eq!(request.price, Some(Decimal::new(654322, 1)));
+        assert_eq!(request.quantity, Decimal::new(1234, 3));
+
+        // 0.0001 BTC rounds down to nothing
+        let command = place(OrderSide::Buy, Decimal::from(65432), Decimal::new(1, 4));
+        let error = builder.build_order_request(&command, "TE_2".to_string(), &signer).unwrap_err();
+        assert!(error.to_string().contains("below minimum"), "{}", error);
+
+        // Unknown instruments are sent as given
+        let command = place(OrderSide::Buy, Decimal::new(6543217, 2), Decimal::new(1, 4));
+        let request = OrderBuilder::new(adapter)
+            .build_order_request(&command, "TE_3".to_string(), &signer)
+            .unwrap();
+        assert_eq!(request.price, command.price);
+        assert_eq!(request.quantity, command.quantity);
+    }
+
+    #[test]
+    fn test_amend_rounded_to_instrument_filters() {
+        let adapters = ExchangeAdapter::new();
+        let adapter = adapters.get_adapter("bitget").unwrap();
+        let signer = Signer::new("key".to_string(), "secret".to_string());
+        let instrument = btc_futures();
+        let builder = OrderBuilder::new(adapter).with_instrument(Some(&instrument));
+        let order = place(OrderSide::Buy, Decimal::from(65000), Decimal::new(1, 2));
+
+        let request = builder
+            .build_amend_request(
+                &order,
+                "TE_1",
+                "TE_2".to_string(),
+                Some(Decimal::new(6543217, 2)),
+                Some(Decimal::new(12345, 4)),
+                &signer,
+            )
+            .unwrap();
+        assert_eq!(request.new_price, Some(Decimal::new(654321, 1)));
+        assert_eq!(request.new_quantity, Some(Decimal::new(1234, 3)));
+
+        // Only the amended field is sent
+        let request = builder
+            .build_amend_request(&order, "TE_1", "TE_3".to_string(), None, Some(Decimal::new(25, 3)), &signer)
+            .unwrap();
+        assert_eq!(request.new_price, None);
+        assert_eq!(request.new_quantity, Some(Decimal::new(25, 3)));
+
+        // A price cut that leaves the current quantity below the minimum notional
+        let error = builder
+            .build_amend_request(&order, "TE_1", "TE_4".to_string(), Some(Decimal::from(100)), None, &signer)
+            .unwrap_err();
+        assert!(error.to_string().contains("below minimum"), "{}", error);
+    }
+}
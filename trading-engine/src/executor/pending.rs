@@ -0,0 +1,159 @@
+use super::types::OrderResponse;
+use crate::adapters::ExchangeAdapter;
+use dashmap::DashMap;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+use tracing::debug;
+use uuid::Uuid;
+
+/// Ack for an in-flight order, resolved by the connection that carried it.
+#[derive(Debug)]
+pub struct OrderAck {
+    pub response: OrderResponse,
+    /// Time between registering the request and the exchange ack arriving
+    pub rtt: Duration,
+}
+
+struct Waiter {
+    sent_at: Instant,
+    tx: oneshot::Sender<OrderAck>,
+}
+
+/// In-flight order requests, keyed per connection by the request id the venue echoes.
+///
+/// The executor registers a waiter before writing a request to a connection; the
+/// connection runner feeds every inbound frame to `resolve`, which matches the echoed
+/// id and parses the ack with the exchange adapter. Place requests are keyed by their
+/// client order id, cancels and amends by their own id, so requests for the same
+/// order never take each other's acks. Frames that do not match anything in flight
+/// are left for the regular message stream.
+pub struct PendingRequests {
+    adapters: Arc<ExchangeAdapter>,
+    inflight: DashMap<Uuid, HashMap<String, Waiter>>,
+}
+
+impl PendingRequests {
+    pub fn new(adapters: Arc<ExchangeAdapter>) -> Self {
+        Self {
+            adapters,
+            inflight: DashMap::new(),
+        }
+    }
+
+    pub fn adapters(&self) -> &Arc<ExchangeAdapter> {
+        &self.adapters
+    }
+
+    /// Register a waiter; call before the order is written so fast acks are not missed.
+    pub fn register(&self, connection_id: Uuid, request_id: &str) -> oneshot::Receiver<OrderAck> {
+        let (tx, rx) = oneshot::channel();
+        self.inflight.entry(connection_id).or_default().insert(
+            request_id.to_string(),
+            Waiter {
+                sent_at: Instant::now(),
+                tx,
+            },
+        );
+        rx
+    }
+
+    /// Drop a waiter that timed out or whose send failed.
+    pub fn cancel(&self, connection_id: Uuid, request_id: &str) {
+        if let Some(mut waiters) = self.inflight.get_mut(&connection_id) {
+            waiters.remove(request_id);
+        }
+    }
+
+    /// Fail every waiter on a connection that went away; receivers see a closed channel.
+    pub fn fail_connection(&self, connection_id: Uuid) {
+        if let Some((_, waiters)) = self.inflight.remove(&connection_id) {
+            if !waiters.is_empty() {
+                debug!("Dropping {} in-flight requests on connection {}", waiters.len(), connection_id);
+            }
+        }
+    }
+
+    pub fn inflight_count(&self, connection_id: Uuid) -> usize {
+        self.inflight
+            .get(&connection_id)
+            .map(|waiters| waiters.len())
+            .unwrap_or(0)
+    }
+
+    /// Try to resolve an in-flight request with an inbound frame.
+    /// Returns true when the frame was an ack for this connection and has been consumed.
+    pub fn resolve(&self, connection_id: Uuid, exchange: &str, data: &[u8]) -> bool {
+        // Fast path: most frames arrive while nothing is in flight
+        if self.inflight_count(connection_id) == 0 {
+            return false;
+        }
+
+        let Some(adapter) = self.adapters.get_adapter(exchange) else {
+            return false;
+        };
+
+        let Some(request_id) = adapter.ack_request_id(data) else {
+            return false;
+        };
+
+        let awaited = self.inflight
+            .get(&connection_id)
+            .map(|waiters| waiters.contains_key(&request_id))
+            .unwrap_or(false);
+        if !awaited {
+            return false;
+        }
+
+        // Every request carries a single order, so its ack carries a single response
+        let response = match adapter.parse_order_response(data) {
+            Ok(response) => response,
+            Err(_) => return false,
+        };
+
+        let Some(waiter) = self.inflight
+            .get_mut(&connection_id)
+            .and_then(|mut waiters| waiters.remove(&request_id))
+        else {
+            return false;
+        };
+
+        let rtt = waiter.sent_at.elapsed();
+        // The executor may have timed out already; the ack is still consumed
+        let _ = waiter.tx.send(OrderAck { response, rtt });
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CANCEL_ACK: &[u8] = br#"{"id":"TE_2","op":"cancel-order","code":"0","msg":"","data":[{"clOrdId":"TE_1","ordId":"1001","sCode":"0","sMsg":""}]}"#;
+    const AMEND_ACK: &[u8] = br#"{"id":"TE_3","op":"amend-order","code":"0","msg":"","data":[{"clOrdId":"TE_1","ordId":"1001","sCode":"0","sMsg":""}]}"#;
+
+    #[test]
+    fn test_acks_for_the_same_order_resolve_their_own_request() {
+        let pending = PendingRequests::new(Arc::new(ExchangeAdapter::new()));
+        let connection_id = Uuid::new_v4();
+
+        let mut place = pending.register(connection_id, "TE_1");
+        let mut cancel = pending.register(connection_id, "TE_2");
+        let mut amend = pending.register(connection_id, "TE_3");
+
+        // The cancel ack names the order, but only the cancel waiter may take it
+        assert!(pending.resolve(connection_id, "okex", CANCEL_ACK));
+        assert_eq!(cancel.try_recv().unwrap().response.client_order_id, "TE_1");
+        assert!(place.try_recv().is_err());
+        assert!(amend.try_recv().is_err());
+
+        assert!(pending.resolve(connection_id, "okex", AMEND_ACK));
+        assert!(amend.try_recv().is_ok());
+        assert!(place.try_recv().is_err());
+        assert_eq!(pending.inflight_count(connection_id), 1);
+
+        // A repeated ack finds no waiter and is left for the message stream
+        assert!(!pending.resolve(connection_id, "okex", CANCEL_ACK));
+    }
+}
@@ -0,0 +1,263 @@
+use super::types::{OrderSide, OrderType};
+use crate::adapters::{OrderUpdate, UpdateKind};
+use chrono::{TimeZone, Utc};
+use common::instrument::{InstrumentId, InstrumentRegistry, MarketType};
+use common::types::{self, Exchange, ExecutionReport, ExecutionType, OrderStatus, Side};
+use rust_decimal::prelude::ToPrimitive;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Turns exchange-native order updates into `common::types::ExecutionReport`s.
+///
+/// Instruments are resolved from the exchange-native symbol through the shared
+/// instrument registry; symbols missing from it are reported as `InstrumentId::UNKNOWN`.
+pub struct ReportNormalizer {
+    instruments: Arc<InstrumentRegistry>,
+}
+
+impl ReportNormalizer {
+    pub fn new(instruments: Arc<InstrumentRegistry>) -> Self {
+        Self { instruments }
+    }
+
+    pub fn load(market_config_dir: &str) -> anyhow::Result<Self> {
+        Ok(Self::new(Arc::new(InstrumentRegistry::load(market_config_dir)?)))
+    }
+
+    /// Returns None for exchanges or market types the shared types do not know
+    pub fn report(&self, exchange: &str, market_type: &str, update: &OrderUpdate) -> Option<ExecutionReport> {
+        let exchange_enum = Exchange::from_key(exchange)?;
+        let market_type_enum: MarketType = market_type.parse().ok()?;
+
+        let instrument = self
+            .instruments
+            .resolve(exchange_enum, market_type_enum, &update.symbol)
+            .unwrap_or_else(|| {
+                debug!("No instrument for {} {} {}", exchange, market_type, update.symbol);
+                InstrumentId::UNKNOWN
+            });
+
+        let (status, execution_type) = match update.kind {
+            UpdateKind::New => (OrderStatus::Placed, ExecutionType::New),
+            UpdateKind::Amended if update.cumulative_filled_qty.is_zero() => {
+                (OrderStatus::Placed, ExecutionType::Replaced)
+            }
+            UpdateKind::Amended => (OrderStatus::PartiallyFilled, ExecutionType::Replaced),
+            UpdateKind::Trade if update.cumulative_filled_qty >= update.quantity => {
+                (OrderStatus::Filled, ExecutionType::Trade)
+            }
+            UpdateKind::Trade => (OrderStatus::PartiallyFilled, ExecutionType::Trade),
+            UpdateKind::Cancelled => (OrderStatus::Cancelled, ExecutionType::Cancelled),
+            UpdateKind::Rejected => (OrderStatus::Rejected, ExecutionType::Rejected),
+            UpdateKind::Expired => (OrderStatus::Cancelled, ExecutionType::Expired),
+        };
+
+        // Trades report the fill itself; other updates the order as placed
+        let (price, filled_quantity) = match update.kind {
+            UpdateKind::Trade => (update.last_filled_price, update.last_filled_qty),
+            _ => (update.price, update.cumulative_filled_qty),
+        };
+
+        Some(ExecutionReport {
+            order_id: update.order_id.clone(),
+            client_order_id: update.client_order_id.clone(),
+            instrument,
+            side: match update.side {
+                OrderSide::Buy => Side::Buy,
+                OrderSide::Sell => Side::Sell,
+            },
+            order_type: match update.order_type {
+                OrderType::Market | OrderType::StopMarket => types::OrderType::Market,
+                OrderType::Limit | OrderType::StopLimit => types::OrderType::Limit,
+            },
+            price: price.to_f64().unwrap_or_default(),
+            quantity: update.quantity.to_f64().unwrap_or_default(),
+            filled_quantity: filled_quantity.to_f64().unwrap_or_default(),
+            status,
+            execution_type,
+            timestamp: Utc
+                .timestamp_millis_opt(update.timestamp)
+                .single()
+                .unwrap_or_else(Utc::now),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::{ExchangeAdapter, OpenOrders, UserDataEvent};
+    use once_cell::sync::Lazy;
+
+    static ADAPTERS: Lazy<ExchangeAdapter> = Lazy::new(ExchangeAdapter::new);
+    static NORMALIZER: Lazy<ReportNormalizer> =
+        Lazy::new(|| ReportNormalizer::load(concat!(env!("CARGO_MANIFEST_DIR"), "/../config")).unwrap());
+
+    macro_rules! fixture {
+        ($path:literal) => {
+            include_bytes!(concat!("../../tests/fixtures/", $path))
+        };
+    }
+
+    fn reports(exchange: &str, market_type: &str, data: &[u8]) -> Vec<ExecutionReport> {
+        let protocol = ADAPTERS.get_adapter(exchange).unwrap().user_data().unwrap();
+        protocol
+            .parse_user_data(data)
+            .unwrap()
+            .into_iter()
+            .filter_map(|event| match event {
+                UserDataEvent::Order(update) => NORMALIZER.report(exchange, market_type, &update),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Frames in order through one stream's open-order tracking
+    fn tracked_reports(exchange: &str, market_type: &str, frames: &[&[u8]]) -> Vec<ExecutionReport> {
+        let protocol = ADAPTERS.get_adapter(exchange).unwrap().user_data().unwrap();
+        let mut open_orders = OpenOrders::new();
+        frames
+            .iter()
+            .flat_map(|data| protocol.parse_user_data(data).unwrap())
+            .filter_map(|event| match event {
+                UserDataEvent::Order(mut update) => open_orders
+                    .track(&mut update)
+                    .then(|| NORMALIZER.report(exchange, market_type, &update))
+                    .flatten(),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_binance_spot_partial_fill() {
+        let reports = reports("binance", "spot", fixture!("binance/execution_report_trade.json"));
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert_eq!(report.client_order_id, "TE_6001");
+        assert_eq!(report.order_id, "4293153");
+        assert_eq!(report.instrument, InstrumentId::new(1, 1));
+        assert_eq!(report.execution_type, ExecutionType::Trade);
+        assert_eq!(report.status, OrderStatus::PartiallyFilled);
+        assert_eq!(report.price, 0.7499);
+        assert_eq!(report.filled_quantity, 40.0);
+        assert_eq!(report.timestamp.timestamp_millis(), 1711001595299);
+    }
+
+    #[test]
+    fn test_binance_futures_cancel() {
+        let reports = reports("binance", "futures", fixture!("binance/order_trade_update_canceled.json"));
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].client_order_id, "TE_6002");
+        assert_eq!(reports[0].side, Side::Sell);
+        assert_eq!(reports[0].execution_type, ExecutionType::Cancelled);
+        assert_eq!(reports[0].status, OrderStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_binance_futures_amendment_is_replaced() {
+        let reports = reports("binance", "futures", fixture!("binance/order_trade_update_amendment.json"));
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].client_order_id, "TE_6003");
+        assert_eq!(reports[0].execution_type, ExecutionType::Replaced);
+        assert_eq!(reports[0].status, OrderStatus::Placed);
+        assert_eq!(reports[0].price, 0.81);
+        assert_eq!(reports[0].quantity, 60.0);
+    }
+
+    #[test]
+    fn test_okex_fill_completes_order() {
+        let reports = reports("okex", "spot", fixture!("okex/orders_filled.json"));
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].client_order_id, "TE_1001");
+        assert_eq!(reports[0].status, OrderStatus::Filled);
+        assert_eq!(reports[0].filled_quantity, 0.01);
+        // Not in okex_spot.csv
+        assert_eq!(reports[0].instrument, InstrumentId::UNKNOWN);
+    }
+
+    #[test]
+    fn test_okex_amend_result_is_replaced() {
+        let reports = reports("okex", "spot", fixture!("okex/orders_amended.json"));
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].client_order_id, "TE_1002");
+        assert_eq!(reports[0].execution_type, ExecutionType::Replaced);
+        assert_eq!(reports[0].price, 41800.0);
+        assert_eq!(reports[0].quantity, 0.02);
+
+        // A failed amend leaves the order as it was
+        let failed = std::str::from_utf8(fixture!("okex/orders_amended.json"))
+            .unwrap()
+            .replace(r#""amendResult": "0""#, r#""amendResult": "-1""#);
+        assert!(self::reports("okex", "spot", failed.as_bytes()).is_empty());
+    }
+
+    #[test]
+    fn test_bybit_new_for_known_order_is_amended() {
+        let reports = tracked_reports(
+            "bybit",
+            "futures",
+            &[
+                fixture!("bybit/order_new.json"),
+                fixture!("bybit/order_amended.json"),
+                fixture!("bybit/order_amended.json"),
+            ],
+        );
+        // The repeated push changed nothing and is dropped
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].execution_type, ExecutionType::New);
+        assert_eq!(reports[1].client_order_id, "TE_3002");
+        assert_eq!(reports[1].execution_type, ExecutionType::Replaced);
+        assert_eq!(reports[1].price, 64800.0);
+    }
+
+    #[test]
+    fn test_bybit_execution_uses_remaining_quantity() {
+        let reports = reports("bybit", "futures", fixture!("bybit/execution_partial.json"));
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].client_order_id, "TE_3001");
+        assert_eq!(reports[0].status, OrderStatus::PartiallyFilled);
+        assert_eq!(reports[0].price, 65000.5);
+        assert_eq!(reports[0].filled_quantity, 0.001);
+    }
+
+    #[test]
+    fn test_bitget_live_order_is_placed() {
+        let reports = reports("bitget", "futures", fixture!("bitget/orders_new.json"));
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].instrument, InstrumentId::new(8, 1));
+        assert_eq!(reports[0].execution_type, ExecutionType::New);
+        assert_eq!(reports[0].status, OrderStatus::Placed);
+        assert_eq!(reports[0].price, 57000.0);
+    }
+
+    #[test]
+    fn test_bitget_live_for_known_order_is_amended() {
+        let reports = tracked_reports(
+            "bitget",
+            "futures",
+            &[fixture!("bitget/orders_new.json"), fixture!("bitget/orders_amended.json")],
+        );
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].execution_type, ExecutionType::New);
+        assert_eq!(reports[1].client_order_id, "TE_5001");
+        assert_eq!(reports[1].execution_type, ExecutionType::Replaced);
+        assert_eq!(reports[1].status, OrderStatus::Placed);
+        assert_eq!(reports[1].price, 56800.0);
+    }
+
+    #[test]
+    fn test_login_acks() {
+        let okex = ADAPTERS.get_adapter("okex").unwrap().user_data().unwrap();
+        let events = okex.parse_user_data(br#"{"event":"login","code":"0","msg":"","connId":"a4d3ae55"}"#).unwrap();
+        assert!(matches!(events[..], [UserDataEvent::Authenticated]));
+
+        let bybit = ADAPTERS.get_adapter("bybit").unwrap().user_data().unwrap();
+        let events = bybit.parse_user_data(br#"{"success":false,"ret_msg":"Params Error","op":"auth","conn_id":"x"}"#).unwrap();
+        assert!(matches!(events[..], [UserDataEvent::AuthFailed(_)]));
+
+        let binance = ADAPTERS.get_adapter("binance").unwrap().user_data().unwrap();
+        let events = binance.parse_user_data(br#"{"id":"listen_key","status":200,"result":{"listenKey":"xs0mRXdA"}}"#).unwrap();
+        assert!(matches!(&events[..], [UserDataEvent::ListenKey(key)] if key == "xs0mRXdA"));
+    }
+}
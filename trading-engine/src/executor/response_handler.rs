@@ -1,112 +1,24 @@
 use super::types::*;
-use rust_decimal::Decimal;
-use serde_json::Value;
-use tracing::{debug, error, warn};
+use crate::adapters::AdapterTrait;
 
-pub struct ResponseHandler {
-    exchange: String,
+/// Exchange-specific parsing lives in the adapters; this only picks among the
+/// acks collected from concurrent sends.
+pub struct ResponseHandler<'a> {
+    adapter: &'a dyn AdapterTrait,
 }
 
-impl ResponseHandler {
-    pub fn new(exchange: String) -> Self {
-        Self { exchange }
+impl<'a> ResponseHandler<'a> {
+    pub fn new(adapter: &'a dyn AdapterTrait) -> Self {
+        Self { adapter }
     }
 
     pub fn parse_response(&self, data: &[u8]) -> Result<OrderResponse, anyhow::Error> {
-        let json: Value = serde_json::from_slice(data)?;
-        
-        match self.exchange.as_str() {
-            "binance" => self.parse_binance_response(json),
-            "okex" => self.parse_okex_response(json),
-            "bybit" => self.parse_bybit_response(json),
-            _ => Err(anyhow::anyhow!("Unsupported exchange: {}", self.exchange)),
-        }
-    }
-
-    fn parse_binance_response(&self, json: Value) -> Result<OrderResponse, anyhow::Error> {
-        if let Some(code) = json.get("code") {
-            let error_msg = json.get("msg")
-                .and_then(|v| v.as_str())
-                .unwrap_or("Unknown error")
-                .to_string();
-            
-            return Ok(OrderResponse {
-                order_id: String::new(),
-                client_order_id: String::new(),
-                symbol: String::new(),
-                status: OrderStatus::Rejected,
-                executed_qty: Decimal::ZERO,
-                executed_price: None,
-                timestamp: chrono::Utc::now().timestamp_millis(),
-                error: Some(error_msg),
-            });
-        }
-
-        let order_id = json.get("orderId")
-            .and_then(|v| v.as_i64())
-            .map(|v| v.to_string())
-            .unwrap_or_default();
-            
-        let client_order_id = json.get("clientOrderId")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-            
-        let symbol = json.get("symbol")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-            
-        let status = json.get("status")
-            .and_then(|v| v.as_str())
-            .map(|s| self.parse_binance_status(s))
-            .unwrap_or(OrderStatus::New);
-            
-        let executed_qty = json.get("executedQty")
-            .and_then(|v| v.as_str())
-            .and_then(|s| s.parse::<Decimal>().ok())
-            .unwrap_or(Decimal::ZERO);
-            
-        let executed_price = json.get("price")
-            .and_then(|v| v.as_str())
-            .and_then(|s| s.parse::<Decimal>().ok());
-            
-        let timestamp = json.get("transactTime")
-            .and_then(|v| v.as_i64())
-            .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
-
-        Ok(OrderResponse {
-            order_id,
-            client_order_id,
-            symbol,
-            status,
-            executed_qty,
-            executed_price,
-            timestamp,
-            error: None,
-        })
-    }
-
-    fn parse_okex_response(&self, json: Value) -> Result<OrderResponse, anyhow::Error> {
-        // Similar parsing logic for OKEx
-        todo!("Implement OKEx response parsing")
-    }
-
-    fn parse_bybit_response(&self, json: Value) -> Result<OrderResponse, anyhow::Error> {
-        // Similar parsing logic for Bybit
-        todo!("Implement Bybit response parsing")
-    }
-
-    fn parse_binance_status(&self, status: &str) -> OrderStatus {
-        match status {
-            "NEW" => OrderStatus::New,
-            "PARTIALLY_FILLED" => OrderStatus::PartiallyFilled,
-            "FILLED" => OrderStatus::Filled,
-            "CANCELED" => OrderStatus::Canceled,
-            "REJECTED" => OrderStatus::Rejected,
-            "EXPIRED" => OrderStatus::Expired,
-            _ => OrderStatus::New,
-        }
+        self.adapter.parse_order_response(data)
+    }
+
+    /// Parse an ack into one response per order; batch acks may mix accepted and rejected orders
+    pub fn parse_responses(&self, data: &[u8]) -> Result<Vec<OrderResponse>, anyhow::Error> {
+        self.adapter.parse_order_responses(data)
     }
 
     pub fn select_best_response(&self, responses: Vec<OrderResponse>) -> Option<OrderResponse> {
@@ -123,4 +35,175 @@ impl ResponseHandler {
                 OrderStatus::Rejected => 1,
             })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::ExchangeAdapter;
+    use once_cell::sync::Lazy;
+
+    static ADAPTERS: Lazy<ExchangeAdapter> = Lazy::new(ExchangeAdapter::new);
+
+    macro_rules! fixture {
+        ($path:literal) => {
+            include_bytes!(concat!("../../tests/fixtures/", $path))
+        };
+    }
+
+    fn okex() -> ResponseHandler<'static> {
+        ResponseHandler::new(ADAPTERS.get_adapter("okex").unwrap())
+    }
+
+    fn bybit() -> ResponseHandler<'static> {
+        ResponseHandler::new(ADAPTERS.get_adapter("bybit").unwrap())
+    }
+
+    fn bitget() -> ResponseHandler<'static> {
+        ResponseHandler::new(ADAPTERS.get_adapter("bitget").unwrap())
+    }
+
+    #[test]
+    fn test_binance_rejection_keeps_request_id() {
+        let handler = ResponseHandler::new(ADAPTERS.get_adapter("binance").unwrap());
+        let response = handler
+            .parse_response(br#"{"id":"TE_9","code":-2010,"msg":"Account has insufficient balance"}"#)
+            .unwrap();
+        assert_eq!(response.client_order_id, "TE_9");
+        assert!(matches!(response.status, OrderStatus::Rejected));
+    }
+
+    #[test]
+    fn test_okex_order_ack() {
+        let response = okex().parse_response(fixture!("okex/order_ack.json")).unwrap();
+        assert_eq!(response.order_id, "612398452387659776");
+        assert_eq!(response.client_order_id, "TE_1001");
+        assert!(matches!(response.status, OrderStatus::New));
+        assert_eq!(response.timestamp, 1695190491421);
+        assert!(response.error.is_none());
+    }
+
+    #[test]
+    fn test_okex_order_rejected() {
+        let response = okex().parse_response(fixture!("okex/order_rejected.json")).unwrap();
+        assert_eq!(response.client_order_id, "TE_1002");
+        assert!(matches!(response.status, OrderStatus::Rejected));
+        let error = response.error.unwrap();
+        assert!(error.starts_with("Error 51008: Order amount exceeds limit"), "{}", error);
+        assert!(error.contains("Insufficient USDT balance"), "{}", error);
+    }
+
+    #[test]
+    fn test_okex_batch_partial() {
+        let responses = okex().parse_responses(fixture!("okex/batch_partial.json")).unwrap();
+        assert_eq!(responses.len(), 3);
+
+        assert_eq!(responses[0].client_order_id, "TE_2001");
+        assert!(matches!(responses[0].status, OrderStatus::New));
+        assert_eq!(responses[0].order_id, "612398452387659777");
+
+        assert_eq!(responses[1].client_order_id, "TE_2002");
+        assert!(matches!(responses[1].status, OrderStatus::Rejected));
+        assert!(responses[1].error.as_ref().unwrap().contains("lot size"));
+
+        assert_eq!(responses[2].client_order_id, "TE_2003");
+        assert!(responses[2].error.is_none());
+    }
+
+    #[test]
+    fn test_okex_envelope_error() {
+        let responses = okex().parse_responses(fixture!("okex/envelope_error.json")).unwrap();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].client_order_id, "TE_1003");
+        assert!(matches!(responses[0].status, OrderStatus::Rejected));
+        assert_eq!(responses[0].error.as_deref(), Some("Error 60011: Not logged in (Please log in)"));
+    }
+
+    #[test]
+    fn test_okex_missing_code() {
+        assert!(okex().parse_response(br#"{"arg":{"channel":"tickers"},"data":[]}"#).is_err());
+    }
+
+    #[test]
+    fn test_bybit_ws_order_ack() {
+        let response = bybit().parse_response(fixture!("bybit/order_ack_ws.json")).unwrap();
+        assert_eq!(response.order_id, "1321003749386327552");
+        assert_eq!(response.client_order_id, "TE_3001");
+        assert!(matches!(response.status, OrderStatus::New));
+        assert_eq!(response.timestamp, 1711001595209);
+        assert!(response.error.is_none());
+    }
+
+    #[test]
+    fn test_bybit_rest_order_ack() {
+        let response = bybit().parse_response(fixture!("bybit/order_ack_rest.json")).unwrap();
+        assert_eq!(response.order_id, "1321003749386327553");
+        assert_eq!(response.client_order_id, "TE_3002");
+        assert_eq!(response.timestamp, 1711001595210);
+    }
+
+    #[test]
+    fn test_bybit_order_rejected() {
+        let response = bybit().parse_response(fixture!("bybit/order_rejected.json")).unwrap();
+        assert_eq!(response.client_order_id, "TE_3003");
+        assert!(matches!(response.status, OrderStatus::Rejected));
+        assert_eq!(
+            response.error.as_deref(),
+            Some("Error 110007: Insufficient available balance (ab not enough for new order)")
+        );
+    }
+
+    #[test]
+    fn test_bybit_batch_partial() {
+        let responses = bybit().parse_responses(fixture!("bybit/batch_partial.json")).unwrap();
+        assert_eq!(responses.len(), 2);
+
+        assert_eq!(responses[0].client_order_id, "TE_4001");
+        assert_eq!(responses[0].symbol, "BTCUSDT");
+        assert_eq!(responses[0].timestamp, 1711001595400);
+        assert!(responses[0].error.is_none());
+
+        assert_eq!(responses[1].client_order_id, "TE_4002");
+        assert_eq!(responses[1].symbol, "ETHUSDT");
+        assert!(matches!(responses[1].status, OrderStatus::Rejected));
+        assert!(responses[1].error.as_ref().unwrap().starts_with("Error 110003"));
+    }
+
+    #[test]
+    fn test_bitget_ws_order_ack() {
+        let response = bitget().parse_response(fixture!("bitget/order_ack_ws.json")).unwrap();
+        assert_eq!(response.order_id, "1193235441392488448");
+        assert_eq!(response.client_order_id, "TE_5001");
+        assert_eq!(response.symbol, "BTCUSDT");
+        assert!(matches!(response.status, OrderStatus::New));
+        assert_eq!(response.timestamp, 1720591925441);
+        assert!(response.error.is_none());
+    }
+
+    #[test]
+    fn test_bitget_rest_order_ack() {
+        let response = bitget().parse_response(fixture!("bitget/order_ack_rest.json")).unwrap();
+        assert_eq!(response.order_id, "1193235441392488449");
+        assert_eq!(response.client_order_id, "TE_5002");
+        assert_eq!(response.timestamp, 1720591925450);
+    }
+
+    #[test]
+    fn test_bitget_order_rejected() {
+        let response = bitget().parse_response(fixture!("bitget/order_rejected.json")).unwrap();
+        assert_eq!(response.client_order_id, "TE_5003");
+        assert_eq!(response.symbol, "ETHUSDT");
+        assert!(matches!(response.status, OrderStatus::Rejected));
+        assert_eq!(
+            response.error.as_deref(),
+            Some("Error 40762: Order amount exceeds balance (The order amount exceeds the balance)")
+        );
+    }
+
+    #[test]
+    fn test_select_best_response_skips_rejections() {
+        let responses = okex().parse_responses(fixture!("okex/batch_partial.json")).unwrap();
+        let best = okex().select_best_response(responses).unwrap();
+        assert!(best.error.is_none());
+    }
+}
@@ -8,6 +8,7 @@ type HmacSha256 = Hmac<Sha256>;
 pub struct Signer {
     api_key: String,
     secret_key: String,
+    passphrase: Option<String>,
 }
 
 impl Signer {
@@ -15,6 +16,16 @@ impl Signer {
         Self {
             api_key,
             secret_key,
+            passphrase: None,
+        }
+    }
+
+    /// Signer for venues that also require an API passphrase (Bitget)
+    pub fn with_passphrase(api_key: String, secret_key: String, passphrase: String) -> Self {
+        Self {
+            api_key,
+            secret_key,
+            passphrase: Some(passphrase),
         }
     }
 
@@ -56,7 +67,32 @@ impl Signer {
         hex::encode(result.into_bytes())
     }
 
+    /// Private stream auth: `GET/realtime` plus the expiry, hex encoded
+    pub fn sign_bybit_auth(&self, expires: i64) -> String {
+        let mut mac = HmacSha256::new_from_slice(self.secret_key.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(format!("GET/realtime{}", expires).as_bytes());
+        let result = mac.finalize();
+        hex::encode(result.into_bytes())
+    }
+
+    /// Bitget signs `timestamp + METHOD + requestPath + body`; the passphrase
+    /// is sent alongside in the ACCESS-PASSPHRASE header.
+    pub fn sign_bitget(&self, timestamp: &str, method: &str, path: &str, body: &str) -> String {
+        let message = format!("{}{}{}{}", timestamp, method.to_uppercase(), path, body);
+
+        let mut mac = HmacSha256::new_from_slice(self.secret_key.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(message.as_bytes());
+        let result = mac.finalize();
+        base64::Engine::encode(&base64::engine::general_purpose::STANDARD, result.into_bytes())
+    }
+
     pub fn api_key(&self) -> &str {
         &self.api_key
     }
+
+    pub fn passphrase(&self) -> Option<&str> {
+        self.passphrase.as_deref()
+    }
 }
\ No newline at end of file
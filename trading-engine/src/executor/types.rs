@@ -1,85 +1,61 @@
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+
+pub use common::execution::{
+    CommandAction, ExecutionCommand, ExecutionResult, OrderResponse, OrderSide, OrderStatus, OrderType,
+    TimeInForce,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExecutionCommand {
-    pub id: Uuid,
-    pub exchange: String,
-    pub market_type: String, // "spot" or "futures"
+pub struct OrderRequest {
     pub symbol: String,
+    /// "spot" or "futures", for venues that select the market per request
+    pub market_type: String,
     pub side: OrderSide,
     pub order_type: OrderType,
     pub quantity: Decimal,
     pub price: Option<Decimal>,
     pub time_in_force: TimeInForce,
-    pub client_order_id: Option<String>,
     pub reduce_only: bool,
-    pub post_only: bool,
-}
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub enum OrderSide {
-    Buy,
-    Sell,
-}
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub enum OrderType {
-    Market,
-    Limit,
-    StopMarket,
-    StopLimit,
-}
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub enum TimeInForce {
-    GTC,  // Good Till Cancel
-    IOC,  // Immediate or Cancel
-    FOK,  // Fill or Kill
-    GTX,  // Good Till Crossing
+    pub client_order_id: String,
+    pub timestamp: i64,
+    /// Sent alongside the signature by venues that authenticate each request
+    pub api_key: String,
+    pub signature: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OrderRequest {
+pub struct CancelRequest {
     pub symbol: String,
-    pub side: OrderSide,
-    pub order_type: OrderType,
-    pub quantity: Decimal,
-    pub price: Option<Decimal>,
-    pub time_in_force: TimeInForce,
+    pub market_type: String,
     pub client_order_id: String,
+    /// Engine-generated id echoed back in the ack, distinct from the order's own id
+    pub request_id: String,
     pub timestamp: i64,
+    pub api_key: String,
     pub signature: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OrderResponse {
-    pub order_id: String,
-    pub client_order_id: String,
+pub struct CancelAllRequest {
     pub symbol: String,
-    pub status: OrderStatus,
-    pub executed_qty: Decimal,
-    pub executed_price: Option<Decimal>,
+    /// Engine-generated id echoed back in the ack
+    pub request_id: String,
     pub timestamp: i64,
-    pub error: Option<String>,
-}
-
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub enum OrderStatus {
-    New,
-    PartiallyFilled,
-    Filled,
-    Canceled,
-    Rejected,
-    Expired,
+    pub api_key: String,
+    pub signature: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExecutionResult {
-    pub command_id: Uuid,
-    pub success: bool,
-    pub responses: Vec<OrderResponse>,
-    pub selected_response: Option<OrderResponse>,
-    pub error: Option<String>,
-}
\ No newline at end of file
+pub struct AmendRequest {
+    pub symbol: String,
+    pub side: OrderSide,
+    pub client_order_id: String,
+    /// Engine-generated id echoed back in the ack, distinct from the order's own id
+    pub request_id: String,
+    pub new_price: Option<Decimal>,
+    pub new_quantity: Option<Decimal>,
+    pub timestamp: i64,
+    pub api_key: String,
+    pub signature: String,
+}
@@ -15,6 +15,8 @@ pub struct HealthMetrics {
     pub total_errors: u64,
     pub last_update: Instant,
     pub consecutive_failures: u32,
+    /// Only connected sockets can carry orders
+    pub connected: bool,
 }
 
 impl HealthMetrics {
@@ -30,9 +32,21 @@ impl HealthMetrics {
             total_errors: 0,
             last_update: Instant::now(),
             consecutive_failures: 0,
+            connected: false,
         }
     }
 
+    /// Heartbeat round trip; the first sample replaces the unmeasured zero
+    pub fn update_rtt(&mut self, rtt_ms: f64) {
+        if self.rtt_ms == 0.0 {
+            self.rtt_ms = rtt_ms;
+        } else {
+            self.rtt_ms = (self.rtt_ms * 0.9) + (rtt_ms * 0.1);
+        }
+        self.last_update = Instant::now();
+        self.recalculate_health_score();
+    }
+
     pub fn update_success(&mut self, rtt_ms: f64) {
         self.total_messages += 1;
         self.consecutive_failures = 0;
@@ -104,7 +118,7 @@ impl HealthMetrics {
     }
 
     pub fn is_healthy(&self) -> bool {
-        self.health_score >= 50.0 && self.consecutive_failures < 5
+        self.connected && self.health_score >= 50.0 && self.consecutive_failures < 5
     }
 }
 
@@ -136,6 +150,19 @@ impl HealthTracker {
         }
     }
 
+    pub fn record_rtt(&self, connection_id: Uuid, rtt_ms: f64) {
+        if let Some(mut entry) = self.metrics.get_mut(&connection_id) {
+            entry.update_rtt(rtt_ms);
+        }
+    }
+
+    pub fn set_connected(&self, connection_id: Uuid, connected: bool) {
+        if let Some(mut entry) = self.metrics.get_mut(&connection_id) {
+            entry.connected = connected;
+            entry.last_update = Instant::now();
+        }
+    }
+
     pub fn get_metrics(&self, connection_id: Uuid) -> Option<HealthMetrics> {
         self.metrics.get(&connection_id).map(|entry| entry.clone())
     }
@@ -192,4 +219,39 @@ impl HealthTracker {
             self.metrics.remove(&id);
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registered_connection_selectable_once_connected() {
+        let tracker = HealthTracker::new();
+        let id = Uuid::new_v4();
+        tracker.register_connection(id, "binance".to_string(), "futures".to_string());
+        assert!(tracker.get_top_k_connections("binance", "futures", 3).is_empty());
+
+        tracker.set_connected(id, true);
+        let selected = tracker.get_top_k_connections("binance", "futures", 3);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].connection_id, id);
+        assert!(tracker.get_top_k_connections("binance", "spot", 3).is_empty());
+
+        tracker.set_connected(id, false);
+        assert!(tracker.get_top_k_connections("binance", "futures", 3).is_empty());
+    }
+
+    #[test]
+    fn test_rtt_samples_drive_rtt_ms() {
+        let tracker = HealthTracker::new();
+        let id = Uuid::new_v4();
+        tracker.register_connection(id, "okex".to_string(), "spot".to_string());
+
+        tracker.record_rtt(id, 40.0);
+        assert_eq!(tracker.get_metrics(id).unwrap().rtt_ms, 40.0);
+
+        tracker.record_rtt(id, 140.0);
+        assert!((tracker.get_metrics(id).unwrap().rtt_ms - 50.0).abs() < 1e-9);
+    }
+}
@@ -1,81 +1,400 @@
 use crate::config::IpcConfig;
-use crate::executor::types::ExecutionCommand;
-use bytes::Bytes;
+use crate::executor::types::{ExecutionCommand, ExecutionResult};
+use crate::ipc::codec::{self, IpcFrame};
+use common::pod::PodExecutionReport;
+use common::types::ExecutionReport;
+use common::wakeup::{TopicNotifier, TopicWaiter};
+use iceoryx2::port::publisher::Publisher;
+use iceoryx2::port::subscriber::Subscriber;
 use iceoryx2::prelude::*;
-use tokio::sync::mpsc;
-use tracing::{debug, info};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, error, info, warn};
 
+/// Bridges the engine to pre-post-processor over iceoryx2.
+///
+/// Commands arrive on `input_topic` and results leave on `output_topic`, both as
+/// fixed-size `IpcFrame`s (see `codec`). Each port is owned by a dedicated thread.
+/// Both services are created with safe overflow disabled, so a slow consumer
+/// applies backpressure instead of silently losing messages:
+/// - commands: the engine runs at most `max_in_flight` commands at once and stops
+///   draining the bounded command channel while they are all busy, so the
+///   receiver blocks on it and leaves samples in the iceoryx2 buffer;
+/// - results: `result_sender()` is bounded by `buffer_size` and the publisher
+///   blocks when the subscriber buffer is full.
+///
+/// Every publish is followed by a notification on the topic's event service
+/// (`common::wakeup`), and the command receiver blocks on its listener (or
+/// busy-spins, per `IpcConfig::wakeup`) instead of sleeping between polls.
+///
+/// Execution reports from the user-data streams leave on `report_topic` as
+/// fixed-layout `PodExecutionReport`s written in place via `loan_uninit`, with the
+/// service settings pre-post-processor opens it with.
 pub struct IpcManager {
     config: IpcConfig,
-    command_service: Option<Node<ipc::Service>>,
-    response_service: Option<Node<ipc::Service>>,
-    command_tx: mpsc::UnboundedSender<ExecutionCommand>,
-    response_rx: Option<mpsc::UnboundedReceiver<Bytes>>,
+    command_tx: mpsc::Sender<ExecutionCommand>,
+    result_tx: Option<mpsc::Sender<ExecutionResult>>,
+    result_rx: Option<mpsc::Receiver<ExecutionResult>>,
+    report_tx: Option<mpsc::Sender<ExecutionReport>>,
+    report_rx: Option<mpsc::Receiver<ExecutionReport>>,
+    shutdown: Arc<AtomicBool>,
 }
 
 impl IpcManager {
     pub fn new(
         config: IpcConfig,
-        command_tx: mpsc::UnboundedSender<ExecutionCommand>,
+        command_tx: mpsc::Sender<ExecutionCommand>,
     ) -> anyhow::Result<Self> {
-        let (response_tx, response_rx) = mpsc::unbounded_channel();
-        
+        let (result_tx, result_rx) = mpsc::channel(config.buffer_size.max(1));
+        let (report_tx, report_rx) = mpsc::channel(config.buffer_size.max(1));
+
         Ok(Self {
             config,
-            command_service: None,
-            response_service: None,
             command_tx,
-            response_rx: Some(response_rx),
+            result_tx: Some(result_tx),
+            result_rx: Some(result_rx),
+            report_tx: Some(report_tx),
+            report_rx: Some(report_rx),
+            shutdown: Arc::new(AtomicBool::new(false)),
         })
     }
-    
+
     pub fn initialize(&mut self) -> anyhow::Result<()> {
         info!("Initializing IPC manager");
-        
-        // Create iceoryx2 nodes for command and response services
-        let command_node = NodeBuilder::new()
-            .name(&NodeName::new(&format!("{}_cmd", self.config.service_name))?)
-            .create::<ipc::Service>()?;
-            
-        let response_node = NodeBuilder::new()
-            .name(&NodeName::new(&format!("{}_resp", self.config.service_name))?)
-            .create::<ipc::Service>()?;
-        
-        // Store services (we'll create the actual ports when needed)
-        self.command_service = Some(command_node);
-        self.response_service = Some(response_node);
-        
+
+        // Validate names up front so misconfiguration fails before any thread is spawned
+        NodeName::new(&format!("{}_cmd", self.config.service_name))?;
+        NodeName::new(&format!("{}_resp", self.config.service_name))?;
+        NodeName::new(&format!("{}_report", self.config.service_name))?;
+        ServiceName::new(&self.config.input_topic)?;
+        ServiceName::new(&self.config.output_topic)?;
+        ServiceName::new(&self.config.report_topic)?;
+
+        if self.config.buffer_size == 0 {
+            anyhow::bail!("ipc.buffer_size must be greater than zero");
+        }
+
         info!("IPC manager initialized successfully");
         Ok(())
     }
-    
+
     pub async fn start(&mut self) -> anyhow::Result<()> {
         info!("Starting IPC manager");
-        
+
         // Start command receiver
-        self.start_command_receiver();
-        
+        self.start_command_receiver().await?;
+
         // Start response sender
-        self.start_response_sender();
-        
+        self.start_response_sender().await?;
+
+        // Start execution report publisher
+        self.start_report_publisher().await?;
+
         Ok(())
     }
-    
-    fn start_command_receiver(&self) {
-        // For now, we'll skip the IPC receiver implementation
-        // as it requires setting up proper iceoryx2 service and subscriber
-        info!("Command receiver would be started here");
+
+    /// Handle used by execution tasks to publish results; `send().await`
+    /// waits while the outbound queue is full.
+    pub fn result_sender(&self) -> mpsc::Sender<ExecutionResult> {
+        self.result_tx
+            .clone()
+            .expect("result sender requested after IPC manager shutdown")
     }
-    
-    fn start_response_sender(&mut self) {
-        // For now, we'll skip the IPC sender implementation
-        // as it requires setting up proper iceoryx2 service and publisher
-        info!("Response sender would be started here");
+
+    /// Handle used by the user-data streams to publish execution reports
+    pub fn report_sender(&self) -> mpsc::Sender<ExecutionReport> {
+        self.report_tx
+            .clone()
+            .expect("report sender requested after IPC manager shutdown")
     }
-    
-    pub fn send_response(&self, data: Bytes) -> anyhow::Result<()> {
-        // For now, we'll skip the IPC send implementation
-        debug!("Would send response of {} bytes", data.len());
-        Ok(())
+
+    pub fn shutdown(&mut self) {
+        info!("Shutting down IPC manager");
+        self.shutdown.store(true, Ordering::Release);
+        // The publisher threads exit once every result and report sender is gone
+        self.result_tx.take();
+        self.report_tx.take();
+    }
+
+    async fn start_command_receiver(&mut self) -> anyhow::Result<()> {
+        let config = self.config.clone();
+        let command_tx = self.command_tx.clone();
+        let shutdown = self.shutdown.clone();
+        let (ready_tx, ready_rx) = oneshot::channel();
+
+        std::thread::Builder::new()
+            .name(format!("{}-ipc-cmd", self.config.service_name))
+            .spawn(move || {
+                if let Err(e) = run_command_receiver(config, command_tx, shutdown, ready_tx) {
+                    error!("Command receiver thread error: {}", e);
+                }
+            })?;
+
+        wait_ready(ready_rx, "command receiver").await
+    }
+
+    async fn start_response_sender(&mut self) -> anyhow::Result<()> {
+        let config = self.config.clone();
+        let result_rx = self
+            .result_rx
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("response sender already started"))?;
+        let (ready_tx, ready_rx) = oneshot::channel();
+
+        std::thread::Builder::new()
+            .name(format!("{}-ipc-resp", self.config.service_name))
+            .spawn(move || {
+                if let Err(e) = run_response_sender(config, result_rx, ready_tx) {
+                    error!("Response sender thread error: {}", e);
+                }
+            })?;
+
+        wait_ready(ready_rx, "response sender").await
+    }
+
+    async fn start_report_publisher(&mut self) -> anyhow::Result<()> {
+        let config = self.config.clone();
+        let report_rx = self
+            .report_rx
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("report publisher already started"))?;
+        let (ready_tx, ready_rx) = oneshot::channel();
+
+        std::thread::Builder::new()
+            .name(format!("{}-ipc-report", self.config.service_name))
+            .spawn(move || {
+                if let Err(e) = run_report_publisher(config, report_rx, ready_tx) {
+                    error!("Report publisher thread error: {}", e);
+                }
+            })?;
+
+        wait_ready(ready_rx, "report publisher").await
+    }
+}
+
+type ReadySender = oneshot::Sender<Result<(), String>>;
+
+async fn wait_ready(
+    ready_rx: oneshot::Receiver<Result<(), String>>,
+    what: &str,
+) -> anyhow::Result<()> {
+    match ready_rx.await {
+        Ok(Ok(())) => {
+            info!("IPC {} ready", what);
+            Ok(())
+        }
+        Ok(Err(e)) => Err(anyhow::anyhow!("failed to start IPC {}: {}", what, e)),
+        Err(_) => Err(anyhow::anyhow!("IPC {} thread exited during startup", what)),
+    }
+}
+
+type CommandPort = (Node<ipc::Service>, Subscriber<ipc::Service, IpcFrame, ()>, TopicWaiter);
+type ResultPort = (Node<ipc::Service>, Publisher<ipc::Service, IpcFrame, ()>, TopicNotifier);
+type ReportPort = (Node<ipc::Service>, Publisher<ipc::Service, PodExecutionReport, ()>, TopicNotifier);
+
+fn open_command_port(config: &IpcConfig) -> anyhow::Result<CommandPort> {
+    let node = NodeBuilder::new()
+        .name(&NodeName::new(&format!("{}_cmd", config.service_name))?)
+        .create::<ipc::Service>()?;
+
+    let service = node
+        .service_builder(&ServiceName::new(&config.input_topic)?)
+        .publish_subscribe::<IpcFrame>()
+        .subscriber_max_buffer_size(config.buffer_size)
+        .enable_safe_overflow(false)
+        .open_or_create()?;
+
+    let subscriber = service
+        .subscriber_builder()
+        .buffer_size(config.buffer_size)
+        .create()?;
+    let waiter = TopicWaiter::open(&node, &config.input_topic, &config.wakeup)?;
+
+    Ok((node, subscriber, waiter))
+}
+
+fn open_result_port(config: &IpcConfig) -> anyhow::Result<ResultPort> {
+    let node = NodeBuilder::new()
+        .name(&NodeName::new(&format!("{}_resp", config.service_name))?)
+        .create::<ipc::Service>()?;
+
+    let service = node
+        .service_builder(&ServiceName::new(&config.output_topic)?)
+        .publish_subscribe::<IpcFrame>()
+        .subscriber_max_buffer_size(config.buffer_size)
+        .enable_safe_overflow(false)
+        .open_or_create()?;
+
+    let publisher = service
+        .publisher_builder()
+        .unable_to_deliver_strategy(UnableToDeliverStrategy::Block)
+        .create()?;
+    let notifier = TopicNotifier::open(&node, &config.output_topic)?;
+
+    Ok((node, publisher, notifier))
+}
+
+fn open_report_port(config: &IpcConfig) -> anyhow::Result<ReportPort> {
+    let node = NodeBuilder::new()
+        .name(&NodeName::new(&format!("{}_report", config.service_name))?)
+        .create::<ipc::Service>()?;
+
+    let service = node
+        .service_builder(&ServiceName::new(&config.report_topic)?)
+        .publish_subscribe::<PodExecutionReport>()
+        .open_or_create()?;
+
+    let publisher = service.publisher_builder().create()?;
+    let notifier = TopicNotifier::open(&node, &config.report_topic)?;
+
+    Ok((node, publisher, notifier))
+}
+
+fn run_command_receiver(
+    config: IpcConfig,
+    command_tx: mpsc::Sender<ExecutionCommand>,
+    shutdown: Arc<AtomicBool>,
+    ready_tx: ReadySender,
+) -> anyhow::Result<()> {
+    let (node, subscriber, mut waiter) = match open_command_port(&config) {
+        Ok(ports) => {
+            let _ = ready_tx.send(Ok(()));
+            ports
+        }
+        Err(e) => {
+            let _ = ready_tx.send(Err(e.to_string()));
+            return Err(e);
+        }
+    };
+    info!("Command subscriber ready on topic: {}", config.input_topic);
+
+    let mut received: u64 = 0;
+    let mut rejected: u64 = 0;
+
+    while !shutdown.load(Ordering::Acquire) {
+        while let Some(sample) = subscriber.receive()? {
+            match codec::decode_command(sample.payload()) {
+                Ok(command) => {
+                    received += 1;
+                    debug!("Received command {} for {} {}", command.id, command.exchange, command.symbol);
+
+                    // Blocks while the executor is saturated; unread samples stay queued in iceoryx2
+                    if command_tx.blocking_send(command).is_err() {
+                        info!("Command channel closed, stopping command receiver");
+                        return Ok(());
+                    }
+                }
+                Err(e) => {
+                    rejected += 1;
+                    warn!("Dropping undecodable command frame: {}", e);
+                }
+            }
+        }
+
+        if !waiter.wait(&node)? {
+            warn!("Command receiver node received termination signal");
+            break;
+        }
     }
-}
\ No newline at end of file
+
+    info!(
+        "Command receiver stopped: {} commands received, {} frames rejected",
+        received, rejected
+    );
+    Ok(())
+}
+
+fn run_response_sender(
+    config: IpcConfig,
+    mut result_rx: mpsc::Receiver<ExecutionResult>,
+    ready_tx: ReadySender,
+) -> anyhow::Result<()> {
+    let (_node, publisher, notifier) = match open_result_port(&config) {
+        Ok(ports) => {
+            let _ = ready_tx.send(Ok(()));
+            ports
+        }
+        Err(e) => {
+            let _ = ready_tx.send(Err(e.to_string()));
+            return Err(e);
+        }
+    };
+    info!("Result publisher ready on topic: {}", config.output_topic);
+
+    let mut published: u64 = 0;
+
+    while let Some(result) = result_rx.blocking_recv() {
+        let frame = match codec::encode_result(&result) {
+            Ok(frame) => frame,
+            Err(e) => {
+                error!("Failed to encode result for command {}: {}", result.command_id, e);
+                continue;
+            }
+        };
+
+        match publisher.loan_uninit() {
+            Ok(sample) => match sample.write_payload(frame).send() {
+                Ok(_) => {
+                    published += 1;
+                    debug!("Published result for command {}", result.command_id);
+                    if let Err(e) = notifier.notify() {
+                        warn!("Failed to notify result subscribers: {}", e);
+                    }
+                }
+                Err(e) => error!("Failed to send result {}: {:?}", result.command_id, e),
+            },
+            Err(e) => error!("Failed to loan result sample: {:?}", e),
+        }
+    }
+
+    info!("Response sender stopped: {} results published", published);
+    Ok(())
+}
+
+fn run_report_publisher(
+    config: IpcConfig,
+    mut report_rx: mpsc::Receiver<ExecutionReport>,
+    ready_tx: ReadySender,
+) -> anyhow::Result<()> {
+    let (_node, publisher, notifier) = match open_report_port(&config) {
+        Ok(ports) => {
+            let _ = ready_tx.send(Ok(()));
+            ports
+        }
+        Err(e) => {
+            let _ = ready_tx.send(Err(e.to_string()));
+            return Err(e);
+        }
+    };
+    info!("Report publisher ready on topic: {}", config.report_topic);
+
+    let mut published: u64 = 0;
+
+    while let Some(report) = report_rx.blocking_recv() {
+        let pod = match PodExecutionReport::try_from(&report) {
+            Ok(pod) => pod,
+            Err(e) => {
+                error!("Failed to convert report for {}: {}", report.client_order_id, e);
+                continue;
+            }
+        };
+
+        match publisher.loan_uninit() {
+            Ok(sample) => match sample.write_payload(pod).send() {
+                Ok(_) => {
+                    published += 1;
+                    debug!("Published {:?} report for {}", report.execution_type, report.client_order_id);
+                    if let Err(e) = notifier.notify() {
+                        warn!("Failed to notify report subscribers: {}", e);
+                    }
+                }
+                Err(e) => error!("Failed to send report {}: {:?}", report.client_order_id, e),
+            },
+            Err(e) => error!("Failed to loan report sample: {:?}", e),
+        }
+    }
+
+    info!("Report publisher stopped: {} reports published", published);
+    Ok(())
+}
@@ -1,3 +1,4 @@
 pub mod ipc_manager;
 
-pub use ipc_manager::IpcManager;
\ No newline at end of file
+pub use common::codec;
+pub use ipc_manager::IpcManager;
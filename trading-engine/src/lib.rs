@@ -0,0 +1,6 @@
+pub mod config;
+pub mod ws_pool;
+pub mod executor;
+pub mod adapters;
+pub mod health;
+pub mod ipc;
@@ -1,18 +1,12 @@
-mod config;
-mod ws_pool;
-mod executor;
-mod adapters;
-mod health;
-mod ipc;
-
-use config::TradingEngineConfig;
-use executor::OrderExecutor;
-use health::{ConnectionSelector, HealthTracker};
-use ipc::IpcManager;
-use ws_pool::WsPool;
+use trading_engine::config::TradingEngineConfig;
+use trading_engine::executor::{OrderExecutor, ReportNormalizer};
+use trading_engine::health::{self, ConnectionSelector, HealthTracker};
+use trading_engine::ipc::IpcManager;
+use trading_engine::ws_pool::WsPool;
+use common::instrument::InstrumentRegistry;
 use std::sync::Arc;
-use tokio::sync::mpsc;
-use tracing::{error, info};
+use tokio::sync::{mpsc, Semaphore};
+use tracing::{debug, error, info, warn};
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
 #[tokio::main]
@@ -29,9 +23,13 @@ async fn main() -> anyhow::Result<()> {
     let config_path = std::env::var("CONFIG_PATH").unwrap_or_else(|_| "config/trading_engine.toml".to_string());
     let config = TradingEngineConfig::from_file(&config_path)?;
     
+    // Shared by the order builder (filters) and the report normalizer (symbol ids)
+    let instruments = Arc::new(InstrumentRegistry::load(&config.user_data.market_config_dir)?);
+    info!("Loaded {} instruments", instruments.len());
+    
     // Create channels
-    let (command_tx, mut command_rx) = mpsc::unbounded_channel();
-    let (response_tx, response_rx) = mpsc::unbounded_channel();
+    // Bounded so a saturated executor pushes back on the IPC command subscriber
+    let (command_tx, mut command_rx) = mpsc::channel(config.ipc.buffer_size.max(1));
     
     // Initialize components
     let health_tracker = Arc::new(HealthTracker::new());
@@ -41,7 +39,25 @@ async fn main() -> anyhow::Result<()> {
     ));
     
     // Initialize WebSocket pool
-    let mut ws_pool = WsPool::new(config.clone());
+    let mut ws_pool = WsPool::new(config.clone(), health_tracker.clone());
+    
+    // Log connection state changes for monitoring
+    let mut connection_events = ws_pool.subscribe_events();
+    tokio::spawn(async move {
+        loop {
+            match connection_events.recv().await {
+                Ok(event) => info!(
+                    "Connection {} ({} {}) -> {:?}, attempt {}",
+                    event.connection_id, event.exchange, event.market_type, event.state, event.attempt
+                ),
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!("Missed {} connection events", skipped);
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+    
     ws_pool.start().await?;
     
     // Get message receiver before moving ws_pool into Arc
@@ -54,7 +70,7 @@ async fn main() -> anyhow::Result<()> {
         ws_pool.clone(),
         health_tracker.clone(),
         connection_selector.clone(),
-    ));
+    ).with_instruments(instruments.clone()));
     
     // Register signers for each exchange
     for (exchange_name, exchange_config) in &config.exchanges {
@@ -63,6 +79,7 @@ async fn main() -> anyhow::Result<()> {
                 exchange_name.clone(),
                 exchange_config.api_key.clone(),
                 exchange_config.secret_key.clone(),
+                exchange_config.passphrase.clone(),
             );
         }
     }
@@ -71,17 +88,23 @@ async fn main() -> anyhow::Result<()> {
     let mut ipc_manager = IpcManager::new(config.ipc.clone(), command_tx)?;
     ipc_manager.initialize()?;
     ipc_manager.start().await?;
+    let result_tx = ipc_manager.result_sender();
+    
+    // Private streams report fills and order state straight from the exchanges
+    if config.user_data.enabled {
+        let normalizer = Arc::new(ReportNormalizer::new(instruments.clone()));
+        let started = ws_pool.start_user_data_streams(normalizer, ipc_manager.report_sender());
+        info!("Started {} user-data streams", started);
+    } else {
+        warn!("User-data streams disabled, pre-post-processor receives no execution reports");
+    }
     
     // Use message receiver from WebSocket pool
     if let Some(mut message_rx) = message_rx {
-        // Start message forwarding task
-        let response_tx_clone = response_tx.clone();
+        // Drain inbound WebSocket messages so the pool never backs up
         tokio::spawn(async move {
             while let Some(msg) = message_rx.recv().await {
-                // Forward WebSocket messages to IPC output
-                if let Err(e) = response_tx_clone.send(msg) {
-                    error!("Failed to forward WebSocket message: {}", e);
-                }
+                debug!("Received WebSocket message: {} bytes", msg.len());
             }
         });
     }
@@ -89,11 +112,23 @@ async fn main() -> anyhow::Result<()> {
     // Main execution loop
     info!("Trading Engine started successfully");
     
+    // Each command holds a permit until its result is queued, so at most
+    // `max_in_flight` run at once and the rest stay in the command channel
+    let in_flight = Arc::new(Semaphore::new(config.ipc.max_in_flight.max(1)));
+    
     loop {
+        let permit = tokio::select! {
+            permit = in_flight.clone().acquire_owned() => permit?,
+            _ = tokio::signal::ctrl_c() => {
+                info!("Received shutdown signal");
+                break;
+            }
+        };
+        
         tokio::select! {
             Some(command) = command_rx.recv() => {
                 let executor = executor.clone();
-                let response_tx = response_tx.clone();
+                let result_tx = result_tx.clone();
                 tokio::spawn(async move {
                     info!("Executing command: {:?}", command.id);
                     let result = executor.execute(command).await;
@@ -104,11 +139,11 @@ async fn main() -> anyhow::Result<()> {
                         error!("Command execution failed: {:?} - {:?}", result.command_id, result.error);
                     }
                     
-                    // Send result through IPC if needed
-                    if let Ok(result_bytes) = serde_json::to_vec(&result) {
-                        // Send through response channel
-                        let _ = response_tx.send(bytes::Bytes::from(result_bytes));
+                    // Publish result back to pre-post-processor
+                    if let Err(e) = result_tx.send(result).await {
+                        error!("Failed to queue execution result: {}", e);
                     }
+                    drop(permit);
                 });
             }
             _ = tokio::signal::ctrl_c() => {
@@ -120,6 +155,7 @@ async fn main() -> anyhow::Result<()> {
     
     // Cleanup
     info!("Shutting down Trading Engine");
+    ipc_manager.shutdown();
     ws_pool.shutdown().await;
     
     Ok(())
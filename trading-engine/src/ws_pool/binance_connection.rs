@@ -11,14 +11,20 @@ pub struct BinanceConnection {
     pub base: BaseConnection,
     delay_interval: Duration,
     ping_interval: Duration,
+    /// Binance only pings every 3 minutes; our own pings measure RTT in between
+    rtt_probe_interval: Duration,
+    ping_sent_at: Option<Instant>,
 }
 
 impl BinanceConnection {
     pub fn new(base: BaseConnection) -> Self {
+        let rtt_probe_interval = base.heartbeat_interval;
         Self {
             base,
             delay_interval: Duration::from_secs(5),
             ping_interval: Duration::from_secs(180),
+            rtt_probe_interval,
+            ping_sent_at: None,
         }
     }
     
@@ -31,7 +37,7 @@ impl BinanceConnection {
             }
             Message::Text(text) => {
                 let bytes = Bytes::from(text.into_bytes());
-                if let Err(e) = self.base.message_tx.send(bytes) {
+                if let Err(e) = self.base.dispatch_inbound(bytes) {
                     error!("Failed to send message: {}", e);
                     return true; // Should break
                 }
@@ -46,7 +52,7 @@ impl BinanceConnection {
             }
             Message::Binary(data) => {
                 let bytes = Bytes::from(data);
-                if let Err(e) = self.base.message_tx.send(bytes) {
+                if let Err(e) = self.base.dispatch_inbound(bytes) {
                     error!("Failed to send message: {}", e);
                     return true;
                 }
@@ -62,9 +68,9 @@ impl BinanceConnection {
                 true
             }
             Message::Pong(_) => {
-                self.base.update_stats(|stats| {
-                    stats.rtt_ms = 20.0; // Approximate RTT
-                });
+                if let Some(sent_at) = self.ping_sent_at.take() {
+                    self.base.record_rtt(sent_at.elapsed());
+                }
                 false
             }
             _ => {
@@ -87,7 +93,9 @@ impl WsConnectionRunner for BinanceConnection {
                 Err(e) => {
                     error!("Failed to connect: {}", e);
                     self.base.set_state(ConnectionState::Error);
-                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    if !self.base.wait_before_reconnect().await? {
+                        return Ok(());
+                    }
                     continue;
                 }
             };
@@ -97,17 +105,23 @@ impl WsConnectionRunner for BinanceConnection {
             // Send subscription message
             if let Err(e) = write.send(Message::Text(self.base.sub_msg.to_string())).await {
                 error!("Failed to send subscription: {}", e);
+                self.base.set_state(ConnectionState::Error);
+                if !self.base.wait_before_reconnect().await? {
+                    return Ok(());
+                }
                 continue;
             }
             
             info!("Connected to Binance {} {}", self.base.exchange, self.base.market_type);
-            self.base.set_state(ConnectionState::Connected);
-            
-            // Create command channel
+
+            // Create command channel; in place before the connection becomes selectable
             let (command_tx, mut command_rx) = mpsc::unbounded_channel();
             self.base.set_command_tx(command_tx);
+            self.base.set_state(ConnectionState::Connected);
             
             let mut ping_send_timer = Instant::now() + self.ping_interval + self.delay_interval;
+            let mut rtt_probe = time::interval_at(Instant::now() + self.rtt_probe_interval, self.rtt_probe_interval);
+            self.ping_sent_at = None;
             
             loop {
                 tokio::select! {
@@ -127,6 +141,15 @@ impl WsConnectionRunner for BinanceConnection {
                         break; // Reconnect
                     }
                     
+                    // Probe RTT
+                    _ = rtt_probe.tick() => {
+                        if let Err(e) = write.send(Message::Ping(Vec::new())).await {
+                            error!("Failed to send ping: {}", e);
+                            break;
+                        }
+                        self.ping_sent_at = Some(Instant::now());
+                    }
+                    
                     // Handle commands
                     Some(cmd) = command_rx.recv() => {
                         match cmd {
@@ -177,8 +200,10 @@ impl WsConnectionRunner for BinanceConnection {
             }
             
             self.base.set_state(ConnectionState::Disconnected);
-            info!("Binance connection disconnected, will reconnect in 5 seconds");
-            tokio::time::sleep(Duration::from_secs(5)).await;
+            info!("Binance {} connection disconnected, reconnecting", self.base.market_type);
+            if !self.base.wait_before_reconnect().await? {
+                return Ok(());
+            }
         }
     }
 }
\ No newline at end of file
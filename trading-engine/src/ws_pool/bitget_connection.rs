@@ -0,0 +1,257 @@
+use super::connection::{BaseConnection, ConnectionCommand, ConnectionState, WsConnectionRunner};
+use crate::adapters::bitget::BitgetAdapter;
+use crate::adapters::user_data::{UserDataAdapter, UserDataEvent};
+use crate::executor::signer::Signer;
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use tokio::sync::mpsc;
+use tokio::time::{self, Duration, Instant};
+use tokio_tungstenite::{connect_async, tungstenite::{self, Message}};
+use tracing::{debug, error, info, warn};
+
+/// How long to wait for the login ack before reconnecting
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct BitgetConnection {
+    pub base: BaseConnection,
+    signer: Signer,
+    protocol: BitgetAdapter,
+}
+
+impl BitgetConnection {
+    pub fn new(base: BaseConnection, signer: Signer) -> Self {
+        Self {
+            base,
+            signer,
+            protocol: BitgetAdapter::new(),
+        }
+    }
+    
+    /// Trade requests are only accepted on a logged-in connection, so every
+    /// (re)connect logs in and waits for the ack before taking orders
+    async fn login<W, R>(&self, write: &mut W, read: &mut R) -> anyhow::Result<()>
+    where
+        W: Sink<Message, Error = tungstenite::Error> + Unpin,
+        R: Stream<Item = Result<Message, tungstenite::Error>> + Unpin,
+    {
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        let login = self.protocol
+            .login_message(&self.signer, timestamp)
+            .ok_or_else(|| anyhow::anyhow!("no login message"))?;
+        write.send(Message::Text(login)).await?;
+        
+        time::timeout(LOGIN_TIMEOUT, async {
+            while let Some(msg) = read.next().await {
+                let data = match msg? {
+                    Message::Text(text) => text.into_bytes(),
+                    Message::Binary(data) => data,
+                    Message::Close(frame) => anyhow::bail!("closed during login: {:?}", frame),
+                    _ => continue,
+                };
+                for event in self.protocol.parse_user_data(&data).unwrap_or_default() {
+                    match event {
+                        UserDataEvent::Authenticated => return Ok(()),
+                        UserDataEvent::AuthFailed(reason) => anyhow::bail!("login rejected: {}", reason),
+                        _ => {}
+                    }
+                }
+            }
+            anyhow::bail!("connection closed during login")
+        })
+        .await
+        .map_err(|_| anyhow::anyhow!("login timed out"))?
+    }
+    
+    async fn handle_message(&mut self, msg: &Message) -> bool {
+        match msg {
+            Message::Text(text) => {
+                // Skip pong messages
+                if text != "pong" {
+                    let bytes = Bytes::from(text.as_bytes().to_vec());
+                    if let Err(e) = self.base.dispatch_inbound(bytes) {
+                        error!("Failed to send message: {}", e);
+                        return true;
+                    }
+                    
+                    self.base.update_stats(|stats| {
+                        stats.total_messages += 1;
+                        stats.last_message_time = Some(Instant::now());
+                        stats.success_rate = (stats.total_messages as f64) / 
+                            ((stats.total_messages + stats.total_errors) as f64) * 100.0;
+                    });
+                }
+                false
+            }
+            Message::Binary(data) => {
+                let bytes = Bytes::from(data.clone());
+                if let Err(e) = self.base.dispatch_inbound(bytes) {
+                    error!("Failed to send message: {}", e);
+                    return true;
+                }
+                
+                self.base.update_stats(|stats| {
+                    stats.total_messages += 1;
+                    stats.last_message_time = Some(Instant::now());
+                });
+                false
+            }
+            Message::Close(frame) => {
+                warn!("Received close frame: {:?}", frame);
+                true
+            }
+            _ => {
+                debug!("Received other message type");
+                false
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl WsConnectionRunner for BitgetConnection {
+    async fn run(&mut self) -> anyhow::Result<()> {
+        loop {
+            // Connect to WebSocket
+            self.base.set_state(ConnectionState::Connecting);
+            
+            let ws_stream = match connect_async(&self.base.url).await {
+                Ok((ws, _)) => ws,
+                Err(e) => {
+                    error!("Failed to connect: {}", e);
+                    self.base.set_state(ConnectionState::Error);
+                    if !self.base.wait_before_reconnect().await? {
+                        return Ok(());
+                    }
+                    continue;
+                }
+            };
+            
+            let (mut write, mut read) = ws_stream.split();
+            
+            if let Err(e) = self.login(&mut write, &mut read).await {
+                error!("Bitget {} login failed: {}", self.base.market_type, e);
+                self.base.set_state(ConnectionState::Error);
+                if !self.base.wait_before_reconnect().await? {
+                    return Ok(());
+                }
+                continue;
+            }
+            
+            // Send subscription message
+            if let Err(e) = write.send(Message::Text(self.base.sub_msg.to_string())).await {
+                error!("Failed to send subscription: {}", e);
+                self.base.set_state(ConnectionState::Error);
+                if !self.base.wait_before_reconnect().await? {
+                    return Ok(());
+                }
+                continue;
+            }
+            
+            info!("Connected to Bitget {} {}", self.base.exchange, self.base.market_type);
+
+            // Create command channel; in place before the connection becomes selectable
+            let (command_tx, mut command_rx) = mpsc::unbounded_channel();
+            self.base.set_command_tx(command_tx);
+            self.base.set_state(ConnectionState::Connected);
+            
+            // Bitget requires a "ping" text at least every 30 seconds
+            let heartbeat = self.base.heartbeat_interval.min(Duration::from_secs(30));
+            let mut reset_timer = Instant::now() + heartbeat;
+            let mut waiting_pong = false;
+            let mut ping_sent_at = Instant::now();
+            
+            loop {
+                tokio::select! {
+                    // Handle shutdown signal
+                    _ = self.base.shutdown_rx.changed() => {
+                        let should_close = *self.base.shutdown_rx.borrow();
+                        if should_close {
+                            let _ = write.send(Message::Close(None)).await;
+                            return Ok(());
+                        }
+                    }
+                    
+                    // Handle timeout
+                    _ = time::sleep_until(reset_timer) => {
+                        if waiting_pong {
+                            warn!("Bitget {}: Ping timeout, reconnecting...", self.base.market_type);
+                            let _ = write.send(Message::Close(None)).await;
+                            break;
+                        } else {
+                            // Send ping message
+                            if let Err(e) = write.send(Message::Text("ping".to_string())).await {
+                                error!("Failed to send ping: {:?}", e);
+                                break;
+                            }
+                            reset_timer = Instant::now() + heartbeat;
+                            waiting_pong = true;
+                            ping_sent_at = Instant::now();
+                            debug!("Sent ping to Bitget");
+                        }
+                    }
+                    
+                    // Handle commands
+                    Some(cmd) = command_rx.recv() => {
+                        match cmd {
+                            ConnectionCommand::SendMessage(data) => {
+                                // Bitget only accepts trade requests as text frames
+                                let text = String::from_utf8_lossy(&data).into_owned();
+                                if let Err(e) = write.send(Message::Text(text)).await {
+                                    error!("Failed to send message: {}", e);
+                                    break;
+                                }
+                            }
+                            ConnectionCommand::Disconnect => {
+                                let _ = write.send(Message::Close(None)).await;
+                                break;
+                            }
+                        }
+                    }
+                    
+                    // Handle WebSocket messages
+                    Some(msg) = read.next() => {
+                        match msg {
+                            Ok(msg) => {
+                                // Check for pong response
+                                if let Message::Text(ref text) = msg {
+                                    if text == "pong" && waiting_pong {
+                                        waiting_pong = false;
+                                        self.base.record_rtt(ping_sent_at.elapsed());
+                                        reset_timer = Instant::now() + heartbeat;
+                                        debug!("Received pong from Bitget");
+                                        continue;
+                                    }
+                                }
+                                
+                                let should_break = self.handle_message(&msg).await;
+                                if should_break {
+                                    break;
+                                }
+                                
+                                // Reset timer on any message if not waiting for pong
+                                if !waiting_pong {
+                                    reset_timer = Instant::now() + heartbeat;
+                                }
+                            }
+                            Err(e) => {
+                                error!("WebSocket error: {:?}", e);
+                                self.base.update_stats(|stats| {
+                                    stats.total_errors += 1;
+                                    stats.last_error_time = Some(Instant::now());
+                                });
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+            
+            self.base.set_state(ConnectionState::Disconnected);
+            info!("Bitget {} connection disconnected, reconnecting", self.base.market_type);
+            if !self.base.wait_before_reconnect().await? {
+                return Ok(());
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -9,11 +9,15 @@ use tracing::{debug, error, info, warn};
 
 pub struct BybitConnection {
     pub base: BaseConnection,
+    ping_sent_at: Option<Instant>,
 }
 
 impl BybitConnection {
     pub fn new(base: BaseConnection) -> Self {
-        Self { base }
+        Self {
+            base,
+            ping_sent_at: None,
+        }
     }
     
     async fn handle_message(&mut self, msg: &Message) -> bool {
@@ -22,12 +26,12 @@ impl BybitConnection {
                 // Check if it's a pong response
                 if text.contains("\"op\":\"pong\"") || text.contains("\"ret_msg\":\"pong\"") {
                     debug!("Received pong from Bybit");
-                    self.base.update_stats(|stats| {
-                        stats.rtt_ms = 20.0; // Approximate RTT
-                    });
+                    if let Some(sent_at) = self.ping_sent_at.take() {
+                        self.base.record_rtt(sent_at.elapsed());
+                    }
                 } else {
                     let bytes = Bytes::from(text.as_bytes().to_vec());
-                    if let Err(e) = self.base.message_tx.send(bytes) {
+                    if let Err(e) = self.base.dispatch_inbound(bytes) {
                         error!("Failed to send message: {}", e);
                         return true;
                     }
@@ -43,7 +47,7 @@ impl BybitConnection {
             }
             Message::Binary(data) => {
                 let bytes = Bytes::from(data.clone());
-                if let Err(e) = self.base.message_tx.send(bytes) {
+                if let Err(e) = self.base.dispatch_inbound(bytes) {
                     error!("Failed to send message: {}", e);
                     return true;
                 }
@@ -82,7 +86,9 @@ impl WsConnectionRunner for BybitConnection {
                 Err(e) => {
                     error!("Failed to connect: {}", e);
                     self.base.set_state(ConnectionState::Error);
-                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    if !self.base.wait_before_reconnect().await? {
+                        return Ok(());
+                    }
                     continue;
                 }
             };
@@ -92,18 +98,23 @@ impl WsConnectionRunner for BybitConnection {
             // Send subscription message
             if let Err(e) = write.send(Message::Text(self.base.sub_msg.to_string())).await {
                 error!("Failed to send subscription: {}", e);
+                self.base.set_state(ConnectionState::Error);
+                if !self.base.wait_before_reconnect().await? {
+                    return Ok(());
+                }
                 continue;
             }
             
             info!("Connected to Bybit {} {}", self.base.exchange, self.base.market_type);
-            self.base.set_state(ConnectionState::Connected);
-            
-            // Create command channel
+
+            // Create command channel; in place before the connection becomes selectable
             let (command_tx, mut command_rx) = mpsc::unbounded_channel();
             self.base.set_command_tx(command_tx);
+            self.base.set_state(ConnectionState::Connected);
             
             // Bybit requires ping every 20 seconds
-            let mut ping_timer = Instant::now() + Duration::from_secs(20);
+            let heartbeat = self.base.heartbeat_interval.min(Duration::from_secs(20));
+            let mut ping_timer = Instant::now() + heartbeat;
             
             loop {
                 tokio::select! {
@@ -127,7 +138,8 @@ impl WsConnectionRunner for BybitConnection {
                             break;
                         }
                         
-                        ping_timer = Instant::now() + Duration::from_secs(20);
+                        ping_timer = Instant::now() + heartbeat;
+                        self.ping_sent_at = Some(Instant::now());
                         debug!("Sent ping to Bybit");
                     }
                     
@@ -179,8 +191,10 @@ impl WsConnectionRunner for BybitConnection {
             }
             
             self.base.set_state(ConnectionState::Disconnected);
-            info!("Bybit connection disconnected, will reconnect in 5 seconds");
-            tokio::time::sleep(Duration::from_secs(5)).await;
+            info!("Bybit {} connection disconnected, reconnecting", self.base.market_type);
+            if !self.base.wait_before_reconnect().await? {
+                return Ok(());
+            }
         }
     }
 }
\ No newline at end of file
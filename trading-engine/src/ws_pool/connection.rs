@@ -1,11 +1,17 @@
+use super::reconnect::ReconnectPolicy;
+use crate::config::WsPoolConfig;
+use crate::executor::pending::PendingRequests;
+use crate::health::HealthTracker;
 use async_trait::async_trait;
 use bytes::Bytes;
-use tokio::sync::{mpsc, watch};
+use tokio::sync::{broadcast, mpsc, watch};
 use tokio::time::{Duration, Instant};
 use uuid::Uuid;
 use serde_json::Value;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use parking_lot::RwLock;
+use tracing::debug;
 
 #[derive(Debug, Clone)]
 pub enum ConnectionCommand {
@@ -31,6 +37,18 @@ pub enum ConnectionState {
     Error,
 }
 
+/// State change of a pool connection, published for monitoring
+#[derive(Debug, Clone)]
+pub struct ConnectionEvent {
+    pub connection_id: Uuid,
+    pub exchange: String,
+    pub market_type: String,
+    pub state: ConnectionState,
+    /// Reconnect attempts since the connection was last up
+    pub attempt: usize,
+    pub timestamp: i64,
+}
+
 #[derive(Clone)]
 pub struct BaseConnection {
     pub id: Uuid,
@@ -40,9 +58,17 @@ pub struct BaseConnection {
     pub sub_msg: Value,
     pub message_tx: mpsc::UnboundedSender<Bytes>,
     pub shutdown_rx: watch::Receiver<bool>,
-    pub command_tx: Option<mpsc::UnboundedSender<ConnectionCommand>>,
+    // Shared so the pool's copy sees the channel installed by the runner on (re)connect
+    pub command_tx: Arc<RwLock<Option<mpsc::UnboundedSender<ConnectionCommand>>>>,
+    pub pending: Arc<PendingRequests>,
+    pub health: Arc<HealthTracker>,
+    pub events: broadcast::Sender<ConnectionEvent>,
+    pub reconnect: ReconnectPolicy,
+    /// Client heartbeat period; runners cap it at what their venue requires
+    pub heartbeat_interval: Duration,
     pub state: Arc<RwLock<ConnectionState>>,
     pub stats: Arc<RwLock<ConnectionStats>>,
+    reconnect_attempts: Arc<AtomicUsize>,
 }
 
 #[async_trait]
@@ -51,6 +77,7 @@ pub trait WsConnectionRunner: Send {
 }
 
 impl BaseConnection {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         exchange: String,
         market_type: String,
@@ -58,6 +85,10 @@ impl BaseConnection {
         sub_msg: Value,
         message_tx: mpsc::UnboundedSender<Bytes>,
         shutdown_rx: watch::Receiver<bool>,
+        pending: Arc<PendingRequests>,
+        health: Arc<HealthTracker>,
+        events: broadcast::Sender<ConnectionEvent>,
+        config: &WsPoolConfig,
     ) -> Self {
         Self {
             id: Uuid::new_v4(),
@@ -67,7 +98,12 @@ impl BaseConnection {
             sub_msg,
             message_tx,
             shutdown_rx,
-            command_tx: None,
+            command_tx: Arc::new(RwLock::new(None)),
+            pending,
+            health,
+            events,
+            reconnect: ReconnectPolicy::from_config(config),
+            heartbeat_interval: Duration::from_millis(config.heartbeat_interval_ms.max(1000)),
             state: Arc::new(RwLock::new(ConnectionState::Disconnected)),
             stats: Arc::new(RwLock::new(ConnectionStats {
                 total_messages: 0,
@@ -77,15 +113,16 @@ impl BaseConnection {
                 rtt_ms: 0.0,
                 success_rate: 100.0,
             })),
+            reconnect_attempts: Arc::new(AtomicUsize::new(0)),
         }
     }
 
-    pub fn set_command_tx(&mut self, tx: mpsc::UnboundedSender<ConnectionCommand>) {
-        self.command_tx = Some(tx);
+    pub fn set_command_tx(&self, tx: mpsc::UnboundedSender<ConnectionCommand>) {
+        *self.command_tx.write() = Some(tx);
     }
 
     pub fn send_command(&self, command: ConnectionCommand) -> anyhow::Result<()> {
-        if let Some(tx) = &self.command_tx {
+        if let Some(tx) = self.command_tx.read().as_ref() {
             tx.send(command).map_err(|e| anyhow::anyhow!("Failed to send command: {}", e))?;
             Ok(())
         } else {
@@ -113,8 +150,82 @@ impl BaseConnection {
         f(&mut stats);
     }
     
+    /// Take the connection out of (or back into) order selection without touching the socket
+    pub fn set_selectable(&self, selectable: bool) {
+        self.health.set_connected(self.id, selectable);
+    }
+    
     pub fn set_state(&self, state: ConnectionState) {
-        *self.state.write() = state;
+        let previous = std::mem::replace(&mut *self.state.write(), state);
+
+        match state {
+            ConnectionState::Connected => {
+                self.reconnect_attempts.store(0, Ordering::Relaxed);
+                self.health.set_connected(self.id, true);
+            }
+            ConnectionState::Disconnected | ConnectionState::Error => {
+                // Unselectable first, so a retry of a failed order does not pick this socket
+                self.health.set_connected(self.id, false);
+                if state == ConnectionState::Error {
+                    self.health.update_failure(self.id);
+                }
+                // Nothing in flight on a dead socket will be acked
+                self.command_tx.write().take();
+                self.pending.fail_connection(self.id);
+            }
+            ConnectionState::Connecting => {}
+        }
+
+        if previous != state {
+            // No subscribers is fine
+            let _ = self.events.send(ConnectionEvent {
+                connection_id: self.id,
+                exchange: self.exchange.clone(),
+                market_type: self.market_type.clone(),
+                state,
+                attempt: self.reconnect_attempts.load(Ordering::Relaxed),
+                timestamp: chrono::Utc::now().timestamp_millis(),
+            });
+        }
+    }
+
+    /// Back off before the next connect attempt. Returns false if shutdown was
+    /// requested while waiting, and an error once `max_reconnect_attempts`
+    /// attempts in a row have failed.
+    pub async fn wait_before_reconnect(&mut self) -> anyhow::Result<bool> {
+        if *self.shutdown_rx.borrow() {
+            return Ok(false);
+        }
+
+        let attempt = self.reconnect_attempts.fetch_add(1, Ordering::Relaxed) + 1;
+        let Some(delay) = self.reconnect.delay(attempt) else {
+            anyhow::bail!(
+                "{} {}: giving up after {} reconnect attempts",
+                self.exchange, self.market_type, self.reconnect.max_attempts
+            );
+        };
+        debug!("{} {} connection {}: reconnect attempt {} in {:?}", self.exchange, self.market_type, self.id, attempt, delay);
+
+        tokio::select! {
+            _ = self.shutdown_rx.changed() => Ok(!*self.shutdown_rx.borrow()),
+            _ = tokio::time::sleep(delay) => Ok(true),
+        }
+    }
+
+    /// Heartbeat round trip measured by the runner
+    pub fn record_rtt(&self, rtt: Duration) {
+        let rtt_ms = rtt.as_secs_f64() * 1000.0;
+        self.update_stats(|stats| stats.rtt_ms = rtt_ms);
+        self.health.record_rtt(self.id, rtt_ms);
+    }
+
+    /// Route an inbound frame: order acks resolve their in-flight request,
+    /// everything else goes to the pool's message stream.
+    pub fn dispatch_inbound(&self, bytes: Bytes) -> Result<(), mpsc::error::SendError<Bytes>> {
+        if self.pending.resolve(self.id, &self.exchange, &bytes) {
+            return Ok(());
+        }
+        self.message_tx.send(bytes)
     }
 
     pub fn state(&self) -> ConnectionState {
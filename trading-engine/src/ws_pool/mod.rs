@@ -1,13 +1,19 @@
 pub mod connection;
 pub mod pool;
 pub mod message;
+pub mod reconnect;
 pub mod binance_connection;
 pub mod okex_connection;
 pub mod bybit_connection;
+pub mod bitget_connection;
+pub mod user_data_connection;
 
-pub use connection::{BaseConnection, WsConnectionRunner};
+pub use connection::{BaseConnection, ConnectionEvent, ConnectionState, WsConnectionRunner};
+pub use reconnect::ReconnectPolicy;
 pub use pool::WsPool;
 pub use message::WsMessage;
 pub use binance_connection::BinanceConnection;
 pub use okex_connection::OkexConnection;
-pub use bybit_connection::BybitConnection;
\ No newline at end of file
+pub use bybit_connection::BybitConnection;
+pub use bitget_connection::BitgetConnection;
+pub use user_data_connection::UserDataConnection;
\ No newline at end of file
@@ -22,7 +22,7 @@ impl OkexConnection {
                 // Skip pong messages
                 if text != "pong" {
                     let bytes = Bytes::from(text.as_bytes().to_vec());
-                    if let Err(e) = self.base.message_tx.send(bytes) {
+                    if let Err(e) = self.base.dispatch_inbound(bytes) {
                         error!("Failed to send message: {}", e);
                         return true;
                     }
@@ -38,7 +38,7 @@ impl OkexConnection {
             }
             Message::Binary(data) => {
                 let bytes = Bytes::from(data.clone());
-                if let Err(e) = self.base.message_tx.send(bytes) {
+                if let Err(e) = self.base.dispatch_inbound(bytes) {
                     error!("Failed to send message: {}", e);
                     return true;
                 }
@@ -73,7 +73,9 @@ impl WsConnectionRunner for OkexConnection {
                 Err(e) => {
                     error!("Failed to connect: {}", e);
                     self.base.set_state(ConnectionState::Error);
-                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    if !self.base.wait_before_reconnect().await? {
+                        return Ok(());
+                    }
                     continue;
                 }
             };
@@ -83,19 +85,25 @@ impl WsConnectionRunner for OkexConnection {
             // Send subscription message
             if let Err(e) = write.send(Message::Text(self.base.sub_msg.to_string())).await {
                 error!("Failed to send subscription: {}", e);
+                self.base.set_state(ConnectionState::Error);
+                if !self.base.wait_before_reconnect().await? {
+                    return Ok(());
+                }
                 continue;
             }
             
             info!("Connected to OKEx {} {}", self.base.exchange, self.base.market_type);
-            self.base.set_state(ConnectionState::Connected);
-            
-            // Create command channel
+
+            // Create command channel; in place before the connection becomes selectable
             let (command_tx, mut command_rx) = mpsc::unbounded_channel();
             self.base.set_command_tx(command_tx);
+            self.base.set_state(ConnectionState::Connected);
             
             // OKEx requires ping every 25 seconds
-            let mut reset_timer = Instant::now() + Duration::from_secs(25);
+            let heartbeat = self.base.heartbeat_interval.min(Duration::from_secs(25));
+            let mut reset_timer = Instant::now() + heartbeat;
             let mut waiting_pong = false;
+            let mut ping_sent_at = Instant::now();
             
             loop {
                 tokio::select! {
@@ -120,8 +128,9 @@ impl WsConnectionRunner for OkexConnection {
                                 error!("Failed to send ping: {:?}", e);
                                 break;
                             }
-                            reset_timer = Instant::now() + Duration::from_secs(25);
+                            reset_timer = Instant::now() + heartbeat;
                             waiting_pong = true;
+                            ping_sent_at = Instant::now();
                             debug!("Sent ping to OKEx");
                         }
                     }
@@ -150,7 +159,8 @@ impl WsConnectionRunner for OkexConnection {
                                 if let Message::Text(ref text) = msg {
                                     if text == "pong" && waiting_pong {
                                         waiting_pong = false;
-                                        reset_timer = Instant::now() + Duration::from_secs(25);
+                                        self.base.record_rtt(ping_sent_at.elapsed());
+                                        reset_timer = Instant::now() + heartbeat;
                                         debug!("Received pong from OKEx");
                                         continue;
                                     }
@@ -163,7 +173,7 @@ impl WsConnectionRunner for OkexConnection {
                                 
                                 // Reset timer on any message if not waiting for pong
                                 if !waiting_pong {
-                                    reset_timer = Instant::now() + Duration::from_secs(25);
+                                    reset_timer = Instant::now() + heartbeat;
                                 }
                             }
                             Err(e) => {
@@ -180,8 +190,10 @@ impl WsConnectionRunner for OkexConnection {
             }
             
             self.base.set_state(ConnectionState::Disconnected);
-            info!("OKEx connection disconnected, will reconnect in 5 seconds");
-            tokio::time::sleep(Duration::from_secs(5)).await;
+            info!("OKEx {} connection disconnected, reconnecting", self.base.market_type);
+            if !self.base.wait_before_reconnect().await? {
+                return Ok(());
+            }
         }
     }
 }
\ No newline at end of file
@@ -1,12 +1,19 @@
-use super::connection::{BaseConnection, ConnectionCommand, ConnectionState};
-use super::{BinanceConnection, OkexConnection, BybitConnection, WsConnectionRunner};
+use super::connection::{BaseConnection, ConnectionCommand, ConnectionEvent, ConnectionState};
+use super::reconnect::ReconnectPolicy;
+use super::{BinanceConnection, OkexConnection, BybitConnection, BitgetConnection, UserDataConnection, WsConnectionRunner};
 use super::message::WsMessage;
+use crate::adapters::ExchangeAdapter;
 use crate::config::{ExchangeConfig, TradingEngineConfig, WsPoolConfig};
+use crate::executor::pending::PendingRequests;
+use crate::executor::reports::ReportNormalizer;
+use crate::executor::signer::Signer;
+use crate::health::HealthTracker;
+use common::types::ExecutionReport;
 use bytes::Bytes;
 use dashmap::DashMap;
 use std::sync::Arc;
-use tokio::sync::{mpsc, watch};
-use tokio::time::{self, Duration};
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::time::{self, Duration, Instant};
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 use serde_json::Value;
@@ -19,12 +26,34 @@ pub struct WsPool {
     shutdown_tx: watch::Sender<bool>,
     shutdown_rx: watch::Receiver<bool>,
     exchanges: Arc<DashMap<String, ExchangeConfig>>,
+    pending: Arc<PendingRequests>,
+    health: Arc<HealthTracker>,
+    events_tx: broadcast::Sender<ConnectionEvent>,
 }
 
+/// Credentials of an exchange account, with the passphrase where the venue needs one
+fn exchange_signer(config: &ExchangeConfig) -> Signer {
+    match &config.passphrase {
+        Some(passphrase) => Signer::with_passphrase(
+            config.api_key.clone(),
+            config.secret_key.clone(),
+            passphrase.clone(),
+        ),
+        None => Signer::new(config.api_key.clone(), config.secret_key.clone()),
+    }
+}
+
+/// How long a rolling restart waits for a connection to come back before moving on
+const RESTART_SETTLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long a rolling restart waits for in-flight requests to be acked before disconnecting
+const RESTART_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
 impl WsPool {
-    pub fn new(config: TradingEngineConfig) -> Self {
+    pub fn new(config: TradingEngineConfig, health: Arc<HealthTracker>) -> Self {
         let (message_tx, message_rx) = mpsc::unbounded_channel();
         let (shutdown_tx, shutdown_rx) = watch::channel(false);
+        let (events_tx, _) = broadcast::channel(256);
         
         let exchanges = Arc::new(DashMap::new());
         for (name, exchange_config) in config.exchanges {
@@ -41,9 +70,22 @@ impl WsPool {
             shutdown_tx,
             shutdown_rx,
             exchanges,
+            pending: Arc::new(PendingRequests::new(Arc::new(ExchangeAdapter::new()))),
+            health,
+            events_tx,
         }
     }
 
+    /// Connection state changes, for monitoring; slow subscribers lose the oldest events
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.events_tx.subscribe()
+    }
+
+    /// Registry the executor uses to await acks for orders sent through this pool
+    pub fn pending_requests(&self) -> Arc<PendingRequests> {
+        self.pending.clone()
+    }
+
     pub async fn start(&mut self) -> anyhow::Result<()> {
         info!("Starting WebSocket pool");
         
@@ -93,6 +135,63 @@ impl WsPool {
         Ok(())
     }
 
+    /// Open one authenticated user-data stream per enabled exchange and market type.
+    /// Returns the number of streams started.
+    pub fn start_user_data_streams(
+        &self,
+        normalizer: Arc<ReportNormalizer>,
+        report_tx: mpsc::Sender<ExecutionReport>,
+    ) -> usize {
+        let mut started = 0;
+        
+        for entry in self.exchanges.iter() {
+            let exchange_name = entry.key().clone();
+            let exchange_config = entry.value();
+            
+            let Some(protocol) = self.pending.adapters()
+                .get_adapter(&exchange_name)
+                .and_then(|adapter| adapter.user_data()) else {
+                warn!("{} has no user-data stream, fills will not be reported", exchange_name);
+                continue;
+            };
+            
+            let signer = exchange_signer(exchange_config);
+            
+            for (market_type, endpoint) in [("spot", &exchange_config.spot), ("futures", &exchange_config.futures)] {
+                if !endpoint.enabled {
+                    continue;
+                }
+                
+                let url = endpoint.user_data_endpoint
+                    .clone()
+                    .unwrap_or_else(|| protocol.user_data_url(market_type));
+                
+                let mut connection = UserDataConnection::new(
+                    exchange_name.clone(),
+                    market_type.to_string(),
+                    url,
+                    signer.clone(),
+                    self.pending.adapters().clone(),
+                    normalizer.clone(),
+                    report_tx.clone(),
+                    self.shutdown_rx.clone(),
+                    ReconnectPolicy::from_config(&self.config),
+                );
+                let name = exchange_name.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = connection.run().await {
+                        error!("{} {} user-data stream error: {}", name, market_type, e);
+                    }
+                });
+                
+                info!("Started user-data stream for {} {}", exchange_name, market_type);
+                started += 1;
+            }
+        }
+        
+        started
+    }
+
     async fn create_connection(
         &self,
         exchange: String,
@@ -107,45 +206,45 @@ impl WsPool {
             sub_msg,
             self.message_tx.clone(),
             self.shutdown_rx.clone(),
+            self.pending.clone(),
+            self.health.clone(),
+            self.events_tx.clone(),
+            &self.config,
         );
         
         let id = base.id;
         
-        // Store base connection for tracking
-        self.connections.insert(id, base.clone());
-        
-        // Create and spawn the appropriate connection runner
-        match exchange.as_str() {
-            "binance" => {
-                let mut connection = BinanceConnection::new(base);
-                tokio::spawn(async move {
-                    if let Err(e) = connection.run().await {
-                        error!("Binance connection error: {}", e);
-                    }
-                });
-            }
-            "okex" => {
-                let mut connection = OkexConnection::new(base);
-                tokio::spawn(async move {
-                    if let Err(e) = connection.run().await {
-                        error!("OKEx connection error: {}", e);
-                    }
-                });
-            }
-            "bybit" => {
-                let mut connection = BybitConnection::new(base);
-                tokio::spawn(async move {
-                    if let Err(e) = connection.run().await {
-                        error!("Bybit connection error: {}", e);
-                    }
-                });
-            }
-            _ => {
-                self.connections.remove(&id);
-                return Err(anyhow::anyhow!("Unsupported exchange: {}", exchange));
+        // Create the appropriate connection runner
+        let mut connection: Box<dyn WsConnectionRunner> = match exchange.as_str() {
+            "binance" => Box::new(BinanceConnection::new(base.clone())),
+            "okex" => Box::new(OkexConnection::new(base.clone())),
+            "bybit" => Box::new(BybitConnection::new(base.clone())),
+            "bitget" => {
+                let signer = self.exchanges
+                    .get(&exchange)
+                    .map(|config| exchange_signer(&config))
+                    .ok_or_else(|| anyhow::anyhow!("No config for exchange: {}", exchange))?;
+                Box::new(BitgetConnection::new(base.clone(), signer))
             }
+            _ => return Err(anyhow::anyhow!("Unsupported exchange: {}", exchange)),
         };
         
+        // Store base connection for tracking; selectable once the runner reports Connected
+        self.connections.insert(id, base);
+        self.health.register_connection(id, exchange.clone(), market_type.clone());
+        
+        let connections = self.connections.clone();
+        let health = self.health.clone();
+        let name = format!("{} {}", exchange, market_type);
+        tokio::spawn(async move {
+            if let Err(e) = connection.run().await {
+                error!("{} connection {} error: {}", name, id, e);
+            }
+            // Runners only return on shutdown or after giving up on reconnecting
+            connections.remove(&id);
+            health.remove_connection(id);
+        });
+        
         info!("Created connection {} for {} {}", id, exchange, market_type);
         Ok(id)
     }
@@ -166,19 +265,34 @@ impl WsPool {
         });
     }
 
+    /// Runners reconnect on their own with `ReconnectPolicy` backoff. This
+    /// restarts every connection periodically, one at a time per exchange and
+    /// market type, and never the last connected one of a group, so the
+    /// redundant feeds are never all down at once.
     fn start_reconnection_manager(&self) {
-        // Reconnection is handled automatically by the connection runners
-        // This method can be enhanced to manage reconnection centrally if needed
-        let reconnect_delay_ms = self.config.reconnect_delay_ms;
-        let max_attempts = self.config.max_reconnect_attempts;
+        if self.config.rolling_restart_interval_ms == 0 {
+            info!("Rolling restarts disabled");
+            return;
+        }
+        
+        let period = Duration::from_millis(self.config.rolling_restart_interval_ms);
+        let connections = self.connections.clone();
+        let mut shutdown_rx = self.shutdown_rx.clone();
         
         tokio::spawn(async move {
-            let mut interval = time::interval(Duration::from_millis(5000));
+            let mut interval = time::interval_at(Instant::now() + period, period);
             
             loop {
-                interval.tick().await;
-                // Connection runners handle their own reconnection
-                debug!("Reconnection check interval triggered");
+                tokio::select! {
+                    _ = shutdown_rx.changed() => {
+                        if *shutdown_rx.borrow() {
+                            return;
+                        }
+                    }
+                    _ = interval.tick() => {
+                        rolling_restart(&connections).await;
+                    }
+                }
             }
         });
     }
@@ -237,6 +351,12 @@ impl WsPool {
                     "args": []
                 })
             }
+            "bitget" => {
+                serde_json::json!({
+                    "op": "subscribe",
+                    "args": []
+                })
+            }
             _ => serde_json::json!({})
         }
     }
@@ -252,4 +372,140 @@ impl WsPool {
         
         self.connections.clear();
     }
-}
\ No newline at end of file
+}
+
+async fn rolling_restart(connections: &DashMap<Uuid, BaseConnection>) {
+    let mut ids: Vec<(String, String, Uuid)> = connections
+        .iter()
+        .map(|entry| (entry.exchange.clone(), entry.market_type.clone(), *entry.key()))
+        .collect();
+    ids.sort();
+    
+    info!("Rolling restart of {} connections", ids.len());
+    
+    for (exchange, market_type, id) in ids {
+        let Some(connection) = connections.get(&id).map(|entry| entry.clone()) else {
+            continue;
+        };
+        if connection.state() != ConnectionState::Connected {
+            continue;
+        }
+        
+        let live_siblings = connections
+            .iter()
+            .filter(|entry| {
+                *entry.key() != id
+                    && entry.exchange == exchange
+                    && entry.market_type == market_type
+                    && entry.state() == ConnectionState::Connected
+            })
+            .count();
+        if live_siblings == 0 {
+            debug!("Skipping restart of {}: last connected {} {} connection", id, exchange, market_type);
+            continue;
+        }
+        
+        // Stop routing new orders to it and let the ones in flight get their acks
+        connection.set_selectable(false);
+        let deadline = Instant::now() + RESTART_DRAIN_TIMEOUT;
+        while connection.pending.inflight_count(id) > 0 && Instant::now() < deadline {
+            time::sleep(Duration::from_millis(10)).await;
+        }
+        let inflight = connection.pending.inflight_count(id);
+        if inflight > 0 {
+            warn!("Restarting connection {} with {} requests still in flight", id, inflight);
+        }
+        
+        if let Err(e) = connection.send_command(ConnectionCommand::Disconnect) {
+            warn!("Failed to restart connection {}: {}", id, e);
+            if connection.state() == ConnectionState::Connected {
+                connection.set_selectable(true);
+            }
+            continue;
+        }
+        
+        // Wait for it to come back before touching the next one
+        let deadline = Instant::now() + RESTART_SETTLE_TIMEOUT;
+        time::sleep(Duration::from_millis(100)).await;
+        while connection.state() != ConnectionState::Connected && Instant::now() < deadline {
+            time::sleep(Duration::from_millis(100)).await;
+        }
+        if connection.state() != ConnectionState::Connected {
+            warn!("Connection {} ({} {}) not back after restart", id, exchange, market_type);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool_config() -> WsPoolConfig {
+        WsPoolConfig {
+            max_connections_per_exchange: 4,
+            heartbeat_interval_ms: 30_000,
+            reconnect_delay_ms: 1000,
+            max_reconnect_delay_ms: 30_000,
+            max_reconnect_attempts: 0,
+            rolling_restart_interval_ms: 0,
+            health_check_interval_ms: 1000,
+            message_buffer_size: 16,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rolling_restart_drains_in_flight_requests() {
+        let config = pool_config();
+        let health = Arc::new(HealthTracker::new());
+        let pending = Arc::new(PendingRequests::new(Arc::new(ExchangeAdapter::new())));
+        let (message_tx, _message_rx) = mpsc::unbounded_channel();
+        let (_shutdown_tx, shutdown_rx) = watch::channel(false);
+        let (events_tx, _) = broadcast::channel(16);
+
+        let connections = Arc::new(DashMap::new());
+        let mut commands = Vec::new();
+        for _ in 0..2 {
+            let base = BaseConnection::new(
+                "binance".to_string(),
+                "futures".to_string(),
+                "ws://mock".to_string(),
+                Value::Null,
+                message_tx.clone(),
+                shutdown_rx.clone(),
+                pending.clone(),
+                health.clone(),
+                events_tx.clone(),
+                &config,
+            );
+            health.register_connection(base.id, "binance".to_string(), "futures".to_string());
+            let (command_tx, command_rx) = mpsc::unbounded_channel();
+            base.set_command_tx(command_tx);
+            base.set_state(ConnectionState::Connected);
+            commands.push((base.id, command_rx));
+            connections.insert(base.id, base);
+        }
+        // Restarts go in id order
+        commands.sort_by_key(|(id, _)| *id);
+        let (first, first_commands) = &mut commands[0];
+        let first = *first;
+        let _ack = pending.register(first, "TE_1");
+
+        let restarting = connections.clone();
+        let restart = tokio::spawn(async move { rolling_restart(&restarting).await });
+
+        // Out of selection, but kept open while its order waits for an ack
+        time::sleep(Duration::from_millis(100)).await;
+        let selectable: Vec<Uuid> = health
+            .get_top_k_connections("binance", "futures", 2)
+            .into_iter()
+            .map(|m| m.connection_id)
+            .collect();
+        assert!(!selectable.contains(&first));
+        assert!(first_commands.try_recv().is_err());
+
+        pending.cancel(first, "TE_1");
+        let command = time::timeout(Duration::from_secs(1), first_commands.recv()).await.unwrap();
+        assert!(matches!(command, Some(ConnectionCommand::Disconnect)));
+        restart.abort();
+    }
+}
@@ -0,0 +1,68 @@
+use crate::config::WsPoolConfig;
+use std::time::Duration;
+
+/// Exponential backoff with jitter, bounded by `WsPoolConfig`.
+///
+/// The delay doubles from `reconnect_delay_ms` up to `max_reconnect_delay_ms`;
+/// half of it is randomized so redundant connections that dropped together do
+/// not come back (and fail) in lockstep.
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Consecutive attempts before giving up; 0 retries forever
+    pub max_attempts: usize,
+}
+
+impl ReconnectPolicy {
+    pub fn from_config(config: &WsPoolConfig) -> Self {
+        let base_delay = Duration::from_millis(config.reconnect_delay_ms);
+        Self {
+            base_delay,
+            max_delay: Duration::from_millis(config.max_reconnect_delay_ms).max(base_delay),
+            max_attempts: config.max_reconnect_attempts,
+        }
+    }
+
+    /// Delay before the 1-based `attempt`, or None once attempts are exhausted
+    pub fn delay(&self, attempt: usize) -> Option<Duration> {
+        if self.max_attempts != 0 && attempt > self.max_attempts {
+            return None;
+        }
+
+        let exponent = attempt.saturating_sub(1).min(16) as u32;
+        let ceiling = self.base_delay.saturating_mul(1 << exponent).min(self.max_delay);
+        let half = ceiling / 2;
+        Some(half + half.mul_f64(rand::random::<f64>()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(max_attempts: usize) -> ReconnectPolicy {
+        ReconnectPolicy {
+            base_delay: Duration::from_millis(1000),
+            max_delay: Duration::from_millis(8000),
+            max_attempts,
+        }
+    }
+
+    #[test]
+    fn test_delay_doubles_within_jitter_bounds() {
+        let policy = policy(0);
+        for (attempt, ceiling_ms) in [(1, 1000), (2, 2000), (3, 4000), (4, 8000), (5, 8000), (40, 8000)] {
+            let delay = policy.delay(attempt).unwrap();
+            assert!(delay >= Duration::from_millis(ceiling_ms / 2), "attempt {}: {:?}", attempt, delay);
+            assert!(delay <= Duration::from_millis(ceiling_ms), "attempt {}: {:?}", attempt, delay);
+        }
+    }
+
+    #[test]
+    fn test_gives_up_after_max_attempts() {
+        let policy = policy(3);
+        assert!(policy.delay(3).is_some());
+        assert!(policy.delay(4).is_none());
+    }
+}
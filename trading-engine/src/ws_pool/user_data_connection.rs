@@ -0,0 +1,258 @@
+use super::reconnect::ReconnectPolicy;
+use crate::adapters::{ExchangeAdapter, OpenOrders, UserDataAdapter, UserDataEvent};
+use crate::executor::reports::ReportNormalizer;
+use crate::executor::signer::Signer;
+use common::types::ExecutionReport;
+use futures_util::{SinkExt, StreamExt};
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch};
+use tokio::time::{self, Duration, Instant};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, info, warn};
+
+const AUTH_TIMEOUT: Duration = Duration::from_secs(10);
+/// Timer period for venues without a heartbeat or listen key; the branch is disabled anyway
+const IDLE_PERIOD: Duration = Duration::from_secs(3600);
+
+/// One authenticated private stream for an exchange and market type.
+///
+/// Order updates are normalized to `ExecutionReport`s and forwarded to
+/// `report_tx`, with amend pushes told apart from acks by the orders seen so
+/// far; the session reconnects (and re-authenticates) on any failure,
+/// backing off per the pool's policy but never giving up.
+pub struct UserDataConnection {
+    exchange: String,
+    market_type: String,
+    url: String,
+    signer: Signer,
+    adapters: Arc<ExchangeAdapter>,
+    normalizer: Arc<ReportNormalizer>,
+    report_tx: mpsc::Sender<ExecutionReport>,
+    shutdown_rx: watch::Receiver<bool>,
+    reconnect: ReconnectPolicy,
+    attempt: usize,
+    open_orders: OpenOrders,
+}
+
+impl UserDataConnection {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        exchange: String,
+        market_type: String,
+        url: String,
+        signer: Signer,
+        adapters: Arc<ExchangeAdapter>,
+        normalizer: Arc<ReportNormalizer>,
+        report_tx: mpsc::Sender<ExecutionReport>,
+        shutdown_rx: watch::Receiver<bool>,
+        reconnect: ReconnectPolicy,
+    ) -> Self {
+        Self {
+            exchange,
+            market_type,
+            url,
+            signer,
+            adapters,
+            normalizer,
+            report_tx,
+            shutdown_rx,
+            reconnect: ReconnectPolicy {
+                max_attempts: 0,
+                ..reconnect
+            },
+            attempt: 0,
+            open_orders: OpenOrders::new(),
+        }
+    }
+
+    pub async fn run(&mut self) -> anyhow::Result<()> {
+        let adapters = self.adapters.clone();
+        let protocol = adapters
+            .get_adapter(&self.exchange)
+            .and_then(|adapter| adapter.user_data())
+            .ok_or_else(|| anyhow::anyhow!("{} has no user-data stream", self.exchange))?;
+
+        loop {
+            let error = match self.run_session(protocol).await {
+                Ok(()) => return Ok(()),
+                Err(e) => e,
+            };
+
+            self.attempt += 1;
+            let delay = self.reconnect.delay(self.attempt).unwrap_or(self.reconnect.max_delay);
+            warn!(
+                "User-data stream {} {} failed: {}, reconnecting in {:?}",
+                self.exchange, self.market_type, error, delay
+            );
+
+            tokio::select! {
+                _ = self.shutdown_rx.changed() => {
+                    if *self.shutdown_rx.borrow() {
+                        return Ok(());
+                    }
+                }
+                _ = time::sleep(delay) => {}
+            }
+        }
+    }
+
+    /// Returns Ok only on shutdown; every other exit is an error that triggers a reconnect
+    async fn run_session(&mut self, protocol: &dyn UserDataAdapter) -> anyhow::Result<()> {
+        let listen_key_request = protocol.listen_key_request(&self.signer, &self.market_type);
+
+        let url = match &listen_key_request {
+            Some((api_url, request)) => {
+                let listen_key = fetch_listen_key(protocol, api_url, request).await?;
+                format!("{}/{}", self.url.trim_end_matches('/'), listen_key)
+            }
+            None => self.url.clone(),
+        };
+
+        let (ws_stream, _) = connect_async(&url).await?;
+        let (mut write, mut read) = ws_stream.split();
+
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        if let Some(login) = protocol.login_message(&self.signer, timestamp) {
+            write.send(Message::Text(login)).await?;
+            time::timeout(AUTH_TIMEOUT, async {
+                while let Some(msg) = read.next().await {
+                    let data = match msg? {
+                        Message::Text(text) => text.into_bytes(),
+                        Message::Binary(data) => data,
+                        _ => continue,
+                    };
+                    for event in protocol.parse_user_data(&data).unwrap_or_default() {
+                        match event {
+                            UserDataEvent::Authenticated => return Ok(()),
+                            UserDataEvent::AuthFailed(reason) => anyhow::bail!("login rejected: {}", reason),
+                            _ => {}
+                        }
+                    }
+                }
+                anyhow::bail!("connection closed during login")
+            })
+            .await
+            .map_err(|_| anyhow::anyhow!("login timed out"))??;
+        }
+
+        for msg in protocol.subscribe_messages(&self.market_type) {
+            write.send(Message::Text(msg)).await?;
+        }
+
+        info!("User-data stream connected: {} {}", self.exchange, self.market_type);
+        self.attempt = 0;
+
+        let heartbeat = protocol.heartbeat();
+        let heartbeat_period = heartbeat.as_ref().map(|(period, _)| *period).unwrap_or(IDLE_PERIOD);
+        let mut heartbeat_timer = time::interval_at(Instant::now() + heartbeat_period, heartbeat_period);
+
+        let keepalive = listen_key_request.as_ref().and(protocol.listen_key_keepalive());
+        let keepalive_period = keepalive.unwrap_or(IDLE_PERIOD);
+        let mut keepalive_timer = time::interval_at(Instant::now() + keepalive_period, keepalive_period);
+
+        loop {
+            tokio::select! {
+                _ = self.shutdown_rx.changed() => {
+                    if *self.shutdown_rx.borrow() {
+                        let _ = write.send(Message::Close(None)).await;
+                        return Ok(());
+                    }
+                }
+
+                _ = heartbeat_timer.tick(), if heartbeat.is_some() => {
+                    if let Some((_, ping)) = &heartbeat {
+                        write.send(Message::Text(ping.clone())).await?;
+                    }
+                }
+
+                _ = keepalive_timer.tick(), if keepalive.is_some() => {
+                    // A failed keepalive is retried next period; the stream reports expiry itself
+                    if let Some((api_url, request)) = &listen_key_request {
+                        if let Err(e) = fetch_listen_key(protocol, api_url, request).await {
+                            warn!("Listen key keepalive failed for {} {}: {}", self.exchange, self.market_type, e);
+                        }
+                    }
+                }
+
+                msg = read.next() => {
+                    let data = match msg {
+                        Some(Ok(Message::Text(text))) => text.into_bytes(),
+                        Some(Ok(Message::Binary(data))) => data,
+                        Some(Ok(Message::Ping(payload))) => {
+                            write.send(Message::Pong(payload)).await?;
+                            continue;
+                        }
+                        Some(Ok(Message::Close(frame))) => anyhow::bail!("closed by server: {:?}", frame),
+                        Some(Ok(_)) => continue,
+                        Some(Err(e)) => return Err(e.into()),
+                        None => anyhow::bail!("stream ended"),
+                    };
+                    self.handle_message(protocol, &data).await?;
+                }
+            }
+        }
+    }
+
+    async fn handle_message(&mut self, protocol: &dyn UserDataAdapter, data: &[u8]) -> anyhow::Result<()> {
+        let events = match protocol.parse_user_data(data) {
+            Ok(events) => events,
+            Err(e) => {
+                debug!("Unparsable user-data frame from {}: {}", self.exchange, e);
+                return Ok(());
+            }
+        };
+
+        for event in events {
+            match event {
+                UserDataEvent::Order(mut update) => {
+                    if !self.open_orders.track(&mut update) {
+                        debug!("{} {} repeated ack for order {}", self.exchange, self.market_type, update.order_id);
+                        continue;
+                    }
+                    let Some(report) = self.normalizer.report(&self.exchange, &self.market_type, &update) else {
+                        continue;
+                    };
+                    debug!(
+                        "{} {} order {} {:?}",
+                        self.exchange, self.market_type, report.client_order_id, report.execution_type
+                    );
+                    if self.report_tx.send(report).await.is_err() {
+                        anyhow::bail!("report channel closed");
+                    }
+                }
+                UserDataEvent::AuthFailed(reason) => anyhow::bail!("session rejected: {}", reason),
+                UserDataEvent::Authenticated | UserDataEvent::ListenKey(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Run a listen key request on the venue's WS API and wait for the key
+async fn fetch_listen_key(protocol: &dyn UserDataAdapter, api_url: &str, request: &str) -> anyhow::Result<String> {
+    let (mut ws_stream, _) = connect_async(api_url).await?;
+    ws_stream.send(Message::Text(request.to_string())).await?;
+
+    let listen_key = time::timeout(AUTH_TIMEOUT, async {
+        while let Some(msg) = ws_stream.next().await {
+            let data = match msg? {
+                Message::Text(text) => text.into_bytes(),
+                Message::Binary(data) => data,
+                _ => continue,
+            };
+            for event in protocol.parse_user_data(&data).unwrap_or_default() {
+                match event {
+                    UserDataEvent::ListenKey(key) => return Ok(key),
+                    UserDataEvent::AuthFailed(reason) => anyhow::bail!("listen key rejected: {}", reason),
+                    _ => {}
+                }
+            }
+        }
+        anyhow::bail!("connection closed before listen key arrived")
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("listen key request timed out"))?;
+
+    let _ = ws_stream.close(None).await;
+    listen_key
+}
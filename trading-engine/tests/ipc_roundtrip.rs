@@ -0,0 +1,286 @@
+use common::instrument::InstrumentId;
+use common::wakeup::{TopicNotifier, WakeupConfig};
+use iceoryx2::prelude::*;
+use rust_decimal::Decimal;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use trading_engine::config::IpcConfig;
+use trading_engine::executor::types::{
+    CommandAction, ExecutionCommand, ExecutionResult, OrderResponse, OrderSide, OrderStatus,
+    OrderType, TimeInForce,
+};
+use trading_engine::ipc::codec::{self, IpcFrame, WIRE_VERSION};
+use trading_engine::ipc::IpcManager;
+use uuid::Uuid;
+
+const BUFFER_SIZE: usize = 16;
+
+fn test_config(tag: &str) -> IpcConfig {
+    let suffix = format!("{}_{}", tag, std::process::id());
+    IpcConfig {
+        service_name: format!("te_test_{}", suffix),
+        input_topic: format!("te_test_commands_{}", suffix),
+        output_topic: format!("te_test_results_{}", suffix),
+        buffer_size: BUFFER_SIZE,
+        report_topic: format!("te_test_reports_{}", suffix),
+        wakeup: WakeupConfig::default(),
+        max_in_flight: BUFFER_SIZE,
+    }
+}
+
+fn sample_command(i: u32) -> ExecutionCommand {
+    ExecutionCommand {
+        id: Uuid::new_v4(),
+        instrument: InstrumentId::new(2, 1 + i),
+        exchange: "binance".to_string(),
+        market_type: "futures".to_string(),
+        symbol: "BTCUSDT".to_string(),
+        side: if i % 2 == 1 { OrderSide::Sell } else { OrderSide::Buy },
+        order_type: OrderType::Limit,
+        quantity: Decimal::new(1 + i as i64, 3),
+        price: Some(Decimal::new(6500012, 2)),
+        time_in_force: TimeInForce::GTC,
+        client_order_id: Some(format!("TE_{}", i)),
+        reduce_only: false,
+        post_only: i % 2 == 1,
+        action: CommandAction::Place,
+    }
+}
+
+fn filled_result(command: &ExecutionCommand) -> ExecutionResult {
+    let response = OrderResponse {
+        order_id: format!("ex-{}", command.id.simple()),
+        client_order_id: command.client_order_id.clone().unwrap_or_default(),
+        symbol: command.symbol.clone(),
+        status: OrderStatus::Filled,
+        executed_qty: command.quantity,
+        executed_price: command.price,
+        timestamp: 1_700_000_000_000,
+        error: None,
+    };
+    ExecutionResult {
+        command_id: command.id,
+        success: true,
+        outcome_unknown: false,
+        responses: vec![response.clone()],
+        selected_response: Some(response),
+        error: None,
+    }
+}
+
+/// Open the peer side (pre-post-processor's view) with the same service settings as the engine
+fn open_peer(
+    node: &Node<ipc::Service>,
+    config: &IpcConfig,
+) -> (
+    iceoryx2::port::publisher::Publisher<ipc::Service, IpcFrame, ()>,
+    iceoryx2::port::subscriber::Subscriber<ipc::Service, IpcFrame, ()>,
+) {
+    let commands = node
+        .service_builder(&ServiceName::new(&config.input_topic).unwrap())
+        .publish_subscribe::<IpcFrame>()
+        .subscriber_max_buffer_size(config.buffer_size)
+        .enable_safe_overflow(false)
+        .open_or_create()
+        .unwrap();
+    let results = node
+        .service_builder(&ServiceName::new(&config.output_topic).unwrap())
+        .publish_subscribe::<IpcFrame>()
+        .subscriber_max_buffer_size(config.buffer_size)
+        .enable_safe_overflow(false)
+        .open_or_create()
+        .unwrap();
+
+    (
+        commands.publisher_builder().create().unwrap(),
+        results
+            .subscriber_builder()
+            .buffer_size(config.buffer_size)
+            .create()
+            .unwrap(),
+    )
+}
+
+async fn recv_command(rx: &mut mpsc::Receiver<ExecutionCommand>) -> ExecutionCommand {
+    tokio::time::timeout(Duration::from_secs(5), rx.recv())
+        .await
+        .expect("timed out waiting for command")
+        .expect("command channel closed")
+}
+
+fn recv_result(
+    subscriber: &iceoryx2::port::subscriber::Subscriber<ipc::Service, IpcFrame, ()>,
+) -> ExecutionResult {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    loop {
+        if let Some(sample) = subscriber.receive().unwrap() {
+            return codec::decode_result(sample.payload()).expect("undecodable result");
+        }
+        assert!(Instant::now() < deadline, "timed out waiting for result");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn commands_in_results_out() {
+    let config = test_config("roundtrip");
+    let (command_tx, mut command_rx) = mpsc::channel(BUFFER_SIZE);
+
+    let mut manager = IpcManager::new(config.clone(), command_tx).unwrap();
+    manager.initialize().unwrap();
+    manager.start().await.unwrap();
+    let result_tx = manager.result_sender();
+
+    let node = NodeBuilder::new().create::<ipc::Service>().unwrap();
+    let (publisher, subscriber) = open_peer(&node, &config);
+
+    let sent: Vec<ExecutionCommand> = (0..5).map(sample_command).collect();
+    for command in &sent {
+        publisher.send_copy(codec::encode_command(command).unwrap()).unwrap();
+    }
+
+    for expected in &sent {
+        let command = recv_command(&mut command_rx).await;
+        assert_eq!(command.id, expected.id);
+        assert_eq!(command.instrument, expected.instrument);
+        assert_eq!(command.quantity, expected.quantity);
+        assert_eq!(command.price, expected.price);
+        assert_eq!(command.client_order_id, expected.client_order_id);
+        assert_eq!(command.post_only, expected.post_only);
+        assert_eq!(command.action, expected.action);
+
+        result_tx.send(filled_result(&command)).await.unwrap();
+    }
+
+    for expected in &sent {
+        let result = recv_result(&subscriber);
+        assert_eq!(result.command_id, expected.id);
+        assert!(result.success);
+        let selected = result.selected_response.expect("missing selected response");
+        assert!(matches!(selected.status, OrderStatus::Filled));
+        assert_eq!(selected.executed_qty, expected.quantity);
+        assert_eq!(result.responses.len(), 1);
+    }
+
+    manager.shutdown();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn notification_wakes_command_receiver() {
+    let mut config = test_config("wakeup");
+    // Far beyond the receive deadline below, so only the notification can deliver in time
+    config.wakeup.max_wait_us = 60_000_000;
+    let (command_tx, mut command_rx) = mpsc::channel(BUFFER_SIZE);
+
+    let mut manager = IpcManager::new(config.clone(), command_tx).unwrap();
+    manager.initialize().unwrap();
+    manager.start().await.unwrap();
+
+    let node = NodeBuilder::new().create::<ipc::Service>().unwrap();
+    let (publisher, _subscriber) = open_peer(&node, &config);
+    let notifier = TopicNotifier::open(&node, &config.input_topic).unwrap();
+
+    // Let the receiver finish its startup drain and block on the listener
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    let command = sample_command(3);
+    publisher.send_copy(codec::encode_command(&command).unwrap()).unwrap();
+    assert_eq!(notifier.notify().unwrap(), 1);
+
+    let received = tokio::time::timeout(Duration::from_secs(1), command_rx.recv())
+        .await
+        .expect("receiver was not woken by the notification")
+        .expect("command channel closed");
+    assert_eq!(received.id, command.id);
+
+    manager.shutdown();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn malformed_frames_are_skipped() {
+    let config = test_config("malformed");
+    let (command_tx, mut command_rx) = mpsc::channel(BUFFER_SIZE);
+
+    let mut manager = IpcManager::new(config.clone(), command_tx).unwrap();
+    manager.initialize().unwrap();
+    manager.start().await.unwrap();
+
+    let node = NodeBuilder::new().create::<ipc::Service>().unwrap();
+    let (publisher, _subscriber) = open_peer(&node, &config);
+
+    let command = sample_command(7);
+    let mut stale = codec::encode_command(&command).unwrap();
+    // Byte 2 of the common frame header is the wire version
+    stale[2] = WIRE_VERSION + 1;
+    publisher.send_copy(stale).unwrap();
+    publisher.send_copy([0u8; codec::IPC_FRAME_SIZE]).unwrap();
+    publisher.send_copy(codec::encode_command(&command).unwrap()).unwrap();
+
+    let received = recv_command(&mut command_rx).await;
+    assert_eq!(received.id, command.id);
+    assert!(command_rx.try_recv().is_err());
+
+    manager.shutdown();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn saturated_executor_holds_commands_back() {
+    let config = test_config("backpressure");
+    // Capacity of one: the receiver thread must block rather than drop
+    let (command_tx, mut command_rx) = mpsc::channel(1);
+
+    let mut manager = IpcManager::new(config.clone(), command_tx).unwrap();
+    manager.initialize().unwrap();
+    manager.start().await.unwrap();
+
+    let node = NodeBuilder::new().create::<ipc::Service>().unwrap();
+    let (publisher, _subscriber) = open_peer(&node, &config);
+
+    let sent: Vec<ExecutionCommand> = (0..8).map(sample_command).collect();
+    for command in &sent {
+        publisher.send_copy(codec::encode_command(command).unwrap()).unwrap();
+    }
+
+    // Let the receiver run into the full channel before draining
+    tokio::time::sleep(Duration::from_millis(50)).await;
+
+    for expected in &sent {
+        assert_eq!(recv_command(&mut command_rx).await.id, expected.id);
+    }
+
+    manager.shutdown();
+}
+
+#[test]
+fn cancel_and_amend_actions_survive_the_wire() {
+    let actions = [
+        CommandAction::Cancel {
+            orig_client_order_id: "TE_1".to_string(),
+        },
+        CommandAction::CancelAll,
+        CommandAction::Amend {
+            orig_client_order_id: "TE_2".to_string(),
+            new_price: Some(Decimal::new(6499900, 2)),
+            new_quantity: None,
+        },
+    ];
+
+    for action in actions {
+        let mut command = sample_command(1);
+        command.action = action.clone();
+        let decoded = codec::decode_command(&codec::encode_command(&command).unwrap()).unwrap();
+        assert_eq!(decoded.id, command.id);
+        assert_eq!(decoded.action, action);
+    }
+}
+
+#[test]
+fn oversized_response_list_is_an_encode_error() {
+    let mut result = filled_result(&sample_command(1));
+    result.responses = vec![result.responses[0].clone(); u16::MAX as usize + 1];
+
+    assert_eq!(
+        codec::encode_result(&result).unwrap_err(),
+        codec::EncodeError::TooManyEntries { field: "result.responses", len: 65536, max: 65535 }
+    );
+}
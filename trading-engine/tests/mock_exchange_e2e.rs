@@ -0,0 +1,343 @@
+use bytes::Bytes;
+use common::instrument::InstrumentId;
+use futures_util::{SinkExt, StreamExt};
+use mock_exchange::{Behavior, MockExchange, OrderKind, Venue};
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use trading_engine::config::TradingEngineConfig;
+use trading_engine::executor::types::{
+    CommandAction, ExecutionCommand, ExecutionResult, OrderSide, OrderStatus, OrderType, TimeInForce,
+};
+use trading_engine::executor::OrderExecutor;
+use trading_engine::health::connection_selector::SelectionStrategy;
+use trading_engine::health::{ConnectionSelector, HealthTracker};
+use trading_engine::ws_pool::WsPool;
+use uuid::Uuid;
+
+const TEST_CONFIG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../config/trading_engine_test.toml");
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Engine {
+    executor: OrderExecutor,
+    health: Arc<HealthTracker>,
+    messages: mpsc::UnboundedReceiver<Bytes>,
+    exchange: String,
+    market_type: String,
+}
+
+/// Engine built from the test config with only `exchange` `market_type`
+/// enabled and pointed at `mock`; returns once every connection is up
+async fn engine(
+    mock: &MockExchange,
+    exchange: &str,
+    market_type: &str,
+    connections: usize,
+    order_timeout_ms: u64,
+) -> Engine {
+    let mut config = TradingEngineConfig::from_file(TEST_CONFIG).unwrap();
+    config.executor.order_timeout_ms = order_timeout_ms;
+    for (name, exchange_config) in config.exchanges.iter_mut() {
+        exchange_config.enabled = name == exchange;
+        exchange_config.spot.enabled = false;
+        exchange_config.futures.enabled = false;
+    }
+
+    let exchange_config = config.exchanges.get_mut(exchange).unwrap();
+    let endpoint = match market_type {
+        "spot" => &mut exchange_config.spot,
+        _ => &mut exchange_config.futures,
+    };
+    endpoint.enabled = true;
+    endpoint.ws_endpoints = vec![mock.url()];
+    endpoint.connection_count = connections;
+
+    let health = Arc::new(HealthTracker::new());
+    let selector = Arc::new(ConnectionSelector::new(health.clone(), SelectionStrategy::HealthScore));
+    let mut pool = WsPool::new(config.clone(), health.clone());
+    pool.start().await.unwrap();
+    let messages = pool.take_message_receiver().unwrap();
+
+    let executor = OrderExecutor::new(config.executor.clone(), Arc::new(pool), health.clone(), selector);
+    let exchange_config = &config.exchanges[exchange];
+    executor.register_signer(
+        exchange.to_string(),
+        exchange_config.api_key.clone(),
+        exchange_config.secret_key.clone(),
+        exchange_config.passphrase.clone(),
+    );
+
+    let engine = Engine {
+        executor,
+        health,
+        messages,
+        exchange: exchange.to_string(),
+        market_type: market_type.to_string(),
+    };
+    engine.wait_connected(connections).await;
+    engine
+}
+
+impl Engine {
+    async fn wait_connected(&self, count: usize) {
+        let deadline = Instant::now() + CONNECT_TIMEOUT;
+        while self.health.get_healthy_connections(&self.exchange, &self.market_type, 0.0).len() < count {
+            assert!(Instant::now() < deadline, "{} {} connections did not come up", self.exchange, self.market_type);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    }
+
+    fn command(&self, symbol: &str, quantity: Decimal) -> ExecutionCommand {
+        ExecutionCommand {
+            id: Uuid::new_v4(),
+            instrument: InstrumentId::UNKNOWN,
+            exchange: self.exchange.clone(),
+            market_type: self.market_type.clone(),
+            symbol: symbol.to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            quantity,
+            price: Some(Decimal::new(6500012, 2)),
+            time_in_force: TimeInForce::GTC,
+            client_order_id: None,
+            reduce_only: false,
+            post_only: false,
+            action: CommandAction::Place,
+        }
+    }
+
+    async fn place(&self, quantity: Decimal) -> ExecutionResult {
+        self.executor.execute(self.command("BTCUSDT", quantity)).await
+    }
+}
+
+fn selected_status(result: &ExecutionResult) -> Option<OrderStatus> {
+    result.selected_response.as_ref().map(|r| r.status)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn binance_fill_is_preferred_over_resting_ack() {
+    let mock = MockExchange::start(Venue::Binance).await.unwrap();
+    let engine = engine(&mock, "binance", "futures", 2, 2000).await;
+
+    mock.enqueue(Behavior::Accept);
+    mock.enqueue(Behavior::Fill);
+    let result = engine.place(Decimal::new(5, 3)).await;
+
+    assert!(result.success, "{:?}", result.error);
+    assert_eq!(result.responses.len(), 2);
+    assert!(matches!(selected_status(&result), Some(OrderStatus::Filled)));
+    let selected = result.selected_response.unwrap();
+    assert_eq!(selected.executed_qty, Decimal::new(5, 3));
+
+    // Both connections carried the same order
+    let received = mock.received();
+    assert_eq!(received.len(), 2);
+    assert!(received.iter().all(|r| r.kind == OrderKind::Place));
+    assert!(received.iter().all(|r| r.client_order_id == selected.client_order_id));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn binance_partial_fill_is_success() {
+    let mock = MockExchange::start(Venue::Binance).await.unwrap();
+    let engine = engine(&mock, "binance", "spot", 1, 2000).await;
+
+    mock.enqueue(Behavior::PartialFill(Decimal::new(4, 1)));
+    let result = engine.place(Decimal::ONE).await;
+
+    assert!(result.success, "{:?}", result.error);
+    assert!(matches!(selected_status(&result), Some(OrderStatus::PartiallyFilled)));
+    assert_eq!(result.selected_response.unwrap().executed_qty, Decimal::new(4, 1));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn binance_reject_surfaces_venue_reason() {
+    let mock = MockExchange::start(Venue::Binance).await.unwrap();
+    let engine = engine(&mock, "binance", "futures", 1, 2000).await;
+
+    mock.set_default(Behavior::Reject {
+        code: -2010,
+        msg: "Account has insufficient balance for requested action.".to_string(),
+    });
+    let result = engine.place(Decimal::ONE).await;
+
+    assert!(!result.success);
+    assert!(!result.outcome_unknown);
+    assert!(result.selected_response.is_none());
+    let error = result.error.unwrap();
+    assert!(error.contains("-2010") && error.contains("insufficient balance"), "{}", error);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn binance_requests_without_signed_params_are_rejected() {
+    let mock = MockExchange::start(Venue::Binance).await.unwrap();
+    let (mut ws, _) = connect_async(mock.url()).await.unwrap();
+
+    let flat = serde_json::json!({
+        "id": "TE_1",
+        "method": "order.place",
+        "symbol": "BTCUSDT",
+        "side": "BUY",
+        "type": "MARKET",
+        "quantity": "1",
+        "timestamp": 1700000000000_i64,
+        "signature": "00",
+    });
+    let mut keyless = flat.clone();
+    keyless["params"] = serde_json::json!({"symbol": "BTCUSDT", "side": "BUY", "quantity": "1"});
+
+    for (request, missing) in [(flat, "'params'"), (keyless, "'apiKey'")] {
+        ws.send(Message::Text(request.to_string())).await.unwrap();
+        let reply = match ws.next().await.unwrap().unwrap() {
+            Message::Text(text) => serde_json::from_str::<serde_json::Value>(&text).unwrap(),
+            other => panic!("unexpected frame {:?}", other),
+        };
+        assert_eq!(reply["id"], "TE_1");
+        assert_eq!(reply["status"], 400);
+        assert_eq!(reply["error"]["code"], -1102);
+        assert!(reply["error"]["msg"].as_str().unwrap().contains(missing), "{}", reply);
+    }
+    assert!(mock.received().is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn reject_on_one_connection_is_masked_by_the_other() {
+    let mock = MockExchange::start(Venue::Binance).await.unwrap();
+    let engine = engine(&mock, "binance", "futures", 2, 2000).await;
+
+    mock.enqueue(Behavior::Reject { code: -1003, msg: "Too many requests.".to_string() });
+    mock.enqueue(Behavior::Accept);
+    let result = engine.place(Decimal::ONE).await;
+
+    assert!(result.success, "{:?}", result.error);
+    assert!(matches!(selected_status(&result), Some(OrderStatus::New)));
+    assert_eq!(result.responses.iter().filter(|r| r.error.is_some()).count(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn dropped_ack_times_out() {
+    let mock = MockExchange::start(Venue::Binance).await.unwrap();
+    let engine = engine(&mock, "binance", "futures", 1, 300).await;
+
+    mock.set_default(Behavior::Drop);
+    let started = Instant::now();
+    let result = engine.place(Decimal::ONE).await;
+
+    // The order may have reached the venue, so the failure is not a reject
+    assert!(!result.success);
+    assert!(result.outcome_unknown);
+    assert!(result.responses.is_empty());
+    assert_eq!(result.error.as_deref(), Some("No ack from exchange, outcome unknown"));
+    assert!(started.elapsed() >= Duration::from_millis(300));
+    assert_eq!(mock.received().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn slow_ack_within_timeout_succeeds() {
+    let mock = MockExchange::start(Venue::Binance).await.unwrap();
+    let engine = engine(&mock, "binance", "futures", 1, 2000).await;
+
+    mock.set_latency(Duration::from_millis(150));
+    let started = Instant::now();
+    let result = engine.place(Decimal::ONE).await;
+
+    assert!(result.success, "{:?}", result.error);
+    assert!(started.elapsed() >= Duration::from_millis(150));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn disconnect_fails_in_flight_order_then_reconnects() {
+    let mock = MockExchange::start(Venue::Binance).await.unwrap();
+    let engine = engine(&mock, "binance", "futures", 1, 5000).await;
+
+    mock.enqueue(Behavior::Disconnect);
+    let started = Instant::now();
+    let result = engine.place(Decimal::ONE).await;
+
+    // The dead socket fails the order instead of waiting out the timeout
+    assert!(!result.success);
+    assert!(result.outcome_unknown);
+    assert!(started.elapsed() < Duration::from_secs(5));
+
+    engine.wait_connected(1).await;
+    assert_eq!(mock.accepted_count(), 2);
+
+    let result = engine.place(Decimal::ONE).await;
+    assert!(result.success, "{:?}", result.error);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn duplicate_client_order_id_is_not_sent() {
+    let mock = MockExchange::start(Venue::Binance).await.unwrap();
+    let engine = engine(&mock, "binance", "futures", 1, 2000).await;
+
+    let mut command = engine.command("BTCUSDT", Decimal::ONE);
+    command.client_order_id = Some("TE_dup".to_string());
+    assert!(engine.executor.execute(command.clone()).await.success);
+
+    command.id = Uuid::new_v4();
+    let result = engine.executor.execute(command).await;
+    assert!(!result.success);
+    assert_eq!(result.error.as_deref(), Some("Duplicate order"));
+    assert_eq!(mock.received().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn okex_place_then_cancel() {
+    let mock = MockExchange::start(Venue::Okx).await.unwrap();
+    let engine = engine(&mock, "okex", "spot", 1, 2000).await;
+
+    let result = engine.executor.execute(engine.command("BTC-USDT", Decimal::ONE)).await;
+    assert!(result.success, "{:?}", result.error);
+    assert!(matches!(selected_status(&result), Some(OrderStatus::New)));
+    let client_order_id = result.selected_response.unwrap().client_order_id;
+
+    let mut cancel = engine.command("BTC-USDT", Decimal::ONE);
+    cancel.action = CommandAction::Cancel {
+        orig_client_order_id: client_order_id.clone(),
+    };
+    let result = engine.executor.execute(cancel).await;
+    assert!(result.success, "{:?}", result.error);
+    assert!(matches!(selected_status(&result), Some(OrderStatus::Canceled)));
+
+    let kinds: Vec<_> = mock.received().iter().map(|r| (r.kind, r.client_order_id.clone())).collect();
+    assert_eq!(kinds, vec![(OrderKind::Place, client_order_id.clone()), (OrderKind::Cancel, client_order_id)]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn bybit_reject_surfaces_ret_code() {
+    let mock = MockExchange::start(Venue::Bybit).await.unwrap();
+    let engine = engine(&mock, "bybit", "futures", 1, 2000).await;
+
+    let result = engine.place(Decimal::ONE).await;
+    assert!(result.success, "{:?}", result.error);
+
+    mock.enqueue(Behavior::Reject { code: 170131, msg: "Insufficient balance.".to_string() });
+    let result = engine.place(Decimal::ONE).await;
+    assert!(!result.success);
+    let error = result.error.unwrap();
+    assert!(error.contains("170131"), "{}", error);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn market_data_reaches_the_message_stream() {
+    let mock = MockExchange::start(Venue::Binance).await.unwrap();
+    let mut engine = engine(&mock, "binance", "futures", 1, 2000).await;
+
+    let frame = r#"{"e":"aggTrade","E":1711001595299,"s":"BTCUSDT","p":"65000.10","q":"0.010"}"#;
+    mock.push(frame);
+
+    // The subscription ack arrives on the same stream first
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let received = engine.messages.recv().await.expect("message stream closed");
+            if &received[..] == frame.as_bytes() {
+                break;
+            }
+        }
+    })
+    .await
+    .expect("timed out waiting for market data");
+}